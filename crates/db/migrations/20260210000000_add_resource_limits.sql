@@ -0,0 +1,7 @@
+-- Per-repo resource limits for setup scripts and dev servers
+ALTER TABLE repos ADD COLUMN memory_limit_mb INTEGER;
+ALTER TABLE repos ADD COLUMN cpu_limit_percent INTEGER;
+
+-- Resource usage recorded when an execution process finishes
+ALTER TABLE execution_processes ADD COLUMN peak_memory_bytes INTEGER;
+ALTER TABLE execution_processes ADD COLUMN cpu_time_ms INTEGER;
@@ -0,0 +1,3 @@
+-- How direct merges from workspaces land on the target branch
+ALTER TABLE repos ADD COLUMN merge_strategy TEXT NOT NULL DEFAULT 'squash'
+    CHECK (merge_strategy IN ('squash', 'merge_commit', 'rebase'));
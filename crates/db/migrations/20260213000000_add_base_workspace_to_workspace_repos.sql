@@ -0,0 +1,18 @@
+-- Workspace whose branch a workspace repo is stacked on. Set whenever the
+-- target branch is the branch of another active workspace with the same repo.
+ALTER TABLE workspace_repos ADD COLUMN base_workspace_id BLOB
+    REFERENCES workspaces(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_workspace_repos_base_workspace_id ON workspace_repos(base_workspace_id);
+
+UPDATE workspace_repos
+SET base_workspace_id = (
+    SELECT w.id FROM workspaces w
+    JOIN workspace_repos pwr ON pwr.workspace_id = w.id
+    WHERE w.branch = workspace_repos.target_branch
+      AND pwr.repo_id = workspace_repos.repo_id
+      AND w.id != workspace_repos.workspace_id
+      AND w.archived = FALSE
+    ORDER BY w.created_at DESC
+    LIMIT 1
+);
@@ -0,0 +1,4 @@
+-- How commits of agent turns are worded
+ALTER TABLE repos ADD COLUMN commit_message_template TEXT;
+ALTER TABLE repos ADD COLUMN conventional_commits INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE repos ADD COLUMN agent_commit_messages INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- Where a dev server listens, detected from its output or its sockets
+ALTER TABLE execution_processes ADD COLUMN detected_port INTEGER;
+ALTER TABLE execution_processes ADD COLUMN detected_url TEXT;
@@ -0,0 +1,12 @@
+PRAGMA foreign_keys = ON;
+
+-- Ports reserved for a workspace, so its processes get the same ports across
+-- restarts and never collide with another workspace's
+CREATE TABLE workspace_ports (
+    workspace_id    BLOB NOT NULL,
+    slot            INTEGER NOT NULL,
+    port            INTEGER NOT NULL UNIQUE,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (workspace_id, slot),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
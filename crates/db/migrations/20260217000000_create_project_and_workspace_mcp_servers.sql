@@ -0,0 +1,19 @@
+PRAGMA foreign_keys = ON;
+
+-- MCP servers given to the coding agents of a project, as a JSON object keyed
+-- by server name
+CREATE TABLE project_mcp_servers (
+    project_id      BLOB PRIMARY KEY,
+    servers         TEXT NOT NULL DEFAULT '{}',
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Per-workspace changes to the project's MCP servers. A server set to null
+-- removes the project server of that name.
+CREATE TABLE workspace_mcp_servers (
+    workspace_id    BLOB PRIMARY KEY,
+    servers         TEXT NOT NULL DEFAULT '{}',
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
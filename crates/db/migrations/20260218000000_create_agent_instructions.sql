@@ -0,0 +1,18 @@
+PRAGMA foreign_keys = ON;
+
+-- Markdown instructions for the coding agents of a project, rendered into the
+-- instructions file each agent reads
+CREATE TABLE project_instructions (
+    project_id      BLOB PRIMARY KEY,
+    content         TEXT NOT NULL DEFAULT '',
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Instructions added to the project's for the agents working on one task
+CREATE TABLE task_instructions (
+    task_id         BLOB PRIMARY KEY,
+    content         TEXT NOT NULL DEFAULT '',
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
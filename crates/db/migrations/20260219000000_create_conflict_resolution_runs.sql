@@ -0,0 +1,13 @@
+PRAGMA foreign_keys = ON;
+
+-- Coding agent runs resolving the conflicts of a stopped rebase or merge. When
+-- the run finishes the operation is continued if no conflict markers remain.
+-- Rows are written before the run's execution process is created, so there is
+-- no foreign key to execution_processes.
+CREATE TABLE conflict_resolution_runs (
+    execution_process_id    BLOB PRIMARY KEY,
+    repo_id                 BLOB NOT NULL,
+    files                   TEXT NOT NULL DEFAULT '[]',
+    created_at              TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
@@ -0,0 +1,19 @@
+PRAGMA foreign_keys = ON;
+
+-- Coding agent runs asking the agent of a finished turn for a commit message.
+-- When the run finishes the turn's changes are committed with its reply. Rows
+-- are written before the run's execution process is created, so there is no
+-- foreign key to it.
+CREATE TABLE commit_message_runs (
+    execution_process_id    BLOB PRIMARY KEY,
+    turn_process_id         BLOB NOT NULL,
+    turn                    INTEGER NOT NULL,
+    created_at              TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (turn_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_commit_message_runs_turn_process_id ON commit_message_runs(turn_process_id);
+
+-- Coding agent turns vibe-kanban started on its own (commit messages, conflict
+-- resolution, fallbacks) rather than for a message from the user
+ALTER TABLE coding_agent_turns ADD COLUMN automated BOOLEAN NOT NULL DEFAULT FALSE;
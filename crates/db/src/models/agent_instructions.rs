@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct AgentInstructions {
+    /// Markdown instructions for coding agents
+    pub content: String,
+}
+
+impl AgentInstructions {
+    pub async fn find_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<String, sqlx::Error> {
+        let content = sqlx::query_scalar!(
+            r#"SELECT content FROM project_instructions WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(content.unwrap_or_default())
+    }
+
+    pub async fn set_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        content: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO project_instructions (project_id, content)
+               VALUES ($1, $2)
+               ON CONFLICT(project_id) DO UPDATE SET
+                   content = excluded.content,
+                   updated_at = datetime('now', 'subsec')"#,
+            project_id,
+            content
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_for_task(pool: &SqlitePool, task_id: Uuid) -> Result<String, sqlx::Error> {
+        let content = sqlx::query_scalar!(
+            r#"SELECT content FROM task_instructions WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(content.unwrap_or_default())
+    }
+
+    pub async fn set_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        content: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO task_instructions (task_id, content)
+               VALUES ($1, $2)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   content = excluded.content,
+                   updated_at = datetime('now', 'subsec')"#,
+            task_id,
+            content
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The project's instructions followed by the task's additions, or `None`
+    /// if neither has any
+    pub async fn resolve(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        task_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let project = Self::find_for_project(pool, project_id).await?;
+        let task = Self::find_for_task(pool, task_id).await?;
+        Ok(Self::combine(&project, &task))
+    }
+
+    fn combine(project: &str, task: &str) -> Option<String> {
+        match (project.trim(), task.trim()) {
+            ("", "") => None,
+            (project, "") => Some(project.to_string()),
+            ("", task) => Some(task.to_string()),
+            (project, task) => Some(format!("{project}\n\n## Task instructions\n\n{task}")),
+        }
+    }
+}
@@ -21,6 +21,9 @@ pub struct CodingAgentTurn {
 pub struct CreateCodingAgentTurn {
     pub execution_process_id: Uuid,
     pub prompt: Option<String>,
+    /// Started by vibe-kanban itself rather than for a message from the user
+    #[serde(default)]
+    pub automated: bool,
 }
 
 /// Session info from a coding agent turn, used for follow-up requests
@@ -56,6 +59,28 @@ impl CodingAgentTurn {
         .await
     }
 
+    /// Number of turns in `session_id` the user asked for, up to and including
+    /// the one started at `until`
+    pub async fn count_user_turns(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        until: DateTime<Utc>,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM execution_processes ep
+               JOIN coding_agent_turns cat ON ep.id = cat.execution_process_id
+               WHERE ep.session_id = $1
+                 AND ep.run_reason = 'codingagent'
+                 AND cat.automated = FALSE
+                 AND ep.created_at <= $2"#,
+            session_id,
+            until
+        )
+        .fetch_one(pool)
+        .await
+    }
+
     /// Find coding agent turn by execution process ID
     pub async fn find_by_execution_process_id(
         pool: &SqlitePool,
@@ -125,9 +150,9 @@ impl CodingAgentTurn {
             CodingAgentTurn,
             r#"INSERT INTO coding_agent_turns (
                 id, execution_process_id, agent_session_id, agent_message_id, prompt, summary, seen,
-                created_at, updated_at
+                automated, created_at, updated_at
                )
-               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
                RETURNING
                 id as "id!: Uuid",
                 execution_process_id as "execution_process_id!: Uuid",
@@ -145,8 +170,9 @@ impl CodingAgentTurn {
             data.prompt,
             None::<String>, // summary initially None
             false,          // seen - defaults to unseen
-            now,            // created_at
-            now             // updated_at
+            data.automated,
+            now, // created_at
+            now  // updated_at
         )
         .fetch_one(pool)
         .await
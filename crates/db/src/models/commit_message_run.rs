@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// A coding agent run asking the agent of a finished turn to describe its
+/// changes. Kept in the database so a run that is still queued across a
+/// restart commits the turn it describes when it finishes.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct CommitMessageRun {
+    pub execution_process_id: Uuid,
+    /// The coding agent turn that made the changes
+    pub turn_process_id: Uuid,
+    /// Number of that turn within its session
+    pub turn: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CommitMessageRun {
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        turn_process_id: Uuid,
+        turn: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO commit_message_runs (execution_process_id, turn_process_id, turn)
+               VALUES ($1, $2, $3)"#,
+            execution_process_id,
+            turn_process_id,
+            turn
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            CommitMessageRun,
+            r#"SELECT
+                execution_process_id as "execution_process_id!: Uuid",
+                turn_process_id as "turn_process_id!: Uuid",
+                turn,
+                created_at as "created_at!: DateTime<Utc>"
+               FROM commit_message_runs
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Remove the run started as `execution_process_id`, if any
+    pub async fn delete(pool: &SqlitePool, execution_process_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM commit_message_runs WHERE execution_process_id = $1",
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Whether some of `turn_process_id`'s changes wait for a commit message run
+    pub async fn is_waiting_for_turn(
+        pool: &SqlitePool,
+        turn_process_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let waiting = sqlx::query_scalar!(
+            r#"SELECT EXISTS(
+                SELECT 1 FROM commit_message_runs WHERE turn_process_id = $1
+               ) as "waiting!: bool""#,
+            turn_process_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(waiting)
+    }
+}
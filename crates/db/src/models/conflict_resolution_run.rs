@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use uuid::Uuid;
+
+/// A coding agent run started to resolve the conflicts in one repo. Kept in
+/// the database so a run that is still queued across a restart is continued
+/// when it finishes.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ConflictResolutionRun {
+    pub execution_process_id: Uuid,
+    pub repo_id: Uuid,
+    /// Files that were conflicted when the agent started
+    pub files: Json<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ConflictResolutionRun {
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        repo_id: Uuid,
+        files: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let files = Json(files);
+        sqlx::query!(
+            r#"INSERT INTO conflict_resolution_runs (execution_process_id, repo_id, files)
+               VALUES ($1, $2, $3)"#,
+            execution_process_id,
+            repo_id,
+            files
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove and return the run started as `execution_process_id`, if any
+    pub async fn take(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ConflictResolutionRun,
+            r#"DELETE FROM conflict_resolution_runs
+               WHERE execution_process_id = $1
+               RETURNING
+                execution_process_id as "execution_process_id!: Uuid",
+                repo_id as "repo_id!: Uuid",
+                files as "files!: Json<Vec<String>>",
+                created_at as "created_at!: DateTime<Utc>""#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
 use chrono::{DateTime, Utc};
 use executors::{
@@ -43,6 +43,8 @@ pub enum ExecutionProcessError {
 #[serde(rename_all = "lowercase")]
 #[ts(use_ts_enum)]
 pub enum ExecutionProcessStatus {
+    // Waiting for a free coding agent slot
+    Queued,
     Running,
     Completed,
     Failed,
@@ -69,6 +71,14 @@ pub struct ExecutionProcess {
     pub executor_action: sqlx::types::Json<ExecutorActionField>,
     pub status: ExecutionProcessStatus,
     pub exit_code: Option<i64>,
+    /// Peak resident memory of the process group, sampled while it ran
+    pub peak_memory_bytes: Option<i64>,
+    /// User + system CPU time of the process group, in milliseconds
+    pub cpu_time_ms: Option<i64>,
+    /// Port a dev server listens on, detected from its output or its sockets
+    pub detected_port: Option<i64>,
+    /// URL a dev server can be reached at, once its port is known
+    pub detected_url: Option<String>,
     /// dropped: true if this process is excluded from the current
     /// history view (due to restore/trimming). Hidden from logs/timeline;
     /// still listed in the Processes tab.
@@ -144,6 +154,10 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.peak_memory_bytes,
+                    ep.cpu_time_ms,
+                    ep.detected_port,
+                    ep.detected_url,
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -218,6 +232,10 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.peak_memory_bytes,
+                    ep.cpu_time_ms,
+                    ep.detected_port,
+                    ep.detected_url,
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -245,6 +263,10 @@ impl ExecutionProcess {
                       ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                       ep.status          as "status!: ExecutionProcessStatus",
                       ep.exit_code,
+                      ep.peak_memory_bytes,
+                      ep.cpu_time_ms,
+                      ep.detected_port,
+                      ep.detected_url,
                       ep.dropped as "dropped!: bool",
                       ep.started_at      as "started_at!: DateTime<Utc>",
                       ep.completed_at    as "completed_at?: DateTime<Utc>",
@@ -272,6 +294,10 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.peak_memory_bytes,
+                    ep.cpu_time_ms,
+                    ep.detected_port,
+                    ep.detected_url,
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -291,7 +317,7 @@ impl ExecutionProcess {
         sqlx::query_as!(
             ExecutionProcess,
             r#"SELECT ep.id as "id!: Uuid", ep.session_id as "session_id!: Uuid", ep.run_reason as "run_reason!: ExecutionProcessRunReason", ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
-                      ep.status as "status!: ExecutionProcessStatus", ep.exit_code,
+                      ep.status as "status!: ExecutionProcessStatus", ep.exit_code, ep.peak_memory_bytes, ep.cpu_time_ms, ep.detected_port, ep.detected_url,
                       ep.dropped as "dropped!: bool", ep.started_at as "started_at!: DateTime<Utc>", ep.completed_at as "completed_at?: DateTime<Utc>", ep.created_at as "created_at!: DateTime<Utc>", ep.updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_processes ep
                JOIN sessions s ON ep.session_id = s.id
@@ -305,6 +331,32 @@ impl ExecutionProcess {
         .await
     }
 
+    /// Find queued execution processes, oldest first
+    pub async fn find_queued(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcess,
+            r#"SELECT
+                    ep.id as "id!: Uuid",
+                    ep.session_id as "session_id!: Uuid",
+                    ep.run_reason as "run_reason!: ExecutionProcessRunReason",
+                    ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
+                    ep.status as "status!: ExecutionProcessStatus",
+                    ep.exit_code,
+                    ep.peak_memory_bytes,
+                    ep.cpu_time_ms,
+                    ep.detected_port,
+                    ep.detected_url,
+                    ep.dropped as "dropped!: bool",
+                    ep.started_at as "started_at!: DateTime<Utc>",
+                    ep.completed_at as "completed_at?: DateTime<Utc>",
+                    ep.created_at as "created_at!: DateTime<Utc>",
+                    ep.updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_processes ep WHERE ep.status = 'queued' ORDER BY ep.created_at ASC"#,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Check if there are running processes (excluding dev servers) for a workspace (across all sessions)
     pub async fn has_running_non_dev_server_processes_for_workspace(
         pool: &SqlitePool,
@@ -315,7 +367,7 @@ impl ExecutionProcess {
                FROM execution_processes ep
                JOIN sessions s ON ep.session_id = s.id
                WHERE s.workspace_id = $1
-                 AND ep.status = 'running'
+                 AND ep.status IN ('running', 'queued')
                  AND ep.run_reason != 'devserver'"#,
             workspace_id
         )
@@ -339,6 +391,10 @@ impl ExecutionProcess {
             ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
             ep.status as "status!: ExecutionProcessStatus",
             ep.exit_code,
+            ep.peak_memory_bytes,
+            ep.cpu_time_ms,
+            ep.detected_port,
+            ep.detected_url,
             ep.dropped as "dropped!: bool",
             ep.started_at as "started_at!: DateTime<Utc>",
             ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -372,6 +428,10 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.peak_memory_bytes,
+                    ep.cpu_time_ms,
+                    ep.detected_port,
+                    ep.detected_url,
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -402,6 +462,10 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.peak_memory_bytes,
+                    ep.cpu_time_ms,
+                    ep.detected_port,
+                    ep.detected_url,
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -429,6 +493,7 @@ impl ExecutionProcess {
         pool: &SqlitePool,
         data: &CreateExecutionProcess,
         process_id: Uuid,
+        status: ExecutionProcessStatus,
         repo_states: &[CreateExecutionProcessRepoState],
     ) -> Result<Self, sqlx::Error> {
         let now = Utc::now();
@@ -443,7 +508,7 @@ impl ExecutionProcess {
             data.session_id,
             data.run_reason,
             executor_action_json,
-            ExecutionProcessStatus::Running,
+            status,
             None::<i64>,
             now,
             None::<DateTime<Utc>>,
@@ -472,6 +537,42 @@ impl ExecutionProcess {
         false
     }
 
+    /// Move a queued process to running. Returns false if it already left the queue.
+    pub async fn mark_started(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            r#"UPDATE execution_processes
+               SET status = 'running', started_at = $1
+               WHERE id = $2 AND status = 'queued'"#,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Take a process out of the queue without running it. Returns false if it
+    /// was not queued.
+    pub async fn cancel_queued(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: ExecutionProcessStatus,
+    ) -> Result<bool, sqlx::Error> {
+        let completed_at = Utc::now();
+        let result = sqlx::query!(
+            r#"UPDATE execution_processes
+               SET status = $1, completed_at = $2
+               WHERE id = $3 AND status = 'queued'"#,
+            status,
+            completed_at,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Update execution process status and completion info
     pub async fn update_completion(
         pool: &SqlitePool,
@@ -479,7 +580,10 @@ impl ExecutionProcess {
         status: ExecutionProcessStatus,
         exit_code: Option<i64>,
     ) -> Result<(), sqlx::Error> {
-        let completed_at = if matches!(status, ExecutionProcessStatus::Running) {
+        let completed_at = if matches!(
+            status,
+            ExecutionProcessStatus::Queued | ExecutionProcessStatus::Running
+        ) {
             None
         } else {
             Some(Utc::now())
@@ -500,6 +604,47 @@ impl ExecutionProcess {
         Ok(())
     }
 
+    /// Record the resource usage measured for a finished process
+    pub async fn update_resource_usage(
+        pool: &SqlitePool,
+        id: Uuid,
+        peak_memory_bytes: Option<i64>,
+        cpu_time_ms: Option<i64>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET peak_memory_bytes = $1, cpu_time_ms = $2
+               WHERE id = $3"#,
+            peak_memory_bytes,
+            cpu_time_ms,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_detected_url(
+        pool: &SqlitePool,
+        id: Uuid,
+        port: i64,
+        url: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET detected_port = $1, detected_url = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $3"#,
+            port,
+            url,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub fn executor_action(&self) -> Result<&ExecutorAction, anyhow::Error> {
         match &self.executor_action.0 {
             ExecutorActionField::ExecutorAction(action) => Ok(action),
@@ -629,6 +774,10 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.peak_memory_bytes,
+                    ep.cpu_time_ms,
+                    ep.detected_port,
+                    ep.detected_url,
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -712,26 +861,32 @@ impl ExecutionProcess {
     }
 
     /// Find all workspaces with running dev servers, filtered by archived status.
-    /// Returns a set of workspace IDs that have at least one running dev server.
+    /// Returns the workspace IDs with at least one running dev server, mapped to
+    /// the lowest port detected among them.
     pub async fn find_workspaces_with_running_dev_servers(
         pool: &SqlitePool,
         archived: bool,
-    ) -> Result<HashSet<Uuid>, sqlx::Error> {
-        let rows: Vec<Uuid> = sqlx::query_scalar!(
+    ) -> Result<HashMap<Uuid, Option<i64>>, sqlx::Error> {
+        let rows = sqlx::query!(
             r#"
-            SELECT DISTINCT s.workspace_id as "workspace_id!: Uuid"
+            SELECT s.workspace_id as "workspace_id!: Uuid",
+                   MIN(ep.detected_port) as "detected_port: i64"
             FROM execution_processes ep
             JOIN sessions s ON ep.session_id = s.id
             JOIN workspaces w ON s.workspace_id = w.id
             WHERE w.archived = $1
               AND ep.status = 'running'
               AND ep.run_reason = 'devserver'
+            GROUP BY s.workspace_id
             "#,
             archived
         )
         .fetch_all(pool)
         .await?;
 
-        Ok(rows.into_iter().collect())
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.workspace_id, row.detected_port))
+            .collect())
     }
 }
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{SqlitePool, types::Json};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum McpServersError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Invalid MCP server '{0}': {1}")]
+    InvalidServer(String, String),
+}
+
+/// MCP servers keyed by name, each in the format of Claude Code's
+/// `mcpServers`: `{ "command", "args", "env" }` for stdio servers or
+/// `{ "type": "http", "url", "headers" }` for remote ones
+pub type McpServerMap = HashMap<String, Value>;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct McpServers {
+    pub servers: McpServerMap,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WorkspaceMcpServers {
+    /// The workspace's changes to the project servers. `null` removes the
+    /// project server of that name.
+    pub overrides: McpServerMap,
+    /// The servers the workspace's coding agents get
+    pub effective: McpServerMap,
+}
+
+impl McpServers {
+    /// Check that every server has a usable name and is either a stdio server
+    /// with a `command` or a remote server with a `url`. `allow_removals`
+    /// accepts `null` entries, as used by workspace overrides.
+    pub fn validate(servers: &McpServerMap, allow_removals: bool) -> Result<(), McpServersError> {
+        for (name, server) in servers {
+            let invalid =
+                |reason: &str| McpServersError::InvalidServer(name.clone(), reason.into());
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                return Err(invalid(
+                    "names may only contain letters, digits, '-' and '_'",
+                ));
+            }
+            match server {
+                Value::Null if allow_removals => {}
+                Value::Object(fields) => {
+                    let has = |key: &str| fields.get(key).is_some_and(Value::is_string);
+                    if !has("command") && !has("url") {
+                        return Err(invalid("expected a \"command\" or a \"url\""));
+                    }
+                }
+                _ => return Err(invalid("expected an object")),
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply workspace `overrides` to the project's `servers`
+    pub fn merge(mut servers: McpServerMap, overrides: McpServerMap) -> McpServerMap {
+        for (name, server) in overrides {
+            if server.is_null() {
+                servers.remove(&name);
+            } else {
+                servers.insert(name, server);
+            }
+        }
+        servers
+    }
+
+    pub async fn find_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<McpServerMap, sqlx::Error> {
+        let servers = sqlx::query_scalar!(
+            r#"SELECT servers as "servers!: Json<McpServerMap>"
+               FROM project_mcp_servers
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(servers.map(|servers| servers.0).unwrap_or_default())
+    }
+
+    pub async fn set_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        servers: &McpServerMap,
+    ) -> Result<(), sqlx::Error> {
+        let servers = Json(servers);
+        sqlx::query!(
+            r#"INSERT INTO project_mcp_servers (project_id, servers)
+               VALUES ($1, $2)
+               ON CONFLICT(project_id) DO UPDATE SET
+                   servers = excluded.servers,
+                   updated_at = datetime('now', 'subsec')"#,
+            project_id,
+            servers
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<McpServerMap, sqlx::Error> {
+        let servers = sqlx::query_scalar!(
+            r#"SELECT servers as "servers!: Json<McpServerMap>"
+               FROM workspace_mcp_servers
+               WHERE workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(servers.map(|servers| servers.0).unwrap_or_default())
+    }
+
+    pub async fn set_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        overrides: &McpServerMap,
+    ) -> Result<(), sqlx::Error> {
+        let overrides = Json(overrides);
+        sqlx::query!(
+            r#"INSERT INTO workspace_mcp_servers (workspace_id, servers)
+               VALUES ($1, $2)
+               ON CONFLICT(workspace_id) DO UPDATE SET
+                   servers = excluded.servers,
+                   updated_at = datetime('now', 'subsec')"#,
+            workspace_id,
+            overrides
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The servers the coding agents of a workspace get: the project's servers
+    /// with the workspace's overrides applied
+    pub async fn resolve_for_workspace(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<McpServerMap, sqlx::Error> {
+        Ok(Self::merge(
+            Self::find_for_project(pool, project_id).await?,
+            Self::find_for_workspace(pool, workspace_id).await?,
+        ))
+    }
+}
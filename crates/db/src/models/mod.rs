@@ -1,8 +1,12 @@
+pub mod agent_instructions;
 pub mod coding_agent_turn;
+pub mod commit_message_run;
+pub mod conflict_resolution_run;
 pub mod execution_process;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
 pub mod image;
+pub mod mcp_servers;
 pub mod merge;
 pub mod migration_state;
 pub mod project;
@@ -13,4 +17,5 @@ pub mod session;
 pub mod tag;
 pub mod task;
 pub mod workspace;
+pub mod workspace_port;
 pub mod workspace_repo;
@@ -7,7 +7,7 @@ use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::repo::Repo;
+use super::repo::{MergeStrategy, Repo};
 
 #[derive(Debug, Error)]
 pub enum ProjectRepoError {
@@ -85,6 +85,12 @@ impl ProjectRepo {
                       r.dev_server_script,
                       r.default_target_branch,
                       r.default_working_dir,
+                      r.memory_limit_mb,
+                      r.cpu_limit_percent,
+                      r.merge_strategy as "merge_strategy!: MergeStrategy",
+                      r.commit_message_template,
+                      r.conventional_commits as "conventional_commits!: bool",
+                      r.agent_commit_messages as "agent_commit_messages!: bool",
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -1,9 +1,10 @@
 use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Utc};
+use executors::resource_limits::ResourceLimits;
 use serde::{Deserialize, Serialize};
 use serde_with::rust::double_option;
-use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -16,6 +17,21 @@ pub enum RepoError {
     NotFound,
 }
 
+/// How a direct merge lands the task branch on the target branch
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, TS, PartialEq, Eq, Default)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// Squash the task's changes into a single commit
+    #[default]
+    Squash,
+    /// Create a merge commit that keeps the task's commits
+    MergeCommit,
+    /// Rebase the task branch onto the target and fast-forward it
+    Rebase,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct Repo {
     pub id: Uuid,
@@ -30,6 +46,19 @@ pub struct Repo {
     pub dev_server_script: Option<String>,
     pub default_target_branch: Option<String>,
     pub default_working_dir: Option<String>,
+    /// Memory limit for this repo's scripts and dev servers, in megabytes
+    pub memory_limit_mb: Option<i64>,
+    /// CPU quota for this repo's scripts and dev servers, in percent of one core
+    pub cpu_limit_percent: Option<i64>,
+    /// Default strategy for direct merges into the target branch
+    pub merge_strategy: MergeStrategy,
+    /// Template for the commit made after each agent turn, with placeholders
+    /// such as `{summary}`, `{task_id}`, `{executor}` and `{turn}`
+    pub commit_message_template: Option<String>,
+    /// Format commit subjects as Conventional Commits (`type: description`)
+    pub conventional_commits: bool,
+    /// Ask the agent to summarize its own changes as the commit message
+    pub agent_commit_messages: bool,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -110,9 +139,60 @@ pub struct UpdateRepo {
     )]
     #[ts(optional, type = "string | null")]
     pub default_working_dir: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "number | null")]
+    pub memory_limit_mb: Option<Option<i64>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "number | null")]
+    pub cpu_limit_percent: Option<Option<i64>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub merge_strategy: Option<MergeStrategy>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub commit_message_template: Option<Option<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub conventional_commits: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub agent_commit_messages: Option<bool>,
 }
 
 impl Repo {
+    /// Limits applied to this repo's setup/cleanup/archive scripts and dev servers
+    pub fn resource_limits(&self) -> Option<ResourceLimits> {
+        let limits = ResourceLimits {
+            memory_mb: self
+                .memory_limit_mb
+                .and_then(|mb| u32::try_from(mb).ok())
+                .filter(|mb| *mb > 0),
+            cpu_percent: self
+                .cpu_limit_percent
+                .and_then(|pct| u32::try_from(pct).ok())
+                .filter(|pct| *pct > 0),
+        };
+        (!limits.is_empty()).then_some(limits)
+    }
+
     /// Get repos that still have the migration sentinel as their name.
     /// Used by the startup backfill to fix repo names.
     pub async fn list_needing_name_fix(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
@@ -130,6 +210,12 @@ impl Repo {
                       dev_server_script,
                       default_target_branch,
                       default_working_dir,
+                      memory_limit_mb,
+                      cpu_limit_percent,
+                      merge_strategy as "merge_strategy!: MergeStrategy",
+                      commit_message_template,
+                      conventional_commits as "conventional_commits!: bool",
+                      agent_commit_messages as "agent_commit_messages!: bool",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -171,6 +257,12 @@ impl Repo {
                       dev_server_script,
                       default_target_branch,
                       default_working_dir,
+                      memory_limit_mb,
+                      cpu_limit_percent,
+                      merge_strategy as "merge_strategy!: MergeStrategy",
+                      commit_message_template,
+                      conventional_commits as "conventional_commits!: bool",
+                      agent_commit_messages as "agent_commit_messages!: bool",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -229,6 +321,12 @@ impl Repo {
                          dev_server_script,
                          default_target_branch,
                          default_working_dir,
+                         memory_limit_mb,
+                         cpu_limit_percent,
+                         merge_strategy as "merge_strategy!: MergeStrategy",
+                         commit_message_template,
+                         conventional_commits as "conventional_commits!: bool",
+                         agent_commit_messages as "agent_commit_messages!: bool",
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
@@ -266,6 +364,12 @@ impl Repo {
                       dev_server_script,
                       default_target_branch,
                       default_working_dir,
+                      memory_limit_mb,
+                      cpu_limit_percent,
+                      merge_strategy as "merge_strategy!: MergeStrategy",
+                      commit_message_template,
+                      conventional_commits as "conventional_commits!: bool",
+                      agent_commit_messages as "agent_commit_messages!: bool",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -292,6 +396,12 @@ impl Repo {
                       r.dev_server_script,
                       r.default_target_branch,
                       r.default_working_dir,
+                      r.memory_limit_mb,
+                      r.cpu_limit_percent,
+                      r.merge_strategy as "merge_strategy!: MergeStrategy",
+                      r.commit_message_template,
+                      r.conventional_commits as "conventional_commits!: bool",
+                      r.agent_commit_messages as "agent_commit_messages!: bool",
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -354,6 +464,25 @@ impl Repo {
             None => existing.default_working_dir,
             Some(v) => v.clone(),
         };
+        let memory_limit_mb = match &payload.memory_limit_mb {
+            None => existing.memory_limit_mb,
+            Some(v) => *v,
+        };
+        let cpu_limit_percent = match &payload.cpu_limit_percent {
+            None => existing.cpu_limit_percent,
+            Some(v) => *v,
+        };
+        let merge_strategy = payload.merge_strategy.unwrap_or(existing.merge_strategy);
+        let commit_message_template = match &payload.commit_message_template {
+            None => existing.commit_message_template,
+            Some(v) => v.clone(),
+        };
+        let conventional_commits = payload
+            .conventional_commits
+            .unwrap_or(existing.conventional_commits);
+        let agent_commit_messages = payload
+            .agent_commit_messages
+            .unwrap_or(existing.agent_commit_messages);
 
         sqlx::query_as!(
             Repo,
@@ -367,8 +496,14 @@ impl Repo {
                    dev_server_script = $7,
                    default_target_branch = $8,
                    default_working_dir = $9,
+                   memory_limit_mb = $10,
+                   cpu_limit_percent = $11,
+                   merge_strategy = $12,
+                   commit_message_template = $13,
+                   conventional_commits = $14,
+                   agent_commit_messages = $15,
                    updated_at = datetime('now', 'subsec')
-               WHERE id = $10
+               WHERE id = $16
                RETURNING id as "id!: Uuid",
                          path,
                          name,
@@ -381,6 +516,12 @@ impl Repo {
                          dev_server_script,
                          default_target_branch,
                          default_working_dir,
+                         memory_limit_mb,
+                         cpu_limit_percent,
+                         merge_strategy as "merge_strategy!: MergeStrategy",
+                         commit_message_template,
+                         conventional_commits as "conventional_commits!: bool",
+                         agent_commit_messages as "agent_commit_messages!: bool",
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             display_name,
@@ -392,6 +533,12 @@ impl Repo {
             dev_server_script,
             default_target_branch,
             default_working_dir,
+            memory_limit_mb,
+            cpu_limit_percent,
+            merge_strategy,
+            commit_message_template,
+            conventional_commits,
+            agent_commit_messages,
             id
         )
         .fetch_one(pool)
@@ -40,6 +40,8 @@ pub struct TaskWithAttemptStatus {
     #[ts(flatten)]
     pub task: Task,
     pub has_in_progress_attempt: bool,
+    /// A coding agent for this task is waiting for a concurrency slot
+    pub is_queued: bool,
     pub last_attempt_failed: bool,
     pub executor: String,
 }
@@ -139,6 +141,16 @@ impl Task {
      LIMIT 1
   ) THEN 1 ELSE 0 END            AS "has_in_progress_attempt!: i64",
 
+  CASE WHEN EXISTS (
+    SELECT 1
+      FROM workspaces w
+      JOIN sessions s ON s.workspace_id = w.id
+      JOIN execution_processes ep ON ep.session_id = s.id
+     WHERE w.task_id       = t.id
+       AND ep.status        = 'queued'
+     LIMIT 1
+  ) THEN 1 ELSE 0 END            AS "is_queued!: i64",
+
   CASE WHEN (
     SELECT ep.status
       FROM workspaces w
@@ -181,6 +193,7 @@ ORDER BY t.created_at DESC"#,
                     updated_at: rec.updated_at,
                 },
                 has_in_progress_attempt: rec.has_in_progress_attempt != 0,
+                is_queued: rec.is_queued != 0,
                 last_attempt_failed: rec.last_attempt_failed != 0,
                 executor: rec.executor,
             })
@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// A port reserved for a workspace. `slot` numbers the ports of one workspace
+/// from 0, so the same slot keeps the same port across restarts.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct WorkspacePort {
+    pub workspace_id: Uuid,
+    pub slot: i64,
+    pub port: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WorkspacePort {
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspacePort,
+            r#"SELECT
+                workspace_id as "workspace_id!: Uuid",
+                slot,
+                port,
+                created_at as "created_at!: DateTime<Utc>"
+               FROM workspace_ports
+               WHERE workspace_id = $1
+               ORDER BY slot ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Every port reserved by any workspace
+    pub async fn find_all_ports(pool: &SqlitePool) -> Result<Vec<i64>, sqlx::Error> {
+        sqlx::query_scalar!(r#"SELECT port FROM workspace_ports"#)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Reserve `port` for a slot of the workspace and return the slot's
+    /// reservation. If the slot was reserved meanwhile, e.g. by a concurrent
+    /// start of the same workspace, that reservation is returned instead. Fails
+    /// with a unique violation if another workspace already holds the port.
+    pub async fn reserve(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        slot: i64,
+        port: i64,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO workspace_ports (workspace_id, slot, port)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(workspace_id, slot) DO NOTHING"#,
+            workspace_id,
+            slot,
+            port
+        )
+        .execute(pool)
+        .await?;
+        sqlx::query_as!(
+            WorkspacePort,
+            r#"SELECT
+                workspace_id as "workspace_id!: Uuid",
+                slot,
+                port,
+                created_at as "created_at!: DateTime<Utc>"
+               FROM workspace_ports
+               WHERE workspace_id = $1 AND slot = $2"#,
+            workspace_id,
+            slot
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -6,7 +6,7 @@ use sqlx::{FromRow, SqlitePool};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::repo::Repo;
+use super::repo::{MergeStrategy, Repo};
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct WorkspaceRepo {
@@ -14,6 +14,9 @@ pub struct WorkspaceRepo {
     pub workspace_id: Uuid,
     pub repo_id: Uuid,
     pub target_branch: String,
+    /// Workspace whose branch `target_branch` is, when this repo is stacked
+    /// on another in-progress workspace
+    pub base_workspace_id: Option<Uuid>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -32,6 +35,7 @@ pub struct RepoWithTargetBranch {
     #[serde(flatten)]
     pub repo: Repo,
     pub target_branch: String,
+    pub base_workspace_id: Option<Uuid>,
 }
 
 /// Repo info with copy_files configuration.
@@ -63,12 +67,20 @@ impl WorkspaceRepo {
             let id = Uuid::new_v4();
             let workspace_repo = sqlx::query_as!(
                 WorkspaceRepo,
-                r#"INSERT INTO workspace_repos (id, workspace_id, repo_id, target_branch)
-                   VALUES ($1, $2, $3, $4)
+                r#"INSERT INTO workspace_repos (id, workspace_id, repo_id, target_branch, base_workspace_id)
+                   VALUES ($1, $2, $3, $4, (
+                       SELECT w.id FROM workspaces w
+                       JOIN workspace_repos pwr ON pwr.workspace_id = w.id
+                       WHERE w.branch = $4 AND pwr.repo_id = $3 AND w.id != $2
+                         AND w.archived = FALSE
+                       ORDER BY w.created_at DESC
+                       LIMIT 1
+                   ))
                    RETURNING id as "id!: Uuid",
                              workspace_id as "workspace_id!: Uuid",
                              repo_id as "repo_id!: Uuid",
                              target_branch,
+                             base_workspace_id as "base_workspace_id: Uuid",
                              created_at as "created_at!: DateTime<Utc>",
                              updated_at as "updated_at!: DateTime<Utc>""#,
                 id,
@@ -95,6 +107,7 @@ impl WorkspaceRepo {
                       workspace_id as "workspace_id!: Uuid",
                       repo_id as "repo_id!: Uuid",
                       target_branch,
+                      base_workspace_id as "base_workspace_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM workspace_repos
@@ -123,6 +136,12 @@ impl WorkspaceRepo {
                       r.dev_server_script,
                       r.default_target_branch,
                       r.default_working_dir,
+                      r.memory_limit_mb,
+                      r.cpu_limit_percent,
+                      r.merge_strategy as "merge_strategy!: MergeStrategy",
+                      r.commit_message_template,
+                      r.conventional_commits as "conventional_commits!: bool",
+                      r.agent_commit_messages as "agent_commit_messages!: bool",
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -152,9 +171,16 @@ impl WorkspaceRepo {
                       r.dev_server_script,
                       r.default_target_branch,
                       r.default_working_dir,
+                      r.memory_limit_mb,
+                      r.cpu_limit_percent,
+                      r.merge_strategy as "merge_strategy!: MergeStrategy",
+                      r.commit_message_template,
+                      r.conventional_commits as "conventional_commits!: bool",
+                      r.agent_commit_messages as "agent_commit_messages!: bool",
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>",
-                      wr.target_branch
+                      wr.target_branch,
+                      wr.base_workspace_id as "base_workspace_id: Uuid"
                FROM repos r
                JOIN workspace_repos wr ON r.id = wr.repo_id
                WHERE wr.workspace_id = $1
@@ -180,10 +206,17 @@ impl WorkspaceRepo {
                     dev_server_script: row.dev_server_script,
                     default_target_branch: row.default_target_branch,
                     default_working_dir: row.default_working_dir,
+                    memory_limit_mb: row.memory_limit_mb,
+                    cpu_limit_percent: row.cpu_limit_percent,
+                    merge_strategy: row.merge_strategy,
+                    commit_message_template: row.commit_message_template,
+                    conventional_commits: row.conventional_commits,
+                    agent_commit_messages: row.agent_commit_messages,
                     created_at: row.created_at,
                     updated_at: row.updated_at,
                 },
                 target_branch: row.target_branch,
+                base_workspace_id: row.base_workspace_id,
             })
             .collect())
     }
@@ -199,6 +232,7 @@ impl WorkspaceRepo {
                       workspace_id as "workspace_id!: Uuid",
                       repo_id as "repo_id!: Uuid",
                       target_branch,
+                      base_workspace_id as "base_workspace_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM workspace_repos
@@ -210,6 +244,8 @@ impl WorkspaceRepo {
         .await
     }
 
+    /// Change the target branch, stacking the repo on the workspace that owns
+    /// `new_target_branch` (or unstacking it when no workspace does)
     pub async fn update_target_branch(
         pool: &SqlitePool,
         workspace_id: Uuid,
@@ -217,7 +253,18 @@ impl WorkspaceRepo {
         new_target_branch: &str,
     ) -> Result<(), sqlx::Error> {
         sqlx::query!(
-            "UPDATE workspace_repos SET target_branch = $1, updated_at = datetime('now') WHERE workspace_id = $2 AND repo_id = $3",
+            r#"UPDATE workspace_repos
+               SET target_branch = $1,
+                   base_workspace_id = (
+                       SELECT w.id FROM workspaces w
+                       JOIN workspace_repos pwr ON pwr.workspace_id = w.id
+                       WHERE w.branch = $1 AND pwr.repo_id = $3 AND w.id != $2
+                         AND w.archived = FALSE
+                       ORDER BY w.created_at DESC
+                       LIMIT 1
+                   ),
+                   updated_at = datetime('now')
+               WHERE workspace_id = $2 AND repo_id = $3"#,
             new_target_branch,
             workspace_id,
             repo_id
@@ -237,10 +284,13 @@ impl WorkspaceRepo {
             r#"UPDATE workspace_repos
                SET target_branch = $1, updated_at = datetime('now')
                WHERE target_branch = $2
-                 AND workspace_id IN (
-                     SELECT w.id FROM workspaces w
-                     JOIN tasks t ON w.task_id = t.id
-                     WHERE t.parent_workspace_id = $3
+                 AND (
+                     base_workspace_id = $3
+                     OR workspace_id IN (
+                         SELECT w.id FROM workspaces w
+                         JOIN tasks t ON w.task_id = t.id
+                         WHERE t.parent_workspace_id = $3
+                     )
                  )"#,
             new_branch,
             old_branch,
@@ -251,6 +301,32 @@ impl WorkspaceRepo {
         Ok(result.rows_affected())
     }
 
+    /// Repos of active workspaces stacked on `base_workspace_id`'s branch in `repo_id`
+    pub async fn find_stacked_children(
+        pool: &SqlitePool,
+        base_workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceRepo,
+            r#"SELECT wr.id as "id!: Uuid",
+                      wr.workspace_id as "workspace_id!: Uuid",
+                      wr.repo_id as "repo_id!: Uuid",
+                      wr.target_branch,
+                      wr.base_workspace_id as "base_workspace_id: Uuid",
+                      wr.created_at as "created_at!: DateTime<Utc>",
+                      wr.updated_at as "updated_at!: DateTime<Utc>"
+               FROM workspace_repos wr
+               JOIN workspaces w ON wr.workspace_id = w.id
+               WHERE wr.base_workspace_id = $1 AND wr.repo_id = $2 AND w.archived = FALSE
+               ORDER BY w.created_at ASC"#,
+            base_workspace_id,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn find_unique_repos_for_task(
         pool: &SqlitePool,
         task_id: Uuid,
@@ -269,6 +345,12 @@ impl WorkspaceRepo {
                       r.dev_server_script,
                       r.default_target_branch,
                       r.default_working_dir,
+                      r.memory_limit_mb,
+                      r.cpu_limit_percent,
+                      r.merge_strategy as "merge_strategy!: MergeStrategy",
+                      r.commit_message_template,
+                      r.conventional_commits as "conventional_commits!: bool",
+                      r.agent_commit_messages as "agent_commit_messages!: bool",
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -11,6 +11,7 @@ use crate::{
     approvals::ExecutorApprovalService,
     env::ExecutionEnv,
     executors::{BaseCodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
+    instructions::write_agent_instructions,
     profile::ExecutorProfileId,
 };
 
@@ -58,6 +59,13 @@ impl Executable for CodingAgentFollowUpRequest {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
         let effective_dir = self.effective_dir(current_dir);
+        // Pick up instructions edited since the session started
+        write_agent_instructions(
+            self.base_executor(),
+            &effective_dir,
+            env.agent_instructions.as_deref(),
+        )
+        .await;
 
         #[cfg(feature = "qa-mode")]
         {
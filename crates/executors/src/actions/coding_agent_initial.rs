@@ -11,6 +11,7 @@ use crate::{
     approvals::ExecutorApprovalService,
     env::ExecutionEnv,
     executors::{BaseCodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
+    instructions::write_agent_instructions,
     profile::ExecutorProfileId,
 };
 
@@ -25,6 +26,11 @@ pub struct CodingAgentInitialRequest {
     /// If None, uses the container_ref directory directly.
     #[serde(default)]
     pub working_dir: Option<String>,
+    /// Profiles left to fall back to when this run was itself started as a
+    /// fallback. Otherwise the fallback profiles of its own profile apply.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub fallback_profiles: Option<Vec<ExecutorProfileId>>,
 }
 
 impl CodingAgentInitialRequest {
@@ -50,12 +56,21 @@ impl Executable for CodingAgentInitialRequest {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
         let effective_dir = self.effective_dir(current_dir);
+        let instructions = env.agent_instructions.as_deref();
+        let written =
+            write_agent_instructions(self.base_executor(), &effective_dir, instructions).await;
+        let prompt = match instructions {
+            // The repository has its own instructions file, so pass them with
+            // the first prompt instead
+            Some(instructions) if !written => format!("{instructions}\n\n{}", self.prompt),
+            _ => self.prompt.clone(),
+        };
 
         #[cfg(feature = "qa-mode")]
         {
             tracing::info!("QA mode: using mock executor instead of real agent");
             let executor = crate::executors::qa_mock::QaMockExecutor;
-            return executor.spawn(&effective_dir, &self.prompt, env).await;
+            return executor.spawn(&effective_dir, &prompt, env).await;
         }
 
         #[cfg(not(feature = "qa-mode"))]
@@ -69,7 +84,7 @@ impl Executable for CodingAgentInitialRequest {
 
             agent.use_approvals(approvals.clone());
 
-            agent.spawn(&effective_dir, &self.prompt, env).await
+            agent.spawn(&effective_dir, &prompt, env).await
         }
     }
 }
@@ -10,6 +10,7 @@ use crate::{
     approvals::ExecutorApprovalService,
     env::ExecutionEnv,
     executors::{BaseCodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
+    instructions::write_agent_instructions,
     profile::{ExecutorConfigs, ExecutorProfileId},
 };
 
@@ -59,6 +60,12 @@ impl Executable for ReviewRequest {
             Some(rel_path) => current_dir.join(rel_path),
             None => current_dir.to_path_buf(),
         };
+        write_agent_instructions(
+            self.base_executor(),
+            &effective_dir,
+            env.agent_instructions.as_deref(),
+        )
+        .await;
 
         let executor_profile_id = self.executor_profile_id.clone();
         let mut agent = ExecutorConfigs::get_cached()
@@ -12,6 +12,7 @@ use crate::{
     approvals::ExecutorApprovalService,
     env::ExecutionEnv,
     executors::{ExecutorError, SpawnedChild},
+    resource_limits::ResourceLimits,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -37,6 +38,9 @@ pub struct ScriptRequest {
     /// If None, uses the container_ref directory directly.
     #[serde(default)]
     pub working_dir: Option<String>,
+    /// Memory/CPU limits from the repository settings
+    #[serde(default)]
+    pub resource_limits: Option<ResourceLimits>,
 }
 
 #[async_trait]
@@ -53,15 +57,31 @@ impl Executable for ScriptRequest {
             None => current_dir.to_path_buf(),
         };
 
-        let (shell_cmd, shell_arg) = get_shell_command();
-        let mut command = Command::new(shell_cmd);
+        // Inside a workspace container the host's login shell may not exist
+        let (program, args) = match &env.oci {
+            Some(oci) => oci.wrap(
+                "sh".to_string(),
+                vec!["-c".to_string(), self.script.clone()],
+            ),
+            None => {
+                let (shell_cmd, shell_arg) = get_shell_command();
+                let shell = (
+                    shell_cmd.into(),
+                    vec![shell_arg.to_string(), self.script.clone()],
+                );
+                match &self.resource_limits {
+                    Some(limits) => limits.wrap(shell.0, shell.1).await,
+                    None => shell,
+                }
+            }
+        };
+        let mut command = Command::new(program);
         command
             .kill_on_drop(true)
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
-            .arg(shell_arg)
-            .arg(&self.script)
+            .args(args)
             .current_dir(&effective_dir);
 
         // Apply environment variables
@@ -0,0 +1,700 @@
+//! Tool-call approvals for agents that leave the decision to an external
+//! program: Cursor and Droid through their hooks, Amp through delegated
+//! permissions.
+//!
+//! Each spawn listens on a unix socket in the hook directory, which the process
+//! sandbox mounts so hooks reach it without network access (a local port on
+//! other platforms). The hook the agent runs is this binary
+//! (`<binary> approval-hook <agent>`), which forwards the tool call to the
+//! listener, waits for the user's decision and answers in the agent's format.
+//! The tool call is logged to the agent's stdout first, so the normalized
+//! conversation has an entry the approval can attach to.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use command_group::AsyncGroupChild;
+use rand::{Rng, distributions::Alphanumeric};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt},
+    process::Command,
+};
+use tokio_util::sync::CancellationToken;
+use workspace_utils::{approvals::ApprovalStatus, assets::asset_dir, path::make_path_relative};
+
+use crate::{
+    approvals::{ExecutorApprovalService, ToolCallMetadata},
+    env::ExecutionEnv,
+    executors::ExecutorError,
+    logs::{ActionType, NormalizedEntry, NormalizedEntryType, ToolStatus},
+    mcp_config::{remove_workspace_config_file, write_workspace_config_file},
+    stdout_dup::{StdoutAppender, tee_stdout_with_appender},
+};
+
+/// Subcommand of the vibe-kanban binary that agents run as their hook
+pub const HOOK_SUBCOMMAND: &str = "approval-hook";
+
+const ADDR_ENV: &str = "VK_APPROVAL_HOOK_ADDR";
+const TOKEN_ENV: &str = "VK_APPROVAL_HOOK_TOKEN";
+
+#[cfg(unix)]
+type HookListener = tokio::net::UnixListener;
+#[cfg(not(unix))]
+type HookListener = tokio::net::TcpListener;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookAgent {
+    Cursor,
+    Droid,
+    Amp,
+}
+
+/// What the hook tells the agent
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Decision {
+    Allow,
+    Deny(String),
+    /// Not running under vibe-kanban: leave the decision to the agent's own
+    /// permission settings
+    Defer,
+}
+
+impl HookAgent {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Cursor => "cursor",
+            Self::Droid => "droid",
+            Self::Amp => "amp",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [Self::Cursor, Self::Droid, Self::Amp]
+            .into_iter()
+            .find(|agent| agent.name() == name)
+    }
+
+    /// The agent's project-level config file the hook is registered in
+    fn config_path(self) -> &'static str {
+        match self {
+            Self::Cursor => ".cursor/hooks.json",
+            Self::Droid => ".factory/settings.json",
+            Self::Amp => ".amp/settings.json",
+        }
+    }
+
+    fn config(self, program: &Path) -> Result<Value, ExecutorError> {
+        let program = program.to_string_lossy();
+        let command =
+            shlex::try_quote(&program).map_err(|e| ExecutorError::Io(std::io::Error::other(e)))?;
+        Ok(match self {
+            Self::Cursor => json!({
+                "version": 1,
+                "hooks": {
+                    "beforeShellExecution": [{ "command": command }],
+                    "beforeMCPExecution": [{ "command": command }],
+                },
+            }),
+            Self::Droid => json!({
+                "hooks": {
+                    "PreToolUse": [{
+                        "matcher": "*",
+                        "hooks": [{ "type": "command", "command": command }],
+                    }],
+                },
+            }),
+            Self::Amp => json!({
+                "amp.permissions": [{ "tool": "*", "action": "delegate", "to": program }],
+            }),
+        })
+    }
+
+    /// Tool name and input of a hook invocation, from what the agent passed
+    /// on stdin
+    fn tool_call(self, input: Value) -> (String, Value) {
+        let str_field = |key: &str| input.get(key).and_then(Value::as_str).map(str::to_string);
+        match self {
+            Self::Cursor
+                if input.get("hook_event_name").and_then(Value::as_str)
+                    == Some("beforeMCPExecution") =>
+            {
+                let tool_input = match input.get("tool_input") {
+                    Some(Value::String(raw)) => {
+                        serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.clone()))
+                    }
+                    Some(value) => value.clone(),
+                    None => Value::Null,
+                };
+                (
+                    str_field("tool_name").unwrap_or_else(|| "mcp".to_string()),
+                    tool_input,
+                )
+            }
+            Self::Cursor => (
+                "Shell".to_string(),
+                json!({ "command": str_field("command").unwrap_or_default() }),
+            ),
+            Self::Droid => (
+                str_field("tool_name").unwrap_or_else(|| "tool".to_string()),
+                input.get("tool_input").cloned().unwrap_or(Value::Null),
+            ),
+            Self::Amp => (
+                std::env::var("AGENT_TOOL_NAME").unwrap_or_else(|_| "tool".to_string()),
+                input,
+            ),
+        }
+    }
+
+    /// The hook's answer in the agent's format: stdout, stderr and exit code
+    fn answer(self, decision: &Decision) -> (Option<Value>, Option<String>, i32) {
+        match (self, decision) {
+            (Self::Cursor, Decision::Allow) => (Some(json!({ "permission": "allow" })), None, 0),
+            (Self::Cursor, Decision::Deny(reason)) => (
+                Some(json!({
+                    "permission": "deny",
+                    "userMessage": reason,
+                    "agentMessage": reason,
+                })),
+                None,
+                0,
+            ),
+            (Self::Cursor, Decision::Defer) => (Some(json!({ "permission": "ask" })), None, 0),
+            (Self::Droid, Decision::Allow) => (
+                Some(json!({
+                    "hookSpecificOutput": {
+                        "hookEventName": "PreToolUse",
+                        "permissionDecision": "allow",
+                    },
+                })),
+                None,
+                0,
+            ),
+            (Self::Droid, Decision::Deny(reason)) => (
+                Some(json!({
+                    "hookSpecificOutput": {
+                        "hookEventName": "PreToolUse",
+                        "permissionDecision": "deny",
+                        "permissionDecisionReason": reason,
+                    },
+                })),
+                None,
+                0,
+            ),
+            (Self::Droid, Decision::Defer) => (None, None, 0),
+            (Self::Amp, Decision::Allow) => (None, None, 0),
+            (Self::Amp, Decision::Deny(reason)) => (None, Some(reason.clone()), 2),
+            (Self::Amp, Decision::Defer) => (None, None, 1),
+        }
+    }
+}
+
+/// Hook → listener, one JSON line per connection
+#[derive(Debug, Serialize, Deserialize)]
+struct HookRequest {
+    token: String,
+    tool_name: String,
+    tool_input: Value,
+}
+
+/// Listener → hook
+#[derive(Debug, Serialize, Deserialize)]
+struct HookResponse {
+    status: ApprovalStatus,
+}
+
+/// Logged to the agent's stdout when its hook asks to approve a tool call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ApprovalHookEvent {
+    #[serde(rename = "approval_hook_request")]
+    Request {
+        call_id: String,
+        tool_name: String,
+        tool_input: Value,
+    },
+}
+
+impl ApprovalHookEvent {
+    pub fn parse(line: &str) -> Option<Self> {
+        serde_json::from_str(line.trim()).ok()
+    }
+
+    pub fn raw(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// The pending tool call the user is asked to approve
+    pub fn to_normalized_entry(&self, worktree_path: &str) -> NormalizedEntry {
+        let Self::Request {
+            call_id,
+            tool_name,
+            tool_input,
+        } = self;
+        let str_field = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| tool_input.get(*key).and_then(Value::as_str))
+        };
+
+        let (action_type, content) = if let Some(command) = str_field(&["command", "cmd"]) {
+            (
+                ActionType::CommandRun {
+                    command: command.to_string(),
+                    result: None,
+                },
+                command.to_string(),
+            )
+        } else if let Some(path) = str_field(&["file_path", "path"]) {
+            let path = make_path_relative(path, worktree_path);
+            let lower = tool_name.to_lowercase();
+            let action_type = if ["read", "view", "ls", "glob", "grep"]
+                .iter()
+                .any(|name| lower.contains(name))
+            {
+                ActionType::FileRead { path: path.clone() }
+            } else {
+                ActionType::FileEdit {
+                    path: path.clone(),
+                    changes: vec![],
+                }
+            };
+            (action_type, path)
+        } else {
+            (
+                ActionType::Tool {
+                    tool_name: tool_name.clone(),
+                    arguments: Some(tool_input.clone()),
+                    result: None,
+                },
+                tool_name.clone(),
+            )
+        };
+
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: tool_name.clone(),
+                action_type,
+                status: ToolStatus::Created,
+            },
+            content,
+            metadata: serde_json::to_value(ToolCallMetadata {
+                tool_call_id: call_id.clone(),
+            })
+            .ok(),
+        }
+    }
+}
+
+/// Serves the approval requests of one agent process
+pub struct ApprovalHook {
+    listener: HookListener,
+    /// The socket path or local address hooks connect to
+    addr: String,
+    token: String,
+    approvals: Arc<dyn ExecutorApprovalService>,
+}
+
+impl ApprovalHook {
+    /// The hook for a spawn of `agent` when `enabled` and an approval service
+    /// was handed to the executor. Otherwise a hook left registered by an
+    /// earlier run is removed, so the agent does not wait on it.
+    pub async fn for_spawn(
+        agent: HookAgent,
+        enabled: bool,
+        approvals: Option<&Arc<dyn ExecutorApprovalService>>,
+        current_dir: &Path,
+        env: &ExecutionEnv,
+    ) -> Result<Option<Self>, ExecutorError> {
+        let approvals = match approvals {
+            Some(approvals) if enabled && env.oci.is_none() => approvals.clone(),
+            _ => {
+                if enabled && env.oci.is_some() {
+                    tracing::warn!(
+                        "Tool-call approvals are not supported for agents running in a container"
+                    );
+                }
+                if let Err(e) = Self::unregister(agent, current_dir).await {
+                    tracing::warn!("Failed to remove approval hook: {e}");
+                }
+                return Ok(None);
+            }
+        };
+        Self::bind(agent, current_dir, approvals).await.map(Some)
+    }
+
+    /// Register the hook in `agent`'s project config in `current_dir` and
+    /// listen for its requests
+    pub async fn bind(
+        agent: HookAgent,
+        current_dir: &Path,
+        approvals: Arc<dyn ExecutorApprovalService>,
+    ) -> Result<Self, ExecutorError> {
+        let program = write_hook_program(agent).await?;
+        if !write_workspace_config_file(current_dir, agent.config_path(), &agent.config(&program)?)
+            .await?
+        {
+            return Err(ExecutorError::Io(std::io::Error::other(format!(
+                "Cannot register the approval hook: {} is part of the repository",
+                agent.config_path()
+            ))));
+        }
+
+        #[cfg(unix)]
+        let (listener, addr) = {
+            let id = uuid::Uuid::new_v4().simple().to_string();
+            let path = hook_dir().join(format!("{}-{}.sock", agent.name(), &id[..8]));
+            (
+                HookListener::bind(&path)?,
+                path.to_string_lossy().into_owned(),
+            )
+        };
+        #[cfg(not(unix))]
+        let (listener, addr) = {
+            let listener = HookListener::bind(("127.0.0.1", 0)).await?;
+            let addr = listener.local_addr()?.to_string();
+            (listener, addr)
+        };
+        let token = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+        Ok(Self {
+            listener,
+            addr,
+            token,
+            approvals,
+        })
+    }
+
+    /// Remove the hook registered by an earlier run
+    async fn unregister(agent: HookAgent, current_dir: &Path) -> Result<(), ExecutorError> {
+        remove_workspace_config_file(current_dir, agent.config_path()).await
+    }
+
+    /// Point the hook at this listener; hooks inherit the agent's environment
+    pub fn apply_to_command(&self, command: &mut Command) -> Result<(), ExecutorError> {
+        command
+            .env(ADDR_ENV, &self.addr)
+            .env(TOKEN_ENV, &self.token);
+        Ok(())
+    }
+
+    /// Answer the hook's requests until the agent's stdout ends, logging each
+    /// tool call to it for the normalizer
+    pub fn serve(self, child: &mut AsyncGroupChild) -> Result<(), ExecutorError> {
+        let (_, appender) = tee_stdout_with_appender(child)?;
+        let approvals = self.approvals;
+        let token = Arc::new(self.token);
+        let listener = self.listener;
+        #[cfg(unix)]
+        let socket = self.addr;
+
+        tokio::spawn(async move {
+            let cancel = CancellationToken::new();
+            loop {
+                tokio::select! {
+                    _ = appender.closed() => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => {
+                            let token = token.clone();
+                            let approvals = approvals.clone();
+                            let appender = appender.clone();
+                            let cancel = cancel.clone();
+                            tokio::spawn(async move {
+                                tokio::select! {
+                                    _ = cancel.cancelled() => {}
+                                    result = handle_request(stream, &token, approvals, &appender, cancel.clone()) => {
+                                        if let Err(e) = result {
+                                            tracing::warn!("Approval hook request failed: {e}");
+                                        }
+                                    }
+                                }
+                            });
+                        }
+                        Err(e) => {
+                            tracing::error!("Approval hook listener failed: {e}");
+                            break;
+                        }
+                    },
+                }
+            }
+            cancel.cancel();
+            #[cfg(unix)]
+            if let Err(e) = tokio::fs::remove_file(&socket).await {
+                tracing::debug!("Failed to remove approval hook socket {socket}: {e}");
+            }
+        });
+        Ok(())
+    }
+}
+
+async fn handle_request(
+    stream: impl AsyncRead + AsyncWrite,
+    token: &str,
+    approvals: Arc<dyn ExecutorApprovalService>,
+    appender: &StdoutAppender,
+    cancel: CancellationToken,
+) -> Result<(), ExecutorError> {
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut line = String::new();
+    tokio::io::BufReader::new(reader)
+        .read_line(&mut line)
+        .await?;
+    let request: HookRequest = serde_json::from_str(&line)?;
+    if request.token != token {
+        tracing::warn!("Ignoring approval hook request with a wrong token");
+        return Ok(());
+    }
+
+    let call_id = uuid::Uuid::new_v4().to_string();
+    appender.append_line(
+        ApprovalHookEvent::Request {
+            call_id: call_id.clone(),
+            tool_name: request.tool_name.clone(),
+            tool_input: request.tool_input.clone(),
+        }
+        .raw(),
+    );
+    let status = approvals
+        .request_tool_approval(&request.tool_name, request.tool_input, &call_id, cancel)
+        .await?;
+
+    let mut response = serde_json::to_string(&HookResponse { status })?;
+    response.push('\n');
+    writer.write_all(response.as_bytes()).await?;
+    Ok(())
+}
+
+/// Directory of the hook programs and the sockets they connect to
+pub(crate) fn hook_dir() -> PathBuf {
+    asset_dir().join("hooks")
+}
+
+/// Write the program the agent runs as its hook. Agents run hooks by path, so
+/// it is a script calling this binary with the hook subcommand.
+async fn write_hook_program(agent: HookAgent) -> Result<PathBuf, ExecutorError> {
+    let exe = std::env::current_exe()?;
+    let dir = hook_dir();
+    tokio::fs::create_dir_all(&dir).await?;
+
+    #[cfg(unix)]
+    let (path, script) = {
+        let exe = exe.to_string_lossy();
+        let exe =
+            shlex::try_quote(&exe).map_err(|e| ExecutorError::Io(std::io::Error::other(e)))?;
+        (
+            dir.join(format!("{HOOK_SUBCOMMAND}-{}", agent.name())),
+            format!(
+                "#!/bin/sh\nexec {exe} {HOOK_SUBCOMMAND} {} \"$@\"\n",
+                agent.name()
+            ),
+        )
+    };
+    #[cfg(windows)]
+    let (path, script) = (
+        dir.join(format!("{HOOK_SUBCOMMAND}-{}.cmd", agent.name())),
+        format!(
+            "@\"{}\" {HOOK_SUBCOMMAND} {} %*\r\n",
+            exe.display(),
+            agent.name()
+        ),
+    );
+
+    if tokio::fs::read_to_string(&path).await.ok().as_deref() != Some(script.as_str()) {
+        tokio::fs::write(&path, script).await?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            tokio::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).await?;
+        }
+    }
+    Ok(path)
+}
+
+/// Run as an agent's approval hook when the process was started as one
+/// (`<binary> approval-hook <agent>`), returning the exit code
+pub fn run_hook_from_args() -> Option<i32> {
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() != Some(HOOK_SUBCOMMAND) {
+        return None;
+    }
+    let Some(agent) = args.next().as_deref().and_then(HookAgent::from_name) else {
+        eprintln!("Usage: {HOOK_SUBCOMMAND} <cursor|droid|amp>");
+        return Some(2);
+    };
+
+    let decision = request_decision(agent)
+        .unwrap_or_else(|e| Decision::Deny(format!("Could not ask Vibe Kanban for approval: {e}")));
+    let (stdout, stderr, code) = agent.answer(&decision);
+    if let Some(stdout) = stdout {
+        println!("{stdout}");
+    }
+    if let Some(stderr) = stderr {
+        eprintln!("{stderr}");
+    }
+    Some(code)
+}
+
+fn request_decision(agent: HookAgent) -> std::io::Result<Decision> {
+    let (Ok(addr), Ok(token)) = (std::env::var(ADDR_ENV), std::env::var(TOKEN_ENV)) else {
+        return Ok(Decision::Defer);
+    };
+
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    let (tool_name, tool_input) = agent.tool_call(serde_json::from_str(&input).unwrap_or_default());
+
+    let response = send_request(
+        &addr,
+        &HookRequest {
+            token,
+            tool_name,
+            tool_input,
+        },
+    )?;
+    Ok(decision(response.status))
+}
+
+fn send_request(addr: &str, request: &HookRequest) -> std::io::Result<HookResponse> {
+    #[cfg(unix)]
+    let mut stream = std::os::unix::net::UnixStream::connect(addr)?;
+    #[cfg(not(unix))]
+    let mut stream = std::net::TcpStream::connect(addr)?;
+
+    let mut request = serde_json::to_string(request)?;
+    request.push('\n');
+    stream.write_all(request.as_bytes())?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    Ok(serde_json::from_str(&line)?)
+}
+
+fn decision(status: ApprovalStatus) -> Decision {
+    match status {
+        ApprovalStatus::Approved => Decision::Allow,
+        ApprovalStatus::Denied { reason } => Decision::Deny(
+            reason
+                .filter(|reason| !reason.trim().is_empty())
+                .unwrap_or_else(|| "The user denied this tool call".to_string()),
+        ),
+        ApprovalStatus::TimedOut => Decision::Deny("The approval request timed out".to_string()),
+        ApprovalStatus::Pending => Decision::Deny("The tool call was not approved".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approvals::NoopExecutorApprovalService;
+
+    #[test]
+    fn cursor_shell_and_mcp_calls_are_read_from_hook_input() {
+        let (name, input) = HookAgent::Cursor.tool_call(json!({
+            "hook_event_name": "beforeShellExecution",
+            "command": "rm -rf target",
+            "cwd": "/repo",
+        }));
+        assert_eq!(name, "Shell");
+        assert_eq!(input, json!({ "command": "rm -rf target" }));
+
+        let (name, input) = HookAgent::Cursor.tool_call(json!({
+            "hook_event_name": "beforeMCPExecution",
+            "tool_name": "create_issue",
+            "tool_input": "{\"title\":\"Bug\"}",
+        }));
+        assert_eq!(name, "create_issue");
+        assert_eq!(input, json!({ "title": "Bug" }));
+    }
+
+    #[test]
+    fn denials_are_answered_in_each_agents_format() {
+        let deny = decision(ApprovalStatus::Denied { reason: None });
+        assert_eq!(
+            HookAgent::Cursor.answer(&deny).0.unwrap()["permission"],
+            "deny"
+        );
+        assert_eq!(
+            HookAgent::Droid.answer(&deny).0.unwrap()["hookSpecificOutput"]["permissionDecision"],
+            "deny"
+        );
+        assert_eq!(
+            HookAgent::Amp.answer(&deny),
+            (None, Some("The user denied this tool call".to_string()), 2)
+        );
+        assert_eq!(HookAgent::Amp.answer(&Decision::Allow).2, 0);
+    }
+
+    #[test]
+    fn hook_request_becomes_pending_tool_use() {
+        let event = ApprovalHookEvent::Request {
+            call_id: "call-1".to_string(),
+            tool_name: "Edit".to_string(),
+            tool_input: json!({ "file_path": "/repo/src/lib.rs" }),
+        };
+        let entry = ApprovalHookEvent::parse(&event.raw())
+            .unwrap()
+            .to_normalized_entry("/repo");
+
+        assert_eq!(entry.content, "src/lib.rs");
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::FileEdit { .. },
+                status: ToolStatus::Created,
+                ..
+            }
+        ));
+        assert_eq!(entry.metadata.unwrap()["tool_call_id"], "call-1");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn hooks_are_answered_over_a_socket_the_sandbox_mounts() {
+        use command_group::AsyncCommandGroup;
+
+        let dir = std::env::temp_dir().join(format!("vk-approval-hook-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let hook = ApprovalHook::bind(
+            HookAgent::Droid,
+            &dir,
+            Arc::new(NoopExecutorApprovalService),
+        )
+        .await
+        .unwrap();
+        // Sandboxes without network access still see the hook directory
+        assert!(Path::new(&hook.addr).starts_with(hook_dir()));
+
+        let (addr, token) = (hook.addr.clone(), hook.token.clone());
+        let mut child = Command::new("sh")
+            .args(["-c", "sleep 10"])
+            .stdout(std::process::Stdio::piped())
+            .group_spawn()
+            .unwrap();
+        hook.serve(&mut child).unwrap();
+
+        let response = tokio::task::spawn_blocking(move || {
+            send_request(
+                &addr,
+                &HookRequest {
+                    token,
+                    tool_name: "Execute".to_string(),
+                    tool_input: json!({ "command": "ls" }),
+                },
+            )
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(decision(response.status), Decision::Allow);
+
+        child.kill().await.ok();
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
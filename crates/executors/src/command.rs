@@ -6,7 +6,10 @@ use thiserror::Error;
 use ts_rs::TS;
 use workspace_utils::shell::resolve_executable_path;
 
-use crate::executors::ExecutorError;
+use crate::{
+    env::ExecutionEnv, executors::ExecutorError, resource_limits::ResourceLimits,
+    sandbox::ProcessSandbox,
+};
 
 #[derive(Debug, Error)]
 pub enum CommandBuildError {
@@ -24,19 +27,52 @@ pub enum CommandBuildError {
 pub struct CommandParts {
     program: String,
     args: Vec<String>,
+    sandbox: Option<ProcessSandbox>,
+    resource_limits: Option<ResourceLimits>,
 }
 
 impl CommandParts {
     pub fn new(program: String, args: Vec<String>) -> Self {
-        Self { program, args }
+        Self {
+            program,
+            args,
+            sandbox: None,
+            resource_limits: None,
+        }
     }
 
-    pub async fn into_resolved(self) -> Result<(PathBuf, Vec<String>), ExecutorError> {
-        let CommandParts { program, args } = self;
+    /// Resolve the program path, wrapping it in the profile's resource limits and
+    /// sandbox if configured.
+    ///
+    /// Inside a workspace container the program is resolved by the container instead.
+    pub async fn into_resolved(
+        self,
+        env: &ExecutionEnv,
+    ) -> Result<(PathBuf, Vec<String>), ExecutorError> {
+        let CommandParts {
+            program,
+            args,
+            sandbox,
+            resource_limits,
+        } = self;
+        if let Some(oci) = &env.oci {
+            if sandbox.is_some() {
+                tracing::debug!("Skipping process sandbox inside the workspace container");
+            }
+            return Ok(oci.wrap(program, args));
+        }
         let executable = resolve_executable_path(&program)
             .await
             .ok_or(ExecutorError::ExecutableNotFound { program })?;
-        Ok((executable, args))
+        let (executable, args) = match sandbox {
+            Some(sandbox) => sandbox.wrap(executable, args, &env.repo_context).await?,
+            None => (executable, args),
+        };
+        // Outermost, so the limits also cover the sandbox helper
+        match resource_limits {
+            Some(limits) => Ok(limits.wrap(executable, args).await),
+            None => Ok((executable, args)),
+        }
     }
 
     #[cfg(test)]
@@ -65,6 +101,30 @@ pub struct CmdOverrides {
     )]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub env: Option<HashMap<String, String>>,
+    #[schemars(
+        title = "Process Sandbox",
+        description = "Restrict filesystem writes to the workspace and optionally limit network access (Linux, requires bubblewrap)"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_sandbox: Option<ProcessSandbox>,
+    #[schemars(
+        title = "Resource Limits",
+        description = "Memory and CPU limits for the agent process and everything it spawns"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_limits: Option<ResourceLimits>,
+    #[schemars(
+        title = "Co-authors",
+        description = "`Name <email>` entries added as Co-authored-by trailers to the commits of this profile's turns"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub co_authors: Option<Vec<String>>,
+    #[schemars(
+        title = "Fallback Profiles",
+        description = "Profiles to continue with, in order, when a run of this profile hits a rate limit or fails to authenticate, as `EXECUTOR` or `EXECUTOR:VARIANT`, e.g. `CODEX:HIGH`"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_profiles: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
@@ -73,6 +133,16 @@ pub struct CommandBuilder {
     pub base: String,
     /// Optional parameters to append to the base command
     pub params: Option<Vec<String>>,
+    /// Sandbox applied when the built command is resolved
+    #[serde(skip)]
+    #[ts(skip)]
+    #[schemars(skip)]
+    pub sandbox: Option<ProcessSandbox>,
+    /// Resource limits applied when the built command is resolved
+    #[serde(skip)]
+    #[ts(skip)]
+    #[schemars(skip)]
+    pub resource_limits: Option<ResourceLimits>,
 }
 
 impl CommandBuilder {
@@ -80,6 +150,8 @@ impl CommandBuilder {
         Self {
             base: base.into(),
             params: None,
+            sandbox: None,
+            resource_limits: None,
         }
     }
 
@@ -160,7 +232,11 @@ impl CommandBuilder {
         }
 
         let program = parts.remove(0);
-        Ok(CommandParts::new(program, parts))
+        Ok(CommandParts {
+            sandbox: self.sandbox.clone(),
+            resource_limits: self.resource_limits,
+            ..CommandParts::new(program, parts)
+        })
     }
 }
 
@@ -185,11 +261,18 @@ pub fn apply_overrides(
     builder: CommandBuilder,
     overrides: &CmdOverrides,
 ) -> Result<CommandBuilder, CommandBuildError> {
-    let builder = if let Some(ref base) = overrides.base_command_override {
+    let mut builder = if let Some(ref base) = overrides.base_command_override {
         builder.override_base(base.clone())
     } else {
         builder
     };
+    builder.sandbox = overrides
+        .process_sandbox
+        .clone()
+        .filter(|sandbox| sandbox.enabled);
+    builder.resource_limits = overrides
+        .resource_limits
+        .filter(|limits| !limits.is_empty());
     if let Some(ref extra) = overrides.additional_params {
         builder.extend_shell_params(extra.clone())
     } else {
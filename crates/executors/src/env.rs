@@ -1,9 +1,13 @@
 use std::{collections::HashMap, path::PathBuf};
 
 use git::GitService;
+use serde_json::Value;
 use tokio::process::Command;
 
-use crate::command::CmdOverrides;
+use crate::{
+    command::CmdOverrides,
+    oci::{OCI_ENV_KEYS, OciExec},
+};
 
 /// Repository context for executor operations
 #[derive(Debug, Clone, Default)]
@@ -81,6 +85,14 @@ pub struct ExecutionEnv {
     pub repo_context: RepoContext,
     pub commit_reminder: bool,
     pub commit_reminder_prompt: String,
+    /// Workspace container that commands are executed in, if enabled
+    pub oci: Option<OciExec>,
+    /// MCP servers configured for the project and workspace, keyed by name in
+    /// the canonical (Claude Code) format
+    pub mcp_servers: HashMap<String, Value>,
+    /// Project and task instructions to render into the agent's instructions
+    /// file
+    pub agent_instructions: Option<String>,
 }
 
 impl ExecutionEnv {
@@ -94,6 +106,9 @@ impl ExecutionEnv {
             repo_context,
             commit_reminder,
             commit_reminder_prompt,
+            oci: None,
+            mcp_servers: HashMap::new(),
+            agent_instructions: None,
         }
     }
 
@@ -128,6 +143,11 @@ impl ExecutionEnv {
         for (key, value) in &self.vars {
             command.env(key, value);
         }
+        if self.oci.is_some() {
+            let mut keys: Vec<&str> = self.vars.keys().map(String::as_str).collect();
+            keys.sort();
+            command.env(OCI_ENV_KEYS, keys.join(" "));
+        }
     }
 
     pub fn contains_key(&self, key: &str) -> bool {
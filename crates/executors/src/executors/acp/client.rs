@@ -40,6 +40,10 @@ impl AcpClient {
         self.send_event(AcpEvent::User(prompt.to_string()));
     }
 
+    pub fn record_steer_event(&self, prompt: &str) {
+        self.send_event(AcpEvent::Steer(prompt.to_string()));
+    }
+
     /// Send an event to the event channel
     fn send_event(&self, event: AcpEvent) {
         if let Err(e) = self.event_tx.send(event) {
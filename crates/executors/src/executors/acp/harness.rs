@@ -9,6 +9,7 @@ use agent_client_protocol as proto;
 use agent_client_protocol::Agent as _;
 use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use futures::StreamExt;
+use serde_json::{Map, Value, json};
 use tokio::{io::AsyncWriteExt, process::Command, sync::mpsc};
 use tokio_util::{
     compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt},
@@ -24,8 +25,68 @@ use crate::{
     command::{CmdOverrides, CommandParts},
     env::ExecutionEnv,
     executors::{ExecutorError, ExecutorExitResult, SpawnedChild, acp::AcpEvent},
+    mcp_config::{Adapter, workspace_mcp_servers},
+    steering::{SteerReceiver, steer_channel},
 };
 
+/// The workspace MCP servers of `env` as ACP session servers. Servers the
+/// protocol cannot express are skipped.
+fn acp_mcp_servers(env: &ExecutionEnv) -> Vec<proto::McpServer> {
+    let Some(Value::Object(servers)) = workspace_mcp_servers(env, Adapter::Passthrough) else {
+        return Vec::new();
+    };
+
+    servers
+        .into_iter()
+        .filter_map(|(name, server)| {
+            let server = acp_mcp_server_json(&name, server.as_object()?)?;
+            serde_json::from_value(server)
+                .inspect_err(|e| tracing::warn!("Skipping MCP server '{}': {}", name, e))
+                .ok()
+        })
+        .collect()
+}
+
+/// ACP's JSON shape of a server in the canonical format: name/value lists
+/// instead of maps for `env` and `headers`
+fn acp_mcp_server_json(name: &str, server: &Map<String, Value>) -> Option<Value> {
+    let pairs = |key: &str| -> Vec<Value> {
+        server
+            .get(key)
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .map(|(name, value)| {
+                let value = value
+                    .as_str()
+                    .map_or_else(|| value.to_string(), str::to_string);
+                json!({ "name": name, "value": value })
+            })
+            .collect()
+    };
+
+    if let Some(url) = server.get("url").and_then(Value::as_str) {
+        let transport = match server.get("type").and_then(Value::as_str) {
+            Some("sse") => "sse",
+            _ => "http",
+        };
+        return Some(json!({
+            "type": transport,
+            "name": name,
+            "url": url,
+            "headers": pairs("headers"),
+        }));
+    }
+
+    let command = server.get("command").and_then(Value::as_str)?;
+    Some(json!({
+        "name": name,
+        "command": command,
+        "args": server.get("args").cloned().unwrap_or_else(|| json!([])),
+        "env": pairs("env"),
+    }))
+}
+
 /// Reusable harness for ACP-based conns (Gemini, Qwen, etc.)
 pub struct AcpAgentHarness {
     session_namespace: String,
@@ -78,7 +139,7 @@ impl AcpAgentHarness {
         cmd_overrides: &CmdOverrides,
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = command_parts.into_resolved(env).await?;
         let mut command = Command::new(program_path);
         command
             .kill_on_drop(true)
@@ -98,10 +159,12 @@ impl AcpAgentHarness {
 
         let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<ExecutorExitResult>();
         let cancel = CancellationToken::new();
+        let (steer, steer_rx) = steer_channel();
 
         Self::bootstrap_acp_connection(
             &mut child,
             current_dir.to_path_buf(),
+            acp_mcp_servers(env),
             None,
             prompt,
             Some(exit_tx),
@@ -109,6 +172,7 @@ impl AcpAgentHarness {
             self.model.clone(),
             self.mode.clone(),
             approvals,
+            steer_rx,
             cancel.clone(),
         )
         .await?;
@@ -117,6 +181,7 @@ impl AcpAgentHarness {
             child,
             exit_signal: Some(exit_rx),
             cancel: Some(cancel),
+            steer: Some(steer),
         })
     }
 
@@ -131,7 +196,7 @@ impl AcpAgentHarness {
         cmd_overrides: &CmdOverrides,
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = command_parts.into_resolved(env).await?;
         let mut command = Command::new(program_path);
         command
             .kill_on_drop(true)
@@ -151,10 +216,12 @@ impl AcpAgentHarness {
 
         let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<ExecutorExitResult>();
         let cancel = CancellationToken::new();
+        let (steer, steer_rx) = steer_channel();
 
         Self::bootstrap_acp_connection(
             &mut child,
             current_dir.to_path_buf(),
+            acp_mcp_servers(env),
             Some(session_id.to_string()),
             prompt,
             Some(exit_tx),
@@ -162,6 +229,7 @@ impl AcpAgentHarness {
             self.model.clone(),
             self.mode.clone(),
             approvals,
+            steer_rx,
             cancel.clone(),
         )
         .await?;
@@ -170,6 +238,7 @@ impl AcpAgentHarness {
             child,
             exit_signal: Some(exit_rx),
             cancel: Some(cancel),
+            steer: Some(steer),
         })
     }
 
@@ -177,6 +246,7 @@ impl AcpAgentHarness {
     async fn bootstrap_acp_connection(
         child: &mut AsyncGroupChild,
         cwd: PathBuf,
+        mcp_servers: Vec<proto::McpServer>,
         existing_session: Option<String>,
         prompt: String,
         exit_signal: Option<tokio::sync::oneshot::Sender<ExecutorExitResult>>,
@@ -184,6 +254,7 @@ impl AcpAgentHarness {
         model: Option<String>,
         mode: Option<String>,
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
+        mut steer: SteerReceiver,
         cancel: CancellationToken,
     ) -> Result<(), ExecutorError> {
         // Take child's stdio for ACP wiring
@@ -303,6 +374,7 @@ impl AcpAgentHarness {
                         let client =
                             AcpClient::new(event_tx.clone(), approvals.clone(), cancel.clone());
                         let client_feedback_handle = client.clone();
+                        let client_steer_handle = client.clone();
 
                         client.record_user_prompt_event(&prompt);
 
@@ -335,6 +407,7 @@ impl AcpAgentHarness {
                                     history.map(|h| serde_json::json!({ "history_jsonl": h }));
 
                                 let mut req = proto::NewSessionRequest::new(cwd.clone());
+                                req.mcp_servers = mcp_servers;
                                 if let Some(m) = meta
                                     && let Some(obj) = m.as_object()
                                 {
@@ -354,10 +427,9 @@ impl AcpAgentHarness {
                                 }
                             } else {
                                 // New session
-                                match conn
-                                    .new_session(proto::NewSessionRequest::new(cwd.clone()))
-                                    .await
-                                {
+                                let mut req = proto::NewSessionRequest::new(cwd.clone());
+                                req.mcp_servers = mcp_servers;
+                                match conn.new_session(req).await {
                                     Ok(resp) => {
                                         let sid = resp.session_id.0.to_string();
                                         (sid.clone(), sid, prompt)
@@ -430,6 +502,24 @@ impl AcpAgentHarness {
                             }
                         });
 
+                        // Queue messages sent during a prompt to follow it, cancelling
+                        // the prompt first when asked to interrupt
+                        let conn_for_steer = conn.clone();
+                        let acp_session_id_for_steer = acp_session_id.clone();
+                        tokio::task::spawn_local(async move {
+                            while let Some(request) = steer.recv().await {
+                                client_steer_handle.record_steer_event(&request.prompt);
+                                client_steer_handle.enqueue_feedback(request.prompt).await;
+                                if request.interrupt {
+                                    let _ = conn_for_steer
+                                        .cancel(proto::CancelNotification::new(
+                                            proto::SessionId::new(acp_session_id_for_steer.clone()),
+                                        ))
+                                        .await;
+                                }
+                            }
+                        });
+
                         // Save prompt to session
                         let _ = session_manager.append_raw_line(
                             &display_session_id,
@@ -16,6 +16,8 @@ use workspace_utils::approvals::ApprovalStatus;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AcpEvent {
     User(String),
+    /// A message the user sent while a prompt was running
+    Steer(String),
     SessionStart(String),
     Message(agent_client_protocol::ContentBlock),
     Thought(agent_client_protocol::ContentBlock),
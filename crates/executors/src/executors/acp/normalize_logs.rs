@@ -18,14 +18,18 @@ use crate::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
         TodoItem, ToolResult, ToolResultValueType, ToolStatus as LogToolStatus,
         stderr_processor::normalize_stderr_logs,
+        token_usage::estimate_context_usage,
         utils::{ConversationPatch, EntryIndexProvider},
     },
 };
 
-pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
+/// `model_context_window` is the window of the agent's default model, used to
+/// report context usage: ACP does not report token counts, so it is estimated
+pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path, model_context_window: u32) {
     // stderr normalization
     let entry_index = EntryIndexProvider::start_from(&msg_store);
     normalize_stderr_logs(msg_store.clone(), entry_index.clone());
+    estimate_context_usage(msg_store.clone(), entry_index.clone(), model_context_window);
 
     // stdout normalization (main loop)
     let worktree_path = worktree_path.to_path_buf();
@@ -53,7 +57,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                         let entry = NormalizedEntry {
                             timestamp: None,
                             entry_type: NormalizedEntryType::ErrorMessage {
-                                error_type: NormalizedEntryError::Other,
+                                error_type: NormalizedEntryError::classify(&msg),
                             },
                             content: msg,
                             metadata: None,
@@ -252,6 +256,18 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                                 .push_patch(ConversationPatch::add_normalized_entry(idx, entry));
                         }
                     }
+                    AcpEvent::Steer(prompt) => {
+                        streaming.assistant_text = None;
+                        streaming.thinking_text = None;
+                        let idx = entry_index.next();
+                        let entry = NormalizedEntry {
+                            timestamp: None,
+                            entry_type: NormalizedEntryType::UserMessage,
+                            content: prompt,
+                            metadata: None,
+                        };
+                        msg_store.push_patch(ConversationPatch::add_normalized_entry(idx, entry));
+                    }
                     AcpEvent::User(_) | AcpEvent::Other(_) => (),
                 }
             }
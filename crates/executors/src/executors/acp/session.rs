@@ -77,6 +77,7 @@ impl SessionManager {
             | AcpEvent::Other(..) => return None,
 
             AcpEvent::User(..)
+            | AcpEvent::Steer(..)
             | AcpEvent::Message(..)
             | AcpEvent::Thought(..)
             | AcpEvent::ToolCall(..)
@@ -90,7 +91,7 @@ impl SessionManager {
         }
 
         match event {
-            AcpEvent::User(prompt) => {
+            AcpEvent::User(prompt) | AcpEvent::Steer(prompt) => {
                 return serde_json::to_string(&serde_json::json!({"user": prompt})).ok();
             }
             AcpEvent::Message(ref content) | AcpEvent::Thought(ref content) => {
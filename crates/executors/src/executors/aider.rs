@@ -0,0 +1,306 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use command_group::AsyncCommandGroup;
+use git::GitService;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use ts_rs::TS;
+use uuid::Uuid;
+use workspace_utils::{assets::asset_dir, msg_store::MsgStore};
+
+use crate::{
+    command::{CommandBuildError, CommandBuilder, CommandParts},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+    },
+    logs::utils::EntryIndexProvider,
+    stdout_dup::tee_stdout_with_appender,
+};
+
+pub mod normalize_logs;
+
+use normalize_logs::{SESSION_MARKER, normalize_logs};
+
+/// Instructions file rendered by vibe-kanban, passed to aider as read-only
+/// context since aider does not pick up instruction files on its own
+const CONVENTIONS_FILE: &str = "CONVENTIONS.md";
+
+/// Aider executor configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct Aider {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Model",
+        description = "Model to use (e.g., sonnet, gpt-5, deepseek, openrouter/anthropic/claude-sonnet-4.5)"
+    )]
+    pub model: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Edit Format",
+        description = "Edit format the model uses: diff, diff-fenced, udiff or whole. Defaults to the model's preferred format"
+    )]
+    pub edit_format: Option<String>,
+
+    #[serde(flatten)]
+    pub cmd: crate::command::CmdOverrides,
+}
+
+impl Aider {
+    pub fn build_command_builder(&self) -> Result<CommandBuilder, CommandBuildError> {
+        use crate::command::{CommandBuilder, apply_overrides};
+
+        // Answer every confirmation, never commit (vibe-kanban commits the
+        // workspace itself) and keep aider's own files out of the repository
+        let mut builder = CommandBuilder::new("aider").params([
+            "--no-pretty",
+            "--no-fancy-input",
+            "--yes-always",
+            "--no-auto-commits",
+            "--no-dirty-commits",
+            "--no-gitignore",
+            "--no-check-update",
+            "--no-show-release-notes",
+            "--no-show-model-warnings",
+            "--no-analytics",
+        ]);
+
+        if let Some(model) = &self.model {
+            builder = builder.extend_params(["--model", model.as_str()]);
+        }
+
+        if let Some(edit_format) = &self.edit_format {
+            builder = builder.extend_params(["--edit-format", edit_format.as_str()]);
+        }
+
+        apply_overrides(builder, &self.cmd)
+    }
+
+    /// Arguments tying the run to a session's chat history and passing the
+    /// prompt
+    fn session_args(
+        current_dir: &Path,
+        session_id: &str,
+        restore: bool,
+        in_git_repo: bool,
+        prompt: &str,
+    ) -> Vec<String> {
+        let history = session_history_path(session_id);
+        let mut args = vec![
+            "--chat-history-file".to_string(),
+            history.to_string_lossy().to_string(),
+            "--input-history-file".to_string(),
+            history
+                .with_extension("input")
+                .to_string_lossy()
+                .to_string(),
+        ];
+        if restore {
+            args.push("--restore-chat-history".to_string());
+        }
+        if !in_git_repo {
+            // Otherwise aider offers to create a repository, which
+            // --yes-always accepts
+            args.push("--no-git".to_string());
+        }
+        if current_dir.join(CONVENTIONS_FILE).is_file() {
+            args.extend(["--read".to_string(), CONVENTIONS_FILE.to_string()]);
+        }
+        args.extend(["--message".to_string(), prompt.to_string()]);
+        args
+    }
+
+    async fn spawn_session(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        restore: bool,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let in_git_repo = {
+            let current_dir = current_dir.to_path_buf();
+            tokio::task::spawn_blocking(move || {
+                let git = GitService::new();
+                let in_git_repo = git.is_in_worktree(&current_dir);
+                if in_git_repo && let Err(e) = git.exclude_path(&current_dir.join(TAGS_CACHE_DIR)) {
+                    tracing::warn!("Failed to exclude aider's tags cache from git: {}", e);
+                }
+                in_git_repo
+            })
+            .await
+            .unwrap_or(false)
+        };
+
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        let command_parts = self
+            .build_command_builder()?
+            .build_follow_up(&Self::session_args(
+                current_dir,
+                session_id,
+                restore,
+                in_git_repo,
+                &combined_prompt,
+            ))?;
+
+        spawn_aider(command_parts, session_id, current_dir, env, &self.cmd).await
+    }
+}
+
+/// Repository map cache aider creates in the repository root
+const TAGS_CACHE_DIR: &str = ".aider.tags.cache.v4";
+
+fn sessions_dir() -> PathBuf {
+    asset_dir().join("aider").join("sessions")
+}
+
+/// Aider keeps a conversation in a markdown chat history file, which
+/// `--restore-chat-history` loads back into a new run
+fn session_history_path(session_id: &str) -> PathBuf {
+    sessions_dir().join(format!("{session_id}.md"))
+}
+
+/// Copy a session's chat history into a new session, so a follow-up never
+/// changes the history of the run it continues
+async fn fork_session(session_id: &str) -> Result<String, ExecutorError> {
+    let source = session_history_path(session_id);
+    if !tokio::fs::try_exists(&source).await.unwrap_or(false) {
+        return Err(ExecutorError::FollowUpNotSupported(format!(
+            "Aider chat history for session {session_id} not found"
+        )));
+    }
+    let forked = Uuid::new_v4().to_string();
+    tokio::fs::copy(&source, session_history_path(&forked))
+        .await
+        .map_err(ExecutorError::Io)?;
+    Ok(forked)
+}
+
+async fn spawn_aider(
+    command_parts: CommandParts,
+    session_id: &str,
+    current_dir: &Path,
+    env: &ExecutionEnv,
+    cmd_overrides: &crate::command::CmdOverrides,
+) -> Result<SpawnedChild, ExecutorError> {
+    tokio::fs::create_dir_all(sessions_dir())
+        .await
+        .map_err(ExecutorError::Io)?;
+
+    let (program_path, args) = command_parts.into_resolved(env).await?;
+
+    let mut command = Command::new(program_path);
+    command
+        .kill_on_drop(true)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .current_dir(current_dir)
+        .env("PYTHONUNBUFFERED", "1")
+        .args(args);
+
+    env.clone()
+        .with_profile(cmd_overrides)
+        .apply_to_command(&mut command);
+
+    let mut child = command.group_spawn()?;
+
+    // Aider never reports a session, so announce the one its chat history
+    // belongs to for log normalization to pick up
+    let (_, appender) = tee_stdout_with_appender(&mut child)?;
+    appender.append_line(format!("{SESSION_MARKER}{session_id}"));
+
+    Ok(child.into())
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for Aider {
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let session_id = Uuid::new_v4().to_string();
+        self.spawn_session(current_dir, prompt, &session_id, false, env)
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        _reset_to_message_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let session_id = fork_session(session_id).await?;
+        self.spawn_session(current_dir, prompt, &session_id, true, env)
+            .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, current_dir: &Path) {
+        normalize_logs(
+            msg_store.clone(),
+            current_dir,
+            EntryIndexProvider::start_from(&msg_store),
+        );
+    }
+
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        // Aider has no MCP support
+        None
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        // Aider keeps its caches and analytics settings in ~/.aider
+        let aider_dir_found = dirs::home_dir()
+            .map(|home| home.join(".aider").exists())
+            .unwrap_or(false);
+
+        if aider_dir_found {
+            AvailabilityInfo::InstallationFound
+        } else {
+            AvailabilityInfo::NotFound
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::CmdOverrides;
+
+    #[test]
+    fn aider_session_args_follow_configured_params() {
+        let aider = Aider {
+            append_prompt: AppendPrompt::default(),
+            model: Some("sonnet".to_string()),
+            edit_format: Some("diff".to_string()),
+            cmd: CmdOverrides::default(),
+        };
+
+        let builder = aider.build_command_builder().unwrap();
+        let (_, initial_args) = builder.build_initial().unwrap().into_parts();
+        let session_args =
+            Aider::session_args(Path::new("/nonexistent"), "abc", true, false, "Fix it");
+        let (program, args) = builder.build_follow_up(&session_args).unwrap().into_parts();
+
+        assert_eq!(program, "aider");
+        assert!(args.starts_with(&initial_args));
+        assert!(initial_args.windows(2).any(|w| w == ["--model", "sonnet"]));
+        assert!(args.contains(&"--restore-chat-history".to_string()));
+        assert!(args.contains(&"--no-git".to_string()));
+        assert!(args.ends_with(&["--message".to_string(), "Fix it".to_string()]));
+    }
+}
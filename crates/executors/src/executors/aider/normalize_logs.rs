@@ -0,0 +1,462 @@
+//! Normalization of aider's plain-text output.
+//!
+//! With `--no-pretty` aider prints its startup banner and status lines, the
+//! streamed assistant reply with SEARCH/REPLACE edit blocks inline, and lines
+//! for shell commands it runs. [`message_boundary`] splits the stream into
+//! those parts so each becomes its own entry.
+
+use std::{path::Path, sync::Arc};
+
+use futures::StreamExt;
+use workspace_utils::{diff::create_unified_diff, msg_store::MsgStore, path::make_path_relative};
+
+use crate::logs::{
+    ActionType, CommandRunResult, FileChange, NormalizedEntry, NormalizedEntryError,
+    NormalizedEntryType, ToolStatus,
+    plain_text_processor::{MessageBoundary, PlainTextLogProcessor},
+    stderr_processor::normalize_stderr_logs,
+    token_usage::{DEFAULT_CONTEXT_WINDOW, token_usage_entry},
+    utils::EntryIndexProvider,
+};
+
+/// Line vibe-kanban writes ahead of aider's output to record the session
+pub const SESSION_MARKER: &str = "[vibe-kanban] aider session: ";
+
+const SEARCH_MARKER: &str = "<<<<<<< SEARCH";
+const DIVIDER_MARKER: &str = "=======";
+const REPLACE_MARKER: &str = ">>>>>>> REPLACE";
+const COMMAND_PREFIX: &str = "Running ";
+/// Prefix of the usage line aider prints after each reply, e.g.
+/// `Tokens: 2.1k sent, 120 received. Cost: $0.01 message, $0.03 session.`
+const USAGE_PREFIX: &str = "Tokens:";
+
+/// Prefixes of aider's own status lines
+const SYSTEM_PREFIXES: &[&str] = &[
+    "Aider v",
+    "Main model:",
+    "Weak model:",
+    "Editor model:",
+    "Model:",
+    "Git repo:",
+    "Repo-map:",
+    "Restored previous conversation history",
+    "Cost:",
+    "Applied edit to ",
+    "Use /help",
+    "https://aider.chat/",
+    "Summarizing",
+];
+
+/// Prefixes of aider's error lines
+const ERROR_PREFIXES: &[&str] = &[
+    "Did not apply edit to ",
+    "The LLM did not conform to the edit format",
+    "litellm.",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    System,
+    Usage,
+    Error,
+    Command,
+    Text,
+}
+
+fn classify_line(line: &str) -> LineKind {
+    let line = line.trim_end();
+    if line.starts_with(USAGE_PREFIX) {
+        LineKind::Usage
+    } else if SYSTEM_PREFIXES.iter().any(|p| line.starts_with(p))
+        || (line.starts_with("Added ") && line.ends_with(" to the chat."))
+        || is_confirmation(line)
+    {
+        LineKind::System
+    } else if ERROR_PREFIXES.iter().any(|p| line.starts_with(p))
+        || (line.starts_with("# ") && line.contains("SEARCH/REPLACE block"))
+    {
+        LineKind::Error
+    } else if line.starts_with(COMMAND_PREFIX) && line.len() > COMMAND_PREFIX.len() {
+        LineKind::Command
+    } else {
+        LineKind::Text
+    }
+}
+
+/// A confirmation aider answered itself, e.g.
+/// `Add file to the chat? (Y)es/(N)o/(D)on't ask again [Yes]: y`
+fn is_confirmation(line: &str) -> bool {
+    line.contains("(Y)es/(N)o") && (line.ends_with(": y") || line.ends_with(": n"))
+}
+
+fn is_fence(line: &str) -> bool {
+    line.trim_start().starts_with("```")
+}
+
+fn is_complete(line: &str) -> bool {
+    line.ends_with('\n')
+}
+
+/// Index of the first line of the edit block whose SEARCH marker is at
+/// `search`. Aider puts the file name and the opening fence before the marker,
+/// in either order depending on the edit format.
+fn edit_block_start(lines: &[String], search: usize) -> Option<usize> {
+    let start = search.checked_sub(2)?;
+    let (first, second) = (&lines[start], &lines[start + 1]);
+    let named = |line: &str| !line.trim().is_empty() && !is_fence(line);
+    ((is_fence(first) && named(second)) || (named(first) && is_fence(second))).then_some(start)
+}
+
+/// Start of the first edit block in `lines`
+fn find_edit_block(lines: &[String]) -> Option<usize> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.trim_end() == SEARCH_MARKER)
+        .find_map(|(idx, _)| edit_block_start(lines, idx))
+}
+
+/// Number of lines of the edit block at the start of `lines`, if it is complete
+fn edit_block_len(lines: &[String]) -> Option<usize> {
+    let replace = lines
+        .iter()
+        .position(|line| line.trim_end() == REPLACE_MARKER && is_complete(line))?;
+    match lines.get(replace + 1) {
+        Some(line) if is_fence(line) && is_complete(line) => Some(replace + 2),
+        Some(line) if !is_fence(line) => Some(replace + 1),
+        // Wait for the closing fence
+        _ => None,
+    }
+}
+
+/// Where the entry at the start of `lines` ends
+pub fn message_boundary(lines: &[String]) -> Option<MessageBoundary> {
+    let first = lines.first()?;
+
+    if find_edit_block(lines) == Some(0) {
+        return match edit_block_len(lines) {
+            Some(len) => Some(MessageBoundary::Split(len)),
+            None => Some(MessageBoundary::IncompleteContent),
+        };
+    }
+
+    let kind = classify_line(first);
+    // Let status lines arrive whole, so they are not shown as assistant text
+    if !is_complete(first) && kind != LineKind::Text {
+        return Some(MessageBoundary::IncompleteContent);
+    }
+
+    let edit_start = find_edit_block(lines);
+    let end = lines.iter().enumerate().skip(1).find_map(|(idx, line)| {
+        if edit_start == Some(idx) {
+            return Some(idx);
+        }
+        let line_kind = classify_line(line);
+        let ends = match kind {
+            // A command's output runs until the next blank line
+            LineKind::Command => line.trim().is_empty() || line_kind != LineKind::Text,
+            LineKind::System | LineKind::Error => line_kind != kind,
+            LineKind::Usage => true,
+            LineKind::Text => line_kind != LineKind::Text,
+        };
+        ends.then_some(idx)
+    });
+
+    end.map(MessageBoundary::Split)
+}
+
+struct SearchReplace {
+    path: String,
+    search: String,
+    replace: String,
+}
+
+fn parse_edit_block(content: &str) -> Option<SearchReplace> {
+    let lines: Vec<&str> = content.lines().collect();
+    let search = lines.iter().position(|l| l.trim_end() == SEARCH_MARKER)?;
+    let divider = search
+        + lines[search..]
+            .iter()
+            .position(|l| l.trim_end() == DIVIDER_MARKER)?;
+    let replace = divider
+        + lines[divider..]
+            .iter()
+            .position(|l| l.trim_end() == REPLACE_MARKER)?;
+    let path = lines[..search]
+        .iter()
+        .rev()
+        .find(|l| !l.trim().is_empty() && !is_fence(l))?
+        .trim()
+        .to_string();
+
+    let join = |lines: &[&str]| {
+        let mut text = lines.join("\n");
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text
+    };
+    Some(SearchReplace {
+        path,
+        search: join(&lines[search + 1..divider]),
+        replace: join(&lines[divider + 1..replace]),
+    })
+}
+
+fn edit_entry(content: String, worktree_path: &str) -> Option<NormalizedEntry> {
+    let edit = parse_edit_block(&content)?;
+    let path = make_path_relative(&edit.path, worktree_path);
+    let change = if edit.search.is_empty() {
+        // An empty SEARCH section creates the file
+        FileChange::Write {
+            content: edit.replace,
+        }
+    } else {
+        FileChange::Edit {
+            unified_diff: create_unified_diff(&path, &edit.search, &edit.replace),
+            has_line_numbers: false,
+        }
+    };
+    Some(NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::ToolUse {
+            tool_name: "edit".to_string(),
+            action_type: ActionType::FileEdit {
+                path: path.clone(),
+                changes: vec![change],
+            },
+            status: ToolStatus::Success,
+        },
+        content: path,
+        metadata: None,
+    })
+}
+
+fn command_entry(content: String) -> NormalizedEntry {
+    let (command, output) = content.split_once('\n').unwrap_or((&content, ""));
+    let command = command
+        .trim_end()
+        .strip_prefix(COMMAND_PREFIX)
+        .unwrap_or(command)
+        .trim_start_matches(':')
+        .trim()
+        .to_string();
+    let output = output.trim_end();
+    NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::ToolUse {
+            tool_name: "bash".to_string(),
+            action_type: ActionType::CommandRun {
+                command: command.clone(),
+                result: (!output.is_empty()).then(|| CommandRunResult {
+                    exit_status: None,
+                    output: Some(output.to_string()),
+                }),
+            },
+            status: ToolStatus::Success,
+        },
+        content: command,
+        metadata: None,
+    }
+}
+
+/// Token count as aider formats it: `850`, `2.1k`, `12k` or `1.2M`
+fn parse_token_count(count: &str) -> Option<u32> {
+    let (number, scale) = match count.as_bytes().last()? {
+        b'k' => (&count[..count.len() - 1], 1e3),
+        b'M' => (&count[..count.len() - 1], 1e6),
+        _ => (count, 1.0),
+    };
+    let tokens = number.parse::<f64>().ok()? * scale;
+    (tokens >= 0.0).then(|| tokens.round() as u32)
+}
+
+/// Context used by the reply a usage line reports: the tokens sent with the
+/// request plus the tokens received
+fn usage_entry(line: &str) -> Option<NormalizedEntry> {
+    // The cost report follows the first full stop
+    let report = line.strip_prefix(USAGE_PREFIX)?.split(". ").next()?;
+    let mut total = 0u32;
+    for part in report.trim_end_matches('.').split(',') {
+        let (count, kind) = part.trim().split_once(' ')?;
+        if matches!(kind, "sent" | "received") {
+            total = total.saturating_add(parse_token_count(count)?);
+        }
+    }
+    (total > 0).then(|| token_usage_entry(total, DEFAULT_CONTEXT_WINDOW))
+}
+
+fn normalize_entry(content: String, worktree_path: &str) -> NormalizedEntry {
+    let content = strip_ansi_escapes::strip_str(&content);
+    if content.contains(SEARCH_MARKER)
+        && let Some(entry) = edit_entry(content.clone(), worktree_path)
+    {
+        return entry;
+    }
+
+    let entry_type = match content.lines().next().map(classify_line) {
+        Some(LineKind::Usage) => match usage_entry(content.trim()) {
+            Some(entry) => return entry,
+            None => NormalizedEntryType::SystemMessage,
+        },
+        Some(LineKind::System) => NormalizedEntryType::SystemMessage,
+        Some(LineKind::Error) => NormalizedEntryType::ErrorMessage {
+            error_type: NormalizedEntryError::Other,
+        },
+        Some(LineKind::Command) => return command_entry(content),
+        _ => NormalizedEntryType::AssistantMessage,
+    };
+    NormalizedEntry {
+        timestamp: None,
+        entry_type,
+        content: content.trim().to_string(),
+        metadata: None,
+    }
+}
+
+pub fn normalize_logs(
+    msg_store: Arc<MsgStore>,
+    worktree_path: &Path,
+    entry_index_provider: EntryIndexProvider,
+) {
+    normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
+
+    let worktree_path = worktree_path.to_string_lossy().to_string();
+    tokio::spawn(async move {
+        let mut stdout = msg_store.stdout_chunked_stream();
+
+        let session_store = msg_store.clone();
+        let mut processor = PlainTextLogProcessor::builder()
+            .normalized_entry_producer(move |content: String| {
+                normalize_entry(content, &worktree_path)
+            })
+            .transform_lines(Box::new(move |lines: &mut Vec<String>| {
+                lines.retain(|line| match line.strip_prefix(SESSION_MARKER) {
+                    Some(session_id) if is_complete(line) => {
+                        session_store.push_session_id(session_id.trim().to_string());
+                        false
+                    }
+                    _ => true,
+                });
+                // Blank lines between entries would start new ones
+                while lines
+                    .first()
+                    .is_some_and(|l| is_complete(l) && l.trim().is_empty())
+                {
+                    lines.remove(0);
+                }
+            }))
+            .message_boundary_predicate(Box::new(message_boundary))
+            .index_provider(entry_index_provider)
+            .build();
+
+        while let Some(Ok(chunk)) = stdout.next().await {
+            for patch in processor.process(chunk) {
+                msg_store.push_patch(patch);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.split_inclusive('\n')
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn splits_banner_text_and_edit_blocks() {
+        let output = lines(
+            "Aider v0.86.1\nMain model: sonnet with diff edit format\n\
+             I'll rename the function.\n\
+             src/app.py\n```python\n<<<<<<< SEARCH\ndef old():\n=======\ndef new():\n>>>>>>> REPLACE\n```\n\
+             Tokens: 2.1k sent, 120 received.\n",
+        );
+
+        assert_eq!(message_boundary(&output), Some(MessageBoundary::Split(2)));
+        let output = &output[2..];
+        assert_eq!(message_boundary(output), Some(MessageBoundary::Split(1)));
+        let output = &output[1..];
+        assert_eq!(message_boundary(output), Some(MessageBoundary::Split(8)));
+        assert_eq!(message_boundary(&output[8..]), None);
+    }
+
+    #[test]
+    fn usage_lines_report_context_usage() {
+        let entry = normalize_entry(
+            "Tokens: 12k sent, 3.4k cache hit, 1.2k received. Cost: $0.05 message, $0.10 session.\n"
+                .to_string(),
+            "/repo",
+        );
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::TokenUsageInfo(ref usage) if usage.total_tokens == 13_200
+        ));
+    }
+
+    #[test]
+    fn waits_for_the_end_of_an_edit_block() {
+        let output = lines("src/app.py\n```python\n<<<<<<< SEARCH\ndef old():\n=======\n");
+        assert_eq!(
+            message_boundary(&output),
+            Some(MessageBoundary::IncompleteContent)
+        );
+    }
+
+    #[test]
+    fn parses_search_replace_into_file_edits() {
+        let entry = normalize_entry(
+            "/repo/src/app.py\n```python\n<<<<<<< SEARCH\ndef old():\n=======\ndef new():\n>>>>>>> REPLACE\n```\n"
+                .to_string(),
+            "/repo",
+        );
+        let NormalizedEntryType::ToolUse {
+            action_type: ActionType::FileEdit { path, changes },
+            ..
+        } = entry.entry_type
+        else {
+            panic!("expected a file edit, got {:?}", entry.entry_type);
+        };
+        assert_eq!(path, "src/app.py");
+        let [FileChange::Edit { unified_diff, .. }] = changes.as_slice() else {
+            panic!("expected one edit");
+        };
+        assert!(unified_diff.contains("-def old():"));
+        assert!(unified_diff.contains("+def new():"));
+
+        // diff-fenced puts the file name inside the fence, and an empty SEARCH
+        // section creates the file
+        let entry = normalize_entry(
+            "```\nnotes.md\n<<<<<<< SEARCH\n=======\n# Notes\n>>>>>>> REPLACE\n```\n".to_string(),
+            "/repo",
+        );
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::FileEdit { ref changes, .. },
+                ..
+            } if matches!(changes.as_slice(), [FileChange::Write { content }] if content == "# Notes\n")
+        ));
+    }
+
+    #[test]
+    fn commands_keep_their_output() {
+        let output = lines("Running pytest -q\n3 passed\n\nAll tests pass.\n");
+        assert_eq!(message_boundary(&output), Some(MessageBoundary::Split(2)));
+
+        let entry = normalize_entry("Running pytest -q\n3 passed\n".to_string(), "/repo");
+        let NormalizedEntryType::ToolUse {
+            action_type: ActionType::CommandRun { command, result },
+            ..
+        } = entry.entry_type
+        else {
+            panic!("expected a command");
+        };
+        assert_eq!(command, "pytest -q");
+        assert_eq!(result.unwrap().output.as_deref(), Some("3 passed"));
+    }
+}
@@ -2,6 +2,7 @@ use std::{path::Path, process::Stdio, sync::Arc};
 
 use async_trait::async_trait;
 use command_group::AsyncCommandGroup;
+use derivative::Derivative;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tokio::{io::AsyncWriteExt, process::Command};
@@ -9,6 +10,8 @@ use ts_rs::TS;
 use workspace_utils::msg_store::MsgStore;
 
 use crate::{
+    approval_hook::{ApprovalHook, HookAgent},
+    approvals::ExecutorApprovalService,
     command::{CmdOverrides, CommandBuildError, CommandBuilder, apply_overrides},
     env::ExecutionEnv,
     executors::{
@@ -16,9 +19,11 @@ use crate::{
         claude::{ClaudeLogProcessor, HistoryStrategy},
     },
     logs::{stderr_processor::normalize_stderr_logs, utils::EntryIndexProvider},
+    mcp_config::{Adapter, workspace_mcp_servers},
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
 pub struct Amp {
     #[serde(default)]
     pub append_prompt: AppendPrompt,
@@ -28,8 +33,18 @@ pub struct Amp {
         description = "Allow all commands to be executed, even if they are not safe."
     )]
     pub dangerously_allow_all: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Approvals",
+        description = "Ask for approval before each tool call. Ignored with Dangerously Allow All."
+    )]
+    pub approvals: Option<bool>,
     #[serde(flatten)]
     pub cmd: CmdOverrides,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub approvals_service: Option<Arc<dyn ExecutorApprovalService>>,
 }
 
 impl Amp {
@@ -41,10 +56,36 @@ impl Amp {
         }
         apply_overrides(builder, &self.cmd)
     }
+
+    async fn approval_hook(
+        &self,
+        current_dir: &Path,
+        env: &ExecutionEnv,
+    ) -> Result<Option<ApprovalHook>, ExecutorError> {
+        ApprovalHook::for_spawn(
+            HookAgent::Amp,
+            self.approvals.unwrap_or(false) && !self.dangerously_allow_all.unwrap_or(false),
+            self.approvals_service.as_ref(),
+            current_dir,
+            env,
+        )
+        .await
+    }
+}
+
+/// `--mcp-config` for the workspace MCP servers, if any
+fn mcp_config_args(env: &ExecutionEnv) -> Vec<String> {
+    workspace_mcp_servers(env, Adapter::Passthrough)
+        .map(|servers| vec!["--mcp-config".to_string(), servers.to_string()])
+        .unwrap_or_default()
 }
 
 #[async_trait]
 impl StandardCodingAgentExecutor for Amp {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals_service = Some(approvals);
+    }
+
     async fn spawn(
         &self,
         current_dir: &Path,
@@ -52,9 +93,10 @@ impl StandardCodingAgentExecutor for Amp {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
         let command_parts = self.build_command_builder()?.build_initial()?;
-        let (executable_path, args) = command_parts.into_resolved().await?;
+        let (executable_path, args) = command_parts.into_resolved(env).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        let approval_hook = self.approval_hook(current_dir, env).await?;
 
         let mut command = Command::new(executable_path);
         command
@@ -64,13 +106,20 @@ impl StandardCodingAgentExecutor for Amp {
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .env("NPM_CONFIG_LOGLEVEL", "error")
-            .args(&args);
+            .args(&args)
+            .args(mcp_config_args(env));
 
         env.clone()
             .with_profile(&self.cmd)
             .apply_to_command(&mut command);
+        if let Some(hook) = &approval_hook {
+            hook.apply_to_command(&mut command)?;
+        }
 
         let mut child = command.group_spawn()?;
+        if let Some(hook) = approval_hook {
+            hook.serve(&mut child)?;
+        }
 
         // Feed the prompt in, then close the pipe so amp sees EOF
         if let Some(mut stdin) = child.inner().stdin.take() {
@@ -96,7 +145,7 @@ impl StandardCodingAgentExecutor for Amp {
             "fork".to_string(),
             session_id.to_string(),
         ])?;
-        let (fork_program, fork_args) = fork_line.into_resolved().await?;
+        let (fork_program, fork_args) = fork_line.into_resolved(env).await?;
         let fork_output = Command::new(fork_program)
             .kill_on_drop(true)
             .stdout(Stdio::piped())
@@ -128,9 +177,10 @@ impl StandardCodingAgentExecutor for Amp {
             "continue".to_string(),
             new_thread_id.clone(),
         ])?;
-        let (continue_program, continue_args) = continue_line.into_resolved().await?;
+        let (continue_program, continue_args) = continue_line.into_resolved(env).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        let approval_hook = self.approval_hook(current_dir, env).await?;
 
         let mut command = Command::new(continue_program);
         command
@@ -140,13 +190,20 @@ impl StandardCodingAgentExecutor for Amp {
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .env("NPM_CONFIG_LOGLEVEL", "error")
-            .args(&continue_args);
+            .args(&continue_args)
+            .args(mcp_config_args(env));
 
         env.clone()
             .with_profile(&self.cmd)
             .apply_to_command(&mut command);
+        if let Some(hook) = &approval_hook {
+            hook.apply_to_command(&mut command)?;
+        }
 
         let mut child = command.group_spawn()?;
+        if let Some(hook) = approval_hook {
+            hook.serve(&mut child)?;
+        }
 
         // Feed the prompt in, then close the pipe so amp sees EOF
         if let Some(mut stdin) = child.inner().stdin.take() {
@@ -30,6 +30,7 @@ use self::{
     types::{ControlRequestType, ControlResponseType, PermissionMode},
 };
 use crate::{
+    approval_hook::ApprovalHookEvent,
     approvals::ExecutorApprovalService,
     command::{CmdOverrides, CommandBuildError, CommandBuilder, CommandParts, apply_overrides},
     env::ExecutionEnv,
@@ -41,12 +42,15 @@ use crate::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
         TodoItem, ToolStatus,
         stderr_processor::normalize_stderr_logs,
+        token_usage::token_usage_entry,
         utils::{
             EntryIndexProvider,
             patch::{self, ConversationPatch},
         },
     },
+    mcp_config::{Adapter, workspace_mcp_servers},
     stdout_dup::create_stdout_pipe_writer,
+    steering::steer_channel,
 };
 
 fn base_command(claude_code_router: bool) -> &'static str {
@@ -298,7 +302,7 @@ impl ClaudeCode {
         command_parts: CommandParts,
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = command_parts.into_resolved(env).await?;
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
         let mut command = Command::new(program_path);
@@ -311,6 +315,12 @@ impl ClaudeCode {
             .env("NPM_CONFIG_LOGLEVEL", "error")
             .args(&args);
 
+        if let Some(servers) = workspace_mcp_servers(env, Adapter::Passthrough) {
+            command
+                .arg("--mcp-config")
+                .arg(serde_json::json!({ "mcpServers": servers }).to_string());
+        }
+
         env.clone()
             .with_profile(&self.cmd)
             .apply_to_command(&mut command);
@@ -336,6 +346,7 @@ impl ClaudeCode {
 
         // Create cancellation token for graceful shutdown
         let cancel = CancellationToken::new();
+        let (steer, steer_rx) = steer_channel();
 
         // Spawn task to handle the SDK client with control protocol
         let prompt_clone = combined_prompt.clone();
@@ -352,8 +363,13 @@ impl ClaudeCode {
                 commit_reminder_prompt,
                 cancel_for_task.clone(),
             );
-            let protocol_peer =
-                ProtocolPeer::spawn(child_stdin, child_stdout, client.clone(), cancel_for_task);
+            let protocol_peer = ProtocolPeer::spawn(
+                child_stdin,
+                child_stdout,
+                client.clone(),
+                steer_rx,
+                cancel_for_task,
+            );
 
             // Initialize control protocol
             if let Err(e) = protocol_peer.initialize(hooks).await {
@@ -381,6 +397,7 @@ impl ClaudeCode {
             child,
             exit_signal: None,
             cancel: Some(cancel),
+            steer: Some(steer),
         })
     }
 }
@@ -482,6 +499,16 @@ impl ClaudeLogProcessor {
                         continue;
                     }
 
+                    // Tool calls awaiting approval through Amp's permission hook
+                    if let Some(event) = ApprovalHookEvent::parse(trimmed) {
+                        let patch_id = entry_index_provider.next();
+                        msg_store.push_patch(ConversationPatch::add_normalized_entry(
+                            patch_id,
+                            event.to_normalized_entry(&worktree_path),
+                        ));
+                        continue;
+                    }
+
                     match serde_json::from_str::<ClaudeJson>(trimmed) {
                         Ok(claude_json) => {
                             if !session_id_extracted
@@ -925,6 +952,21 @@ impl ClaudeLogProcessor {
                     patches.push(patch);
                 }
 
+                // Amp does not stream message deltas; its assistant messages
+                // carry the usage of the model call instead
+                if matches!(self.strategy, HistoryStrategy::AmpResume)
+                    && let Some(usage) = &message.usage
+                {
+                    let total_tokens = usage.input_tokens.unwrap_or(0)
+                        + usage.cache_creation_input_tokens.unwrap_or(0)
+                        + usage.cache_read_input_tokens.unwrap_or(0)
+                        + usage.output_tokens.unwrap_or(0);
+                    if total_tokens > 0 {
+                        self.context_tokens_used = total_tokens as u32;
+                        patches.push(self.add_token_usage_entry(entry_index_provider));
+                    }
+                }
+
                 let mut streaming_message_state = message
                     .id
                     .as_ref()
@@ -1378,6 +1420,23 @@ impl ClaudeLogProcessor {
                     };
                     let idx = entry_index_provider.next();
                     patches.push(ConversationPatch::add_normalized_entry(idx, entry));
+                } else if is_error.unwrap_or(false)
+                    && let Some(text) = result.as_ref().and_then(|v| v.as_str())
+                    && NormalizedEntryError::classify(text) == NormalizedEntryError::RateLimited
+                {
+                    // e.g. "Claude AI usage limit reached|<reset timestamp>"
+                    let entry = NormalizedEntry {
+                        timestamp: None,
+                        entry_type: NormalizedEntryType::ErrorMessage {
+                            error_type: NormalizedEntryError::RateLimited,
+                        },
+                        content: text.to_string(),
+                        metadata: Some(
+                            serde_json::to_value(claude_json).unwrap_or(serde_json::Value::Null),
+                        ),
+                    };
+                    let idx = entry_index_provider.next();
+                    patches.push(ConversationPatch::add_normalized_entry(idx, entry));
                 } else if matches!(subtype.as_deref(), Some("success"))
                     && let Some(text) = result.as_ref().and_then(|v| v.as_str())
                     && (self.last_assistant_message.is_none()
@@ -1547,18 +1606,7 @@ impl ClaudeLogProcessor {
         &mut self,
         entry_index_provider: &EntryIndexProvider,
     ) -> json_patch::Patch {
-        let entry = NormalizedEntry {
-            timestamp: None,
-            entry_type: NormalizedEntryType::TokenUsageInfo(crate::logs::TokenUsageInfo {
-                total_tokens: self.context_tokens_used,
-                model_context_window: self.main_model_context_window,
-            }),
-            content: format!(
-                "Tokens used: {} / Context window: {}",
-                self.context_tokens_used, self.main_model_context_window
-            ),
-            metadata: None,
-        };
+        let entry = token_usage_entry(self.context_tokens_used, self.main_model_context_window);
         let idx = entry_index_provider.next();
         ConversationPatch::add_normalized_entry(idx, entry)
     }
@@ -1849,6 +1897,8 @@ pub struct ClaudeMessage {
     pub model: Option<String>,
     pub content: ClaudeMessageContent,
     pub stop_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<ClaudeUsage>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -2260,6 +2310,21 @@ mod tests {
         assert_eq!(entries[0].content, "Final result");
     }
 
+    #[test]
+    fn test_usage_limit_result_is_rate_limited() {
+        let result_json = r#"{"type":"result","subtype":"success","is_error":true,"duration_ms":120,"result":"Claude AI usage limit reached|1760000000"}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+
+        let entries = normalize(&parsed, "");
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::ErrorMessage {
+                error_type: NormalizedEntryError::RateLimited
+            }
+        ));
+    }
+
     #[test]
     fn test_thinking_content() {
         let thinking_json = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"Let me think about this..."}]}}"#;
@@ -2375,6 +2440,10 @@ mod tests {
                 base_command_override: None,
                 additional_params: None,
                 env: None,
+                process_sandbox: None,
+                resource_limits: None,
+                co_authors: None,
+                fallback_profiles: None,
             },
             approvals_service: None,
             disable_api_key: None,
@@ -17,8 +17,69 @@ use crate::{
             types::{Message, PermissionMode, SDKControlRequest, SDKControlRequestType},
         },
     },
+    steering::{SteerReceiver, SteerRequest},
 };
 
+/// What to send Claude after a steering request or a result
+#[derive(Debug, PartialEq)]
+enum TurnAction {
+    SendMessage(String),
+    Interrupt,
+    /// Keep reading
+    Wait,
+    /// Every turn has reported its result
+    Finish,
+}
+
+/// The turns of a run that can be steered. A message is sent into the running
+/// turn, or the turn is interrupted and the message kept until its result
+/// arrives.
+#[derive(Debug)]
+struct TurnTracker {
+    /// Each user message sent ends with a result; the initial prompt is the first
+    open_turns: usize,
+    /// Instructions to continue with once an interrupted turn reports its result
+    after_interrupt: Option<String>,
+}
+
+impl TurnTracker {
+    fn new() -> Self {
+        Self {
+            open_turns: 1,
+            after_interrupt: None,
+        }
+    }
+
+    fn on_steer(&mut self, request: SteerRequest) -> TurnAction {
+        if !request.interrupt {
+            self.open_turns += 1;
+            return TurnAction::SendMessage(request.prompt);
+        }
+        let interrupt_pending = self.after_interrupt.is_some();
+        self.after_interrupt = Some(match self.after_interrupt.take() {
+            Some(earlier) => format!("{earlier}\n\n{}", request.prompt),
+            None => request.prompt,
+        });
+        if interrupt_pending {
+            TurnAction::Wait
+        } else {
+            TurnAction::Interrupt
+        }
+    }
+
+    fn on_result(&mut self) -> TurnAction {
+        if let Some(prompt) = self.after_interrupt.take() {
+            return TurnAction::SendMessage(prompt);
+        }
+        self.open_turns = self.open_turns.saturating_sub(1);
+        if self.open_turns == 0 {
+            TurnAction::Finish
+        } else {
+            TurnAction::Wait
+        }
+    }
+}
+
 /// Handles bidirectional control protocol communication
 #[derive(Clone)]
 pub struct ProtocolPeer {
@@ -30,6 +91,7 @@ impl ProtocolPeer {
         stdin: ChildStdin,
         stdout: ChildStdout,
         client: Arc<ClaudeAgentClient>,
+        steer: SteerReceiver,
         cancel: CancellationToken,
     ) -> Self {
         let peer = Self {
@@ -38,7 +100,7 @@ impl ProtocolPeer {
 
         let reader_peer = peer.clone();
         tokio::spawn(async move {
-            if let Err(e) = reader_peer.read_loop(stdout, client, cancel).await {
+            if let Err(e) = reader_peer.read_loop(stdout, client, steer, cancel).await {
                 tracing::error!("Protocol reader loop error: {}", e);
             }
         });
@@ -50,14 +112,15 @@ impl ProtocolPeer {
         &self,
         stdout: ChildStdout,
         client: Arc<ClaudeAgentClient>,
+        mut steer: SteerReceiver,
         cancel: CancellationToken,
     ) -> Result<(), ExecutorError> {
-        let mut reader = BufReader::new(stdout);
-        let mut buffer = String::new();
+        // `next_line` is cancel safe, so steering requests cannot cut a line short
+        let mut lines = BufReader::new(stdout).lines();
         let mut interrupt_sent = false;
+        let mut turns = TurnTracker::new();
 
         loop {
-            buffer.clear();
             tokio::select! {
                 biased;
                 _ = cancel.cancelled(), if !interrupt_sent => {
@@ -68,11 +131,21 @@ impl ProtocolPeer {
                     }
                     // Continue the loop to read Claude's response (it should send a result)
                 }
-                line_result = reader.read_line(&mut buffer) => {
+                Some(request) = steer.recv(), if !interrupt_sent => {
+                    let sent = match turns.on_steer(request) {
+                        TurnAction::SendMessage(prompt) => self.send_user_message(prompt).await,
+                        TurnAction::Interrupt => self.interrupt().await,
+                        TurnAction::Wait | TurnAction::Finish => Ok(()),
+                    };
+                    if let Err(e) = sent {
+                        tracing::warn!("Failed to steer Claude: {e}");
+                    }
+                }
+                line_result = lines.next_line() => {
                     match line_result {
-                        Ok(0) => break, // EOF
-                        Ok(_) => {
-                            let line = buffer.trim();
+                        Ok(None) => break, // EOF
+                        Ok(Some(line)) => {
+                            let line = line.trim();
                             if line.is_empty() {
                                 continue;
                             }
@@ -88,7 +161,16 @@ impl ProtocolPeer {
                                         .await;
                                 }
                                 Ok(CLIMessage::Result(_)) => {
-                                    break;
+                                    if interrupt_sent {
+                                        break;
+                                    }
+                                    match turns.on_result() {
+                                        TurnAction::SendMessage(prompt) => {
+                                            self.send_user_message(prompt).await?;
+                                        }
+                                        TurnAction::Finish => break,
+                                        TurnAction::Interrupt | TurnAction::Wait => {}
+                                    }
                                 }
                                 _ => {}
                             }
@@ -218,3 +300,55 @@ impl ProtocolPeer {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steer(prompt: &str, interrupt: bool) -> SteerRequest {
+        SteerRequest {
+            prompt: prompt.to_string(),
+            interrupt,
+        }
+    }
+
+    #[test]
+    fn steered_message_waits_for_both_results() {
+        let mut turns = TurnTracker::new();
+        assert_eq!(
+            turns.on_steer(steer("also update the docs", false)),
+            TurnAction::SendMessage("also update the docs".to_string())
+        );
+        assert_eq!(turns.on_result(), TurnAction::Wait);
+        assert_eq!(turns.on_result(), TurnAction::Finish);
+    }
+
+    #[test]
+    fn interrupt_continues_once_the_turn_reports_its_result() {
+        let mut turns = TurnTracker::new();
+        assert_eq!(
+            turns.on_steer(steer("stop and use the v2 API", true)),
+            TurnAction::Interrupt
+        );
+        assert_eq!(
+            turns.on_result(),
+            TurnAction::SendMessage("stop and use the v2 API".to_string())
+        );
+        assert_eq!(turns.on_result(), TurnAction::Finish);
+    }
+
+    #[test]
+    fn second_interrupt_is_joined_with_the_pending_one() {
+        let mut turns = TurnTracker::new();
+        assert_eq!(turns.on_steer(steer("stop", true)), TurnAction::Interrupt);
+        assert_eq!(
+            turns.on_steer(steer("then run the tests", true)),
+            TurnAction::Wait
+        );
+        assert_eq!(
+            turns.on_result(),
+            TurnAction::SendMessage("stop\n\nthen run the tests".to_string())
+        );
+        assert_eq!(turns.on_result(), TurnAction::Finish);
+    }
+}
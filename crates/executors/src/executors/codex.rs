@@ -54,7 +54,9 @@ use crate::{
         SpawnedChild, StandardCodingAgentExecutor,
     },
     logs::utils::patch,
+    mcp_config::{Adapter, workspace_mcp_servers},
     stdout_dup::create_stdout_pipe_writer,
+    steering::steer_channel,
 };
 
 /// Sandbox policy modes for Codex
@@ -292,7 +294,11 @@ impl Codex {
         apply_overrides(builder, &self.cmd)
     }
 
-    fn build_new_conversation_params(&self, cwd: &Path) -> NewConversationParams {
+    fn build_new_conversation_params(
+        &self,
+        cwd: &Path,
+        env: &ExecutionEnv,
+    ) -> NewConversationParams {
         let sandbox = match self.sandbox.as_ref() {
             None | Some(SandboxMode::Auto) => Some(CodexSandboxMode::WorkspaceWrite), // match the Auto preset in codex
             Some(SandboxMode::ReadOnly) => Some(CodexSandboxMode::ReadOnly),
@@ -318,7 +324,7 @@ impl Codex {
             cwd: Some(cwd.to_string_lossy().to_string()),
             approval_policy,
             sandbox,
-            config: self.build_config_overrides(),
+            config: self.build_config_overrides(env),
             base_instructions: self.base_instructions.clone(),
             include_apply_patch_tool: self.include_apply_patch_tool,
             model_provider: self.model_provider.clone(),
@@ -327,7 +333,7 @@ impl Codex {
         }
     }
 
-    fn build_config_overrides(&self) -> Option<HashMap<String, Value>> {
+    fn build_config_overrides(&self, env: &ExecutionEnv) -> Option<HashMap<String, Value>> {
         let mut overrides = HashMap::new();
 
         if let Some(effort) = &self.model_reasoning_effort {
@@ -353,6 +359,12 @@ impl Codex {
             );
         }
 
+        if let Some(Value::Object(servers)) = workspace_mcp_servers(env, Adapter::Codex) {
+            for (name, server) in servers {
+                overrides.insert(format!("mcp_servers.{name}"), server);
+            }
+        }
+
         if overrides.is_empty() {
             None
         } else {
@@ -368,7 +380,7 @@ impl Codex {
         resume_session: Option<&str>,
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let params = self.build_new_conversation_params(current_dir);
+        let params = self.build_new_conversation_params(current_dir, env);
         let resume_session = resume_session.map(|s| s.to_string());
 
         self.spawn_app_server(
@@ -450,7 +462,7 @@ impl Codex {
         F: FnOnce(Arc<AppServerClient>, ExitSignalSender) -> Fut + Send + 'static,
         Fut: std::future::Future<Output = Result<(), ExecutorError>> + Send + 'static,
     {
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = command_parts.into_resolved(env).await?;
 
         let mut process = Command::new(program_path);
         process
@@ -491,6 +503,7 @@ impl Codex {
         let commit_reminder = env.commit_reminder;
         let commit_reminder_prompt = env.commit_reminder_prompt.clone();
         let cancel_for_task = cancel.clone();
+        let (steer, mut steer_rx) = steer_channel();
 
         tokio::spawn(async move {
             let exit_signal_tx = ExitSignalSender::new(exit_signal_tx);
@@ -515,6 +528,15 @@ impl Codex {
             );
             client.connect(rpc_peer);
 
+            let steer_client = client.clone();
+            tokio::spawn(async move {
+                while let Some(request) = steer_rx.recv().await {
+                    if let Err(err) = steer_client.steer(request).await {
+                        tracing::warn!("Failed to steer Codex turn: {err}");
+                    }
+                }
+            });
+
             let result = async {
                 client.initialize().await?;
                 task(client, exit_signal_tx.clone()).await
@@ -557,6 +579,7 @@ impl Codex {
             child,
             exit_signal: Some(exit_signal_rx),
             cancel: Some(cancel),
+            steer: Some(steer),
         })
     }
 }
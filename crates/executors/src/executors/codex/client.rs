@@ -13,11 +13,11 @@ use codex_app_server_protocol::{
     AddConversationListenerParams, AddConversationSubscriptionResponse, ApplyPatchApprovalResponse,
     ClientInfo, ClientNotification, ClientRequest, ExecCommandApprovalResponse,
     GetAuthStatusParams, GetAuthStatusResponse, InitializeParams, InitializeResponse, InputItem,
-    JSONRPCError, JSONRPCNotification, JSONRPCRequest, JSONRPCResponse, ListMcpServerStatusParams,
-    ListMcpServerStatusResponse, NewConversationParams, NewConversationResponse, RequestId,
-    ResumeConversationParams, ResumeConversationResponse, ReviewStartParams, ReviewStartResponse,
-    ReviewTarget, SendUserMessageParams, SendUserMessageResponse, ServerNotification,
-    ServerRequest,
+    InterruptConversationParams, InterruptConversationResponse, JSONRPCError, JSONRPCNotification,
+    JSONRPCRequest, JSONRPCResponse, ListMcpServerStatusParams, ListMcpServerStatusResponse,
+    NewConversationParams, NewConversationResponse, RequestId, ResumeConversationParams,
+    ResumeConversationResponse, ReviewStartParams, ReviewStartResponse, ReviewTarget,
+    SendUserMessageParams, SendUserMessageResponse, ServerNotification, ServerRequest,
 };
 use codex_protocol::{ThreadId, protocol::ReviewDecision};
 use serde::{Serialize, de::DeserializeOwned};
@@ -33,7 +33,11 @@ use super::jsonrpc::{JsonRpcCallbacks, JsonRpcPeer};
 use crate::{
     approvals::{ExecutorApprovalError, ExecutorApprovalService},
     env::RepoContext,
-    executors::{ExecutorError, codex::normalize_logs::Approval},
+    executors::{
+        ExecutorError,
+        codex::normalize_logs::{Approval, Steered},
+    },
+    steering::SteerRequest,
 };
 
 pub struct AppServerClient {
@@ -165,6 +169,36 @@ impl AppServerClient {
         self.send_request(request, "sendUserMessage").await
     }
 
+    pub async fn interrupt_conversation(
+        &self,
+        conversation_id: ThreadId,
+    ) -> Result<InterruptConversationResponse, ExecutorError> {
+        let request = ClientRequest::InterruptConversation {
+            request_id: self.next_request_id(),
+            params: InterruptConversationParams { conversation_id },
+        };
+        self.send_request(request, "interruptConversation").await
+    }
+
+    /// Send a message into the running turn. With `interrupt` the turn is
+    /// aborted first and the message follows once Codex reports the abort.
+    pub async fn steer(&self, request: SteerRequest) -> Result<(), ExecutorError> {
+        self.log_writer
+            .log_raw(&Steered::user_message(request.prompt.clone()).raw())
+            .await?;
+        let conversation_id = *self.conversation_id.lock().await;
+        match conversation_id {
+            Some(conversation_id) if request.interrupt => {
+                self.enqueue_user_message(request.prompt).await;
+                self.interrupt_conversation(conversation_id).await?;
+            }
+            Some(conversation_id) => self.spawn_user_message(conversation_id, request.prompt),
+            // Sent once the conversation is registered
+            None => self.enqueue_user_message(request.prompt).await,
+        }
+        Ok(())
+    }
+
     pub async fn get_auth_status(&self) -> Result<GetAuthStatusResponse, ExecutorError> {
         let request = ClientRequest::GetAuthStatus {
             request_id: self.next_request_id(),
@@ -376,9 +410,15 @@ impl AppServerClient {
     }
 
     async fn enqueue_feedback(&self, message: String) {
-        if message.trim().is_empty() {
+        let trimmed = message.trim();
+        if trimmed.is_empty() {
             return;
         }
+        self.enqueue_user_message(format!("User feedback: {trimmed}"))
+            .await;
+    }
+
+    async fn enqueue_user_message(&self, message: String) {
         let mut guard = self.pending_feedback.lock().await;
         guard.push_back(message);
     }
@@ -402,11 +442,7 @@ impl AppServerClient {
         };
 
         for message in messages {
-            let trimmed = message.trim();
-            if trimmed.is_empty() {
-                continue;
-            }
-            self.spawn_user_message(conversation_id, format!("User feedback: {trimmed}"));
+            self.spawn_user_message(conversation_id, message);
         }
     }
 
@@ -369,6 +369,11 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 continue;
             }
 
+            if let Ok(steered) = serde_json::from_str::<Steered>(&line) {
+                add_normalized_entry(&msg_store, &entry_index, steered.to_normalized_entry());
+                continue;
+            }
+
             if let Ok(approval) = serde_json::from_str::<Approval>(&line) {
                 if let Some(entry) = approval.to_normalized_entry_opt() {
                     add_normalized_entry(&msg_store, &entry_index, entry);
@@ -985,7 +990,9 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                         NormalizedEntry {
                             timestamp: None,
                             entry_type: NormalizedEntryType::ErrorMessage {
-                                error_type: NormalizedEntryError::Other,
+                                error_type: NormalizedEntryError::classify(&format!(
+                                    "{message} {codex_error_info:?}"
+                                )),
                             },
                             content: format!("Error: {message} {codex_error_info:?}"),
                             metadata: None,
@@ -1194,6 +1201,34 @@ impl ToNormalizedEntry for Error {
     }
 }
 
+/// A message the user sent into a running turn
+#[derive(Serialize, Deserialize, Debug)]
+pub enum Steered {
+    UserMessage { message: String },
+}
+
+impl Steered {
+    pub fn user_message(message: String) -> Self {
+        Self::UserMessage { message }
+    }
+
+    pub fn raw(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+impl ToNormalizedEntry for Steered {
+    fn to_normalized_entry(&self) -> NormalizedEntry {
+        let Self::UserMessage { message } = self;
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::UserMessage,
+            content: message.clone(),
+            metadata: None,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub enum Approval {
     ApprovalResponse {
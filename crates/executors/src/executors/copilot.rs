@@ -17,6 +17,9 @@ use crate::{
     },
 };
 
+/// Context window Copilot allows its default model
+const COPILOT_CONTEXT_WINDOW: u32 = 128_000;
+
 #[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
 #[derivative(Debug, PartialEq)]
 pub struct Copilot {
@@ -132,7 +135,7 @@ impl StandardCodingAgentExecutor for Copilot {
     }
 
     fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
-        super::acp::normalize_logs(msg_store, worktree_path);
+        super::acp::normalize_logs(msg_store, worktree_path, COPILOT_CONTEXT_WINDOW);
     }
 
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
@@ -3,6 +3,7 @@ use std::{collections::HashMap, path::Path, process::Stdio, sync::Arc, time::Dur
 
 use async_trait::async_trait;
 use command_group::AsyncCommandGroup;
+use derivative::Derivative;
 use futures::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -16,6 +17,8 @@ use workspace_utils::{
 };
 
 use crate::{
+    approval_hook::{ApprovalHook, ApprovalHookEvent, HookAgent},
+    approvals::ExecutorApprovalService,
     command::{CmdOverrides, CommandBuildError, CommandBuilder, apply_overrides},
     env::ExecutionEnv,
     executors::{
@@ -25,14 +28,19 @@ use crate::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
         TodoItem, ToolStatus,
         plain_text_processor::PlainTextLogProcessor,
+        token_usage::{DEFAULT_CONTEXT_WINDOW, estimate_context_usage},
         utils::{ConversationPatch, EntryIndexProvider},
     },
+    mcp_config::{
+        Adapter, remove_workspace_config_file, workspace_mcp_servers, write_workspace_mcp_file,
+    },
 };
 
 mod mcp;
 const CURSOR_AUTH_REQUIRED_MSG: &str = "Authentication required. Please run 'cursor-agent login' first, or set CURSOR_API_KEY environment variable.";
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
 pub struct CursorAgent {
     #[serde(default)]
     pub append_prompt: AppendPrompt,
@@ -44,8 +52,18 @@ pub struct CursorAgent {
         description = "auto, sonnet-4.5, sonnet-4.5-thinking, gpt-5, opus-4.1, grok, composer-1"
     )]
     pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Approvals",
+        description = "Ask for approval before running shell commands and MCP tools"
+    )]
+    pub approvals: Option<bool>,
     #[serde(flatten)]
     pub cmd: CmdOverrides,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub approvals_service: Option<Arc<dyn ExecutorApprovalService>>,
 }
 
 impl CursorAgent {
@@ -67,21 +85,61 @@ impl CursorAgent {
 
         apply_overrides(builder, &self.cmd)
     }
+
+    async fn approval_hook(
+        &self,
+        current_dir: &Path,
+        env: &ExecutionEnv,
+    ) -> Result<Option<ApprovalHook>, ExecutorError> {
+        ApprovalHook::for_spawn(
+            HookAgent::Cursor,
+            self.approvals.unwrap_or(false),
+            self.approvals_service.as_ref(),
+            current_dir,
+            env,
+        )
+        .await
+    }
+}
+
+/// Write the workspace MCP servers to the workspace's `.cursor/mcp.json`, as
+/// `cursor-agent` only reads servers from config files. A file written for
+/// servers that have since been removed is deleted.
+async fn write_workspace_mcp_config(current_dir: &Path, env: &ExecutionEnv) {
+    let Some(servers) = workspace_mcp_servers(env, Adapter::Cursor) else {
+        if let Err(e) =
+            remove_workspace_config_file(current_dir, mcp::PROJECT_MCP_CONFIG_PATH).await
+        {
+            tracing::warn!("Failed to remove Cursor workspace MCP config: {}", e);
+        }
+        return;
+    };
+    if let Err(e) =
+        write_workspace_mcp_file(current_dir, mcp::PROJECT_MCP_CONFIG_PATH, servers).await
+    {
+        tracing::warn!("Failed to write Cursor workspace MCP config: {}", e);
+    }
 }
 
 #[async_trait]
 impl StandardCodingAgentExecutor for CursorAgent {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals_service = Some(approvals);
+    }
+
     async fn spawn(
         &self,
         current_dir: &Path,
         prompt: &str,
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
-        mcp::ensure_mcp_server_trust(self, current_dir).await;
+        write_workspace_mcp_config(current_dir, env).await;
+        mcp::ensure_mcp_server_trust(self, current_dir, env).await;
+        let approval_hook = self.approval_hook(current_dir, env).await?;
 
         let command_parts = self.build_command_builder()?.build_initial()?;
 
-        let (executable_path, args) = command_parts.into_resolved().await?;
+        let (executable_path, args) = command_parts.into_resolved(env).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
@@ -98,8 +156,14 @@ impl StandardCodingAgentExecutor for CursorAgent {
         env.clone()
             .with_profile(&self.cmd)
             .apply_to_command(&mut command);
+        if let Some(hook) = &approval_hook {
+            hook.apply_to_command(&mut command)?;
+        }
 
         let mut child = command.group_spawn()?;
+        if let Some(hook) = approval_hook {
+            hook.serve(&mut child)?;
+        }
 
         if let Some(mut stdin) = child.inner().stdin.take() {
             stdin.write_all(combined_prompt.as_bytes()).await?;
@@ -117,12 +181,14 @@ impl StandardCodingAgentExecutor for CursorAgent {
         _reset_to_message_id: Option<&str>,
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
-        mcp::ensure_mcp_server_trust(self, current_dir).await;
+        write_workspace_mcp_config(current_dir, env).await;
+        mcp::ensure_mcp_server_trust(self, current_dir, env).await;
+        let approval_hook = self.approval_hook(current_dir, env).await?;
 
         let command_parts = self
             .build_command_builder()?
             .build_follow_up(&["--resume".to_string(), session_id.to_string()])?;
-        let (executable_path, args) = command_parts.into_resolved().await?;
+        let (executable_path, args) = command_parts.into_resolved(env).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
@@ -139,8 +205,14 @@ impl StandardCodingAgentExecutor for CursorAgent {
         env.clone()
             .with_profile(&self.cmd)
             .apply_to_command(&mut command);
+        if let Some(hook) = &approval_hook {
+            hook.apply_to_command(&mut command)?;
+        }
 
         let mut child = command.group_spawn()?;
+        if let Some(hook) = approval_hook {
+            hook.serve(&mut child)?;
+        }
 
         if let Some(mut stdin) = child.inner().stdin.take() {
             stdin.write_all(combined_prompt.as_bytes()).await?;
@@ -153,6 +225,13 @@ impl StandardCodingAgentExecutor for CursorAgent {
     fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
         let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
 
+        // cursor-agent does not report token usage
+        estimate_context_usage(
+            msg_store.clone(),
+            entry_index_provider.clone(),
+            DEFAULT_CONTEXT_WINDOW,
+        );
+
         // Custom stderr processor for Cursor that detects login errors
         let msg_store_stderr = msg_store.clone();
         let entry_index_provider_stderr = entry_index_provider.clone();
@@ -219,6 +298,15 @@ impl StandardCodingAgentExecutor for CursorAgent {
             let mut call_index_map: HashMap<String, usize> = HashMap::new();
 
             while let Some(Ok(line)) = lines.next().await {
+                if let Some(event) = ApprovalHookEvent::parse(&line) {
+                    let patch_id = entry_index_provider.next();
+                    msg_store.push_patch(ConversationPatch::add_normalized_entry(
+                        patch_id,
+                        event.to_normalized_entry(&worktree_str),
+                    ));
+                    continue;
+                }
+
                 // Parse line as CursorJson
                 let cursor_json: CursorJson = match serde_json::from_str(&line) {
                     Ok(cursor_json) => cursor_json,
@@ -470,6 +558,25 @@ impl StandardCodingAgentExecutor for CursorAgent {
                         }
                     }
 
+                    CursorJson::Result {
+                        is_error: Some(true),
+                        result: Some(serde_json::Value::String(text)),
+                        ..
+                    } if NormalizedEntryError::classify(&text)
+                        == NormalizedEntryError::RateLimited =>
+                    {
+                        let entry = NormalizedEntry {
+                            timestamp: None,
+                            entry_type: NormalizedEntryType::ErrorMessage {
+                                error_type: NormalizedEntryError::RateLimited,
+                            },
+                            content: text,
+                            metadata: None,
+                        };
+                        let id = entry_index_provider.next();
+                        msg_store.push_patch(ConversationPatch::add_normalized_entry(id, entry));
+                    }
+
                     CursorJson::Result { .. } => {
                         // no-op; metadata-only events not surfaced
                     }
@@ -1229,7 +1336,9 @@ mod tests {
             append_prompt: AppendPrompt::default(),
             force: None,
             model: None,
+            approvals: None,
             cmd: Default::default(),
+            approvals_service: None,
         };
         let msg_store = Arc::new(MsgStore::new());
         let current_dir = std::path::PathBuf::from("/tmp/test-worktree");
@@ -1265,6 +1374,47 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_rate_limited_result_is_surfaced() {
+        let executor = CursorAgent {
+            append_prompt: AppendPrompt::default(),
+            force: None,
+            model: None,
+            approvals: None,
+            cmd: Default::default(),
+            approvals_service: None,
+        };
+        let msg_store = Arc::new(MsgStore::new());
+        let current_dir = std::path::PathBuf::from("/tmp/test-worktree");
+
+        msg_store.push_stdout(format!(
+            "{}\n",
+            r#"{"type":"result","subtype":"error","is_error":true,"result":"Rate limit exceeded, try again later"}"#
+        ));
+        msg_store.push_finished();
+
+        executor.normalize_logs(msg_store.clone(), &current_dir);
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(150)).await;
+
+        let error_types: Vec<serde_json::Value> = msg_store
+            .get_history()
+            .iter()
+            .filter_map(|m| match m {
+                workspace_utils::log_msg::LogMsg::JsonPatch(patch) => {
+                    serde_json::to_value(patch).ok()
+                }
+                _ => None,
+            })
+            .filter_map(|patch| {
+                patch[0]["value"]["content"]["entry_type"]["error_type"]
+                    .get("type")
+                    .cloned()
+            })
+            .collect();
+        assert_eq!(error_types, vec![serde_json::json!("rate_limited")]);
+    }
+
     #[test]
     fn test_session_id_extraction_from_system_line() {
         // System messages no longer extract session_id
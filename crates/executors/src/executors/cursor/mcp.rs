@@ -5,10 +5,17 @@ use tokio::fs;
 use tracing::warn;
 
 use super::CursorAgent;
-use crate::executors::{CodingAgent, ExecutorError, StandardCodingAgentExecutor};
+use crate::{
+    env::ExecutionEnv,
+    executors::{CodingAgent, ExecutorError, StandardCodingAgentExecutor},
+    mcp_config::{Adapter, workspace_mcp_servers},
+};
 
-pub async fn ensure_mcp_server_trust(cursor: &CursorAgent, current_dir: &Path) {
-    if let Err(err) = ensure_mcp_server_trust_impl(cursor, current_dir).await {
+/// Project-level MCP config, relative to the workspace directory
+pub const PROJECT_MCP_CONFIG_PATH: &str = ".cursor/mcp.json";
+
+pub async fn ensure_mcp_server_trust(cursor: &CursorAgent, current_dir: &Path, env: &ExecutionEnv) {
+    if let Err(err) = ensure_mcp_server_trust_impl(cursor, current_dir, env).await {
         tracing::warn!(
             error = %err,
             "Cursor MCP approval bootstrap failed. MCP servers might be unavailable."
@@ -19,6 +26,7 @@ pub async fn ensure_mcp_server_trust(cursor: &CursorAgent, current_dir: &Path) {
 async fn ensure_mcp_server_trust_impl(
     cursor: &CursorAgent,
     current_dir: &Path,
+    env: &ExecutionEnv,
 ) -> Result<(), ExecutorError> {
     let current_dir =
         std::fs::canonicalize(current_dir).unwrap_or_else(|_| current_dir.to_path_buf());
@@ -65,12 +73,19 @@ async fn ensure_mcp_server_trust_impl(
         Err(err) => return Err(ExecutorError::Io(err)),
     };
 
-    let Some(servers) = config_value
+    let mut servers = config_value
         .get("mcpServers")
         .and_then(|value| value.as_object())
-    else {
-        return Ok(());
-    };
+        .cloned()
+        .unwrap_or_default();
+
+    // Approve the workspace servers vibe-kanban writes to the project-level
+    // config; servers a repository ships itself are left to Cursor's prompt
+    if let Some(serde_json::Value::Object(workspace_servers)) =
+        workspace_mcp_servers(env, Adapter::Cursor)
+    {
+        servers.extend(workspace_servers);
+    }
 
     let approvals_path = home_dir
         .join(".cursor")
@@ -97,7 +112,7 @@ async fn ensure_mcp_server_trust_impl(
     let mut approvals_set: HashSet<String> = existing.iter().cloned().collect();
     let mut newly_added = Vec::new();
 
-    for (server_name, definition) in servers {
+    for (server_name, definition) in &servers {
         if server_name == "meta" || !definition.is_object() {
             continue;
         }
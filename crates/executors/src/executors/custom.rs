@@ -0,0 +1,367 @@
+use std::{path::Path, process::Stdio, sync::Arc};
+
+use async_trait::async_trait;
+use command_group::AsyncCommandGroup;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, process::Command};
+use ts_rs::TS;
+use workspace_utils::msg_store::MsgStore;
+
+use crate::{
+    command::{CmdOverrides, CommandBuildError, CommandBuilder, apply_overrides},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+    },
+    logs::utils::EntryIndexProvider,
+};
+
+pub mod normalize_logs;
+
+use normalize_logs::normalize_logs;
+
+const PROMPT_PLACEHOLDER: &str = "{prompt}";
+const SESSION_ID_PLACEHOLDER: &str = "{session_id}";
+const WORKDIR_PLACEHOLDER: &str = "{workdir}";
+
+/// How the agent's output is turned into conversation entries
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomOutputFormat {
+    /// Everything the agent prints is shown as assistant messages
+    #[default]
+    PlainText,
+    /// Every line is a JSON object, mapped to entries by `json_rules`
+    JsonLines,
+}
+
+/// Entry a matching JSON line becomes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomEntryType {
+    AssistantMessage,
+    Thinking,
+    SystemMessage,
+    ErrorMessage,
+    ToolUse,
+}
+
+/// Maps JSON lines to conversation entries. Paths are JSONPath expressions
+/// using `.field`, `['field']` and `[index]` steps, e.g. `$.message.content[0].text`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct JsonLineRule {
+    #[schemars(
+        title = "Match Path",
+        description = "JSONPath of the field that selects this rule, e.g. $.type"
+    )]
+    pub path: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Equals",
+        description = "Value the field must have. Without it, any line that has the field matches"
+    )]
+    pub equals: Option<String>,
+
+    #[schemars(title = "Entry Type")]
+    pub entry_type: CustomEntryType,
+
+    #[schemars(
+        title = "Content Path",
+        description = "JSONPath of the text shown for the entry, e.g. $.message.text"
+    )]
+    pub content_path: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Tool Name Path",
+        description = "JSONPath of the tool name, for tool_use entries"
+    )]
+    pub tool_name_path: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Arguments Path",
+        description = "JSONPath of the tool arguments, for tool_use entries"
+    )]
+    pub arguments_path: Option<String>,
+}
+
+/// Executor for any CLI agent, configured entirely in the profile
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct Custom {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+
+    #[schemars(
+        title = "Command",
+        description = "Command template, e.g. `my-agent run --prompt {prompt}`. {prompt} and {workdir} are replaced in each argument; without {prompt} the prompt is written to stdin"
+    )]
+    pub command: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Follow-up Command",
+        description = "Command template continuing a session, e.g. `my-agent run --resume {session_id} --prompt {prompt}`. Follow-ups are not supported without it"
+    )]
+    pub follow_up_command: Option<String>,
+
+    #[serde(default)]
+    #[schemars(title = "Output Format")]
+    pub output_format: CustomOutputFormat,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(
+        title = "JSON Line Rules",
+        description = "For json_lines output: the first rule matching a line turns it into an entry. Lines no rule matches are skipped"
+    )]
+    pub json_rules: Vec<JsonLineRule>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Session ID Regex",
+        description = "Regex finding the session ID in the output, from its first capture group or the whole match"
+    )]
+    pub session_id_regex: Option<String>,
+
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+}
+
+impl Custom {
+    fn build_command_builder(
+        &self,
+        template: &str,
+        prompt: &str,
+        session_id: Option<&str>,
+        current_dir: &Path,
+    ) -> Result<CommandBuilder, CommandBuildError> {
+        let workdir = current_dir.to_string_lossy();
+        let placeholders = [
+            (PROMPT_PLACEHOLDER, prompt),
+            (SESSION_ID_PLACEHOLDER, session_id.unwrap_or_default()),
+            (WORKDIR_PLACEHOLDER, workdir.as_ref()),
+        ];
+        let mut args = render_template(template, &placeholders)?.into_iter();
+        let program = args.next().ok_or(CommandBuildError::EmptyCommand)?;
+        let builder = CommandBuilder::new(shlex::try_quote(&program)?).params(args);
+
+        apply_overrides(builder, &self.cmd)
+    }
+
+    async fn spawn_template(
+        &self,
+        template: &str,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        let command_parts = self
+            .build_command_builder(template, &combined_prompt, session_id, current_dir)?
+            .build_initial()?;
+        let (program_path, args) = command_parts.into_resolved(env).await?;
+
+        let prompt_on_stdin = !template.contains(PROMPT_PLACEHOLDER);
+        let mut command = Command::new(program_path);
+        command
+            .kill_on_drop(true)
+            .stdin(if prompt_on_stdin {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(current_dir)
+            .args(args);
+
+        env.clone()
+            .with_profile(&self.cmd)
+            .apply_to_command(&mut command);
+
+        let mut child = command.group_spawn()?;
+
+        // Write the prompt in the background: an agent that prints before
+        // reading all of stdin would otherwise fill its stdout pipe and block
+        // while we are still writing
+        if prompt_on_stdin && let Some(mut stdin) = child.inner().stdin.take() {
+            tokio::spawn(async move {
+                if let Err(e) = stdin.write_all(combined_prompt.as_bytes()).await {
+                    tracing::warn!("Failed to write prompt to custom agent: {}", e);
+                }
+                let _ = stdin.shutdown().await;
+            });
+        }
+
+        Ok(child.into())
+    }
+}
+
+/// Split `template` into arguments like a shell would and replace the
+/// placeholders in each, so a value is always passed as a single argument.
+/// Placeholders inside replaced values are left alone.
+fn render_template(
+    template: &str,
+    placeholders: &[(&str, &str)],
+) -> Result<Vec<String>, CommandBuildError> {
+    let args = shlex::split(template)
+        .ok_or_else(|| CommandBuildError::InvalidBase(template.to_string()))?;
+
+    Ok(args
+        .into_iter()
+        .map(|arg| {
+            let mut rendered = String::with_capacity(arg.len());
+            let mut rest = arg.as_str();
+            while !rest.is_empty() {
+                match placeholders
+                    .iter()
+                    .find(|(placeholder, _)| rest.starts_with(placeholder))
+                {
+                    Some((placeholder, value)) => {
+                        rendered.push_str(value);
+                        rest = &rest[placeholder.len()..];
+                    }
+                    None => {
+                        let ch = rest.chars().next().unwrap_or_default();
+                        rendered.push(ch);
+                        rest = &rest[ch.len_utf8()..];
+                    }
+                }
+            }
+            rendered
+        })
+        .collect())
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for Custom {
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        self.spawn_template(&self.command, current_dir, prompt, None, env)
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        _reset_to_message_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let Some(template) = &self.follow_up_command else {
+            return Err(ExecutorError::FollowUpNotSupported(
+                "Custom executor has no follow_up_command".to_string(),
+            ));
+        };
+        self.spawn_template(template, current_dir, prompt, Some(session_id), env)
+            .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, _worktree_path: &Path) {
+        normalize_logs(
+            msg_store.clone(),
+            self,
+            EntryIndexProvider::start_from(&msg_store),
+        );
+    }
+
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        // Where a custom agent reads MCP servers from is unknown
+        None
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        let program = self
+            .cmd
+            .base_command_override
+            .as_deref()
+            .unwrap_or(&self.command);
+        let Some(program) = shlex::split(program).and_then(|args| args.into_iter().next()) else {
+            return AvailabilityInfo::NotFound;
+        };
+
+        let program = Path::new(&program);
+        let found = if program.components().count() > 1 {
+            program.is_file()
+        } else {
+            std::env::var_os("PATH").is_some_and(|paths| {
+                std::env::split_paths(&paths).any(|dir| dir.join(program).is_file())
+            })
+        };
+
+        if found {
+            AvailabilityInfo::InstallationFound
+        } else {
+            AvailabilityInfo::NotFound
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_template_keeps_values_as_single_arguments() {
+        let args = render_template(
+            "my-agent run --cwd={workdir} -p {prompt} '{session_id}'",
+            &[
+                (PROMPT_PLACEHOLDER, "fix the {workdir} bug"),
+                (SESSION_ID_PLACEHOLDER, "abc"),
+                (WORKDIR_PLACEHOLDER, "/tmp/work dir"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            args,
+            [
+                "my-agent",
+                "run",
+                "--cwd=/tmp/work dir",
+                "-p",
+                "fix the {workdir} bug",
+                "abc",
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_profile_deserializes_with_defaults() {
+        let custom: Custom = serde_json::from_value(serde_json::json!({
+            "command": "my-agent --json {prompt}",
+            "output_format": "json_lines",
+            "json_rules": [{
+                "path": "$.type",
+                "equals": "message",
+                "entry_type": "assistant_message",
+                "content_path": "$.text"
+            }],
+            "additional_params": ["--verbose"]
+        }))
+        .unwrap();
+
+        assert_eq!(custom.output_format, CustomOutputFormat::JsonLines);
+        assert_eq!(
+            custom.json_rules[0].entry_type,
+            CustomEntryType::AssistantMessage
+        );
+
+        let (program, args) = custom
+            .build_command_builder(&custom.command, "hi there", None, Path::new("/w"))
+            .unwrap()
+            .build_initial()
+            .unwrap()
+            .into_parts();
+        assert_eq!(program, "my-agent");
+        assert_eq!(args, ["--json", "hi there", "--verbose"]);
+    }
+}
@@ -0,0 +1,308 @@
+//! Normalization of a custom agent's output, as configured in its profile.
+
+use std::sync::Arc;
+
+use futures::{StreamExt, future::ready};
+use regex::Regex;
+use serde_json::Value;
+use workspace_utils::msg_store::MsgStore;
+
+use super::{Custom, CustomEntryType, CustomOutputFormat, JsonLineRule};
+use crate::logs::{
+    ActionType, NormalizedEntry, NormalizedEntryError, NormalizedEntryType, ToolStatus,
+    plain_text_processor::PlainTextLogProcessor,
+    stderr_processor::normalize_stderr_logs,
+    token_usage::{DEFAULT_CONTEXT_WINDOW, estimate_context_usage},
+    utils::{EntryIndexProvider, patch::add_normalized_entry},
+};
+
+pub fn normalize_logs(
+    msg_store: Arc<MsgStore>,
+    executor: &Custom,
+    entry_index_provider: EntryIndexProvider,
+) {
+    normalize_stderr_logs(msg_store.clone(), entry_index_provider.clone());
+    // A custom agent's output format says nothing about token usage
+    estimate_context_usage(
+        msg_store.clone(),
+        entry_index_provider.clone(),
+        DEFAULT_CONTEXT_WINDOW,
+    );
+
+    if let Some(pattern) = &executor.session_id_regex {
+        match Regex::new(pattern) {
+            Ok(regex) => extract_session_id(msg_store.clone(), regex),
+            Err(e) => tracing::warn!("Invalid session_id_regex {pattern:?}: {e}"),
+        }
+    }
+
+    match executor.output_format {
+        CustomOutputFormat::PlainText => normalize_plain_text(msg_store, entry_index_provider),
+        CustomOutputFormat::JsonLines => {
+            normalize_json_lines(msg_store, executor.json_rules.clone(), entry_index_provider)
+        }
+    }
+}
+
+/// Push the session ID from the first output line `regex` matches
+fn extract_session_id(msg_store: Arc<MsgStore>, regex: Regex) {
+    tokio::spawn(async move {
+        let mut lines = msg_store
+            .stdout_lines_stream()
+            .filter_map(|res| ready(res.ok()));
+
+        while let Some(line) = lines.next().await {
+            if let Some(session_id) = find_session_id(&regex, &line) {
+                msg_store.push_session_id(session_id);
+                break;
+            }
+        }
+    });
+}
+
+fn find_session_id(regex: &Regex, line: &str) -> Option<String> {
+    let captures = regex.captures(line)?;
+    let session_id = captures.get(1).or_else(|| captures.get(0))?.as_str().trim();
+    (!session_id.is_empty()).then(|| session_id.to_string())
+}
+
+fn normalize_plain_text(msg_store: Arc<MsgStore>, entry_index_provider: EntryIndexProvider) {
+    tokio::spawn(async move {
+        let mut stdout = msg_store.stdout_chunked_stream();
+
+        let mut processor = PlainTextLogProcessor::builder()
+            .normalized_entry_producer(|content: String| NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::AssistantMessage,
+                content: strip_ansi_escapes::strip_str(&content),
+                metadata: None,
+            })
+            .index_provider(entry_index_provider)
+            .build();
+
+        while let Some(Ok(chunk)) = stdout.next().await {
+            for patch in processor.process(chunk) {
+                msg_store.push_patch(patch);
+            }
+        }
+    });
+}
+
+fn normalize_json_lines(
+    msg_store: Arc<MsgStore>,
+    rules: Vec<JsonLineRule>,
+    entry_index_provider: EntryIndexProvider,
+) {
+    tokio::spawn(async move {
+        let mut lines = msg_store
+            .stdout_lines_stream()
+            .filter_map(|res| ready(res.ok()));
+
+        while let Some(line) = lines.next().await {
+            if let Some(entry) = normalize_json_line(&line, &rules) {
+                add_normalized_entry(&msg_store, &entry_index_provider, entry);
+            }
+        }
+    });
+}
+
+fn normalize_json_line(line: &str, rules: &[JsonLineRule]) -> Option<NormalizedEntry> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let Ok(value) = serde_json::from_str::<Value>(trimmed) else {
+        // Show anything else the agent prints as is
+        return Some(NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::SystemMessage,
+            content: strip_ansi_escapes::strip_str(trimmed),
+            metadata: None,
+        });
+    };
+
+    let rule = rules.iter().find(|rule| {
+        select(&value, &rule.path)
+            .is_some_and(|field| rule.equals.as_ref().is_none_or(|e| *e == to_text(field)))
+    })?;
+    let content = select(&value, &rule.content_path).map(to_text);
+
+    let entry_type = match rule.entry_type {
+        CustomEntryType::AssistantMessage => NormalizedEntryType::AssistantMessage,
+        CustomEntryType::Thinking => NormalizedEntryType::Thinking,
+        CustomEntryType::SystemMessage => NormalizedEntryType::SystemMessage,
+        CustomEntryType::ErrorMessage => NormalizedEntryType::ErrorMessage {
+            error_type: NormalizedEntryError::classify(content.as_deref().unwrap_or_default()),
+        },
+        CustomEntryType::ToolUse => {
+            let tool_name = rule
+                .tool_name_path
+                .as_deref()
+                .and_then(|path| select(&value, path))
+                .map(to_text)
+                .unwrap_or_else(|| "tool".to_string());
+            let arguments = rule
+                .arguments_path
+                .as_deref()
+                .and_then(|path| select(&value, path))
+                .cloned();
+            return Some(NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::ToolUse {
+                    tool_name: tool_name.clone(),
+                    action_type: ActionType::Tool {
+                        tool_name: tool_name.clone(),
+                        arguments,
+                        result: None,
+                    },
+                    status: ToolStatus::Success,
+                },
+                content: content.unwrap_or(tool_name),
+                metadata: None,
+            });
+        }
+    };
+
+    Some(NormalizedEntry {
+        timestamp: None,
+        entry_type,
+        content: content.filter(|c| !c.trim().is_empty())?,
+        metadata: None,
+    })
+}
+
+/// Strings as they are, anything else as JSON
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// The value at a JSONPath made of `.field`, `['field']` and `[index]` steps
+fn select<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut rest = path.trim().strip_prefix('$')?;
+    let mut current = value;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            current = current.get(&after[..end])?;
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']')?;
+            let step = after[..end].trim();
+            current = match step
+                .strip_prefix('\'')
+                .and_then(|s| s.strip_suffix('\''))
+                .or_else(|| step.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+            {
+                Some(key) => current.get(key)?,
+                None => current.get(step.parse::<usize>().ok()?)?,
+            };
+            rest = &after[end + 1..];
+        } else {
+            return None;
+        }
+    }
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(path: &str, equals: &str, entry_type: CustomEntryType, content: &str) -> JsonLineRule {
+        JsonLineRule {
+            path: path.to_string(),
+            equals: Some(equals.to_string()),
+            entry_type,
+            content_path: content.to_string(),
+            tool_name_path: None,
+            arguments_path: None,
+        }
+    }
+
+    #[test]
+    fn select_follows_fields_and_indices() {
+        let value = serde_json::json!({"message": {"content": [{"text": "hi"}]}, "n": 3});
+
+        assert_eq!(select(&value, "$"), Some(&value));
+        assert_eq!(
+            select(&value, "$.message.content[0].text"),
+            Some(&Value::from("hi"))
+        );
+        assert_eq!(
+            select(&value, "$['message'][\"content\"][0]['text']"),
+            Some(&Value::from("hi"))
+        );
+        assert_eq!(select(&value, "$.message.content[1]"), None);
+        assert_eq!(select(&value, "message.content"), None);
+        assert_eq!(to_text(select(&value, "$.n").unwrap()), "3");
+    }
+
+    #[test]
+    fn json_lines_map_to_entries_by_rule() {
+        let mut tool = rule("$.type", "tool", CustomEntryType::ToolUse, "$.summary");
+        tool.tool_name_path = Some("$.name".to_string());
+        tool.arguments_path = Some("$.input".to_string());
+        let rules = vec![
+            rule(
+                "$.type",
+                "text",
+                CustomEntryType::AssistantMessage,
+                "$.text",
+            ),
+            tool,
+        ];
+
+        let entry = normalize_json_line(r#"{"type":"text","text":"Done."}"#, &rules).unwrap();
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::AssistantMessage
+        ));
+        assert_eq!(entry.content, "Done.");
+
+        let entry = normalize_json_line(
+            r#"{"type":"tool","name":"bash","input":{"cmd":"ls"}}"#,
+            &rules,
+        )
+        .unwrap();
+        let NormalizedEntryType::ToolUse {
+            tool_name,
+            action_type: ActionType::Tool { arguments, .. },
+            ..
+        } = entry.entry_type
+        else {
+            panic!("expected a tool use, got {:?}", entry.entry_type);
+        };
+        assert_eq!(tool_name, "bash");
+        assert_eq!(arguments, Some(serde_json::json!({"cmd": "ls"})));
+        assert_eq!(entry.content, "bash");
+
+        assert!(normalize_json_line(r#"{"type":"usage"}"#, &rules).is_none());
+        let entry = normalize_json_line("Starting up", &rules).unwrap();
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::SystemMessage
+        ));
+    }
+
+    #[test]
+    fn session_id_comes_from_the_first_capture_group() {
+        let regex = Regex::new(r#""session_id":\s*"([^"]+)""#).unwrap();
+        assert_eq!(
+            find_session_id(&regex, r#"{"type":"init","session_id": "s-42"}"#),
+            Some("s-42".to_string())
+        );
+        let regex = Regex::new(r"[0-9a-f]{8}-[0-9a-f-]{27}").unwrap();
+        assert_eq!(
+            find_session_id(
+                &regex,
+                "session 0a1b2c3d-0000-1111-2222-333344445555 started"
+            ),
+            Some("0a1b2c3d-0000-1111-2222-333344445555".to_string())
+        );
+        assert_eq!(find_session_id(&regex, "no session here"), None);
+    }
+}
@@ -2,6 +2,7 @@ use std::{path::Path, process::Stdio, sync::Arc};
 
 use async_trait::async_trait;
 use command_group::AsyncCommandGroup;
+use derivative::Derivative;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use strum_macros::AsRefStr;
@@ -10,10 +11,18 @@ use ts_rs::TS;
 use workspace_utils::msg_store::MsgStore;
 
 use crate::{
+    approval_hook::{ApprovalHook, HookAgent},
+    approvals::ExecutorApprovalService,
     command::{CommandBuildError, CommandBuilder, CommandParts},
     env::ExecutionEnv,
     executors::{AppendPrompt, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
-    logs::utils::EntryIndexProvider,
+    logs::{
+        token_usage::{DEFAULT_CONTEXT_WINDOW, estimate_context_usage},
+        utils::EntryIndexProvider,
+    },
+    mcp_config::{
+        Adapter, remove_workspace_config_file, workspace_mcp_servers, write_workspace_mcp_file,
+    },
 };
 
 pub mod normalize_logs;
@@ -49,7 +58,8 @@ pub enum ReasoningEffortLevel {
 }
 
 /// Droid executor configuration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
 pub struct Droid {
     #[serde(default)]
     pub append_prompt: AppendPrompt,
@@ -75,8 +85,20 @@ pub struct Droid {
     )]
     pub reasoning_effort: Option<ReasoningEffortLevel>,
 
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Approvals",
+        description = "Ask for approval before each tool call the autonomy level does not already allow"
+    )]
+    pub approvals: Option<bool>,
+
     #[serde(flatten)]
     pub cmd: crate::command::CmdOverrides,
+
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub approvals_service: Option<Arc<dyn ExecutorApprovalService>>,
 }
 
 impl Droid {
@@ -100,16 +122,52 @@ impl Droid {
 
         apply_overrides(builder, &self.cmd)
     }
+
+    async fn approval_hook(
+        &self,
+        current_dir: &Path,
+        env: &ExecutionEnv,
+    ) -> Result<Option<ApprovalHook>, ExecutorError> {
+        ApprovalHook::for_spawn(
+            HookAgent::Droid,
+            self.approvals.unwrap_or(false),
+            self.approvals_service.as_ref(),
+            current_dir,
+            env,
+        )
+        .await
+    }
 }
 
+/// Project-level MCP config, relative to the workspace directory
+const DROID_MCP_CONFIG_PATH: &str = ".factory/mcp.json";
+
 async fn spawn_droid(
     command_parts: CommandParts,
     prompt: &String,
     current_dir: &Path,
     env: &ExecutionEnv,
     cmd_overrides: &crate::command::CmdOverrides,
+    approval_hook: Option<ApprovalHook>,
 ) -> Result<SpawnedChild, ExecutorError> {
-    let (program_path, args) = command_parts.into_resolved().await?;
+    // Droid only reads MCP servers from config files, so the workspace's
+    // servers go into its project-level config
+    match workspace_mcp_servers(env, Adapter::Passthrough) {
+        Some(servers) => {
+            if let Err(e) =
+                write_workspace_mcp_file(current_dir, DROID_MCP_CONFIG_PATH, servers).await
+            {
+                tracing::warn!("Failed to write Droid workspace MCP config: {}", e);
+            }
+        }
+        None => {
+            if let Err(e) = remove_workspace_config_file(current_dir, DROID_MCP_CONFIG_PATH).await {
+                tracing::warn!("Failed to remove Droid workspace MCP config: {}", e);
+            }
+        }
+    }
+
+    let (program_path, args) = command_parts.into_resolved(env).await?;
 
     let mut command = Command::new(program_path);
     command
@@ -124,8 +182,14 @@ async fn spawn_droid(
     env.clone()
         .with_profile(cmd_overrides)
         .apply_to_command(&mut command);
+    if let Some(hook) = &approval_hook {
+        hook.apply_to_command(&mut command)?;
+    }
 
     let mut child = command.group_spawn()?;
+    if let Some(hook) = approval_hook {
+        hook.serve(&mut child)?;
+    }
 
     if let Some(mut stdin) = child.inner().stdin.take() {
         stdin.write_all(prompt.as_bytes()).await?;
@@ -137,6 +201,10 @@ async fn spawn_droid(
 
 #[async_trait]
 impl StandardCodingAgentExecutor for Droid {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals_service = Some(approvals);
+    }
+
     async fn spawn(
         &self,
         current_dir: &Path,
@@ -145,8 +213,17 @@ impl StandardCodingAgentExecutor for Droid {
     ) -> Result<SpawnedChild, ExecutorError> {
         let droid_command = self.build_command_builder()?.build_initial()?;
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        let approval_hook = self.approval_hook(current_dir, env).await?;
 
-        spawn_droid(droid_command, &combined_prompt, current_dir, env, &self.cmd).await
+        spawn_droid(
+            droid_command,
+            &combined_prompt,
+            current_dir,
+            env,
+            &self.cmd,
+            approval_hook,
+        )
+        .await
     }
 
     async fn spawn_follow_up(
@@ -161,16 +238,28 @@ impl StandardCodingAgentExecutor for Droid {
             .build_command_builder()?
             .build_follow_up(&["--session-id".to_string(), session_id.to_string()])?;
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        let approval_hook = self.approval_hook(current_dir, env).await?;
 
-        spawn_droid(continue_cmd, &combined_prompt, current_dir, env, &self.cmd).await
+        spawn_droid(
+            continue_cmd,
+            &combined_prompt,
+            current_dir,
+            env,
+            &self.cmd,
+            approval_hook,
+        )
+        .await
     }
 
     fn normalize_logs(&self, msg_store: Arc<MsgStore>, current_dir: &Path) {
-        normalize_logs(
+        let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
+        // `droid exec` does not report token usage
+        estimate_context_usage(
             msg_store.clone(),
-            current_dir,
-            EntryIndexProvider::start_from(&msg_store),
+            entry_index_provider.clone(),
+            DEFAULT_CONTEXT_WINDOW,
         );
+        normalize_logs(msg_store, current_dir, entry_index_provider);
     }
 
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
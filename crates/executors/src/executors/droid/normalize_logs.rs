@@ -11,13 +11,16 @@ use workspace_utils::{
     diff::normalize_unified_diff, msg_store::MsgStore, path::make_path_relative,
 };
 
-use crate::logs::{
-    ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
-    NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult, ToolStatus,
-    plain_text_processor::PlainTextLogProcessor,
-    utils::{
-        EntryIndexProvider,
-        patch::{add_normalized_entry, replace_normalized_entry},
+use crate::{
+    approval_hook::ApprovalHookEvent,
+    logs::{
+        ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
+        NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult, ToolStatus,
+        plain_text_processor::PlainTextLogProcessor,
+        utils::{
+            EntryIndexProvider,
+            patch::{add_normalized_entry, replace_normalized_entry},
+        },
     },
 };
 
@@ -42,6 +45,14 @@ pub fn normalize_logs(
 
         while let Some(line) = lines_stream.next().await {
             let trimmed = line.trim();
+            if let Some(event) = ApprovalHookEvent::parse(trimmed) {
+                add_normalized_entry(
+                    &msg_store,
+                    &entry_index_provider,
+                    event.to_normalized_entry(&worktree_path_str),
+                );
+                continue;
+            }
             let droid_json = match serde_json::from_str::<DroidJson>(trimmed) {
                 Ok(droid_json) => droid_json,
                 Err(_) => {
@@ -51,7 +62,7 @@ pub fn normalize_logs(
                         let entry = NormalizedEntry {
                             timestamp: None,
                             entry_type: NormalizedEntryType::ErrorMessage {
-                                error_type: NormalizedEntryError::Other,
+                                error_type: NormalizedEntryError::classify(&error.message),
                             },
                             content: error.message,
                             metadata: None,
@@ -656,7 +667,7 @@ pub fn normalize_logs(
                     let entry = NormalizedEntry {
                         timestamp: None,
                         entry_type: NormalizedEntryType::ErrorMessage {
-                            error_type: NormalizedEntryError::Other,
+                            error_type: NormalizedEntryError::classify(message),
                         },
                         content: message.clone(),
                         metadata: None,
@@ -17,6 +17,9 @@ use crate::{
     },
 };
 
+/// Context window of Gemini 2.5 Pro and Flash
+const GEMINI_CONTEXT_WINDOW: u32 = 1_048_576;
+
 #[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
 #[derivative(Debug, PartialEq)]
 pub struct Gemini {
@@ -115,7 +118,7 @@ impl StandardCodingAgentExecutor for Gemini {
     }
 
     fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
-        super::acp::normalize_logs(msg_store, worktree_path);
+        super::acp::normalize_logs(msg_store, worktree_path, GEMINI_CONTEXT_WINDOW);
     }
 
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
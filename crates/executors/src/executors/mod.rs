@@ -18,22 +18,26 @@ use crate::executors::qa_mock::QaMockExecutor;
 use crate::{
     actions::{ExecutorAction, review::RepoReviewContext},
     approvals::ExecutorApprovalService,
-    command::CommandBuildError,
+    command::{CmdOverrides, CommandBuildError},
     env::ExecutionEnv,
     executors::{
-        amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot, cursor::CursorAgent,
-        droid::Droid, gemini::Gemini, opencode::Opencode, pi::Pi, qwen::QwenCode,
+        aider::Aider, amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot,
+        cursor::CursorAgent, custom::Custom, droid::Droid, gemini::Gemini, opencode::Opencode,
+        pi::Pi, qwen::QwenCode,
     },
     logs::utils::patch,
     mcp_config::McpConfig,
+    steering::SteerSender,
 };
 
 pub mod acp;
+pub mod aider;
 pub mod amp;
 pub mod claude;
 pub mod codex;
 pub mod copilot;
 pub mod cursor;
+pub mod custom;
 pub mod droid;
 pub mod gemini;
 pub mod opencode;
@@ -60,6 +64,8 @@ pub enum BaseAgentCapability {
     SetupHelper,
     /// Agent reports context/token usage information
     ContextUsage,
+    /// Agent accepts messages and interrupts while a turn is running
+    Steering,
 }
 
 #[derive(Debug, Error)]
@@ -88,6 +94,10 @@ pub enum ExecutorError {
     SetupHelperNotSupported,
     #[error("Auth required: {0}")]
     AuthRequired(String),
+    #[error("Sandbox unavailable: {0}")]
+    SandboxUnavailable(String),
+    #[error("Workspace container unavailable: {0}")]
+    ContainerUnavailable(String),
 }
 
 #[enum_dispatch]
@@ -119,6 +129,8 @@ pub enum CodingAgent {
     Copilot,
     Droid,
     Pi,
+    Aider,
+    Custom,
     #[cfg(feature = "qa-mode")]
     QaMock(QaMockExecutor),
 }
@@ -170,6 +182,26 @@ impl CodingAgent {
         }
     }
 
+    /// The profile settings shared by every executor, if it has any
+    pub fn cmd_overrides(&self) -> Option<&CmdOverrides> {
+        match self {
+            Self::ClaudeCode(executor) => Some(&executor.cmd),
+            Self::Amp(executor) => Some(&executor.cmd),
+            Self::Gemini(executor) => Some(&executor.cmd),
+            Self::Codex(executor) => Some(&executor.cmd),
+            Self::Opencode(executor) => Some(&executor.cmd),
+            Self::CursorAgent(executor) => Some(&executor.cmd),
+            Self::QwenCode(executor) => Some(&executor.cmd),
+            Self::Copilot(executor) => Some(&executor.cmd),
+            Self::Droid(executor) => Some(&executor.cmd),
+            Self::Pi(executor) => Some(&executor.cmd),
+            Self::Aider(executor) => Some(&executor.cmd),
+            Self::Custom(executor) => Some(&executor.cmd),
+            #[cfg(feature = "qa-mode")]
+            Self::QaMock(_) => None,
+        }
+    }
+
     pub fn supports_mcp(&self) -> bool {
         self.default_mcp_config_path().is_some()
     }
@@ -179,21 +211,39 @@ impl CodingAgent {
             Self::ClaudeCode(_) => vec![
                 BaseAgentCapability::SessionFork,
                 BaseAgentCapability::ContextUsage,
+                BaseAgentCapability::Steering,
             ],
             Self::Opencode(_) => vec![
                 BaseAgentCapability::SessionFork,
                 BaseAgentCapability::ContextUsage,
+                BaseAgentCapability::Steering,
             ],
             Self::Codex(_) => vec![
                 BaseAgentCapability::SessionFork,
                 BaseAgentCapability::SetupHelper,
                 BaseAgentCapability::ContextUsage,
+                BaseAgentCapability::Steering,
+            ],
+            Self::Gemini(_) | Self::QwenCode(_) => vec![
+                BaseAgentCapability::SessionFork,
+                BaseAgentCapability::ContextUsage,
+                BaseAgentCapability::Steering,
+            ],
+            Self::Amp(_) | Self::Pi(_) => vec![
+                BaseAgentCapability::SessionFork,
+                BaseAgentCapability::ContextUsage,
+            ],
+            Self::CursorAgent(_) => vec![
+                BaseAgentCapability::SetupHelper,
+                BaseAgentCapability::ContextUsage,
+            ],
+            Self::Copilot(_) => vec![
+                BaseAgentCapability::ContextUsage,
+                BaseAgentCapability::Steering,
             ],
-            Self::Amp(_) | Self::Gemini(_) | Self::QwenCode(_) | Self::Pi(_) => {
-                vec![BaseAgentCapability::SessionFork]
+            Self::Droid(_) | Self::Aider(_) | Self::Custom(_) => {
+                vec![BaseAgentCapability::ContextUsage]
             }
-            Self::CursorAgent(_) => vec![BaseAgentCapability::SetupHelper],
-            Self::Copilot(_) | Self::Droid(_) => vec![],
             #[cfg(feature = "qa-mode")]
             Self::QaMock(_) => vec![], // QA mock doesn't need special capabilities
         }
@@ -313,6 +363,9 @@ pub struct SpawnedChild {
     pub exit_signal: Option<ExecutorExitSignal>,
     /// Container → Executor: signals when container wants to cancel the execution
     pub cancel: Option<CancellationToken>,
+    /// Container → Executor: messages for the running turn, for executors
+    /// that accept them
+    pub steer: Option<SteerSender>,
 }
 
 impl From<AsyncGroupChild> for SpawnedChild {
@@ -321,6 +374,7 @@ impl From<AsyncGroupChild> for SpawnedChild {
             child,
             exit_signal: None,
             cancel: None,
+            steer: None,
         }
     }
 }
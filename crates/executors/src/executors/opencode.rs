@@ -20,7 +20,9 @@ use crate::{
         StandardCodingAgentExecutor, opencode::types::OpencodeExecutorEvent,
     },
     logs::utils::patch,
+    mcp_config::{Adapter, workspace_mcp_servers},
     stdout_dup::create_stdout_pipe_writer,
+    steering::steer_channel,
 };
 
 mod models;
@@ -99,7 +101,7 @@ impl Opencode {
         env: &ExecutionEnv,
     ) -> Result<(AsyncGroupChild, ServerPassword), ExecutorError> {
         let command_parts = self.build_command_builder()?.build_initial()?;
-        let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = command_parts.into_resolved(env).await?;
 
         let server_password = generate_server_password();
 
@@ -185,6 +187,7 @@ impl Opencode {
         let resume_session_id = resume_session.map(|s| s.to_string());
         let models_cache_key = self.compute_models_cache_key();
         let cancel_for_task = cancel.clone();
+        let (steer, steer_rx) = steer_channel();
         let commit_reminder = env.commit_reminder;
         let commit_reminder_prompt = env.commit_reminder_prompt.clone();
         let repo_context = env.repo_context.clone();
@@ -224,7 +227,7 @@ impl Opencode {
                 Some(command) => {
                     run_slash_command(config, log_writer.clone(), command, cancel_for_task).await
                 }
-                None => run_session(config, log_writer.clone(), cancel_for_task).await,
+                None => run_session(config, log_writer.clone(), steer_rx, cancel_for_task).await,
             };
             let exit_result = match result {
                 Ok(()) => ExecutorExitResult::Success,
@@ -242,6 +245,7 @@ impl Opencode {
             child,
             exit_signal: Some(exit_signal_rx),
             cancel: Some(cancel),
+            steer: Some(steer),
         })
     }
 }
@@ -347,6 +351,7 @@ impl StandardCodingAgentExecutor for Opencode {
     ) -> Result<SpawnedChild, ExecutorError> {
         let env = setup_permissions_env(self.auto_approve, env);
         let env = setup_compaction_env(self.auto_compact, &env);
+        let env = setup_mcp_env(&env);
         self.spawn_inner(current_dir, prompt, None, &env).await
     }
 
@@ -360,6 +365,7 @@ impl StandardCodingAgentExecutor for Opencode {
     ) -> Result<SpawnedChild, ExecutorError> {
         let env = setup_permissions_env(self.auto_approve, env);
         let env = setup_compaction_env(self.auto_compact, &env);
+        let env = setup_mcp_env(&env);
         self.spawn_inner(current_dir, prompt, Some(session_id), &env)
             .await
     }
@@ -510,3 +516,37 @@ fn merge_compaction_config(existing_json: Option<&str>) -> String {
 
     serde_json::to_string(&config).unwrap_or_else(|_| r#"{"compaction":{"auto":true}}"#.to_string())
 }
+
+fn setup_mcp_env(env: &ExecutionEnv) -> ExecutionEnv {
+    let Some(servers) = workspace_mcp_servers(env, Adapter::Opencode) else {
+        return env.clone();
+    };
+
+    let mut env = env.clone();
+    let merged = merge_mcp_config(
+        env.get("OPENCODE_CONFIG_CONTENT").map(String::as_str),
+        servers,
+    );
+    env.insert("OPENCODE_CONFIG_CONTENT", merged);
+    env
+}
+
+/// Add the workspace MCP servers to the inline OpenCode config
+fn merge_mcp_config(existing_json: Option<&str>, servers: Value) -> String {
+    let mut config: Map<String, Value> = existing_json
+        .and_then(|value| serde_json::from_str(value.trim()).ok())
+        .unwrap_or_default();
+
+    let mut mcp = config
+        .remove("mcp")
+        .and_then(|value| value.as_object().cloned())
+        .unwrap_or_default();
+    if let Value::Object(servers) = servers {
+        for (name, server) in servers {
+            mcp.insert(name, server);
+        }
+    }
+    config.insert("mcp".to_string(), Value::Object(mcp));
+
+    serde_json::to_string(&config).unwrap_or_default()
+}
@@ -126,6 +126,20 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                         ),
                     );
                 }
+                OpencodeExecutorEvent::UserMessage { content } => {
+                    let idx = entry_index.next();
+                    msg_store.push_patch(
+                        crate::logs::utils::ConversationPatch::add_normalized_entry(
+                            idx,
+                            NormalizedEntry {
+                                timestamp: None,
+                                entry_type: NormalizedEntryType::UserMessage,
+                                content,
+                                metadata: None,
+                            },
+                        ),
+                    );
+                }
                 OpencodeExecutorEvent::Error { message } => {
                     let idx = entry_index.next();
                     msg_store.push_patch(
@@ -134,7 +148,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             NormalizedEntry {
                                 timestamp: None,
                                 entry_type: NormalizedEntryType::ErrorMessage {
-                                    error_type: NormalizedEntryError::Other,
+                                    error_type: NormalizedEntryError::classify(&message),
                                 },
                                 content: message,
                                 metadata: None,
@@ -248,7 +262,7 @@ impl LogState {
                             .unwrap_or_else(|| format!("OpenCode session error: {}", err.raw)),
                     ),
                     Some(err) => (
-                        NormalizedEntryError::Other,
+                        NormalizedEntryError::classify(&err.raw.to_string()),
                         format!("OpenCode session error: {}", err.raw),
                     ),
                     None => (
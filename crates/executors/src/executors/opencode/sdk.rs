@@ -29,6 +29,7 @@ use crate::{
         ExecutorError,
         opencode::{OpencodeServer, models::maybe_emit_token_usage},
     },
+    steering::SteerReceiver,
 };
 
 #[derive(Clone)]
@@ -215,6 +216,7 @@ pub enum ControlEvent {
 pub async fn run_session(
     config: RunConfig,
     log_writer: LogWriter,
+    steer: SteerReceiver,
     cancel: CancellationToken,
 ) -> Result<(), ExecutorError> {
     let client = reqwest::Client::builder()
@@ -225,7 +227,7 @@ pub async fn run_session(
         .build()
         .map_err(|err| ExecutorError::Io(io::Error::other(err)))?;
 
-    run_session_inner(config, log_writer, client, cancel).await
+    run_session_inner(config, log_writer, client, steer, cancel).await
 }
 
 pub(super) async fn discover_commands(
@@ -263,6 +265,7 @@ async fn run_session_inner(
     config: RunConfig,
     log_writer: LogWriter,
     client: reqwest::Client,
+    mut steer: SteerReceiver,
     cancel: CancellationToken,
 ) -> Result<(), ExecutorError> {
     tokio::select! {
@@ -313,27 +316,50 @@ async fn run_session_inner(
         event_resp,
     ));
 
-    let prompt_fut = Box::pin(prompt(
-        &client,
-        &config.base_url,
-        &config.directory,
-        &session_id,
-        &config.prompt,
-        model.clone(),
-        config.model_variant.clone(),
-        config.agent.clone(),
-    ));
-    let prompt_result = run_request_with_control(prompt_fut, &mut control_rx, cancel.clone()).await;
+    // Messages sent while a prompt runs are sent together as the next prompt
+    let mut next_prompt = config.prompt.clone();
+    loop {
+        let prompt_fut = Box::pin(prompt(
+            &client,
+            &config.base_url,
+            &config.directory,
+            &session_id,
+            &next_prompt,
+            model.clone(),
+            config.model_variant.clone(),
+            config.agent.clone(),
+        ));
+        let mut queued = Vec::new();
+        let prompt_result = run_request_with_steering(
+            prompt_fut,
+            &mut control_rx,
+            &mut steer,
+            &mut queued,
+            SteerTarget {
+                client: &client,
+                config: &config,
+                session_id: &session_id,
+                log_writer: &log_writer,
+            },
+            cancel.clone(),
+        )
+        .await;
 
-    if cancel.is_cancelled() {
-        send_abort(&client, &config.base_url, &config.directory, &session_id).await;
-        event_handle.abort();
-        return Ok(());
-    }
+        if cancel.is_cancelled() {
+            send_abort(&client, &config.base_url, &config.directory, &session_id).await;
+            event_handle.abort();
+            return Ok(());
+        }
 
-    if let Err(err) = prompt_result {
-        event_handle.abort();
-        return Err(err);
+        if let Err(err) = prompt_result {
+            event_handle.abort();
+            return Err(err);
+        }
+
+        if queued.is_empty() {
+            break;
+        }
+        next_prompt = queued.join("\n\n");
     }
 
     // Handle commit reminder if enabled
@@ -404,6 +430,57 @@ fn append_session_error(session_error: &mut Option<String>, message: String) {
     }
 }
 
+struct SteerTarget<'a> {
+    client: &'a reqwest::Client,
+    config: &'a RunConfig,
+    session_id: &'a str,
+    log_writer: &'a LogWriter,
+}
+
+/// [`run_request_with_control`], queueing the messages the user sends
+/// meanwhile. An interrupt aborts the running prompt, whose outcome is then
+/// ignored.
+async fn run_request_with_steering<F>(
+    request_fut: F,
+    control_rx: &mut mpsc::UnboundedReceiver<ControlEvent>,
+    steer: &mut SteerReceiver,
+    queued: &mut Vec<String>,
+    target: SteerTarget<'_>,
+    cancel: CancellationToken,
+) -> Result<(), ExecutorError>
+where
+    F: Future<Output = Result<(), ExecutorError>> + Unpin,
+{
+    let request = run_request_with_control(request_fut, control_rx, cancel);
+    tokio::pin!(request);
+    let mut interrupted = false;
+
+    loop {
+        tokio::select! {
+            res = &mut request => return if interrupted { Ok(()) } else { res },
+            Some(steer_request) = steer.recv() => {
+                target
+                    .log_writer
+                    .log_event(&OpencodeExecutorEvent::UserMessage {
+                        content: steer_request.prompt.clone(),
+                    })
+                    .await?;
+                queued.push(steer_request.prompt);
+                if steer_request.interrupt && !interrupted {
+                    interrupted = true;
+                    send_abort(
+                        target.client,
+                        &target.config.base_url,
+                        &target.config.directory,
+                        target.session_id,
+                    )
+                    .await;
+                }
+            }
+        }
+    }
+}
+
 pub async fn run_request_with_control<F>(
     mut request_fut: F,
     control_rx: &mut mpsc::UnboundedReceiver<ControlEvent>,
@@ -1461,3 +1538,124 @@ async fn request_permission_approval(
         Err(err) => Err(err),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncReadExt, BufReader},
+        net::TcpListener,
+        sync::oneshot,
+    };
+
+    use super::*;
+    use crate::steering::{SteerRequest, steer_channel};
+
+    fn steer_request(prompt: &str, interrupt: bool) -> SteerRequest {
+        SteerRequest {
+            prompt: prompt.to_string(),
+            interrupt,
+        }
+    }
+
+    #[tokio::test]
+    async fn steering_queues_messages_and_aborts_the_prompt_once() {
+        // Stands in for the OpenCode server, reporting each request line
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (requests_tx, mut requests_rx) = mpsc::unbounded_channel::<String>();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = vec![0; 4096];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                let head = String::from_utf8_lossy(&buf[..n]);
+                let _ = requests_tx.send(head.lines().next().unwrap_or_default().to_string());
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                    .await;
+            }
+        });
+
+        let config = RunConfig {
+            base_url,
+            directory: "/tmp".to_string(),
+            prompt: "fix the parser".to_string(),
+            resume_session_id: None,
+            model: None,
+            model_variant: None,
+            agent: None,
+            approvals: None,
+            auto_approve: true,
+            server_password: String::new(),
+            models_cache_key: String::new(),
+            commit_reminder: false,
+            commit_reminder_prompt: String::new(),
+            repo_context: RepoContext::default(),
+        };
+        let client = reqwest::Client::new();
+        let (log_write, log_read) = tokio::io::duplex(4096);
+        let log_writer = LogWriter::new(log_write);
+        let mut logged = BufReader::new(log_read).lines();
+        let (_control_tx, mut control_rx) = mpsc::unbounded_channel::<ControlEvent>();
+        let (steer_tx, mut steer_rx) = steer_channel();
+
+        // The prompt runs until the test ends it, then fails as aborted
+        let (done_tx, done_rx) = oneshot::channel::<()>();
+        let prompt_fut = Box::pin(async move {
+            let _ = done_rx.await;
+            Err(ExecutorError::Io(io::Error::other("prompt aborted")))
+        });
+
+        steer_tx
+            .send(steer_request("also update the docs", false))
+            .unwrap();
+        steer_tx
+            .send(steer_request("use the v2 API", true))
+            .unwrap();
+
+        let driver = async {
+            for prompt in ["also update the docs", "use the v2 API"] {
+                let line = logged.next_line().await.unwrap().unwrap();
+                assert!(line.contains(prompt), "{line}");
+            }
+            let abort = requests_rx.recv().await.unwrap();
+            assert!(abort.starts_with("POST /session/ses_test/abort"), "{abort}");
+
+            // A second interrupt is queued without aborting again
+            steer_tx
+                .send(steer_request("and run the tests", true))
+                .unwrap();
+            let line = logged.next_line().await.unwrap().unwrap();
+            assert!(line.contains("and run the tests"), "{line}");
+            done_tx.send(()).unwrap();
+        };
+
+        let mut queued = Vec::new();
+        let (result, ()) = tokio::join!(
+            run_request_with_steering(
+                prompt_fut,
+                &mut control_rx,
+                &mut steer_rx,
+                &mut queued,
+                SteerTarget {
+                    client: &client,
+                    config: &config,
+                    session_id: "ses_test",
+                    log_writer: &log_writer,
+                },
+                CancellationToken::new(),
+            ),
+            driver
+        );
+
+        assert!(result.is_ok(), "the aborted prompt's failure is ignored");
+        assert_eq!(
+            queued,
+            vec![
+                "also update the docs",
+                "use the v2 API",
+                "and run the tests"
+            ]
+        );
+        assert!(requests_rx.try_recv().is_err(), "aborted more than once");
+    }
+}
@@ -29,6 +29,10 @@ pub enum OpencodeExecutorEvent {
     SystemMessage {
         content: String,
     },
+    /// A message the user sent while the session was running
+    UserMessage {
+        content: String,
+    },
     Error {
         message: String,
     },
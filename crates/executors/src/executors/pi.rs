@@ -131,7 +131,7 @@ async fn spawn_pi(
     env: &ExecutionEnv,
     cmd_overrides: &crate::command::CmdOverrides,
 ) -> Result<SpawnedChild, ExecutorError> {
-    let (program_path, args) = command_parts.into_resolved().await?;
+    let (program_path, args) = command_parts.into_resolved(env).await?;
 
     let mut command = Command::new(program_path);
     command
@@ -17,6 +17,7 @@ use crate::logs::{
     ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
     NormalizedEntryError, NormalizedEntryType, ToolStatus,
     plain_text_processor::PlainTextLogProcessor,
+    token_usage::{DEFAULT_CONTEXT_WINDOW, token_usage_entry, usage_total_tokens},
     utils::{
         EntryIndexProvider,
         patch::{add_normalized_entry, replace_normalized_entry},
@@ -228,11 +229,24 @@ pub fn normalize_logs(
                     }
                 }
 
+                PiEvent::MessageEnd { message } => {
+                    // Assistant messages carry the usage of the model call that produced them
+                    if message.get("role").and_then(Value::as_str) == Some("assistant")
+                        && let Some(total) = message.get("usage").and_then(usage_total_tokens)
+                    {
+                        add_normalized_entry(
+                            &msg_store,
+                            &entry_index_provider,
+                            token_usage_entry(total, DEFAULT_CONTEXT_WINDOW),
+                        );
+                    }
+                }
+
                 PiEvent::Error { error } => {
                     let entry = NormalizedEntry {
                         timestamp: None,
                         entry_type: NormalizedEntryType::ErrorMessage {
-                            error_type: NormalizedEntryError::Other,
+                            error_type: NormalizedEntryError::classify(&error),
                         },
                         content: error,
                         metadata: None,
@@ -277,7 +291,7 @@ pub fn normalize_logs(
                 }
 
                 _ => {
-                    // Forward-compatible: MessageStart, MessageEnd, TurnStart,
+                    // Forward-compatible: MessageStart, TurnStart,
                     // ToolExecutionUpdate, and any future event types are
                     // silently ignored.
                 }
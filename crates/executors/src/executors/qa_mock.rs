@@ -4,8 +4,15 @@
 //! 1. Performs random file operations (create, delete, modify)
 //! 2. Streams 10 mock log entries over 10 seconds
 //! 3. Outputs logs in ClaudeJson format for compatibility with existing log normalization
+//!
+//! With `VK_QA_REPLAY_FIXTURE` pointing at a recorded fixture, it runs the
+//! fixture with [`ReplayExecutor`] instead.
 
-use std::{path::Path, process::Stdio, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+};
 
 use async_trait::async_trait;
 use command_group::AsyncCommandGroup;
@@ -25,8 +32,16 @@ use crate::{
         },
     },
     logs::utils::EntryIndexProvider,
+    replay::{Fixture, ReplayExecutor},
 };
 
+/// Fixture replayed instead of the generated Claude run when set
+pub const REPLAY_FIXTURE_ENV: &str = "VK_QA_REPLAY_FIXTURE";
+
+fn replay_fixture_path() -> Option<PathBuf> {
+    std::env::var_os(REPLAY_FIXTURE_ENV).map(PathBuf::from)
+}
+
 /// Mock executor for QA testing
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, TS, JsonSchema)]
 pub struct QaMockExecutor;
@@ -37,8 +52,16 @@ impl StandardCodingAgentExecutor for QaMockExecutor {
         &self,
         current_dir: &Path,
         prompt: &str,
-        _env: &ExecutionEnv,
+        env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
+        if let Some(fixture_path) = replay_fixture_path() {
+            info!("QA Mock Executor: replaying fixture {:?}", fixture_path);
+            return ReplayExecutor::load(&fixture_path)
+                .await?
+                .spawn(current_dir, prompt, env)
+                .await;
+        }
+
         info!("QA Mock Executor: spawning mock execution");
 
         // 1. Perform file operations before spawning the log output process
@@ -88,6 +111,19 @@ impl StandardCodingAgentExecutor for QaMockExecutor {
     }
 
     fn normalize_logs(&self, msg_store: Arc<MsgStore>, current_dir: &Path) {
+        if let Some(fixture_path) = replay_fixture_path() {
+            // Normalize as the executor that produced the recorded output
+            match std::fs::read_to_string(&fixture_path)
+                .map_err(ExecutorError::Io)
+                .and_then(|content| {
+                    serde_json::from_str::<Fixture>(&content).map_err(ExecutorError::from)
+                }) {
+                Ok(fixture) => ReplayExecutor::new(fixture).normalize_logs(msg_store, current_dir),
+                Err(e) => warn!("QA Mock: failed to load fixture {:?}: {}", fixture_path, e),
+            }
+            return;
+        }
+
         // Reuse Claude's log processor since we output ClaudeJson format
         let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
         crate::executors::claude::ClaudeLogProcessor::process_logs(
@@ -208,6 +244,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
                     thinking: "Analyzing the QA task and preparing mock execution...".to_string(),
                 }]),
                 stop_reason: None,
+                usage: None,
             },
             session_id: Some(session_id.clone()),
             uuid: Some("uuid-qa-1".to_string()),
@@ -226,6 +263,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
                     },
                 }]),
                 stop_reason: None,
+                usage: None,
             },
             session_id: Some(session_id.clone()),
             uuid: Some("uuid-qa-2".to_string()),
@@ -245,6 +283,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
                     is_error: Some(false),
                 }]),
                 stop_reason: None,
+                usage: None,
             },
             is_synthetic: false,
             is_replay: false,
@@ -266,6 +305,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
                     },
                 }]),
                 stop_reason: None,
+                usage: None,
             },
             session_id: Some(session_id.clone()),
             uuid: Some("uuid-qa-4".to_string()),
@@ -283,6 +323,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
                     is_error: Some(false),
                 }]),
                 stop_reason: None,
+                usage: None,
             },
             session_id: Some(session_id.clone()),
             uuid: Some("uuid-qa-5".to_string()),
@@ -304,6 +345,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
                     },
                 }]),
                 stop_reason: None,
+                usage: None,
             },
             session_id: Some(session_id.clone()),
             uuid: Some("uuid-qa-6".to_string()),
@@ -321,6 +363,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
                     is_error: Some(false),
                 }]),
                 stop_reason: None,
+                usage: None,
             },
             is_synthetic: false,
             session_id: Some(session_id.clone()),
@@ -341,6 +384,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
                     ),
                 }]),
                 stop_reason: Some("end_turn".to_string()),
+                usage: None,
             },
             session_id: Some(session_id.clone()),
             uuid: Some("uuid-qa-8".to_string()),
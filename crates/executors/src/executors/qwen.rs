@@ -17,6 +17,9 @@ use crate::{
     },
 };
 
+/// Context window of Qwen3-Coder
+const QWEN_CONTEXT_WINDOW: u32 = 262_144;
+
 #[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
 #[derivative(Debug, PartialEq)]
 pub struct QwenCode {
@@ -106,7 +109,7 @@ impl StandardCodingAgentExecutor for QwenCode {
     }
 
     fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
-        crate::executors::acp::normalize_logs(msg_store, worktree_path);
+        crate::executors::acp::normalize_logs(msg_store, worktree_path, QWEN_CONTEXT_WINDOW);
     }
 
     // MCP configuration methods
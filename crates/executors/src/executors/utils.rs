@@ -1,10 +1,11 @@
 use std::{
     num::NonZeroUsize,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex, OnceLock},
     time::{Duration, Instant},
 };
 
+use git::GitService;
 use lru::LruCache;
 
 use super::SlashCommandDescription;
@@ -130,3 +131,16 @@ impl SlashCommandCache {
         );
     }
 }
+
+/// Whether a file in a workspace was generated by vibe-kanban, so it can be
+/// rewritten: it is either outside any repository or was excluded from git
+/// when it was written. Files a repository tracks or ignores itself are not.
+pub async fn is_generated_file(path: &Path) -> bool {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let git = GitService::new();
+        git.is_path_excluded(&path) || !git.is_in_worktree(&path)
+    })
+    .await
+    .unwrap_or(false)
+}
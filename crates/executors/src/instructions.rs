@@ -0,0 +1,212 @@
+//! Rendering of project and task instructions into the instructions file each
+//! coding agent reads.
+//!
+//! The instructions are kept in a marked block, so a generated file can also
+//! hold other content (such as the `@import` lines of a workspace `CLAUDE.md`)
+//! and the block can be replaced or removed on the next run. Files are only
+//! written where they do not exist yet or were generated before; new files are
+//! excluded from git so they never end up in a commit.
+
+use std::path::Path;
+
+use git::GitService;
+use tokio::fs;
+
+use crate::executors::{BaseCodingAgent, utils::is_generated_file};
+
+const BLOCK_START: &str = "<!-- vibe-kanban:instructions:start -->";
+const BLOCK_END: &str = "<!-- vibe-kanban:instructions:end -->";
+
+/// The instructions file an agent reads, relative to its working directory,
+/// and the front matter the file needs
+struct InstructionsFile {
+    path: &'static str,
+    front_matter: Option<&'static str>,
+}
+
+fn instructions_file(agent: BaseCodingAgent) -> Option<InstructionsFile> {
+    let (path, front_matter) = match agent {
+        // Rule directories hold any number of files, so these never clash with
+        // the repository's own instructions
+        BaseCodingAgent::ClaudeCode => (".claude/rules/vibe-kanban.md", None),
+        BaseCodingAgent::CursorAgent => (
+            ".cursor/rules/vibe-kanban.mdc",
+            Some("---\ndescription: Vibe Kanban project instructions\nalwaysApply: true\n---"),
+        ),
+        BaseCodingAgent::Copilot => (
+            ".github/instructions/vibe-kanban.instructions.md",
+            Some("---\napplyTo: \"**\"\n---"),
+        ),
+        BaseCodingAgent::Gemini => ("GEMINI.md", None),
+        BaseCodingAgent::QwenCode => ("QWEN.md", None),
+        // Passed to aider with --read
+        BaseCodingAgent::Aider => ("CONVENTIONS.md", None),
+        BaseCodingAgent::Codex
+        | BaseCodingAgent::Opencode
+        | BaseCodingAgent::Amp
+        | BaseCodingAgent::Droid
+        | BaseCodingAgent::Pi => ("AGENTS.md", None),
+        // The one file most agents agree on
+        BaseCodingAgent::Custom => ("AGENTS.md", None),
+        #[cfg(feature = "qa-mode")]
+        BaseCodingAgent::QaMock => return None,
+    };
+    Some(InstructionsFile { path, front_matter })
+}
+
+/// Render `instructions` into the instructions file `agent` reads in `dir`, or
+/// remove earlier instructions when there are none.
+///
+/// Returns `false` if the instructions could not be written because the
+/// repository has its own file there, so the caller can pass them on another
+/// way.
+pub async fn write_agent_instructions(
+    agent: BaseCodingAgent,
+    dir: &Path,
+    instructions: Option<&str>,
+) -> bool {
+    let Some(file) = instructions_file(agent) else {
+        return true;
+    };
+    let path = dir.join(file.path);
+    let instructions = instructions.map(str::trim).filter(|i| !i.is_empty());
+
+    let existing = match fs::read_to_string(&path).await {
+        Ok(existing) => Some(existing),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => {
+            tracing::warn!("Failed to read {}: {}", path.display(), e);
+            return instructions.is_none();
+        }
+    };
+
+    let content = match (&existing, instructions) {
+        (None, None) => return true,
+        (Some(_), _) if !is_generated_file(&path).await => {
+            if instructions.is_some() {
+                tracing::debug!(
+                    "{} belongs to the repository, not writing instructions to it",
+                    path.display()
+                );
+            }
+            return instructions.is_none();
+        }
+        (existing, instructions) => {
+            let existing = existing
+                .as_deref()
+                .or(file.front_matter)
+                .unwrap_or_default();
+            replace_block(existing, instructions)
+        }
+    };
+
+    if existing.as_deref() == Some(content.as_str()) {
+        return true;
+    }
+    // Nothing but what vibe-kanban put there is left
+    if content.trim() == file.front_matter.unwrap_or_default() {
+        if let Err(e) = fs::remove_file(&path).await {
+            tracing::warn!("Failed to remove {}: {}", path.display(), e);
+        }
+        return true;
+    }
+    if let Err(e) = write(&path, &content, existing.is_none()).await {
+        tracing::warn!("Failed to write instructions to {}: {}", path.display(), e);
+        return false;
+    }
+    true
+}
+
+async fn write(path: &Path, content: &str, created: bool) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    fs::write(path, content).await?;
+
+    if created {
+        let path = path.to_path_buf();
+        let excluded =
+            tokio::task::spawn_blocking(move || GitService::new().exclude_path(&path)).await;
+        if let Ok(Err(e)) = excluded {
+            tracing::warn!("Failed to exclude instructions file from git: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// `content` with its instructions block replaced by `instructions`, appended
+/// if it has none, or removed when `instructions` is `None`
+fn replace_block(content: &str, instructions: Option<&str>) -> String {
+    let block = instructions.map(|i| format!("{BLOCK_START}\n{i}\n{BLOCK_END}\n"));
+
+    let (before, after) = match (content.find(BLOCK_START), content.find(BLOCK_END)) {
+        (Some(start), Some(end)) if start < end => {
+            let after = content[end + BLOCK_END.len()..].trim_start_matches('\n');
+            (&content[..start], after)
+        }
+        _ => (content, ""),
+    };
+
+    let mut result = before.trim_end().to_string();
+    if let Some(block) = block {
+        if !result.is_empty() {
+            result.push_str("\n\n");
+        }
+        result.push_str(&block);
+    } else if !result.is_empty() {
+        result.push('\n');
+    }
+    if !after.is_empty() {
+        if !result.is_empty() {
+            result.push('\n');
+        }
+        result.push_str(after);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace_block_appends_replaces_and_removes() {
+        let imports = "@repo/CLAUDE.md\n";
+
+        let added = replace_block(imports, Some("Use pnpm."));
+        assert_eq!(
+            added,
+            format!("@repo/CLAUDE.md\n\n{BLOCK_START}\nUse pnpm.\n{BLOCK_END}\n")
+        );
+
+        let replaced = replace_block(&added, Some("Use npm."));
+        assert_eq!(
+            replaced,
+            format!("@repo/CLAUDE.md\n\n{BLOCK_START}\nUse npm.\n{BLOCK_END}\n")
+        );
+
+        assert_eq!(replace_block(&replaced, None), imports);
+        assert_eq!(
+            replace_block(&format!("{BLOCK_START}\nold\n{BLOCK_END}\n"), None),
+            ""
+        );
+    }
+
+    #[tokio::test]
+    async fn writes_and_removes_instructions_outside_repositories() {
+        let dir = std::env::temp_dir().join(format!("instructions-test-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(".cursor/rules/vibe-kanban.mdc");
+
+        assert!(
+            write_agent_instructions(BaseCodingAgent::CursorAgent, &dir, Some("Be brief.")).await
+        );
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("---\ndescription:"));
+        assert!(content.contains("Be brief."));
+
+        assert!(write_agent_instructions(BaseCodingAgent::CursorAgent, &dir, None).await);
+        assert!(!path.exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
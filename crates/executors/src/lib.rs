@@ -1,9 +1,16 @@
 pub mod actions;
+pub mod approval_hook;
 pub mod approvals;
 pub mod command;
 pub mod env;
 pub mod executors;
+pub mod instructions;
 pub mod logs;
 pub mod mcp_config;
+pub mod oci;
 pub mod profile;
+pub mod replay;
+pub mod resource_limits;
+pub mod sandbox;
 pub mod stdout_dup;
+pub mod steering;
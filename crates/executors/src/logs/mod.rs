@@ -5,6 +5,7 @@ use workspace_utils::approvals::ApprovalStatus;
 
 pub mod plain_text_processor;
 pub mod stderr_processor;
+pub mod token_usage;
 pub mod utils;
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -67,9 +68,47 @@ pub struct NormalizedConversation {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum NormalizedEntryError {
     SetupRequired,
+    /// The agent hit a rate limit or ran out of quota
+    RateLimited,
     Other,
 }
 
+/// Lowercase fragments of the messages agents and model APIs report rate
+/// limits and exhausted quotas with
+const RATE_LIMIT_MARKERS: &[&str] = &[
+    "rate limit exceeded",
+    "rate limit reached",
+    "rate limited",
+    "rate_limit_exceeded",
+    "rate_limit_error",
+    "ratelimiterror",
+    "usage limit reached",
+    "usage limit exceeded",
+    "too many requests",
+    "quota exceeded",
+    "exceeded your current quota",
+    "insufficient_quota",
+    "resource_exhausted",
+    "resource exhausted",
+];
+
+impl NormalizedEntryError {
+    /// `RateLimited` for an error message reporting a rate limit or exhausted
+    /// quota, `Other` for anything else. Only meant for the error and result
+    /// events agents emit, never for raw stdout/stderr lines.
+    pub fn classify(message: &str) -> Self {
+        let message = message.to_lowercase();
+        if RATE_LIMIT_MARKERS
+            .iter()
+            .any(|marker| message.contains(marker))
+        {
+            Self::RateLimited
+        } else {
+            Self::Other
+        }
+    }
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -243,3 +282,41 @@ pub enum FileChange {
         has_line_numbers: bool,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_detects_rate_limits() {
+        for message in [
+            "Claude AI usage limit reached|1760000000",
+            "Error: 429 Too Many Requests",
+            "You exceeded your current quota, please check your plan",
+            "stream error: rate_limit_exceeded",
+        ] {
+            assert_eq!(
+                NormalizedEntryError::classify(message),
+                NormalizedEntryError::RateLimited,
+                "{message}"
+            );
+        }
+    }
+
+    #[test]
+    fn classify_ignores_other_limits() {
+        for message in [
+            "error: could not find Cargo.toml",
+            "Context limit reached, compacting conversation",
+            "Response size limit reached, output truncated",
+            "thread 'rate_limiter::tests::refill' panicked at src/rate_limit.rs:12",
+            "Configure the rate limit for the API in settings.toml",
+        ] {
+            assert_eq!(
+                NormalizedEntryError::classify(message),
+                NormalizedEntryError::Other,
+                "{message}"
+            );
+        }
+    }
+}
@@ -0,0 +1,322 @@
+//! Context usage reporting for the context meter.
+//!
+//! Agents whose output carries token counts report them with
+//! [`token_usage_entry`]. For the others, [`estimate_context_usage`] counts the
+//! tokens of the conversation's entries with the cl100k tokenizer instead,
+//! until the agent reports real usage.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use futures::StreamExt;
+use serde_json::{Value, json};
+use tiktoken_rs::cl100k_base_singleton;
+use workspace_utils::{log_msg::LogMsg, msg_store::MsgStore};
+
+use super::{
+    NormalizedEntry, NormalizedEntryType, TokenUsageInfo,
+    utils::{
+        EntryIndexProvider,
+        patch::{add_normalized_entry, extract_normalized_entry_from_patch},
+    },
+};
+
+/// Context window assumed when the agent does not say which model it runs
+pub const DEFAULT_CONTEXT_WINDOW: u32 = 200_000;
+
+const ESTIMATED_KEY: &str = "estimated";
+
+/// Quiet period after which an estimate is reported
+const REPORT_DELAY: Duration = Duration::from_secs(1);
+
+/// Tokens of the system prompt and tool definitions an agent sends with every
+/// request. Agents do not log them, so estimates start from here.
+const SYSTEM_PROMPT_TOKENS: u32 = 12_000;
+
+pub fn token_usage_entry(total_tokens: u32, model_context_window: u32) -> NormalizedEntry {
+    NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::TokenUsageInfo(TokenUsageInfo {
+            total_tokens,
+            model_context_window,
+        }),
+        content: format!(
+            "Tokens used: {} / Context window: {}",
+            total_tokens, model_context_window
+        ),
+        metadata: None,
+    }
+}
+
+/// Context usage that was estimated rather than reported by the agent
+pub fn estimated_usage_entry(total_tokens: u32, model_context_window: u32) -> NormalizedEntry {
+    NormalizedEntry {
+        metadata: Some(json!({ ESTIMATED_KEY: true })),
+        ..token_usage_entry(total_tokens, model_context_window)
+    }
+}
+
+fn is_estimate(entry: &NormalizedEntry) -> bool {
+    entry
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get(ESTIMATED_KEY))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Tokens in the context after a model call, from a usage object in any of
+/// the shapes agents report: `input_tokens`/`inputTokens`/`input` and the
+/// output and cache counts alongside, or a `total_tokens`/`totalTokens` total
+pub fn usage_total_tokens(usage: &Value) -> Option<u32> {
+    let count = |keys: &[&str]| keys.iter().find_map(|key| usage.get(*key)?.as_u64());
+
+    if let Some(total) = count(&["total_tokens", "totalTokens"]) {
+        return u32::try_from(total).ok().filter(|total| *total > 0);
+    }
+    let total = [
+        count(&["input_tokens", "inputTokens", "input"]),
+        count(&["output_tokens", "outputTokens", "output"]),
+        count(&["cache_read_input_tokens", "cacheReadTokens", "cacheRead"]),
+        count(&[
+            "cache_creation_input_tokens",
+            "cacheWriteTokens",
+            "cacheWrite",
+        ]),
+    ]
+    .into_iter()
+    .flatten()
+    .sum::<u64>();
+    u32::try_from(total).ok().filter(|total| *total > 0)
+}
+
+/// Token count of `text` in cl100k, which tracks the tokenizers of the models
+/// agents run closely enough for the context meter
+pub fn estimate_tokens(text: &str) -> u32 {
+    let tokens = cl100k_base_singleton().encode_ordinary(text).len();
+    u32::try_from(tokens).unwrap_or(u32::MAX)
+}
+
+fn entry_tokens(entry: &NormalizedEntry) -> u32 {
+    let extra = match &entry.entry_type {
+        NormalizedEntryType::TokenUsageInfo(_)
+        | NormalizedEntryType::Loading
+        | NormalizedEntryType::NextAction { .. } => return 0,
+        NormalizedEntryType::ToolUse { action_type, .. } => serde_json::to_string(action_type)
+            .map(|action| estimate_tokens(&action))
+            .unwrap_or(0),
+        _ => 0,
+    };
+    estimate_tokens(&entry.content).saturating_add(extra)
+}
+
+/// The latest context usage in `history`, estimated or reported by the agent
+pub fn latest_usage(history: &[LogMsg]) -> Option<TokenUsageInfo> {
+    history.iter().rev().find_map(|msg| match msg {
+        LogMsg::JsonPatch(patch) => {
+            match extract_normalized_entry_from_patch(patch)?.1.entry_type {
+                NormalizedEntryType::TokenUsageInfo(usage) => Some(usage),
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
+
+/// Token counts of a conversation's entries, kept current as they change
+struct ContextEstimate {
+    /// Tokens the count starts from
+    baseline: u32,
+    tokens_by_entry: HashMap<usize, u32>,
+    /// Latest version of each entry changed since the last count
+    changed: HashMap<usize, NormalizedEntry>,
+    reported: Option<u32>,
+}
+
+impl ContextEstimate {
+    /// Count the changed entries off the async runtime, as tokenizing is CPU
+    /// bound, and report the total unless it is already reported
+    async fn report(
+        &mut self,
+        msg_store: &Arc<MsgStore>,
+        entry_index_provider: &EntryIndexProvider,
+        model_context_window: u32,
+    ) {
+        let changed = std::mem::take(&mut self.changed);
+        match tokio::task::spawn_blocking(move || {
+            changed
+                .into_iter()
+                .map(|(index, entry)| (index, entry_tokens(&entry)))
+                .collect::<Vec<_>>()
+        })
+        .await
+        {
+            Ok(counted) => self.tokens_by_entry.extend(counted),
+            Err(e) => tracing::warn!("Failed to count context tokens: {e}"),
+        }
+
+        let total = self
+            .tokens_by_entry
+            .values()
+            .fold(self.baseline, |total, tokens| total.saturating_add(*tokens));
+        if self.reported != Some(total) {
+            self.reported = Some(total);
+            add_normalized_entry(
+                msg_store,
+                entry_index_provider,
+                estimated_usage_entry(total, model_context_window),
+            );
+        }
+    }
+}
+
+/// Report context usage estimated from the conversation's entries, for agents
+/// whose output carries no token counts. The entries of the running turn are
+/// counted on top of the usage already in `msg_store`, which follow-ups carry
+/// over from the session's previous turn, or of [`SYSTEM_PROMPT_TOKENS`]. An
+/// estimate is reported once the conversation goes quiet and as soon as the
+/// run finishes. Estimates stop as soon as the agent reports usage itself.
+pub fn estimate_context_usage(
+    msg_store: Arc<MsgStore>,
+    entry_index_provider: EntryIndexProvider,
+    model_context_window: u32,
+) {
+    let baseline = latest_usage(&msg_store.get_history())
+        .map_or(SYSTEM_PROMPT_TOKENS, |usage| usage.total_tokens);
+
+    tokio::spawn(async move {
+        let mut stream = msg_store.history_plus_stream();
+        let mut estimate = ContextEstimate {
+            baseline,
+            tokens_by_entry: HashMap::new(),
+            changed: HashMap::new(),
+            reported: None,
+        };
+        let mut finished = false;
+
+        loop {
+            // Report once the conversation goes quiet rather than on every entry.
+            // Entries normalized from a finished process's logs can arrive after
+            // `Finished`, so stop only once none follow for a while.
+            let next = if !estimate.changed.is_empty() || finished {
+                tokio::time::timeout(REPORT_DELAY, stream.next()).await
+            } else {
+                Ok(stream.next().await)
+            };
+
+            let patch = match next {
+                Ok(Some(Ok(LogMsg::JsonPatch(patch)))) => patch,
+                Ok(Some(Ok(LogMsg::Finished))) => {
+                    finished = true;
+                    if !estimate.changed.is_empty() {
+                        estimate
+                            .report(&msg_store, &entry_index_provider, model_context_window)
+                            .await;
+                    }
+                    continue;
+                }
+                Ok(Some(_)) => continue,
+                Ok(None) | Err(_) => {
+                    if !estimate.changed.is_empty() {
+                        estimate
+                            .report(&msg_store, &entry_index_provider, model_context_window)
+                            .await;
+                    }
+                    if finished || next.is_ok() {
+                        break;
+                    }
+                    continue;
+                }
+            };
+
+            let Some((index, entry)) = extract_normalized_entry_from_patch(&patch) else {
+                continue;
+            };
+            if let NormalizedEntryType::TokenUsageInfo(_) = &entry.entry_type {
+                if is_estimate(&entry) {
+                    continue;
+                }
+                return;
+            }
+            estimate.changed.insert(index, entry);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::utils::patch::ConversationPatch;
+
+    #[test]
+    fn estimate_counts_cl100k_tokens() {
+        assert_eq!(
+            estimate_tokens("The quick brown fox jumps over the lazy dog."),
+            10
+        );
+        assert_eq!(estimate_tokens("fn main() {\n    println!(\"hi\");\n}"), 10);
+        assert_eq!(estimate_tokens("2025"), 2);
+        assert_eq!(estimate_tokens(""), 0);
+    }
+
+    #[test]
+    fn usage_total_reads_each_agents_shape() {
+        assert_eq!(
+            usage_total_tokens(&json!({
+                "input_tokens": 1200,
+                "cache_read_input_tokens": 30000,
+                "output_tokens": 300,
+            })),
+            Some(31500)
+        );
+        assert_eq!(
+            usage_total_tokens(&json!({ "input": 10, "output": 5, "cacheRead": 85 })),
+            Some(100)
+        );
+        assert_eq!(
+            usage_total_tokens(&json!({ "totalTokens": 4096, "inputTokens": 4000 })),
+            Some(4096)
+        );
+        assert_eq!(usage_total_tokens(&json!({})), None);
+    }
+
+    #[tokio::test]
+    async fn estimate_continues_from_the_previous_turn() {
+        let msg_store = Arc::new(MsgStore::new());
+        msg_store.push_patch(ConversationPatch::add_normalized_entry(
+            0,
+            estimated_usage_entry(30_000, DEFAULT_CONTEXT_WINDOW),
+        ));
+        let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
+        estimate_context_usage(
+            msg_store.clone(),
+            entry_index_provider.clone(),
+            DEFAULT_CONTEXT_WINDOW,
+        );
+
+        add_normalized_entry(
+            &msg_store,
+            &entry_index_provider,
+            NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::AssistantMessage,
+                content: "The quick brown fox jumps over the lazy dog.".to_string(),
+                metadata: None,
+            },
+        );
+        msg_store.push_finished();
+
+        let usage = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Some(usage) = latest_usage(&msg_store.get_history())
+                    .filter(|usage| usage.total_tokens != 30_000)
+                {
+                    return usage;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("an estimate is reported when the run finishes");
+        assert_eq!(usage.total_tokens, 30_010);
+    }
+}
@@ -5,6 +5,7 @@
 
 use std::{collections::HashMap, path::Path, sync::LazyLock};
 
+use git::GitService;
 use jsonc_parser::{
     ParseOptions,
     cst::{CstObject, CstRootNode},
@@ -14,7 +15,10 @@ use serde_json::{Map, Value};
 use tokio::fs;
 use ts_rs::TS;
 
-use crate::executors::{CodingAgent, ExecutorError};
+use crate::{
+    env::ExecutionEnv,
+    executors::{CodingAgent, ExecutorError, utils::is_generated_file},
+};
 
 fn is_jsonc_file(path: &Path) -> bool {
     path.extension()
@@ -367,7 +371,7 @@ fn adapt_copilot(mut servers: ServerMap, meta: Option<Value>) -> Value {
     attach_meta(servers, meta)
 }
 
-enum Adapter {
+pub(crate) enum Adapter {
     Passthrough,
     Gemini,
     Cursor,
@@ -392,12 +396,93 @@ fn apply_adapter(adapter: Adapter, canonical: Value) -> Value {
     }
 }
 
+/// The workspace MCP servers of `env` in the format `adapter` produces, or
+/// `None` if none are configured or the agent supports none of them
+pub(crate) fn workspace_mcp_servers(env: &ExecutionEnv, adapter: Adapter) -> Option<Value> {
+    if env.mcp_servers.is_empty() {
+        return None;
+    }
+    let canonical = Value::Object(env.mcp_servers.clone().into_iter().collect());
+    Some(apply_adapter(adapter, canonical)).filter(|servers| {
+        servers
+            .as_object()
+            .is_some_and(|servers| !servers.is_empty())
+    })
+}
+
+/// Write the workspace MCP servers to an agent's project-level config file,
+/// such as `.cursor/mcp.json`, for agents that take no servers on the command
+/// line. A file the repository already has is left alone, as it may be
+/// tracked. The written file is excluded from git.
+pub(crate) async fn write_workspace_mcp_file(
+    current_dir: &Path,
+    relative_path: &str,
+    servers: Value,
+) -> Result<(), ExecutorError> {
+    let content = serde_json::json!({ "mcpServers": servers });
+    write_workspace_config_file(current_dir, relative_path, &content)
+        .await
+        .map(|_| ())
+}
+
+/// Write an agent's project-level config file into a workspace and exclude it
+/// from git. A file the repository owns is left alone; returns whether the
+/// workspace now has `content`.
+pub(crate) async fn write_workspace_config_file(
+    current_dir: &Path,
+    relative_path: &str,
+    content: &Value,
+) -> Result<bool, ExecutorError> {
+    let path = current_dir.join(relative_path);
+    let content = serde_json::to_string_pretty(content)?;
+
+    match fs::read_to_string(&path).await {
+        Ok(existing) if existing == content => return Ok(true),
+        Ok(_) if !is_generated_file(&path).await => {
+            tracing::warn!("Not writing {}: it already exists", path.display());
+            return Ok(false);
+        }
+        _ => {}
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    fs::write(&path, content).await?;
+
+    let exclude_path = path.clone();
+    let excluded =
+        tokio::task::spawn_blocking(move || GitService::new().exclude_path(&exclude_path)).await;
+    if let Ok(Err(e)) = excluded {
+        tracing::warn!("Failed to exclude {} from git: {}", path.display(), e);
+    }
+    Ok(true)
+}
+
+/// Remove a config file written by [`write_workspace_config_file`], leaving
+/// files the repository owns alone
+pub(crate) async fn remove_workspace_config_file(
+    current_dir: &Path,
+    relative_path: &str,
+) -> Result<(), ExecutorError> {
+    let path = current_dir.join(relative_path);
+    if fs::try_exists(&path).await? && is_generated_file(&path).await {
+        fs::remove_file(&path).await?;
+    }
+    Ok(())
+}
+
 impl CodingAgent {
     pub fn preconfigured_mcp(&self) -> Value {
         use Adapter::*;
 
         let adapter = match self {
-            CodingAgent::ClaudeCode(_) | CodingAgent::Amp(_) | CodingAgent::Droid(_) | CodingAgent::Pi(_) => Passthrough,
+            CodingAgent::ClaudeCode(_)
+            | CodingAgent::Amp(_)
+            | CodingAgent::Droid(_)
+            | CodingAgent::Pi(_)
+            | CodingAgent::Aider(_)
+            | CodingAgent::Custom(_) => Passthrough,
             CodingAgent::QwenCode(_) | CodingAgent::Gemini(_) => Gemini,
             CodingAgent::CursorAgent(_) => Cursor,
             CodingAgent::Codex(_) => Codex,
@@ -411,3 +496,49 @@ impl CodingAgent {
         apply_adapter(adapter, canonical)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::env::RepoContext;
+
+    fn env_with_servers(servers: Value) -> ExecutionEnv {
+        let mut env = ExecutionEnv::new(RepoContext::default(), false, String::new());
+        env.mcp_servers = serde_json::from_value(servers).unwrap();
+        env
+    }
+
+    #[test]
+    fn workspace_mcp_servers_are_adapted_per_agent() {
+        let env = env_with_servers(json!({
+            "db": { "command": "db-mcp", "args": ["--readonly"] },
+            "docs": { "type": "http", "url": "https://docs.example.com/mcp" },
+        }));
+
+        let codex = workspace_mcp_servers(&env, Adapter::Codex).unwrap();
+        assert_eq!(
+            codex,
+            json!({ "db": { "command": "db-mcp", "args": ["--readonly"] } })
+        );
+
+        let opencode = workspace_mcp_servers(&env, Adapter::Opencode).unwrap();
+        assert_eq!(
+            opencode["db"],
+            json!({ "type": "local", "command": ["db-mcp", "--readonly"], "enabled": true })
+        );
+        assert_eq!(opencode["docs"]["type"], "remote");
+    }
+
+    #[test]
+    fn workspace_mcp_servers_are_none_when_unsupported_or_empty() {
+        let env = env_with_servers(json!({
+            "docs": { "type": "http", "url": "https://docs.example.com/mcp" },
+        }));
+        assert_eq!(workspace_mcp_servers(&env, Adapter::Codex), None);
+
+        let env = env_with_servers(json!({}));
+        assert_eq!(workspace_mcp_servers(&env, Adapter::Passthrough), None);
+    }
+}
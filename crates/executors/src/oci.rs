@@ -0,0 +1,420 @@
+//! Run workspaces inside OCI containers through the Docker or Podman CLI.
+//!
+//! Each workspace gets one long-running container. The workspace directory
+//! (and the git directories its worktrees point at) is bind-mounted at the
+//! same path it has on the host, so paths stored in the database and handed
+//! to agents are valid on both sides. Executor processes and scripts are
+//! started with `<engine> exec` inside that container, which gives every repo
+//! a reproducible toolchain defined by its image.
+
+use std::path::{Path, PathBuf};
+
+use jsonc_parser::ParseOptions;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use ts_rs::TS;
+use uuid::Uuid;
+use workspace_utils::shell::resolve_executable_path;
+
+use crate::{env::RepoContext, executors::ExecutorError, sandbox::workspace_writable_roots};
+
+/// Environment variable listing the keys forwarded into the container.
+///
+/// Set by [`crate::env::ExecutionEnv::apply_to_command`] so the values
+/// themselves never appear on the `exec` command line.
+pub const OCI_ENV_KEYS: &str = "VK_OCI_ENV";
+
+/// Environment variable that tags every process of an execution inside the
+/// container, so [`OciExec::kill_execution`] can find them.
+pub const OCI_EXECUTION_ID: &str = "VK_EXECUTION_ID";
+
+/// Sends `$2` to every process whose environment has
+/// `VK_EXECUTION_ID=$1`. Killing the host-side `exec` client does not reach
+/// them, and they outlive it.
+const KILL_SCRIPT: &str = r#"for proc in /proc/[0-9]*; do
+  if tr '\0' '\n' 2>/dev/null < "$proc/environ" | grep -qx "VK_EXECUTION_ID=$1"; then
+    kill -s "$2" "${proc#/proc/}" 2>/dev/null
+  fi
+done
+exit 0"#;
+
+/// Grace period between `TERM` and `KILL` in [`OciExec::kill_execution`]
+const KILL_GRACE: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Runs `<engine> exec` from the process' working directory, forwarding the
+/// variables named in `VK_OCI_ENV`. Positional args: engine, container,
+/// program, program args. The second loop rotates `container program args`
+/// behind the `-e` flags.
+const EXEC_SCRIPT: &str = r#"engine="$1"; shift
+n=$#
+for key in $VK_OCI_ENV; do set -- "$@" -e "$key"; done
+while [ "$n" -gt 0 ]; do set -- "$@" "$1"; shift; n=$((n - 1)); done
+exec "$engine" exec -i -w "$(pwd)" "$@""#;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OciEngine {
+    #[default]
+    Docker,
+    Podman,
+}
+
+impl OciEngine {
+    pub fn binary(&self) -> &'static str {
+        match self {
+            OciEngine::Docker => "docker",
+            OciEngine::Podman => "podman",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS, Default)]
+pub struct WorkspaceContainerConfig {
+    /// Run setup scripts, dev servers and agents inside a per-workspace container
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub engine: OciEngine,
+    /// Fallback image when no repo declares one in `.devcontainer/devcontainer.json`
+    #[serde(default)]
+    pub image: Option<String>,
+    /// Extra arguments for `<engine> run`, e.g. `--network=host` or credential mounts
+    #[serde(default)]
+    pub run_args: Vec<String>,
+}
+
+/// A running workspace container that executor processes are started in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OciExec {
+    pub engine: OciEngine,
+    pub container: String,
+}
+
+impl OciExec {
+    /// Wrap `program args` so it runs inside the container.
+    ///
+    /// The program is resolved against the container's `PATH`, not the host's.
+    pub fn wrap(&self, program: String, args: Vec<String>) -> (PathBuf, Vec<String>) {
+        let mut wrapped = vec![
+            "-c".to_string(),
+            EXEC_SCRIPT.to_string(),
+            "sh".to_string(),
+            self.engine.binary().to_string(),
+            self.container.clone(),
+            program,
+        ];
+        wrapped.extend(args);
+        (PathBuf::from("sh"), wrapped)
+    }
+
+    /// Stop the processes an execution started inside the container.
+    pub async fn kill_execution(&self, execution_id: &Uuid) -> Result<(), ExecutorError> {
+        let engine = resolve_executable_path(self.engine.binary())
+            .await
+            .ok_or_else(|| {
+                ExecutorError::ContainerUnavailable(format!(
+                    "`{}` not found in PATH",
+                    self.engine.binary()
+                ))
+            })?;
+        let execution_id = execution_id.to_string();
+        for signal in ["TERM", "KILL"] {
+            run_engine(
+                &engine,
+                [
+                    "exec",
+                    &self.container,
+                    "sh",
+                    "-c",
+                    KILL_SCRIPT,
+                    "sh",
+                    &execution_id,
+                    signal,
+                ],
+            )
+            .await?;
+            if signal == "TERM" {
+                tokio::time::sleep(KILL_GRACE).await;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn container_name(workspace_id: &Uuid) -> String {
+    format!("vk-{workspace_id}")
+}
+
+/// Start the workspace container, reusing it if it already exists.
+pub async fn ensure_workspace_container(
+    config: &WorkspaceContainerConfig,
+    workspace_id: &Uuid,
+    repo_context: &RepoContext,
+) -> Result<OciExec, ExecutorError> {
+    if cfg!(windows) {
+        return Err(ExecutorError::ContainerUnavailable(
+            "workspace containers are not supported on Windows".to_string(),
+        ));
+    }
+
+    let engine = resolve_executable_path(config.engine.binary())
+        .await
+        .ok_or_else(|| {
+            ExecutorError::ContainerUnavailable(format!(
+                "`{}` not found in PATH",
+                config.engine.binary()
+            ))
+        })?;
+    let name = container_name(workspace_id);
+
+    match run_engine(
+        &engine,
+        [
+            "container",
+            "inspect",
+            "--format",
+            "{{.State.Running}}",
+            &name,
+        ],
+    )
+    .await
+    {
+        Ok(running) if running.trim() == "true" => {}
+        Ok(_) => {
+            tracing::debug!("Restarting workspace container {name}");
+            run_engine(&engine, ["start", &name]).await?;
+        }
+        Err(_) => {
+            let image = match devcontainer_image(repo_context).await {
+                Some(image) => image,
+                None => config.image.clone().ok_or_else(|| {
+                    ExecutorError::ContainerUnavailable(
+                        "no container image configured: set one in settings or add an `image` to .devcontainer/devcontainer.json".to_string(),
+                    )
+                })?,
+            };
+            tracing::info!("Starting workspace container {name} from {image}");
+            let args = run_args(config, &name, &image, &workspace_mounts(repo_context));
+            run_engine(&engine, args.iter().map(String::as_str)).await?;
+        }
+    }
+
+    Ok(OciExec {
+        engine: config.engine,
+        container: name,
+    })
+}
+
+/// Remove a workspace's container with whichever engine knows about it.
+///
+/// Nothing is removed unless the engine has a container of that name.
+pub async fn remove_workspace_container(workspace_id: &Uuid) {
+    let name = container_name(workspace_id);
+    for engine in [OciEngine::Docker, OciEngine::Podman] {
+        let Some(path) = resolve_executable_path(engine.binary()).await else {
+            continue;
+        };
+        if run_engine(
+            &path,
+            ["container", "inspect", "--format", "{{.Id}}", &name],
+        )
+        .await
+        .is_err()
+        {
+            continue;
+        }
+        match run_engine(&path, ["rm", "-f", &name]).await {
+            Ok(_) => tracing::debug!("Removed workspace container {name}"),
+            Err(e) => tracing::warn!("Failed to remove workspace container {name}: {e}"),
+        }
+    }
+}
+
+fn run_args(
+    config: &WorkspaceContainerConfig,
+    name: &str,
+    image: &str,
+    mounts: &[PathBuf],
+) -> Vec<String> {
+    let mut args: Vec<String> = ["run", "-d", "--init", "--name", name, "--label"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    args.push(format!("vibe-kanban.container={name}"));
+
+    // Keep files written through the bind mounts owned by the host user.
+    match config.engine {
+        OciEngine::Podman => args.push("--userns=keep-id".to_string()),
+        OciEngine::Docker => {
+            if let Some(user) = mounts.first().and_then(|root| owner_of(root)) {
+                args.extend(["--user".to_string(), user]);
+            }
+        }
+    }
+
+    for mount in mounts {
+        let mount = mount.to_string_lossy();
+        args.extend(["-v".to_string(), format!("{mount}:{mount}")]);
+    }
+    args.extend(config.run_args.iter().cloned());
+    args.extend([
+        "--entrypoint".to_string(),
+        "sleep".to_string(),
+        image.to_string(),
+        "infinity".to_string(),
+    ]);
+    args
+}
+
+/// Paths to bind-mount: the workspace and git dirs, plus canonical aliases so
+/// a symlinked workspace base dir resolves the same inside the container.
+fn workspace_mounts(repo_context: &RepoContext) -> Vec<PathBuf> {
+    let mut mounts = Vec::new();
+    for root in workspace_writable_roots(repo_context) {
+        let canonical = root.canonicalize().unwrap_or_else(|_| root.clone());
+        mounts.push(root.clone());
+        if canonical != root {
+            mounts.push(canonical);
+        }
+    }
+    mounts
+}
+
+#[cfg(unix)]
+fn owner_of(path: &Path) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = std::fs::metadata(path).ok()?;
+    Some(format!("{}:{}", metadata.uid(), metadata.gid()))
+}
+
+#[cfg(not(unix))]
+fn owner_of(_path: &Path) -> Option<String> {
+    None
+}
+
+/// The `image` declared in the first repo's `.devcontainer/devcontainer.json`.
+async fn devcontainer_image(repo_context: &RepoContext) -> Option<String> {
+    for repo_path in repo_context.repo_paths() {
+        let path = repo_path.join(".devcontainer").join("devcontainer.json");
+        let Ok(contents) = tokio::fs::read_to_string(&path).await else {
+            continue;
+        };
+        if let Some(image) = parse_devcontainer_image(&contents) {
+            return Some(image);
+        }
+    }
+    None
+}
+
+fn parse_devcontainer_image(contents: &str) -> Option<String> {
+    let value = jsonc_parser::parse_to_serde_value(contents, &ParseOptions::default()).ok()??;
+    value
+        .get("image")?
+        .as_str()
+        .filter(|image| !image.trim().is_empty())
+        .map(str::to_string)
+}
+
+async fn run_engine<'a>(
+    engine: &Path,
+    args: impl IntoIterator<Item = &'a str>,
+) -> Result<String, ExecutorError> {
+    let output = Command::new(engine)
+        .args(args)
+        .output()
+        .await
+        .map_err(ExecutorError::Io)?;
+    if !output.status.success() {
+        return Err(ExecutorError::ContainerUnavailable(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_devcontainer_image_with_comments() {
+        let contents = r#"{
+            // toolchain for this repo
+            "name": "app",
+            "image": "mcr.microsoft.com/devcontainers/rust:1",
+        }"#;
+        assert_eq!(
+            parse_devcontainer_image(contents).as_deref(),
+            Some("mcr.microsoft.com/devcontainers/rust:1")
+        );
+        assert_eq!(parse_devcontainer_image(r#"{ "build": {} }"#), None);
+    }
+
+    #[test]
+    fn run_args_mount_workspace_and_keep_image_last() {
+        let config = WorkspaceContainerConfig {
+            enabled: true,
+            engine: OciEngine::Podman,
+            image: None,
+            run_args: vec!["--network=host".to_string()],
+        };
+        let args = run_args(&config, "vk-test", "node:22", &[PathBuf::from("/work/ws")]);
+
+        let joined = args.join(" ");
+        assert!(joined.starts_with("run -d --init --name vk-test"));
+        assert!(joined.contains("--userns=keep-id"));
+        assert!(joined.contains("-v /work/ws:/work/ws"));
+        assert!(joined.contains("--network=host"));
+        assert!(joined.ends_with("--entrypoint sleep node:22 infinity"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn kill_script_only_signals_the_tagged_execution() {
+        let spawn = |id: &str| {
+            Command::new("sleep")
+                .arg("30")
+                .env(OCI_EXECUTION_ID, id)
+                .kill_on_drop(true)
+                .spawn()
+                .unwrap()
+        };
+        let mut tagged = spawn("exec-1");
+        let mut other = spawn("exec-2");
+
+        let status = Command::new("sh")
+            .args(["-c", KILL_SCRIPT, "sh", "exec-1", "KILL"])
+            .status()
+            .await
+            .unwrap();
+        assert!(status.success());
+
+        assert!(tagged.wait().await.is_ok());
+        assert!(other.try_wait().unwrap().is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn exec_script_forwards_env_keys_before_container() {
+        let exec = OciExec {
+            engine: OciEngine::Docker,
+            container: "vk-test".to_string(),
+        };
+        let (program, mut args) = exec.wrap("npx".to_string(), vec!["-y".to_string()]);
+        // Swap the engine for `echo` to observe the final command line.
+        args[3] = "echo".to_string();
+
+        let output = Command::new(program)
+            .args(args)
+            .env(OCI_ENV_KEYS, "VK_TASK_ID FOO")
+            .current_dir("/")
+            .output()
+            .await
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "exec -i -w / -e VK_TASK_ID -e FOO vk-test npx -y"
+        );
+    }
+}
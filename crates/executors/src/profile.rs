@@ -116,6 +116,27 @@ impl std::fmt::Display for ExecutorProfileId {
     }
 }
 
+impl FromStr for ExecutorProfileId {
+    type Err = ProfileError;
+
+    /// Parse the `EXECUTOR` or `EXECUTOR:VARIANT` form written by `Display`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (executor, variant) = match s.trim().split_once(':') {
+            Some((executor, variant)) => (executor, Some(variant.trim())),
+            None => (s.trim(), None),
+        };
+        let norm = executor.trim().replace('-', "_").to_ascii_uppercase();
+        let executor = BaseCodingAgent::from_str(&norm)
+            .map_err(|_| ProfileError::Validation(format!("Unknown executor '{executor}'")))?;
+        Ok(Self {
+            executor,
+            variant: variant
+                .filter(|variant| !variant.is_empty())
+                .map(canonical_variant_key),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct ExecutorConfig {
     #[serde(flatten)]
@@ -401,6 +422,37 @@ impl ExecutorConfigs {
             .cloned()
     }
 
+    /// The profiles a run of `executor_profile_id` falls back to, in order.
+    /// Entries that don't parse or name no configured profile are skipped.
+    pub fn fallback_chain(
+        &self,
+        executor_profile_id: &ExecutorProfileId,
+    ) -> Vec<ExecutorProfileId> {
+        let Some(fallbacks) = self
+            .get_coding_agent(executor_profile_id)
+            .and_then(|agent| agent.cmd_overrides()?.fallback_profiles.clone())
+        else {
+            return Vec::new();
+        };
+
+        fallbacks
+            .iter()
+            .filter_map(|raw| match ExecutorProfileId::from_str(raw) {
+                Ok(id) if self.get_coding_agent(&id).is_some() => Some(id),
+                Ok(id) => {
+                    tracing::warn!(
+                        "Fallback profile '{id}' of '{executor_profile_id}' is not configured"
+                    );
+                    None
+                }
+                Err(e) => {
+                    tracing::warn!("Invalid fallback profile of '{executor_profile_id}': {e}");
+                    None
+                }
+            })
+            .collect()
+    }
+
     pub fn get_coding_agent_or_default(
         &self,
         executor_profile_id: &ExecutorProfileId,
@@ -0,0 +1,413 @@
+//! Recording of raw executor output into fixtures, and replay of fixtures
+//! through log normalization.
+//!
+//! A fixture holds the executor configuration a run used, the directory it ran
+//! in and its raw stdout/stderr. [`ReplayExecutor`] runs a process writing the
+//! recorded output and normalizes it as the executor that recorded it.
+//! Replaying a fixture normalizes its output and returns the resulting
+//! conversation, which [`assert_snapshot`] compares against a snapshot stored
+//! next to the fixture.
+//! Setting `VK_RECORD_FIXTURES_DIR` records a fixture for every coding agent
+//! run.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use command_group::AsyncCommandGroup;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use workspace_utils::{log_msg::LogMsg, msg_store::MsgStore};
+
+use crate::{
+    env::ExecutionEnv,
+    executors::{CodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
+};
+
+/// Directory fixtures of real runs are recorded into when set
+pub const RECORD_FIXTURES_DIR_ENV: &str = "VK_RECORD_FIXTURES_DIR";
+
+/// Extension of the snapshot stored next to a fixture
+const SNAPSHOT_EXTENSION: &str = "snap.json";
+
+/// How long the normalization of a replayed run may take to finish
+const NORMALIZATION_TIMEOUT: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "stream", content = "data", rename_all = "snake_case")]
+pub enum FixtureChunk {
+    Stdout(String),
+    Stderr(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fixture {
+    /// Configuration of the executor that produced the output
+    pub executor: CodingAgent,
+    /// Directory the executor ran in, which paths in its output are relative to
+    pub worktree_path: PathBuf,
+    pub chunks: Vec<FixtureChunk>,
+}
+
+/// Conversation a fixture normalizes to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Replay {
+    pub session_id: Option<String>,
+    /// Conversation entries with their timestamps removed
+    pub entries: Vec<Value>,
+}
+
+impl Fixture {
+    /// Fixture of a run from the raw output in its message store
+    pub fn from_history(executor: CodingAgent, worktree_path: PathBuf, history: &[LogMsg]) -> Self {
+        let chunks = history
+            .iter()
+            .filter_map(|msg| match msg {
+                LogMsg::Stdout(data) => Some(FixtureChunk::Stdout(data.clone())),
+                LogMsg::Stderr(data) => Some(FixtureChunk::Stderr(data.clone())),
+                _ => None,
+            })
+            .collect();
+        Self {
+            executor,
+            worktree_path,
+            chunks,
+        }
+    }
+
+    pub async fn load(path: &Path) -> Result<Self, ExecutorError> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(ExecutorError::Io)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub async fn save(&self, path: &Path) -> Result<(), ExecutorError> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(ExecutorError::Io)?;
+        }
+        let content = serde_json::to_string_pretty(self)? + "\n";
+        tokio::fs::write(path, content)
+            .await
+            .map_err(ExecutorError::Io)
+    }
+
+    pub fn stdout(&self) -> impl Iterator<Item = &str> {
+        self.chunks.iter().filter_map(|chunk| match chunk {
+            FixtureChunk::Stdout(data) => Some(data.as_str()),
+            FixtureChunk::Stderr(_) => None,
+        })
+    }
+
+    pub fn stderr(&self) -> impl Iterator<Item = &str> {
+        self.chunks.iter().filter_map(|chunk| match chunk {
+            FixtureChunk::Stderr(data) => Some(data.as_str()),
+            FixtureChunk::Stdout(_) => None,
+        })
+    }
+
+    /// Normalize the fixture's output the way a live run would be: each
+    /// recorded chunk is pushed in order and normalized before the next one,
+    /// then the run finishes and the conversation is read once every
+    /// normalization task has ended.
+    ///
+    /// Normalization runs on a single-threaded runtime of its own, so its
+    /// tasks can be waited for and stdout and stderr entries keep the order
+    /// their output was recorded in.
+    pub async fn replay(&self) -> Result<Replay, ExecutorError> {
+        let fixture = self.clone();
+        tokio::task::spawn_blocking(move || fixture.replay_blocking())
+            .await
+            .map_err(|e| ExecutorError::Io(std::io::Error::other(e)))?
+    }
+
+    fn replay_blocking(&self) -> Result<Replay, ExecutorError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(ExecutorError::Io)?;
+        let msg_store = Arc::new(MsgStore::new());
+
+        runtime.block_on(async {
+            self.executor
+                .normalize_logs(msg_store.clone(), &self.worktree_path);
+            for chunk in &self.chunks {
+                match chunk {
+                    FixtureChunk::Stdout(data) => msg_store.push_stdout(data.clone()),
+                    FixtureChunk::Stderr(data) => msg_store.push_stderr(data.clone()),
+                }
+                settle(&msg_store).await;
+            }
+            msg_store.push_finished();
+            wait_for_normalization().await
+        })?;
+
+        conversation(&msg_store.get_history())
+    }
+}
+
+/// Executor whose process writes a fixture's recorded output, normalized as
+/// the executor that recorded it. Runs do not touch the worktree.
+#[derive(Debug, Clone)]
+pub struct ReplayExecutor {
+    fixture: Fixture,
+}
+
+impl ReplayExecutor {
+    pub fn new(fixture: Fixture) -> Self {
+        Self { fixture }
+    }
+
+    pub async fn load(path: &Path) -> Result<Self, ExecutorError> {
+        Fixture::load(path).await.map(Self::new)
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for ReplayExecutor {
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        _prompt: &str,
+        _env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        // Chunks go through files to avoid shell escaping issues, named so the
+        // glob below yields them in order
+        let chunk_dir = std::env::temp_dir().join(format!("vk_replay_{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&chunk_dir)
+            .await
+            .map_err(ExecutorError::Io)?;
+        for (i, chunk) in self.fixture.chunks.iter().enumerate() {
+            let (name, data) = match chunk {
+                FixtureChunk::Stdout(data) => (format!("{i:06}.out"), data),
+                FixtureChunk::Stderr(data) => (format!("{i:06}.err"), data),
+            };
+            tokio::fs::write(chunk_dir.join(name), data)
+                .await
+                .map_err(ExecutorError::Io)?;
+        }
+
+        let script = format!(
+            r#"for f in "{dir}"/*; do case "$f" in *.err) cat "$f" >&2 ;; *) cat "$f" ;; esac; done; rm -rf "{dir}""#,
+            dir = chunk_dir.display()
+        );
+
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c")
+            .arg(&script)
+            .current_dir(current_dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let child = cmd.group_spawn().map_err(ExecutorError::Io)?;
+        Ok(SpawnedChild::from(child))
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        _session_id: &str,
+        _reset_to_message_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        // A fixture records a single run
+        self.spawn(current_dir, prompt, env).await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        self.fixture
+            .executor
+            .normalize_logs(msg_store, worktree_path);
+    }
+
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+}
+
+/// Let the normalization tasks process everything pushed so far. On a
+/// current-thread runtime a yield returns once the other runnable tasks have
+/// been polled, so normalization has settled when a yield adds nothing.
+async fn settle(msg_store: &MsgStore) {
+    loop {
+        let len = msg_store.get_history().len();
+        tokio::task::yield_now().await;
+        if msg_store.get_history().len() == len {
+            break;
+        }
+    }
+}
+
+/// Wait for every task spawned on the current runtime, which replay only uses
+/// for normalization, to end
+async fn wait_for_normalization() -> Result<(), ExecutorError> {
+    let metrics = tokio::runtime::Handle::current().metrics();
+    tokio::time::timeout(NORMALIZATION_TIMEOUT, async {
+        while metrics.num_alive_tasks() > 0 {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    })
+    .await
+    .map_err(|_| {
+        ExecutorError::Io(std::io::Error::other(format!(
+            "Normalization did not finish within {NORMALIZATION_TIMEOUT:?}"
+        )))
+    })
+}
+
+/// Apply the normalization patches in `history` to an empty conversation
+fn conversation(history: &[LogMsg]) -> Result<Replay, ExecutorError> {
+    let mut document = json!({ "entries": [] });
+    let mut session_id = None;
+
+    for msg in history {
+        match msg {
+            LogMsg::JsonPatch(patch) => json_patch::patch(&mut document, patch)
+                .map_err(|e| ExecutorError::Io(std::io::Error::other(e)))?,
+            LogMsg::SessionId(id) => session_id = Some(id.clone()),
+            _ => {}
+        }
+    }
+
+    let mut entries = match document["entries"].take() {
+        Value::Array(entries) => entries,
+        _ => Vec::new(),
+    };
+    // Timestamps differ between runs
+    for entry in &mut entries {
+        if let Some(content) = entry.get_mut("content").and_then(Value::as_object_mut) {
+            content.shift_remove("timestamp");
+        }
+    }
+
+    Ok(Replay {
+        session_id,
+        entries,
+    })
+}
+
+pub fn snapshot_path(fixture_path: &Path) -> PathBuf {
+    fixture_path.with_extension(SNAPSHOT_EXTENSION)
+}
+
+/// Replay the fixture at `fixture_path` and compare the result with its
+/// snapshot.
+///
+/// A missing snapshot is written and the assertion fails so it gets
+/// reviewed; with `UPDATE_SNAPSHOTS` set, outdated snapshots are overwritten.
+pub async fn assert_snapshot(fixture_path: &Path) {
+    let fixture = Fixture::load(fixture_path)
+        .await
+        .unwrap_or_else(|e| panic!("Failed to load {}: {e}", fixture_path.display()));
+    let replay = fixture
+        .replay()
+        .await
+        .unwrap_or_else(|e| panic!("Failed to replay {}: {e}", fixture_path.display()));
+    let actual = serde_json::to_string_pretty(&replay).expect("replay serializes") + "\n";
+
+    let snapshot_path = snapshot_path(fixture_path);
+    let expected = tokio::fs::read_to_string(&snapshot_path).await.ok();
+    if expected.as_deref() == Some(actual.as_str()) {
+        return;
+    }
+
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+    if expected.is_none() || update {
+        tokio::fs::write(&snapshot_path, &actual)
+            .await
+            .unwrap_or_else(|e| panic!("Failed to write {}: {e}", snapshot_path.display()));
+        if update {
+            return;
+        }
+        panic!(
+            "Wrote new snapshot {}, review it and run the test again",
+            snapshot_path.display()
+        );
+    }
+
+    panic!(
+        "{} no longer normalizes to {}; rerun with UPDATE_SNAPSHOTS=1 if the change is intended.\n\nactual:\n{actual}",
+        fixture_path.display(),
+        snapshot_path.display()
+    );
+}
+
+/// Record the run in `msg_store` as a fixture when `VK_RECORD_FIXTURES_DIR` is
+/// set, once the run has finished
+pub fn record_when_finished(
+    msg_store: Arc<MsgStore>,
+    executor: CodingAgent,
+    worktree_path: PathBuf,
+    name: String,
+) {
+    let Some(dir) = std::env::var_os(RECORD_FIXTURES_DIR_ENV).map(PathBuf::from) else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        let mut stream = msg_store.history_plus_stream();
+        while let Some(Ok(msg)) = stream.next().await {
+            if matches!(msg, LogMsg::Finished) {
+                break;
+            }
+        }
+
+        let path = dir.join(format!(
+            "{}-{name}.json",
+            executor.to_string().to_lowercase()
+        ));
+        let fixture = Fixture::from_history(executor, worktree_path, &msg_store.get_history());
+        match fixture.save(&path).await {
+            Ok(()) => tracing::info!("Recorded executor fixture {}", path.display()),
+            Err(e) => tracing::warn!("Failed to record executor fixture: {}", e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversation_applies_patches_and_drops_timestamps() {
+        let add: json_patch::Patch = serde_json::from_value(json!([{
+            "op": "add",
+            "path": "/entries/0",
+            "value": {
+                "type": "NORMALIZED_ENTRY",
+                "content": { "timestamp": "2026-01-01T00:00:00Z", "content": "Hi" }
+            }
+        }]))
+        .unwrap();
+        let replace: json_patch::Patch = serde_json::from_value(json!([{
+            "op": "replace",
+            "path": "/entries/0/content/content",
+            "value": "Hello"
+        }]))
+        .unwrap();
+
+        let replay = conversation(&[
+            LogMsg::Stdout("ignored".to_string()),
+            LogMsg::SessionId("s-1".to_string()),
+            LogMsg::JsonPatch(add),
+            LogMsg::JsonPatch(replace),
+        ])
+        .unwrap();
+
+        assert_eq!(replay.session_id.as_deref(), Some("s-1"));
+        assert_eq!(
+            replay.entries,
+            [json!({ "type": "NORMALIZED_ENTRY", "content": { "content": "Hello" } })]
+        );
+    }
+}
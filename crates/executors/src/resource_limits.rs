@@ -0,0 +1,161 @@
+//! Memory and CPU limits for executor processes.
+//!
+//! Where a systemd user session on cgroup v2 is available the command is
+//! started in a transient scope (`systemd-run --user --scope`) with
+//! `MemoryMax`/`CPUQuota` set, so the limit covers every process the command
+//! spawns. Elsewhere the memory limit falls back to a data-segment rlimit
+//! (`ulimit -d`) and the CPU quota is not enforced. Unlike an address-space
+//! limit, that leaves alone the large reservations runtimes such as V8, the
+//! JVM and Go make at startup, but it applies to each process on its own
+//! rather than to the command as a whole.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::{process::Command, sync::OnceCell};
+use ts_rs::TS;
+use workspace_utils::shell::resolve_executable_path;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema, Default)]
+pub struct ResourceLimits {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Memory Limit (MB)",
+        description = "Maximum memory for the process and its children, in megabytes"
+    )]
+    pub memory_mb: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "CPU Limit (%)",
+        description = "CPU quota in percent of one core, e.g. 200 for two cores (requires cgroup v2)"
+    )]
+    pub cpu_percent: Option<u32>,
+}
+
+impl ResourceLimits {
+    pub fn is_empty(&self) -> bool {
+        self.memory_mb.is_none() && self.cpu_percent.is_none()
+    }
+
+    /// Wrap `program args` so it runs with these limits applied.
+    pub async fn wrap(&self, program: PathBuf, args: Vec<String>) -> (PathBuf, Vec<String>) {
+        if self.is_empty() || cfg!(windows) {
+            return (program, args);
+        }
+
+        if Path::new("/sys/fs/cgroup/cgroup.controllers").exists()
+            && let Some(systemd_run) = resolve_executable_path("systemd-run").await
+            && user_systemd_available().await
+        {
+            return (systemd_run, self.systemd_run_args(&program, args));
+        }
+
+        match self.memory_mb {
+            Some(memory_mb) => {
+                if self.cpu_percent.is_some() {
+                    tracing::debug!("CPU limit requires cgroup v2 with systemd, not applied");
+                }
+                ulimit_wrap(memory_mb, &program, args)
+            }
+            None => {
+                tracing::debug!("CPU limit requires cgroup v2 with systemd, not applied");
+                (program, args)
+            }
+        }
+    }
+
+    fn systemd_run_args(&self, program: &Path, args: Vec<String>) -> Vec<String> {
+        let mut out: Vec<String> = ["--user", "--scope", "--quiet", "--collect"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        if let Some(memory_mb) = self.memory_mb {
+            out.extend([
+                "-p".to_string(),
+                format!("MemoryMax={memory_mb}M"),
+                "-p".to_string(),
+                "MemorySwapMax=0".to_string(),
+            ]);
+        }
+        if let Some(cpu_percent) = self.cpu_percent {
+            out.extend(["-p".to_string(), format!("CPUQuota={cpu_percent}%")]);
+        }
+        out.push("--".to_string());
+        out.push(program.to_string_lossy().to_string());
+        out.extend(args);
+        out
+    }
+}
+
+/// Whether a systemd user manager is running that `systemd-run --user` can
+/// talk to. Containers and SSH sessions without lingering often have the
+/// binaries but no user manager. Probed once per process.
+async fn user_systemd_available() -> bool {
+    static AVAILABLE: OnceCell<bool> = OnceCell::const_new();
+    *AVAILABLE
+        .get_or_init(|| async {
+            let Ok(output) = Command::new("systemctl")
+                .args(["--user", "is-system-running"])
+                .stdin(Stdio::null())
+                .stderr(Stdio::null())
+                .output()
+                .await
+            else {
+                return false;
+            };
+            // Exits non-zero for "degraded", which still accepts transient units
+            let state = String::from_utf8_lossy(&output.stdout);
+            let available = matches!(state.trim(), "running" | "degraded");
+            if !available {
+                tracing::debug!(
+                    "systemd user session unavailable ({}), using rlimit fallback",
+                    state.trim()
+                );
+            }
+            available
+        })
+        .await
+}
+
+/// `sh -c 'ulimit -d <kb> && exec "$@"'`; every child inherits its own copy of
+/// the limit.
+fn ulimit_wrap(memory_mb: u32, program: &Path, args: Vec<String>) -> (PathBuf, Vec<String>) {
+    let mut out = vec![
+        "-c".to_string(),
+        format!("ulimit -d {} && exec \"$@\"", u64::from(memory_mb) * 1024),
+        "sh".to_string(),
+        program.to_string_lossy().to_string(),
+    ];
+    out.extend(args);
+    (PathBuf::from("sh"), out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn systemd_run_args_set_memory_and_cpu() {
+        let limits = ResourceLimits {
+            memory_mb: Some(2048),
+            cpu_percent: Some(150),
+        };
+        let args = limits.systemd_run_args(Path::new("/usr/bin/npm"), vec!["run".to_string()]);
+        assert_eq!(
+            args.join(" "),
+            "--user --scope --quiet --collect -p MemoryMax=2048M -p MemorySwapMax=0 -p CPUQuota=150% -- /usr/bin/npm run"
+        );
+    }
+
+    #[test]
+    fn ulimit_fallback_converts_to_kilobytes() {
+        let (program, args) = ulimit_wrap(512, Path::new("node"), vec!["server.js".to_string()]);
+        assert_eq!(program, PathBuf::from("sh"));
+        assert_eq!(args[1], "ulimit -d 524288 && exec \"$@\"");
+        assert_eq!(&args[3..], ["node", "server.js"]);
+    }
+}
@@ -0,0 +1,346 @@
+//! Opt-in filesystem/network sandbox for executor processes.
+//!
+//! On Linux the executor command is wrapped in bubblewrap (`bwrap`): the whole
+//! filesystem is mounted read-only, and only the workspace directory, the git
+//! directories its worktrees point at, and any declared cache paths are
+//! writable. Network access can be left alone, removed entirely (private
+//! network namespace), or restricted to an allowlist of hosts. The allowlist
+//! also runs in a private network namespace; the only way out is a local
+//! filtering HTTP(S) proxy reached through a unix socket, so clients that
+//! ignore `HTTP(S)_PROXY` get no network at all.
+
+use std::path::{Path, PathBuf};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use workspace_utils::shell::resolve_executable_path;
+
+use crate::{approval_hook, env::RepoContext, executors::ExecutorError};
+
+mod forward;
+mod proxy;
+
+pub use forward::run_forwarder_from_args;
+
+/// How an allowlisted sandbox reaches the egress proxy
+struct NetworkShim {
+    /// The proxy's unix socket
+    socket: PathBuf,
+    /// This binary, which runs the [`forward`] shim inside the sandbox
+    exe: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SandboxNetwork {
+    /// Unrestricted network access
+    #[default]
+    Full,
+    /// No network access at all
+    None,
+    /// Only the hosts listed in `allowed_hosts`, through the sandbox proxy. The
+    /// sandbox has no other network access.
+    Allowlist,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, Default)]
+pub struct ProcessSandbox {
+    #[serde(default)]
+    #[schemars(
+        title = "Enabled",
+        description = "Run the agent inside a bubblewrap sandbox (Linux only)"
+    )]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(
+        title = "Writable Paths",
+        description = "Extra writable paths besides the workspace, e.g. ~/.claude or ~/.cache/npm"
+    )]
+    pub writable_paths: Vec<String>,
+    #[serde(default)]
+    #[schemars(
+        title = "Network",
+        description = "Network access inside the sandbox: full, none, or allowlist"
+    )]
+    pub network: SandboxNetwork,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(
+        title = "Allowed Hosts",
+        description = "Hosts reachable when network is 'allowlist'. Prefix with '*.' to include subdomains"
+    )]
+    pub allowed_hosts: Vec<String>,
+}
+
+impl ProcessSandbox {
+    /// Wrap `program args` so it runs inside the sandbox.
+    ///
+    /// Returns the command unchanged when the sandbox is disabled. When it is
+    /// enabled but cannot be set up, this fails instead of silently running
+    /// the agent unsandboxed.
+    pub async fn wrap(
+        &self,
+        program: PathBuf,
+        args: Vec<String>,
+        repo_context: &RepoContext,
+    ) -> Result<(PathBuf, Vec<String>), ExecutorError> {
+        if !self.enabled {
+            return Ok((program, args));
+        }
+
+        if !cfg!(target_os = "linux") {
+            return Err(ExecutorError::SandboxUnavailable(
+                "process sandboxing is only supported on Linux".to_string(),
+            ));
+        }
+
+        let bwrap = resolve_executable_path("bwrap").await.ok_or_else(|| {
+            ExecutorError::SandboxUnavailable("bubblewrap (`bwrap`) not found in PATH".to_string())
+        })?;
+
+        let exe = std::env::current_exe().map_err(ExecutorError::Io)?;
+        let shim = match self.network {
+            SandboxNetwork::Allowlist => Some(NetworkShim {
+                socket: proxy::ensure_proxy(&self.allowed_hosts).await?,
+                exe: exe.clone(),
+            }),
+            SandboxNetwork::Full | SandboxNetwork::None => None,
+        };
+        // Approval hooks run inside the sandbox as this binary and reach the
+        // server through a socket in the hook directory
+        let read_only_paths = [approval_hook::hook_dir(), exe];
+
+        let bwrap_args = self.bwrap_args(
+            &workspace_writable_roots(repo_context),
+            &read_only_paths,
+            shim.as_ref(),
+            &program,
+            args,
+        );
+
+        tracing::debug!("Sandboxing {} with bwrap", program.display());
+
+        Ok((bwrap, bwrap_args))
+    }
+
+    fn bwrap_args(
+        &self,
+        workspace_roots: &[PathBuf],
+        read_only_paths: &[PathBuf],
+        shim: Option<&NetworkShim>,
+        program: &Path,
+        args: Vec<String>,
+    ) -> Vec<String> {
+        let mut out: Vec<String> = [
+            "--die-with-parent",
+            "--unshare-pid",
+            "--ro-bind",
+            "/",
+            "/",
+            "--dev",
+            "/dev",
+            "--proc",
+            "/proc",
+            "--tmpfs",
+            "/tmp",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+        // Binds after `--tmpfs /tmp` win, so workspaces under /tmp stay visible.
+        for root in workspace_roots {
+            let root = root.to_string_lossy().to_string();
+            out.extend(["--bind".to_string(), root.clone(), root]);
+        }
+        for path in &self.writable_paths {
+            let path = expand_home(path).to_string_lossy().to_string();
+            out.extend(["--bind-try".to_string(), path.clone(), path]);
+        }
+        // Visible even when they sit under the fresh /tmp
+        for path in read_only_paths {
+            let path = path.to_string_lossy().to_string();
+            out.extend(["--ro-bind-try".to_string(), path.clone(), path]);
+        }
+
+        if self.network != SandboxNetwork::Full {
+            out.push("--unshare-net".to_string());
+        }
+
+        // May sit under the fresh /tmp
+        if let Some(shim) = shim {
+            let socket = shim.socket.to_string_lossy().to_string();
+            out.extend(["--bind".to_string(), socket.clone(), socket]);
+        }
+
+        out.push("--".to_string());
+        if let Some(shim) = shim {
+            out.extend([
+                shim.exe.to_string_lossy().to_string(),
+                forward::FORWARD_SUBCOMMAND.to_string(),
+                shim.socket.to_string_lossy().to_string(),
+                "--".to_string(),
+            ]);
+        }
+        out.push(program.to_string_lossy().to_string());
+        out.extend(args);
+        out
+    }
+}
+
+/// The workspace directory plus the git directories its worktrees write to.
+pub(crate) fn workspace_writable_roots(repo_context: &RepoContext) -> Vec<PathBuf> {
+    if repo_context.workspace_root.as_os_str().is_empty() {
+        return Vec::new();
+    }
+
+    let mut roots = vec![repo_context.workspace_root.clone()];
+    for repo_path in repo_context.repo_paths() {
+        if let Some(common_dir) = git_common_dir(&repo_path)
+            && !roots.iter().any(|root| common_dir.starts_with(root))
+        {
+            roots.push(common_dir);
+        }
+    }
+    roots
+}
+
+/// Resolve the shared `.git` directory of a worktree checkout.
+///
+/// A worktree's `.git` is a file (`gitdir: <repo>/.git/worktrees/<name>`) and
+/// commits write objects and refs to the main repository's git directory.
+fn git_common_dir(repo_path: &Path) -> Option<PathBuf> {
+    let dot_git = repo_path.join(".git");
+    if dot_git.is_dir() {
+        return None;
+    }
+
+    let contents = std::fs::read_to_string(&dot_git).ok()?;
+    let gitdir = contents.strip_prefix("gitdir:")?.trim();
+    let gitdir = repo_path.join(gitdir);
+
+    let common_dir = match std::fs::read_to_string(gitdir.join("commondir")) {
+        Ok(common) => gitdir.join(common.trim()),
+        Err(_) => gitdir,
+    };
+    Some(common_dir.canonicalize().unwrap_or(common_dir))
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest))
+            .unwrap_or_else(|| PathBuf::from(path)),
+        None => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bwrap_args_bind_workspace_and_isolate_network() {
+        let sandbox = ProcessSandbox {
+            enabled: true,
+            writable_paths: vec!["/var/cache/agent".to_string()],
+            network: SandboxNetwork::None,
+            allowed_hosts: vec![],
+        };
+        let args = sandbox.bwrap_args(
+            &[PathBuf::from("/work/ws")],
+            &[],
+            None,
+            Path::new("/usr/bin/claude"),
+            vec!["-p".to_string()],
+        );
+
+        let joined = args.join(" ");
+        assert!(joined.starts_with("--die-with-parent"));
+        assert!(joined.contains("--ro-bind / /"));
+        assert!(joined.contains("--bind /work/ws /work/ws"));
+        assert!(joined.contains("--bind-try /var/cache/agent /var/cache/agent"));
+        assert!(joined.contains("--unshare-net"));
+        assert!(joined.ends_with("-- /usr/bin/claude -p"));
+    }
+
+    #[test]
+    fn bwrap_args_route_allowlist_through_proxy() {
+        let sandbox = ProcessSandbox {
+            enabled: true,
+            network: SandboxNetwork::Allowlist,
+            allowed_hosts: vec!["api.anthropic.com".to_string()],
+            ..Default::default()
+        };
+        let shim = NetworkShim {
+            socket: PathBuf::from("/data/sandbox/proxy.sock"),
+            exe: PathBuf::from("/opt/vibe-kanban"),
+        };
+        let args = sandbox.bwrap_args(
+            &[],
+            &[PathBuf::from("/opt/vibe-kanban")],
+            Some(&shim),
+            Path::new("codex"),
+            vec![],
+        );
+
+        let joined = args.join(" ");
+        assert!(joined.contains("--unshare-net"));
+        assert!(joined.contains("--bind /data/sandbox/proxy.sock /data/sandbox/proxy.sock"));
+        assert!(joined.contains("--ro-bind-try /opt/vibe-kanban /opt/vibe-kanban"));
+        assert!(
+            joined
+                .ends_with("-- /opt/vibe-kanban sandbox-forward /data/sandbox/proxy.sock -- codex")
+        );
+    }
+
+    #[test]
+    fn bwrap_args_mount_approval_hooks_without_network() {
+        let sandbox = ProcessSandbox {
+            enabled: true,
+            network: SandboxNetwork::None,
+            ..Default::default()
+        };
+        let hooks = approval_hook::hook_dir();
+        let args = sandbox.bwrap_args(
+            &[PathBuf::from("/tmp/ws")],
+            std::slice::from_ref(&hooks),
+            None,
+            Path::new("droid"),
+            vec![],
+        );
+
+        let hooks = hooks.to_string_lossy().to_string();
+        let tmpfs = args.iter().position(|arg| arg == "--tmpfs").unwrap();
+        let hook_bind = args
+            .windows(3)
+            .position(|window| window == ["--ro-bind-try", hooks.as_str(), hooks.as_str()])
+            .expect("hook directory is mounted");
+        assert!(hook_bind > tmpfs);
+        assert!(args.contains(&"--unshare-net".to_string()));
+    }
+
+    #[test]
+    fn git_common_dir_follows_worktree_pointer() {
+        let tmp = std::env::temp_dir().join(format!("vk-sandbox-{}", uuid::Uuid::new_v4()));
+        let main_git = tmp.join("repo/.git");
+        let worktree_git = main_git.join("worktrees/ws");
+        let worktree = tmp.join("ws");
+        std::fs::create_dir_all(&worktree_git).unwrap();
+        std::fs::create_dir_all(&worktree).unwrap();
+        std::fs::write(worktree_git.join("commondir"), "../..\n").unwrap();
+        std::fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", worktree_git.display()),
+        )
+        .unwrap();
+
+        assert_eq!(
+            git_common_dir(&worktree),
+            Some(main_git.canonicalize().unwrap())
+        );
+        assert_eq!(git_common_dir(&tmp.join("repo")), None);
+
+        std::fs::remove_dir_all(&tmp).ok();
+    }
+}
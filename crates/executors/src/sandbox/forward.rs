@@ -0,0 +1,115 @@
+//! Relay from inside the sandbox to the egress [`proxy`](super::proxy).
+//!
+//! With the network allowlist the sandbox gets a private network namespace,
+//! so the agent cannot open connections of its own. It runs under
+//! `<binary> sandbox-forward <socket> -- <program> <args>` instead: the shim
+//! listens on loopback inside the namespace, points the agent's
+//! `HTTP(S)_PROXY` at that listener, pipes every connection to the proxy's
+//! bind-mounted unix socket and exits with the agent's exit code.
+
+use std::{
+    ffi::OsString,
+    path::PathBuf,
+    process::{Command, ExitStatus},
+};
+
+pub const FORWARD_SUBCOMMAND: &str = "sandbox-forward";
+
+const PROXY_VARS: [&str; 4] = ["HTTP_PROXY", "HTTPS_PROXY", "http_proxy", "https_proxy"];
+const NO_PROXY_VARS: [&str; 2] = ["NO_PROXY", "no_proxy"];
+
+/// Run as the sandbox's network shim when the process was started as one,
+/// returning the exit code
+pub fn run_forwarder_from_args() -> Option<i32> {
+    let mut args = std::env::args_os().skip(1);
+    if args.next()? != FORWARD_SUBCOMMAND {
+        return None;
+    }
+    let (Some(socket), Some(separator), Some(program)) = (args.next(), args.next(), args.next())
+    else {
+        eprintln!("Usage: {FORWARD_SUBCOMMAND} <socket> -- <program> [args...]");
+        return Some(2);
+    };
+    if separator != "--" {
+        eprintln!("Usage: {FORWARD_SUBCOMMAND} <socket> -- <program> [args...]");
+        return Some(2);
+    }
+
+    match forward(PathBuf::from(socket), program, args.collect()) {
+        Ok(status) => Some(exit_code(status)),
+        Err(e) => {
+            eprintln!("{FORWARD_SUBCOMMAND}: {e}");
+            Some(1)
+        }
+    }
+}
+
+#[cfg(unix)]
+fn forward(socket: PathBuf, program: OsString, args: Vec<OsString>) -> std::io::Result<ExitStatus> {
+    use std::{net::TcpListener, thread};
+
+    let listener = TcpListener::bind(("127.0.0.1", 0))?;
+    let proxy_url = format!("http://{}", listener.local_addr()?);
+
+    thread::spawn(move || {
+        for client in listener.incoming().flatten() {
+            let socket = socket.clone();
+            thread::spawn(move || relay(client, &socket));
+        }
+    });
+
+    let mut command = Command::new(program);
+    command.args(args);
+    for var in PROXY_VARS {
+        command.env(var, &proxy_url);
+    }
+    for var in NO_PROXY_VARS {
+        command.env_remove(var);
+    }
+    command.status()
+}
+
+#[cfg(not(unix))]
+fn forward(
+    _socket: PathBuf,
+    _program: OsString,
+    _args: Vec<OsString>,
+) -> std::io::Result<ExitStatus> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "the sandbox network shim needs unix sockets",
+    ))
+}
+
+#[cfg(unix)]
+fn relay(client: std::net::TcpStream, socket: &std::path::Path) {
+    use std::{io, net::Shutdown, os::unix::net::UnixStream, thread};
+
+    let Ok(upstream) = UnixStream::connect(socket) else {
+        return;
+    };
+    let (Ok(mut client_read), Ok(mut upstream_write)) = (client.try_clone(), upstream.try_clone())
+    else {
+        return;
+    };
+    let to_proxy = thread::spawn(move || {
+        let _ = io::copy(&mut client_read, &mut upstream_write);
+        let _ = upstream_write.shutdown(Shutdown::Write);
+    });
+
+    let (mut upstream_read, mut client_write) = (upstream, client);
+    let _ = io::copy(&mut upstream_read, &mut client_write);
+    let _ = client_write.shutdown(Shutdown::Write);
+    let _ = to_proxy.join();
+}
+
+fn exit_code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(1)
+}
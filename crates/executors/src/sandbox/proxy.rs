@@ -0,0 +1,229 @@
+//! Minimal HTTP(S) forward proxy that only lets allowlisted hosts through.
+//!
+//! The proxy listens on a unix socket that is bind-mounted into the sandbox,
+//! whose private network namespace has no other way out; the
+//! [`forward`](super::forward) shim inside relays the agent's `HTTP(S)_PROXY`
+//! connections to it. `CONNECT` tunnels are used for HTTPS, plain HTTP
+//! requests are forwarded as-is. One proxy is started per distinct allowlist
+//! and lives as long as the server.
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, LazyLock},
+};
+
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+    sync::Mutex,
+};
+#[cfg(unix)]
+use workspace_utils::assets::asset_dir;
+
+use crate::executors::ExecutorError;
+
+const MAX_REQUEST_HEAD: usize = 16 * 1024;
+
+static PROXIES: LazyLock<Mutex<HashMap<Vec<String>, PathBuf>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Return the socket of a proxy enforcing `allowed_hosts`, starting it if needed.
+#[cfg(unix)]
+pub(super) async fn ensure_proxy(allowed_hosts: &[String]) -> Result<PathBuf, ExecutorError> {
+    let mut key: Vec<String> = allowed_hosts
+        .iter()
+        .map(|host| host.trim().to_ascii_lowercase())
+        .filter(|host| !host.is_empty())
+        .collect();
+    key.sort();
+    key.dedup();
+
+    let mut proxies = PROXIES.lock().await;
+    if let Some(socket) = proxies.get(&key) {
+        return Ok(socket.clone());
+    }
+
+    let dir = asset_dir().join("sandbox");
+    std::fs::create_dir_all(&dir).map_err(ExecutorError::Io)?;
+    let socket = dir.join(format!(
+        "proxy-{}-{}.sock",
+        std::process::id(),
+        proxies.len()
+    ));
+    // A socket left behind by an earlier run of the same pid
+    let _ = std::fs::remove_file(&socket);
+    let listener = tokio::net::UnixListener::bind(&socket).map_err(ExecutorError::Io)?;
+    tracing::info!(
+        "Started sandbox egress proxy at {} for {:?}",
+        socket.display(),
+        key
+    );
+
+    let allowed = Arc::new(key.clone());
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    tracing::warn!("Sandbox proxy accept failed: {e}");
+                    continue;
+                }
+            };
+            let allowed = allowed.clone();
+            tokio::spawn(async move {
+                if let Err(e) = handle_connection(stream, &allowed).await {
+                    tracing::debug!("Sandbox proxy connection error: {e}");
+                }
+            });
+        }
+    });
+
+    proxies.insert(key, socket.clone());
+    Ok(socket)
+}
+
+#[cfg(not(unix))]
+pub(super) async fn ensure_proxy(_allowed_hosts: &[String]) -> Result<PathBuf, ExecutorError> {
+    Err(ExecutorError::SandboxUnavailable(
+        "the network allowlist needs unix sockets".to_string(),
+    ))
+}
+
+async fn handle_connection<S>(mut client: S, allowed: &[String]) -> std::io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    // Read the request head; anything past it belongs to the body/tunnel.
+    let mut buf = Vec::with_capacity(1024);
+    let head_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if buf.len() > MAX_REQUEST_HEAD {
+            return respond(&mut client, "431 Request Header Fields Too Large").await;
+        }
+        let mut chunk = [0u8; 4096];
+        let n = client.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..head_end]).to_string();
+    let request_line = head.lines().next().unwrap_or_default();
+    let Some((is_connect, host, port)) = parse_request_target(request_line) else {
+        return respond(&mut client, "400 Bad Request").await;
+    };
+
+    if !host_allowed(&host, allowed) {
+        tracing::info!("Sandbox proxy blocked request to {host}");
+        return respond(&mut client, "403 Forbidden").await;
+    }
+
+    let mut upstream = match TcpStream::connect((host.as_str(), port)).await {
+        Ok(upstream) => upstream,
+        Err(_) => return respond(&mut client, "502 Bad Gateway").await,
+    };
+
+    if is_connect {
+        client
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .await?;
+        upstream.write_all(&buf[head_end..]).await?;
+    } else {
+        upstream.write_all(&buf).await?;
+    }
+
+    tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+    Ok(())
+}
+
+async fn respond<S>(client: &mut S, status: &str) -> std::io::Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    client
+        .write_all(format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\n\r\n").as_bytes())
+        .await
+}
+
+/// Parse `CONNECT host:port HTTP/1.1` or `GET http://host[:port]/path HTTP/1.1`.
+fn parse_request_target(request_line: &str) -> Option<(bool, String, u16)> {
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+
+    let (is_connect, authority, default_port) = if method.eq_ignore_ascii_case("CONNECT") {
+        (true, target, 443)
+    } else {
+        let rest = target.strip_prefix("http://")?;
+        (false, rest.split('/').next()?, 80)
+    };
+
+    // `[::1]:8080` keeps its colons inside the brackets
+    let (host, port) = match authority.strip_prefix('[') {
+        Some(bracketed) => {
+            let (host, rest) = bracketed.split_once(']')?;
+            (host, rest.strip_prefix(':'))
+        }
+        None => match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        },
+    };
+    let port = match port {
+        Some(port) => port.parse().ok()?,
+        None => default_port,
+    };
+    if host.is_empty() {
+        return None;
+    }
+
+    Some((is_connect, host.to_ascii_lowercase(), port))
+}
+
+/// `example.com` matches only itself; `*.example.com` also matches subdomains.
+fn host_allowed(host: &str, allowed: &[String]) -> bool {
+    let host = host.to_ascii_lowercase();
+    allowed
+        .iter()
+        .any(|pattern| match pattern.strip_prefix("*.") {
+            Some(domain) => host == domain || host.ends_with(&format!(".{domain}")),
+            None => host == *pattern,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_connect_and_absolute_targets() {
+        assert_eq!(
+            parse_request_target("CONNECT api.anthropic.com:443 HTTP/1.1"),
+            Some((true, "api.anthropic.com".to_string(), 443))
+        );
+        assert_eq!(
+            parse_request_target("GET http://registry.npmjs.org/left-pad HTTP/1.1"),
+            Some((false, "registry.npmjs.org".to_string(), 80))
+        );
+        assert_eq!(
+            parse_request_target("GET http://localhost:8080/ HTTP/1.1"),
+            Some((false, "localhost".to_string(), 8080))
+        );
+        assert_eq!(parse_request_target("GET /relative HTTP/1.1"), None);
+    }
+
+    #[test]
+    fn allowlist_matching() {
+        let allowed = vec!["api.openai.com".to_string(), "*.github.com".to_string()];
+        assert!(host_allowed("api.openai.com", &allowed));
+        assert!(host_allowed("API.OPENAI.COM", &allowed));
+        assert!(host_allowed("github.com", &allowed));
+        assert!(host_allowed("api.github.com", &allowed));
+        assert!(!host_allowed("evil-github.com", &allowed));
+        assert!(!host_allowed("openai.com", &allowed));
+    }
+}
@@ -12,7 +12,7 @@ use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use futures::{StreamExt, stream::BoxStream};
 use tokio::io::{AsyncWrite, AsyncWriteExt};
 use tokio_stream::wrappers::UnboundedReceiverStream;
-use tokio_util::io::ReaderStream;
+use tokio_util::{io::ReaderStream, sync::CancellationToken};
 
 use crate::executors::{ExecutorError, SpawnedChild};
 
@@ -80,6 +80,7 @@ pub fn duplicate_stdout(
 #[derive(Clone)]
 pub struct StdoutAppender {
     tx: tokio::sync::mpsc::UnboundedSender<String>,
+    closed: CancellationToken,
 }
 
 impl StdoutAppender {
@@ -91,6 +92,13 @@ impl StdoutAppender {
         }
         let _ = self.tx.send(line);
     }
+
+    /// Resolves once the child's original stdout has ended. The child's stdout
+    /// stays open until every appender is dropped, so holders should drop
+    /// theirs by then.
+    pub async fn closed(&self) {
+        self.closed.cancelled().await
+    }
 }
 
 /// Tee the child's stdout and provide both a duplicate stream and an appender to write additional
@@ -122,9 +130,12 @@ pub fn tee_stdout_with_appender(
     // Create injector channel
     let (inj_tx, mut inj_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
 
+    let closed = CancellationToken::new();
+
     // Task 1: forward original stdout to child stdout and duplicate stream
     {
         let shared_writer = shared_writer.clone();
+        let closed = closed.clone();
         tokio::spawn(async move {
             let mut stdout_stream = ReaderStream::new(original_stdout);
             while let Some(res) = stdout_stream.next().await {
@@ -142,6 +153,7 @@ pub fn tee_stdout_with_appender(
                     }
                 }
             }
+            closed.cancel();
         });
     }
 
@@ -160,7 +172,7 @@ pub fn tee_stdout_with_appender(
 
     Ok((
         Box::pin(UnboundedReceiverStream::new(dup_rx)),
-        StdoutAppender { tx: inj_tx },
+        StdoutAppender { tx: inj_tx, closed },
     ))
 }
 
@@ -230,6 +242,7 @@ pub fn spawn_local_output_process()
         child,
         exit_signal: None,
         cancel: None,
+        steer: None,
     };
 
     Ok((spawned, writer))
@@ -0,0 +1,25 @@
+//! Steering a coding agent turn while it runs: sending the agent another
+//! message, or interrupting what it is doing to continue with new instructions.
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct SteerRequest {
+    pub prompt: String,
+    /// Stop the agent's current step, e.g. a running tool call, and continue
+    /// with `prompt` instead of letting the step finish first
+    #[serde(default)]
+    pub interrupt: bool,
+}
+
+/// Container → Executor: steering requests for the running turn.
+/// Executors that accept them hand the sender out in `SpawnedChild::steer`.
+pub type SteerSender = mpsc::UnboundedSender<SteerRequest>;
+
+pub type SteerReceiver = mpsc::UnboundedReceiver<SteerRequest>;
+
+pub fn steer_channel() -> (SteerSender, SteerReceiver) {
+    mpsc::unbounded_channel()
+}
@@ -0,0 +1,32 @@
+//! Runs every fixture in `tests/fixtures` with the replay executor, normalizes
+//! its output as the executor that recorded it and compares the result with
+//! the snapshot next to it.
+//!
+//! Record new fixtures by running the app with `VK_RECORD_FIXTURES_DIR` set.
+
+use std::path::{Path, PathBuf};
+
+use executors::replay::assert_snapshot;
+
+fn fixtures() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut fixtures: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == "json")
+                && !path.to_string_lossy().ends_with(".snap.json")
+        })
+        .collect();
+    fixtures.sort();
+    fixtures
+}
+
+#[tokio::test]
+async fn fixtures_match_snapshots() {
+    let fixtures = fixtures();
+    assert!(!fixtures.is_empty(), "No fixtures found");
+    for fixture in fixtures {
+        assert_snapshot(&fixture).await;
+    }
+}
@@ -548,6 +548,26 @@ impl GitCli {
         Ok(())
     }
 
+    /// Perform `git revert --no-edit <from>..<to>`, reverting each commit in the
+    /// range newest first with Git's default messages. A revert that stops
+    /// without conflicts (e.g. one that would be empty) leaves only sequencer
+    /// state behind, so it is rolled back; a conflicted one stays in progress.
+    pub fn revert_range(
+        &self,
+        worktree_path: &Path,
+        from: &str,
+        to: &str,
+    ) -> Result<(), GitCliError> {
+        let range = format!("{from}..{to}");
+        let result = self
+            .git(worktree_path, ["revert", "--no-edit", &range])
+            .map(|_| ());
+        if result.is_err() && !self.is_revert_in_progress(worktree_path).unwrap_or(false) {
+            let _ = self.git(worktree_path, ["revert", "--abort"]);
+        }
+        result
+    }
+
     /// Return true if there is a rebase in progress in this worktree.
     /// We treat this as true when either of Git's rebase state directories exists:
     /// - rebase-merge (interactive rebase)
@@ -615,7 +635,17 @@ impl GitCli {
                 "No rebase in progress".to_string(),
             ));
         }
-        self.git(worktree_path, ["rebase", "--continue"])
+        self.continue_operation(worktree_path, "rebase")
+    }
+
+    /// Run `git <command> --continue` without opening an editor for the commit message.
+    pub fn continue_operation(
+        &self,
+        worktree_path: &Path,
+        command: &str,
+    ) -> Result<(), GitCliError> {
+        let envs = vec![(OsString::from("GIT_EDITOR"), OsString::from("true"))];
+        self.git_with_env(worktree_path, [command, "--continue"], &envs)
             .map(|_| ())
     }
 
@@ -659,6 +689,44 @@ impl GitCli {
         Ok(sha)
     }
 
+    /// Checkout base branch and merge from_branch with a merge commit, even when a
+    /// fast-forward is possible. Aborts the merge on failure. Returns new HEAD sha.
+    pub fn merge_no_ff_commit(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        from_branch: &str,
+        message: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        if let Err(e) = self.git(repo_path, ["merge", "--no-ff", "-m", message, from_branch]) {
+            let _ = self.abort_merge(repo_path);
+            return Err(e);
+        }
+        let sha = self
+            .git(repo_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        Ok(sha)
+    }
+
+    /// Checkout base branch and fast-forward it to from_branch. Returns new HEAD sha.
+    pub fn merge_ff_only(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        from_branch: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        self.git(repo_path, ["merge", "--ff-only", from_branch])
+            .map(|_| ())?;
+        let sha = self
+            .git(repo_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        Ok(sha)
+    }
+
     /// Update a ref to a specific sha in the repo.
     pub fn update_ref(
         &self,
@@ -0,0 +1,104 @@
+//! Conflict markers left in files by a stopped rebase, merge, cherry-pick or revert.
+
+/// Lines of unchanged code kept on either side of a conflict block
+pub const CONFLICT_CONTEXT_LINES: usize = 3;
+
+/// One `<<<<<<<` ... `>>>>>>>` block together with the lines around it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictHunk {
+    /// 1-based line of the `<<<<<<<` marker
+    pub start_line: usize,
+    pub text: String,
+}
+
+/// A file with unresolved conflicts and its conflict blocks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictedFile {
+    pub path: String,
+    pub hunks: Vec<ConflictHunk>,
+}
+
+fn is_start_marker(line: &str) -> bool {
+    line.starts_with("<<<<<<<")
+}
+
+fn is_end_marker(line: &str) -> bool {
+    line.starts_with(">>>>>>>")
+}
+
+/// Whether `content` still contains an opening or closing conflict marker.
+/// A bare `=======` is not enough, as it is common in Markdown and RST.
+pub fn has_conflict_markers(content: &str) -> bool {
+    content
+        .lines()
+        .any(|line| is_start_marker(line) || is_end_marker(line))
+}
+
+/// Extract every conflict block from `content`, with up to `context` lines
+/// before and after it. Overlapping context is not merged; each hunk stands
+/// on its own. An unterminated block runs to the end of the file.
+pub fn find_conflict_hunks(content: &str, context: usize) -> Vec<ConflictHunk> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut hunks = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        if !is_start_marker(lines[i]) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let mut end = start;
+        while end < lines.len() && !is_end_marker(lines[end]) {
+            end += 1;
+        }
+        let end = end.min(lines.len() - 1);
+
+        let from = start.saturating_sub(context);
+        let to = (end + context).min(lines.len() - 1);
+        hunks.push(ConflictHunk {
+            start_line: start + 1,
+            text: lines[from..=to].join("\n"),
+        });
+        i = end + 1;
+    }
+
+    hunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFLICTED: &str = "\
+fn main() {
+    let a = 1;
+<<<<<<< HEAD
+    let b = 2;
+=======
+    let b = 3;
+>>>>>>> 1234abc (Change b)
+    println!(\"{a} {b}\");
+}
+";
+
+    #[test]
+    fn finds_hunk_with_context() {
+        let hunks = find_conflict_hunks(CONFLICTED, 1);
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].start_line, 3);
+        assert!(hunks[0].text.starts_with("    let a = 1;\n<<<<<<< HEAD"));
+        assert!(
+            hunks[0]
+                .text
+                .ends_with(">>>>>>> 1234abc (Change b)\n    println!(\"{a} {b}\");")
+        );
+    }
+
+    #[test]
+    fn detects_markers() {
+        assert!(has_conflict_markers(CONFLICTED));
+        assert!(!has_conflict_markers("Title\n=======\n\nBody\n"));
+        assert!(find_conflict_hunks("no conflicts\n", 3).is_empty());
+    }
+}
@@ -1,4 +1,7 @@
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use chrono::{DateTime, Utc};
 use git2::{
@@ -11,10 +14,12 @@ use ts_rs::TS;
 use utils::diff::{Diff, DiffChangeKind, FileDiffDetails, compute_line_change_counts};
 
 mod cli;
+mod conflicts;
 mod validation;
 
 use cli::{ChangeType, StatusDiffEntry, StatusDiffOptions};
 pub use cli::{GitCli, GitCliError, StatusEntry, WorktreeStatus};
+pub use conflicts::{CONFLICT_CONTEXT_LINES, ConflictHunk, ConflictedFile};
 pub use utils::path::ALWAYS_SKIP_DIRS;
 pub use validation::is_valid_branch_prefix;
 
@@ -181,6 +186,55 @@ impl GitService {
         Repository::open(repo_path).map_err(GitServiceError::from)
     }
 
+    /// Add `path` to the `info/exclude` of the repository containing it, so
+    /// generated files stay out of status and commits without touching the
+    /// repository's `.gitignore`. Does nothing outside a repository.
+    pub fn exclude_path(&self, path: &Path) -> Result<(), GitServiceError> {
+        let Some((exclude_path, pattern)) = Self::exclude_entry(path) else {
+            return Ok(());
+        };
+        let existing = std::fs::read_to_string(&exclude_path).unwrap_or_default();
+        if existing.lines().any(|line| line.trim() == pattern) {
+            return Ok(());
+        }
+        if let Some(parent) = exclude_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut content = existing;
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&pattern);
+        content.push('\n');
+        std::fs::write(&exclude_path, content)?;
+        Ok(())
+    }
+
+    /// Whether `path` was added to `info/exclude` by [`Self::exclude_path`]
+    pub fn is_path_excluded(&self, path: &Path) -> bool {
+        Self::exclude_entry(path).is_some_and(|(exclude_path, pattern)| {
+            std::fs::read_to_string(exclude_path)
+                .is_ok_and(|content| content.lines().any(|line| line.trim() == pattern))
+        })
+    }
+
+    /// Whether `path` is inside a git repository's worktree
+    pub fn is_in_worktree(&self, path: &Path) -> bool {
+        Repository::discover(path).is_ok_and(|repo| repo.workdir().is_some())
+    }
+
+    /// The `info/exclude` file of the repository containing `path` and the
+    /// pattern matching exactly `path`
+    fn exclude_entry(path: &Path) -> Option<(PathBuf, String)> {
+        let repo = Repository::discover(path).ok()?;
+        let workdir = repo.workdir()?;
+        let workdir = std::fs::canonicalize(workdir).unwrap_or_else(|_| workdir.to_path_buf());
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let relative = path.strip_prefix(&workdir).ok()?;
+        let pattern = format!("/{}", relative.to_string_lossy().replace('\\', "/"));
+        Some((repo.commondir().join("info").join("exclude"), pattern))
+    }
+
     /// Ensure local (repo-scoped) identity exists for CLI commits.
     /// Sets user.name/email only if missing in the repo config.
     fn ensure_cli_commit_identity(&self, repo_path: &Path) -> Result<(), GitServiceError> {
@@ -822,6 +876,45 @@ impl GitService {
         task_branch_name: &str,
         base_branch_name: &str,
         commit_message: &str,
+    ) -> Result<String, GitServiceError> {
+        self.merge_into_base(
+            base_worktree_path,
+            task_worktree_path,
+            task_branch_name,
+            base_branch_name,
+            commit_message,
+            true,
+        )
+    }
+
+    /// Merge the task branch into the base branch with a two-parent merge commit,
+    /// keeping the task's individual commits in the base history.
+    pub fn merge_changes_with_merge_commit(
+        &self,
+        base_worktree_path: &Path,
+        task_worktree_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+        commit_message: &str,
+    ) -> Result<String, GitServiceError> {
+        self.merge_into_base(
+            base_worktree_path,
+            task_worktree_path,
+            task_branch_name,
+            base_branch_name,
+            commit_message,
+            false,
+        )
+    }
+
+    fn merge_into_base(
+        &self,
+        base_worktree_path: &Path,
+        task_worktree_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+        commit_message: &str,
+        squash: bool,
     ) -> Result<String, GitServiceError> {
         // Open the repositories
         let task_repo = self.open_repo(task_worktree_path)?;
@@ -859,16 +952,24 @@ impl GitService {
 
                 // Use CLI merge in base context
                 self.ensure_cli_commit_identity(&base_checkout_path)?;
-                let sha = git_cli
-                    .merge_squash_commit(
+                let sha = if squash {
+                    git_cli.merge_squash_commit(
                         &base_checkout_path,
                         base_branch_name,
                         task_branch_name,
                         commit_message,
                     )
-                    .map_err(|e| {
-                        GitServiceError::InvalidRepository(format!("CLI merge failed: {e}"))
-                    })?;
+                } else {
+                    git_cli.merge_no_ff_commit(
+                        &base_checkout_path,
+                        base_branch_name,
+                        task_branch_name,
+                        commit_message,
+                    )
+                }
+                .map_err(|e| {
+                    GitServiceError::InvalidRepository(format!("CLI merge failed: {e}"))
+                })?;
 
                 // Update task branch ref for continuity
                 let task_refname = format!("refs/heads/{task_branch_name}");
@@ -889,28 +990,29 @@ impl GitService {
                 let base_commit = base_branch.get().peel_to_commit()?;
                 let task_commit = task_branch.get().peel_to_commit()?;
 
-                // Create the squash commit in-memory (no checkout) and update the base branch ref
+                // Create the merge commit in-memory (no checkout) and update the base branch ref
                 let signature = self.signature_with_fallback(&task_repo)?;
-                let squash_commit_id = self.perform_squash_merge(
+                let merge_commit_id = self.perform_merge(
                     &task_repo,
                     &base_commit,
                     &task_commit,
                     &signature,
                     commit_message,
                     base_branch_name,
+                    squash,
                 )?;
 
-                // Update the task branch to the new squash commit so follow-up
+                // Update the task branch to the new merge commit so follow-up
                 // work can continue from the merged state without conflicts.
                 let task_refname = format!("refs/heads/{task_branch_name}");
                 base_repo.reference(
                     &task_refname,
-                    squash_commit_id,
+                    merge_commit_id,
                     true,
-                    "Reset task branch after squash merge",
+                    "Reset task branch after merge",
                 )?;
 
-                Ok(squash_commit_id.to_string())
+                Ok(merge_commit_id.to_string())
             }
         }
     }
@@ -989,6 +1091,21 @@ impl GitService {
         self.get_branch_status_inner(&repo, &branch_ref, &base_branch_ref)
     }
 
+    /// Update `branch_name` from its remote if it is a remote-tracking branch.
+    /// Local branches are left as they are.
+    pub fn fetch_if_remote_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let branch_ref = Self::find_branch(&repo, branch_name)?.into_reference();
+        if branch_ref.is_remote() {
+            self.fetch_branch_from_remote(&repo, &branch_ref)?;
+        }
+        Ok(())
+    }
+
     pub fn is_worktree_clean(&self, worktree_path: &Path) -> Result<bool, GitServiceError> {
         let repo = self.open_repo(worktree_path)?;
         match self.check_worktree_clean(&repo) {
@@ -1334,7 +1451,8 @@ impl GitService {
     }
 
     /// Perform a squash merge of task branch into base branch, but fail on conflicts
-    fn perform_squash_merge(
+    #[allow(clippy::too_many_arguments)]
+    fn perform_merge(
         &self,
         repo: &Repository,
         base_commit: &git2::Commit,
@@ -1342,6 +1460,7 @@ impl GitService {
         signature: &git2::Signature,
         commit_message: &str,
         base_branch_name: &str,
+        squash: bool,
     ) -> Result<git2::Oid, GitServiceError> {
         // In-memory merge to detect conflicts without touching the working tree
         let mut merge_opts = git2::MergeOptions::new();
@@ -1363,21 +1482,101 @@ impl GitService {
         let tree_id = index.write_tree_to(repo)?;
         let tree = repo.find_tree(tree_id)?;
 
-        // Create a squash commit: use merged tree with base_commit as sole parent
-        let squash_commit_id = repo.commit(
+        // A squash commit has base_commit as sole parent; a merge commit also
+        // records the task commit so its history is kept
+        let parents: &[&git2::Commit] = if squash {
+            &[base_commit]
+        } else {
+            &[base_commit, task_commit]
+        };
+        let merge_commit_id = repo.commit(
             None,           // Don't update any reference yet
             signature,      // Author
             signature,      // Committer
             commit_message, // Custom message
             &tree,          // Merged tree content
-            &[base_commit], // Single parent: base branch commit
+            parents,
         )?;
 
         // Update the base branch reference to point to the new commit
         let refname = format!("refs/heads/{base_branch_name}");
-        repo.reference(&refname, squash_commit_id, true, "Squash merge")?;
+        let log_message = if squash { "Squash merge" } else { "Merge" };
+        repo.reference(&refname, merge_commit_id, true, log_message)?;
 
-        Ok(squash_commit_id)
+        Ok(merge_commit_id)
+    }
+
+    /// Rebase the task branch onto the base branch (if the base has moved on)
+    /// and fast-forward the base branch to it, so the base history stays linear
+    /// and keeps every task commit. Returns the new base branch head.
+    pub fn rebase_and_fast_forward(
+        &self,
+        base_worktree_path: &Path,
+        task_worktree_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+    ) -> Result<String, GitServiceError> {
+        let (_, task_behind) =
+            self.get_branch_status(base_worktree_path, task_branch_name, base_branch_name)?;
+        if task_behind > 0 {
+            // Same clean-worktree and rebase-in-progress guards as a manual rebase
+            if let Err(e) = self.rebase_branch(
+                base_worktree_path,
+                task_worktree_path,
+                base_branch_name,
+                base_branch_name,
+                task_branch_name,
+            ) {
+                // Leave the task worktree as it was rather than mid-rebase
+                if matches!(e, GitServiceError::MergeConflicts { .. }) {
+                    self.abort_rebase(task_worktree_path)?;
+                }
+                return Err(e);
+            }
+        }
+
+        // The base must now be an ancestor of the task branch
+        let (_, task_behind) =
+            self.get_branch_status(base_worktree_path, task_branch_name, base_branch_name)?;
+        if task_behind > 0 {
+            return Err(GitServiceError::BranchesDiverged(format!(
+                "Cannot fast-forward: base branch '{base_branch_name}' is {task_behind} commits ahead of task branch '{task_branch_name}'.",
+            )));
+        }
+
+        match self.find_checkout_path_for_branch(base_worktree_path, base_branch_name)? {
+            Some(base_checkout_path) => {
+                let git_cli = GitCli::new();
+
+                // Safety check: base branch has no staged changes
+                if git_cli
+                    .has_staged_changes(&base_checkout_path)
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("git diff --cached failed: {e}"))
+                    })?
+                {
+                    return Err(GitServiceError::WorktreeDirty(
+                        base_branch_name.to_string(),
+                        "staged changes present".to_string(),
+                    ));
+                }
+
+                git_cli
+                    .merge_ff_only(&base_checkout_path, base_branch_name, task_branch_name)
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("CLI fast-forward failed: {e}"))
+                    })
+            }
+            None => {
+                let repo = self.open_repo(base_worktree_path)?;
+                let task_commit = Self::find_branch(&repo, task_branch_name)?
+                    .get()
+                    .peel_to_commit()?;
+                let refname = format!("refs/heads/{base_branch_name}");
+                repo.reference(&refname, task_commit.id(), true, "Fast-forward merge")?;
+                Ok(task_commit.id().to_string())
+            }
+        }
     }
 
     /// Rebase a worktree branch onto a new base
@@ -1477,6 +1676,71 @@ impl GitService {
         Ok(final_commit.id().to_string())
     }
 
+    /// Revert the commits in `from_commit..to_commit` on top of the worktree's
+    /// HEAD, keeping any commits made after them. Each commit gets its own
+    /// revert commit. On conflicts the revert is left in progress, to be
+    /// continued or aborted like a stopped rebase. Returns the new HEAD commit.
+    pub fn revert_commits(
+        &self,
+        worktree_path: &Path,
+        from_commit: &str,
+        to_commit: &str,
+    ) -> Result<String, GitServiceError> {
+        let worktree_repo = Repository::open(worktree_path)?;
+        self.check_worktree_clean(&worktree_repo)?;
+        match self.detect_conflict_op(worktree_path)? {
+            Some(ConflictOp::Rebase) => return Err(GitServiceError::RebaseInProgress),
+            Some(op) => {
+                return Err(GitServiceError::InvalidRepository(format!(
+                    "A {op:?} is in progress; resolve or abort it before reverting"
+                )));
+            }
+            None => {}
+        }
+
+        self.ensure_cli_commit_identity(worktree_path)?;
+        let git = GitCli::new();
+        match git.revert_range(worktree_path, from_commit, to_commit) {
+            Ok(()) => {}
+            Err(GitCliError::CommandFailed(output)) => {
+                if git.is_revert_in_progress(worktree_path).unwrap_or(false) {
+                    let conflicted_files =
+                        git.get_conflicted_files(worktree_path).unwrap_or_default();
+                    let short = |oid: &str| oid.chars().take(7).collect::<String>();
+                    return Err(GitServiceError::MergeConflicts {
+                        message: format!(
+                            "Reverting {}..{} stopped with conflicts in {}. Resolve them and continue, or abort the revert.",
+                            short(from_commit),
+                            short(to_commit),
+                            conflicted_files.join(", ")
+                        ),
+                        conflicted_files,
+                    });
+                }
+                if output.contains("nothing to commit") {
+                    return Err(GitServiceError::InvalidRepository(
+                        "Nothing to revert; these changes have already been undone".to_string(),
+                    ));
+                }
+                return Err(GitServiceError::InvalidRepository(format!(
+                    "Revert failed: {}",
+                    output
+                        .lines()
+                        .find(|line| !line.starts_with("---"))
+                        .unwrap_or("")
+                )));
+            }
+            Err(e) => {
+                return Err(GitServiceError::InvalidRepository(format!(
+                    "git revert failed: {e}"
+                )));
+            }
+        }
+
+        let head = worktree_repo.head()?.peel_to_commit()?;
+        Ok(head.id().to_string())
+    }
+
     pub fn find_branch_type(
         &self,
         repo_path: &Path,
@@ -1572,6 +1836,87 @@ impl GitService {
         })
     }
 
+    /// Conflicted files with their conflict blocks and surrounding lines.
+    /// Files that can't be read as text (e.g. binary files) have no hunks.
+    pub fn get_conflict_hunks(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<Vec<ConflictedFile>, GitServiceError> {
+        Ok(self
+            .get_conflicted_files(worktree_path)?
+            .into_iter()
+            .map(|path| {
+                let hunks = std::fs::read_to_string(worktree_path.join(&path))
+                    .map(|content| conflicts::find_conflict_hunks(&content, CONFLICT_CONTEXT_LINES))
+                    .unwrap_or_default();
+                ConflictedFile { path, hunks }
+            })
+            .collect())
+    }
+
+    /// The subset of `paths` whose contents still contain conflict markers.
+    pub fn files_with_conflict_markers(
+        &self,
+        worktree_path: &Path,
+        paths: &[String],
+    ) -> Vec<String> {
+        paths
+            .iter()
+            .filter(|path| {
+                std::fs::read_to_string(worktree_path.join(path))
+                    .is_ok_and(|content| conflicts::has_conflict_markers(&content))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Describe both sides of an in-progress conflict as `<short sha> <subject>`:
+    /// HEAD ("ours") and the commit being replayed, merged, cherry-picked or
+    /// reverted ("theirs").
+    pub fn conflict_sides(
+        &self,
+        worktree_path: &Path,
+        op: &ConflictOp,
+    ) -> Result<(String, String), GitServiceError> {
+        let theirs_ref = match op {
+            ConflictOp::Rebase => "REBASE_HEAD",
+            ConflictOp::Merge => "MERGE_HEAD",
+            ConflictOp::CherryPick => "CHERRY_PICK_HEAD",
+            ConflictOp::Revert => "REVERT_HEAD",
+        };
+        let git = GitCli::new();
+        let describe = |rev: &str| {
+            git.git(worktree_path, ["log", "-1", "--format=%h %s", rev])
+                .map(|out| out.trim().to_string())
+                .map_err(|e| {
+                    GitServiceError::InvalidRepository(format!("git log {rev} failed: {e}"))
+                })
+        };
+        Ok((describe("HEAD")?, describe(theirs_ref)?))
+    }
+
+    /// Stage the resolved worktree and continue the in-progress operation.
+    /// Fails (leaving the operation in progress) if the next step conflicts.
+    pub fn continue_conflict_op(
+        &self,
+        worktree_path: &Path,
+        op: &ConflictOp,
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        git.add_all(worktree_path)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))?;
+        self.ensure_cli_commit_identity(worktree_path)?;
+        let command = match op {
+            ConflictOp::Rebase => return self.continue_rebase(worktree_path),
+            ConflictOp::Merge => "merge",
+            ConflictOp::CherryPick => "cherry-pick",
+            ConflictOp::Revert => "revert",
+        };
+        git.continue_operation(worktree_path, command).map_err(|e| {
+            GitServiceError::InvalidRepository(format!("git {command} --continue failed: {e}"))
+        })
+    }
+
     /// Abort an in-progress rebase in this worktree (no-op if none).
     pub fn abort_rebase(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
         let git = GitCli::new();
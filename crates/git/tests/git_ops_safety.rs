@@ -4,7 +4,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use git::{GitCli, GitCliError, GitService};
+use git::{ConflictOp, GitCli, GitCliError, GitService, GitServiceError};
 use git2::{PushOptions, Repository, build::CheckoutBuilder};
 use tempfile::TempDir;
 // Avoid direct git CLI usage in tests; exercise GitService instead.
@@ -293,6 +293,60 @@ fn push_reports_non_fast_forward() {
     }
 }
 
+#[test]
+fn fetch_if_remote_branch_sees_moved_target() {
+    let temp_dir = TempDir::new().unwrap();
+    let remote_path = temp_dir.path().join("remote.git");
+    Repository::init_bare(&remote_path).expect("init bare remote");
+    let remote_url = remote_path.to_str().expect("remote path str");
+
+    let seed_path = temp_dir.path().join("seed");
+    let service = GitService::new();
+    service
+        .initialize_repo_with_main_branch(&seed_path)
+        .expect("init seed repo");
+    let seed_repo = Repository::open(&seed_path).expect("open seed repo");
+    configure_user(&seed_repo);
+    seed_repo.remote("origin", remote_url).expect("add remote");
+    push_ref(&seed_repo, "refs/heads/main", "refs/heads/main");
+    Repository::open_bare(&remote_path)
+        .expect("open bare remote")
+        .set_head("refs/heads/main")
+        .expect("set remote HEAD");
+
+    let local_path = temp_dir.path().join("local");
+    let local_repo = Repository::clone(remote_url, &local_path).expect("clone local");
+    configure_user(&local_repo);
+    create_branch_from_head(&local_repo, "feature");
+
+    // Someone else moves the target on the remote
+    let updater_path = temp_dir.path().join("updater");
+    let updater_repo = Repository::clone(remote_url, &updater_path).expect("clone updater");
+    configure_user(&updater_repo);
+    checkout_branch(&updater_repo, "main");
+    write_file(&updater_path, "upstream.txt", "upstream change\n");
+    commit_all(&updater_repo, "upstream commit");
+    push_ref(&updater_repo, "refs/heads/main", "refs/heads/main");
+
+    let (_, behind) = service
+        .get_branch_status(&local_path, "feature", "origin/main")
+        .unwrap();
+    assert_eq!(behind, 0, "stale remote-tracking branch");
+
+    service
+        .fetch_if_remote_branch(&local_path, "origin/main")
+        .unwrap();
+    let (_, behind) = service
+        .get_branch_status(&local_path, "feature", "origin/main")
+        .unwrap();
+    assert_eq!(behind, 1);
+
+    // Local branches are not touched
+    let before = service.get_branch_oid(&local_path, "main").unwrap();
+    service.fetch_if_remote_branch(&local_path, "main").unwrap();
+    assert_eq!(service.get_branch_oid(&local_path, "main").unwrap(), before);
+}
+
 #[test]
 fn fetch_with_missing_ref_returns_error() {
     let temp_dir = TempDir::new().unwrap();
@@ -769,6 +823,166 @@ fn rebase_applies_multiple_commits_onto_ahead_base() {
     assert_eq!(feat2, "second change\n");
 }
 
+#[test]
+fn rebase_stacked_branch_after_parent_squash_replays_only_own_commits() {
+    let td = TempDir::new().unwrap();
+    let repo_path = td.path().join("repo");
+    let worktree_path = td.path().join("wt-child");
+    let service = GitService::new();
+    service
+        .initialize_repo_with_main_branch(&repo_path)
+        .expect("init repo");
+    let repo = Repository::open(&repo_path).unwrap();
+    configure_user(&repo);
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "common.txt", "base\n");
+    commit_all(&repo, "initial main commit");
+
+    // Parent branch with two commits to the same file
+    create_branch_from_head(&repo, "parent");
+    checkout_branch(&repo, "parent");
+    write_file(&repo_path, "parent.txt", "draft\n");
+    commit_all(&repo, "parent draft");
+    write_file(&repo_path, "parent.txt", "final\n");
+    commit_all(&repo, "parent final");
+
+    // Child stacked on the parent
+    create_branch_from_head(&repo, "child");
+    service
+        .add_worktree(&repo_path, &worktree_path, "child", false)
+        .expect("create worktree");
+    write_file(&worktree_path, "child.txt", "child change\n");
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    commit_all(&wt_repo, "child commit");
+
+    // Parent lands on main as a single squashed commit
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "parent.txt", "final\n");
+    commit_all(&repo, "squashed parent");
+
+    service
+        .rebase_branch(&repo_path, &worktree_path, "main", "parent", "child")
+        .expect("rebase should replay only the child commit");
+
+    let (ahead, behind) = service
+        .get_branch_status(&repo_path, "child", "main")
+        .unwrap();
+    assert_eq!((ahead, behind), (1, 0));
+    let parent = fs::read_to_string(worktree_path.join("parent.txt")).unwrap();
+    let child = fs::read_to_string(worktree_path.join("child.txt")).unwrap();
+    assert_eq!(parent, "final\n");
+    assert_eq!(child, "child change\n");
+}
+
+/// Parent branch with two commits and a child stacked on it, each checked
+/// out in its own worktree, after which main moves on
+fn setup_stacked_repo(root: &TempDir) -> (PathBuf, PathBuf, PathBuf) {
+    let repo_path = root.path().join("repo");
+    let parent_path = root.path().join("wt-parent");
+    let child_path = root.path().join("wt-child");
+    let service = GitService::new();
+    service
+        .initialize_repo_with_main_branch(&repo_path)
+        .expect("init repo");
+    let repo = Repository::open(&repo_path).unwrap();
+    configure_user(&repo);
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "common.txt", "base\n");
+    commit_all(&repo, "initial main commit");
+
+    create_branch_from_head(&repo, "parent");
+    service
+        .add_worktree(&repo_path, &parent_path, "parent", false)
+        .expect("create parent worktree");
+    let parent_repo = Repository::open(&parent_path).unwrap();
+    write_file(&parent_path, "parent.txt", "draft\n");
+    commit_all(&parent_repo, "parent draft");
+    write_file(&parent_path, "parent.txt", "final\n");
+    commit_all(&parent_repo, "parent final");
+
+    create_branch_from_head(&parent_repo, "child");
+    service
+        .add_worktree(&repo_path, &child_path, "child", false)
+        .expect("create child worktree");
+    let child_repo = Repository::open(&child_path).unwrap();
+    write_file(&child_path, "child.txt", "child change\n");
+    commit_all(&child_repo, "child commit");
+
+    write_file(&repo_path, "main.txt", "main moved\n");
+    commit_all(&repo, "main moved");
+
+    (repo_path, parent_path, child_path)
+}
+
+#[test]
+fn restack_after_parent_rebase_replays_only_child_commits() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, parent_path, child_path) = setup_stacked_repo(&td);
+    let service = GitService::new();
+    let old_parent = service.get_branch_oid(&repo_path, "parent").unwrap();
+
+    service
+        .rebase_branch(&repo_path, &parent_path, "main", "main", "parent")
+        .expect("rebase parent");
+    let fork_point = service
+        .get_fork_point(&repo_path, "parent", "child")
+        .unwrap();
+    assert_eq!(fork_point, old_parent);
+
+    service
+        .rebase_branch(&repo_path, &child_path, "parent", &fork_point, "child")
+        .expect("restack should replay only the child commit");
+    let (ahead, behind) = service
+        .get_branch_status(&repo_path, "child", "parent")
+        .unwrap();
+    assert_eq!((ahead, behind), (1, 0));
+    assert!(child_path.join("main.txt").exists());
+}
+
+#[test]
+fn restack_after_conflicted_parent_rebase_replays_only_child_commits() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, parent_path, child_path) = setup_stacked_repo(&td);
+    let service = GitService::new();
+    let old_parent = service.get_branch_oid(&repo_path, "parent").unwrap();
+
+    // Main changes the file the parent changed, so the parent's rebase stops
+    let repo = Repository::open(&repo_path).unwrap();
+    write_file(&repo_path, "parent.txt", "main version\n");
+    commit_all(&repo, "main touches parent.txt");
+    let res = service.rebase_branch(&repo_path, &parent_path, "main", "main", "parent");
+    assert!(matches!(res, Err(GitServiceError::MergeConflicts { .. })));
+
+    // Both parent commits stop on parent.txt; resolve each the way a
+    // conflict agent would
+    let git = GitCli::new();
+    for resolution in ["resolved draft\n", "resolved final\n"] {
+        assert!(git.is_rebase_in_progress(&parent_path).unwrap());
+        write_file(&parent_path, "parent.txt", resolution);
+        add_path(&parent_path, "parent.txt");
+        let _ = service.continue_rebase(&parent_path);
+    }
+    assert!(!git.is_rebase_in_progress(&parent_path).unwrap());
+
+    let fork_point = service
+        .get_fork_point(&repo_path, "parent", "child")
+        .unwrap();
+    assert_eq!(fork_point, old_parent);
+
+    // Replaying the parent's original commits would conflict with the resolution
+    service
+        .rebase_branch(&repo_path, &child_path, "parent", &fork_point, "child")
+        .expect("restack should replay only the child commit");
+    let (ahead, behind) = service
+        .get_branch_status(&repo_path, "child", "parent")
+        .unwrap();
+    assert_eq!((ahead, behind), (1, 0));
+    assert_eq!(
+        fs::read_to_string(child_path.join("parent.txt")).unwrap(),
+        "resolved final\n"
+    );
+}
+
 #[test]
 fn merge_when_base_ahead_and_feature_ahead_fails() {
     let td = TempDir::new().unwrap();
@@ -1406,3 +1620,281 @@ fn merge_base_ahead_of_task_should_error() {
         "Merge should error when base branch is ahead of task branch"
     );
 }
+
+#[test]
+fn merge_commit_keeps_task_commit_as_second_parent() {
+    // libgit2 path: main repo is not on the base branch
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+    let feature_before = s.get_branch_oid(&repo_path, "feature").unwrap();
+    let main_before = s.get_branch_oid(&repo_path, "main").unwrap();
+
+    let sha = s
+        .merge_changes_with_merge_commit(&repo_path, &worktree_path, "feature", "main", "merge")
+        .expect("merge commit should succeed via libgit2 path");
+
+    let repo = Repository::open(&repo_path).unwrap();
+    let commit = repo
+        .find_commit(git2::Oid::from_str(&sha).unwrap())
+        .unwrap();
+    let parents: Vec<String> = commit.parent_ids().map(|id| id.to_string()).collect();
+    assert_eq!(parents, vec![main_before, feature_before]);
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), sha);
+    assert_eq!(s.get_branch_oid(&repo_path, "feature").unwrap(), sha);
+}
+
+#[test]
+fn merge_commit_cli_path_updates_checked_out_base() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+
+    let sha = s
+        .merge_changes_with_merge_commit(&repo_path, &worktree_path, "feature", "main", "merge")
+        .expect("merge commit should succeed via CLI path");
+
+    let commit = repo
+        .find_commit(git2::Oid::from_str(&sha).unwrap())
+        .unwrap();
+    assert_eq!(commit.parent_count(), 2);
+    let feat = std::fs::read_to_string(repo_path.join("feat.txt")).unwrap();
+    assert_eq!(feat, "feat change\n");
+    assert_eq!(s.get_branch_oid(&repo_path, "feature").unwrap(), sha);
+}
+
+#[test]
+fn merge_commit_refuses_when_base_ahead() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "base_ahead.txt", "base ahead\n");
+    commit_all(&repo, "base ahead commit");
+    let before_main = s.get_branch_oid(&repo_path, "main").unwrap();
+
+    let res =
+        s.merge_changes_with_merge_commit(&repo_path, &worktree_path, "feature", "main", "merge");
+    assert!(matches!(res, Err(GitServiceError::BranchesDiverged(_))));
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), before_main);
+}
+
+#[test]
+fn rebase_and_fast_forward_rebases_when_base_ahead() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "base_ahead.txt", "base ahead\n");
+    commit_all(&repo, "base ahead commit");
+    let base_ahead = s.get_branch_oid(&repo_path, "main").unwrap();
+
+    let sha = s
+        .rebase_and_fast_forward(&repo_path, &worktree_path, "feature", "main")
+        .expect("rebase and fast-forward should succeed");
+
+    // Base and task branch point at the same, linear history
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), sha);
+    assert_eq!(s.get_branch_oid(&repo_path, "feature").unwrap(), sha);
+    let head = repo
+        .find_commit(git2::Oid::from_str(&sha).unwrap())
+        .unwrap();
+    assert_eq!(head.parent_count(), 1);
+    assert!(
+        repo.graph_descendant_of(head.id(), git2::Oid::from_str(&base_ahead).unwrap())
+            .unwrap()
+    );
+    // Checked-out base worktree has both sides' files
+    assert!(repo_path.join("base_ahead.txt").exists());
+    assert!(repo_path.join("feat.txt").exists());
+}
+
+#[test]
+fn rebase_and_fast_forward_refuses_dirty_task_worktree() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "base_ahead.txt", "base ahead\n");
+    commit_all(&repo, "base ahead commit");
+    let before_main = s.get_branch_oid(&repo_path, "main").unwrap();
+
+    // Uncommitted change to a tracked file in the task worktree
+    write_file(&worktree_path, "feat.txt", "dirty\n");
+
+    let res = s.rebase_and_fast_forward(&repo_path, &worktree_path, "feature", "main");
+    assert!(res.is_err(), "should refuse to rebase a dirty worktree");
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), before_main);
+    let feat = std::fs::read_to_string(worktree_path.join("feat.txt")).unwrap();
+    assert_eq!(feat, "dirty\n");
+}
+
+#[test]
+fn rebase_and_fast_forward_aborts_on_conflict() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+    let s = GitService::new();
+    let before_feature = s.get_branch_oid(&repo_path, "feature").unwrap();
+    let before_base = s.get_branch_oid(&repo_path, "new-base").unwrap();
+
+    let res = s.rebase_and_fast_forward(&repo_path, &worktree_path, "feature", "new-base");
+    assert!(matches!(res, Err(GitServiceError::MergeConflicts { .. })));
+
+    // No rebase left behind, and neither branch moved
+    assert_eq!(s.detect_conflict_op(&worktree_path).unwrap(), None);
+    assert_eq!(
+        s.get_branch_oid(&repo_path, "feature").unwrap(),
+        before_feature
+    );
+    assert_eq!(
+        s.get_branch_oid(&repo_path, "new-base").unwrap(),
+        before_base
+    );
+    let content = std::fs::read_to_string(worktree_path.join("conflict.txt")).unwrap();
+    assert_eq!(content, "feature version\n");
+}
+
+#[test]
+fn conflict_hunks_and_continue_after_resolution() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+    let s = GitService::new();
+    let _ = s
+        .rebase_branch(
+            &repo_path,
+            &worktree_path,
+            "new-base",
+            "old-base",
+            "feature",
+        )
+        .expect_err("rebase should stop on conflicts");
+
+    let op = s.detect_conflict_op(&worktree_path).unwrap();
+    assert_eq!(op, Some(ConflictOp::Rebase));
+    let files = s.get_conflict_hunks(&worktree_path).unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].path, "conflict.txt");
+    assert_eq!(files[0].hunks.len(), 1);
+    assert!(files[0].hunks[0].text.contains("new-base version"));
+    assert!(files[0].hunks[0].text.contains("feature version"));
+
+    let (ours, theirs) = s
+        .conflict_sides(&worktree_path, &ConflictOp::Rebase)
+        .unwrap();
+    assert!(ours.ends_with("new-base change"));
+    assert!(theirs.ends_with("feature conflicting change"));
+
+    let paths = vec!["conflict.txt".to_string()];
+    assert_eq!(s.files_with_conflict_markers(&worktree_path, &paths), paths);
+
+    // Resolve and continue
+    write_file(&worktree_path, "conflict.txt", "resolved version\n");
+    assert!(
+        s.files_with_conflict_markers(&worktree_path, &paths)
+            .is_empty()
+    );
+    s.continue_conflict_op(&worktree_path, &ConflictOp::Rebase)
+        .expect("continue should finish the rebase");
+
+    assert_eq!(s.detect_conflict_op(&worktree_path).unwrap(), None);
+    let content = std::fs::read_to_string(worktree_path.join("conflict.txt")).unwrap();
+    assert_eq!(content, "resolved version\n");
+    let (_, behind) = s
+        .get_branch_status(&repo_path, "feature", "new-base")
+        .unwrap();
+    assert_eq!(behind, 0);
+}
+
+// Three agent turns on top of the feature branch; returns the HEAD after each
+fn setup_turns(worktree_path: &Path) -> Vec<String> {
+    let s = GitService::new();
+    let wt_repo = Repository::open(worktree_path).unwrap();
+    let mut heads = vec![s.get_head_info(worktree_path).unwrap().oid];
+    write_file(worktree_path, "turn1.txt", "first turn\n");
+    commit_all(&wt_repo, "turn 1");
+    heads.push(s.get_head_info(worktree_path).unwrap().oid);
+    write_file(worktree_path, "shared.txt", "from turn 2\n");
+    commit_all(&wt_repo, "turn 2");
+    heads.push(s.get_head_info(worktree_path).unwrap().oid);
+    write_file(worktree_path, "turn3.txt", "third turn\n");
+    commit_all(&wt_repo, "turn 3");
+    heads.push(s.get_head_info(worktree_path).unwrap().oid);
+    heads
+}
+
+#[test]
+fn revert_commits_undoes_one_turn_and_keeps_later_ones() {
+    let td = TempDir::new().unwrap();
+    let (_repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let heads = setup_turns(&worktree_path);
+    let s = GitService::new();
+
+    let new_head = s
+        .revert_commits(&worktree_path, &heads[1], &heads[2])
+        .expect("revert should succeed");
+
+    assert_eq!(s.get_head_info(&worktree_path).unwrap().oid, new_head);
+    assert!(!worktree_path.join("shared.txt").exists());
+    assert!(worktree_path.join("turn1.txt").exists());
+    assert!(worktree_path.join("turn3.txt").exists());
+    assert_eq!(
+        s.get_commit_subject(&worktree_path, &new_head).unwrap(),
+        "Revert \"turn 2\""
+    );
+}
+
+#[test]
+fn revert_commits_stops_on_conflicts_and_can_be_aborted() {
+    let td = TempDir::new().unwrap();
+    let (_repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let heads = setup_turns(&worktree_path);
+    // A later turn edits the file the reverted turn added
+    write_file(&worktree_path, "shared.txt", "edited by turn 4\n");
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    commit_all(&wt_repo, "turn 4");
+    let s = GitService::new();
+    let before = s.get_head_info(&worktree_path).unwrap().oid;
+
+    match s.revert_commits(&worktree_path, &heads[1], &heads[2]) {
+        Err(GitServiceError::MergeConflicts {
+            conflicted_files, ..
+        }) => assert_eq!(conflicted_files, vec!["shared.txt".to_string()]),
+        other => panic!("expected conflicts, got {other:?}"),
+    }
+    assert_eq!(
+        s.detect_conflict_op(&worktree_path).unwrap(),
+        Some(ConflictOp::Revert)
+    );
+
+    s.abort_conflicts(&worktree_path).unwrap();
+    assert_eq!(s.detect_conflict_op(&worktree_path).unwrap(), None);
+    assert_eq!(s.get_head_info(&worktree_path).unwrap().oid, before);
+    let shared = fs::read_to_string(worktree_path.join("shared.txt")).unwrap();
+    assert_eq!(shared, "edited by turn 4\n");
+}
+
+#[test]
+fn revert_commits_reports_already_undone_changes() {
+    let td = TempDir::new().unwrap();
+    let (_repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let heads = setup_turns(&worktree_path);
+    let s = GitService::new();
+    s.revert_commits(&worktree_path, &heads[1], &heads[2])
+        .expect("first revert should succeed");
+    let before = s.get_head_info(&worktree_path).unwrap().oid;
+
+    let res = s.revert_commits(&worktree_path, &heads[1], &heads[2]);
+    assert!(
+        matches!(res, Err(GitServiceError::InvalidRepository(ref msg)) if msg.starts_with("Nothing to revert")),
+        "expected nothing to revert, got {res:?}"
+    );
+    // No revert state is left behind
+    assert_eq!(s.detect_conflict_op(&worktree_path).unwrap(), None);
+    assert_eq!(s.get_head_info(&worktree_path).unwrap().oid, before);
+    assert!(s.is_worktree_clean(&worktree_path).unwrap());
+}
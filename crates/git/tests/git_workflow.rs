@@ -520,3 +520,22 @@ fn squash_merge_libgit2_sets_author_without_user() {
         assert_eq!(email.as_deref(), Some("noreply@vibekanban.com"));
     }
 }
+
+#[test]
+fn exclude_path_hides_generated_file_from_status() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, ".cursor/mcp.json", "{}\n");
+
+    s.exclude_path(&repo_path.join(".cursor/mcp.json")).unwrap();
+    // Excluding twice does not duplicate the pattern
+    s.exclude_path(&repo_path.join(".cursor/mcp.json")).unwrap();
+
+    let exclude = fs::read_to_string(repo_path.join(".git/info/exclude")).unwrap();
+    assert_eq!(exclude.matches("/.cursor/mcp.json").count(), 1);
+    assert!(s.is_path_excluded(&repo_path.join(".cursor/mcp.json")));
+    assert!(!s.is_path_excluded(&repo_path.join("file.txt")));
+    let status = s.get_worktree_status(&repo_path).unwrap();
+    assert!(status.entries.is_empty());
+}
@@ -12,11 +12,14 @@ use command_group::AsyncGroupChild;
 use db::{
     DBService,
     models::{
+        agent_instructions::AgentInstructions,
         coding_agent_turn::CodingAgentTurn,
+        commit_message_run::CommitMessageRun,
         execution_process::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
         },
         execution_process_repo_state::ExecutionProcessRepoState,
+        mcp_servers::McpServers,
         repo::Repo,
         scratch::{DraftFollowUpData, Scratch, ScratchType},
         session::{Session, SessionError},
@@ -36,6 +39,9 @@ use executors::{
     env::{ExecutionEnv, RepoContext},
     executors::{BaseCodingAgent, CancellationToken, ExecutorExitResult, ExecutorExitSignal},
     logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
+    oci::{self, OciExec},
+    profile::ExecutorConfigs,
+    steering::{SteerRequest, SteerSender},
 };
 use futures::{FutureExt, TryStreamExt, stream::select};
 use git::GitService;
@@ -43,15 +49,20 @@ use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
-    config::{Config, DEFAULT_COMMIT_REMINDER_PROMPT},
+    commit_message::{CommitMessageVars, format_commit_message},
+    config::{ConcurrencyConfig, Config, DEFAULT_COMMIT_REMINDER_PROMPT},
     container::{ContainerError, ContainerRef, ContainerService},
     diff_stream::{self, DiffStreamHandle},
+    fallback::FallbackReason,
     image::ImageService,
     notification::NotificationService,
     queued_message::QueuedMessageService,
     remote_client::RemoteClient,
     remote_sync,
+    scheduler::ExecutionScheduler,
+    stacked_branches::StackedBranches,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
+    workspace_ports,
 };
 use tokio::{sync::RwLock, task::JoinHandle};
 use tokio_util::io::ReaderStream;
@@ -62,18 +73,23 @@ use utils::{
 };
 use uuid::Uuid;
 
-use crate::{command, copy};
+use crate::{command, copy, port_detection::PortDetector, resource_usage::ResourceMonitor};
 
 #[derive(Clone)]
 pub struct LocalContainerService {
     db: DBService,
     child_store: Arc<RwLock<HashMap<Uuid, Arc<RwLock<AsyncGroupChild>>>>>,
     cancellation_tokens: Arc<RwLock<HashMap<Uuid, CancellationToken>>>,
+    steer_senders: Arc<RwLock<HashMap<Uuid, SteerSender>>>,
+    /// Workspace containers that running executions were started in
+    oci_executions: Arc<RwLock<HashMap<Uuid, OciExec>>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
     /// Tracks background tasks that stream logs to the database.
     /// When stopping execution, we await these to ensure logs are fully persisted.
     db_stream_handles: Arc<RwLock<HashMap<Uuid, JoinHandle<()>>>>,
     exit_monitor_handles: Arc<RwLock<HashMap<Uuid, JoinHandle<()>>>>,
+    resource_monitors: Arc<RwLock<HashMap<Uuid, ResourceMonitor>>>,
+    port_detectors: Arc<RwLock<HashMap<Uuid, PortDetector>>>,
     config: Arc<RwLock<Config>>,
     git: GitService,
     image_service: ImageService,
@@ -81,6 +97,7 @@ pub struct LocalContainerService {
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
     notification_service: NotificationService,
+    scheduler: ExecutionScheduler,
     remote_client: Option<RemoteClient>,
 }
 
@@ -99,17 +116,25 @@ impl LocalContainerService {
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let cancellation_tokens = Arc::new(RwLock::new(HashMap::new()));
+        let steer_senders = Arc::new(RwLock::new(HashMap::new()));
+        let oci_executions = Arc::new(RwLock::new(HashMap::new()));
         let db_stream_handles = Arc::new(RwLock::new(HashMap::new()));
         let exit_monitor_handles = Arc::new(RwLock::new(HashMap::new()));
+        let resource_monitors = Arc::new(RwLock::new(HashMap::new()));
+        let port_detectors = Arc::new(RwLock::new(HashMap::new()));
         let notification_service = NotificationService::new(config.clone());
 
         let container = LocalContainerService {
             db,
             child_store,
             cancellation_tokens,
+            steer_senders,
+            oci_executions,
             msg_stores,
             db_stream_handles,
             exit_monitor_handles,
+            resource_monitors,
+            port_detectors,
             config,
             git,
             image_service,
@@ -117,6 +142,7 @@ impl LocalContainerService {
             approvals,
             queued_message_service,
             notification_service,
+            scheduler: ExecutionScheduler::new(),
             remote_client,
         };
 
@@ -170,7 +196,34 @@ impl LocalContainerService {
         map.remove(id)
     }
 
-    pub async fn cleanup_workspace(db: &DBService, workspace: &Workspace) {
+    async fn add_resource_monitor(&self, id: Uuid, monitor: ResourceMonitor) {
+        let mut map = self.resource_monitors.write().await;
+        map.insert(id, monitor);
+    }
+
+    /// Stop sampling an execution's process group and store its peak usage.
+    async fn record_resource_usage(&self, id: Uuid) {
+        let Some(monitor) = self.resource_monitors.write().await.remove(&id) else {
+            return;
+        };
+        let Some(usage) = monitor.finish() else {
+            return;
+        };
+        if let Err(e) = ExecutionProcess::update_resource_usage(
+            &self.db.pool,
+            id,
+            i64::try_from(usage.peak_memory_bytes).ok(),
+            i64::try_from(usage.cpu_time_ms).ok(),
+        )
+        .await
+        {
+            tracing::warn!("Failed to record resource usage for {}: {}", id, e);
+        }
+    }
+
+    /// Remove a workspace's worktrees, and its container when `remove_container`
+    /// is set because workspace containers are enabled
+    pub async fn cleanup_workspace(db: &DBService, workspace: &Workspace, remove_container: bool) {
         let Some(container_ref) = &workspace.container_ref else {
             return;
         };
@@ -202,11 +255,18 @@ impl LocalContainerService {
                 });
         }
 
+        if remove_container {
+            oci::remove_workspace_container(&workspace.id).await;
+        }
+
         // Clear container_ref so this workspace won't be picked up again
         let _ = Workspace::clear_container_ref(&db.pool, workspace.id).await;
     }
 
-    pub async fn cleanup_expired_workspaces(db: &DBService) -> Result<(), DeploymentError> {
+    pub async fn cleanup_expired_workspaces(
+        db: &DBService,
+        config: &RwLock<Config>,
+    ) -> Result<(), DeploymentError> {
         if std::env::var("DISABLE_WORKTREE_CLEANUP").is_ok() {
             tracing::info!(
                 "Expired workspace cleanup is disabled via DISABLE_WORKTREE_CLEANUP environment variable"
@@ -223,14 +283,16 @@ impl LocalContainerService {
             "Found {} expired workspaces to clean up",
             expired_workspaces.len()
         );
+        let remove_containers = config.read().await.workspace_container.enabled;
         for workspace in &expired_workspaces {
-            Self::cleanup_workspace(db, workspace).await;
+            Self::cleanup_workspace(db, workspace, remove_containers).await;
         }
         Ok(())
     }
 
     pub fn spawn_workspace_cleanup(&self) {
         let db = self.db.clone();
+        let config = self.config.clone();
         let cleanup_expired = Self::cleanup_expired_workspaces;
         tokio::spawn(async move {
             WorkspaceManager::cleanup_orphan_workspaces(&db.pool).await;
@@ -240,7 +302,7 @@ impl LocalContainerService {
             loop {
                 cleanup_interval.tick().await;
                 tracing::info!("Starting periodic workspace cleanup...");
-                cleanup_expired(&db).await.unwrap_or_else(|e| {
+                cleanup_expired(&db, &config).await.unwrap_or_else(|e| {
                     tracing::error!("Failed to clean up expired workspaces: {}", e)
                 });
             }
@@ -268,15 +330,16 @@ impl LocalContainerService {
         }
     }
 
-    /// Get the commit message based on the execution run reason.
-    async fn get_commit_message(&self, ctx: &ExecutionContext) -> String {
+    /// Get the commit message based on the execution run reason. Coding agent
+    /// commits use the summary of `summary_process_id`'s turn.
+    async fn get_commit_message(&self, ctx: &ExecutionContext, summary_process_id: Uuid) -> String {
         match ctx.execution_process.run_reason {
             ExecutionProcessRunReason::CodingAgent => {
                 // Try to retrieve the task summary from the coding agent turn
                 // otherwise fallback to default message
                 match CodingAgentTurn::find_by_execution_process_id(
                     &self.db().pool,
-                    ctx.execution_process.id,
+                    summary_process_id,
                 )
                 .await
                 {
@@ -284,7 +347,7 @@ impl LocalContainerService {
                     Ok(_) => {
                         tracing::debug!(
                             "No summary found for execution process {}, using default message",
-                            ctx.execution_process.id
+                            summary_process_id
                         );
                         format!(
                             "Commit changes from coding agent for workspace {}",
@@ -294,7 +357,7 @@ impl LocalContainerService {
                     Err(e) => {
                         tracing::debug!(
                             "Failed to retrieve summary for execution process {}: {}",
-                            ctx.execution_process.id,
+                            summary_process_id,
                             e
                         );
                         format!(
@@ -326,6 +389,17 @@ impl LocalContainerService {
         for repo in repos {
             let worktree_path = workspace_root.join(&repo.name);
 
+            // Never commit in the middle of a rebase or merge; conflict
+            // markers would end up in history
+            if let Ok(Some(op)) = git.detect_conflict_op(&worktree_path) {
+                tracing::debug!(
+                    "Skipping commit in repo '{}': {:?} in progress",
+                    repo.name,
+                    op
+                );
+                continue;
+            }
+
             match git.get_worktree_status(&worktree_path) {
                 Ok(ws) if !ws.entries.is_empty() => {
                     repos_with_changes.push((repo.clone(), worktree_path));
@@ -376,7 +450,11 @@ impl LocalContainerService {
     }
 
     /// Commit changes to each repo. Logs failures but continues with other repos.
-    fn commit_repos(&self, repos_with_changes: Vec<(Repo, PathBuf)>, message: &str) -> bool {
+    fn commit_repos(
+        &self,
+        repos_with_changes: Vec<(Repo, PathBuf)>,
+        message_for: impl Fn(&Repo) -> String,
+    ) -> bool {
         let mut any_committed = false;
 
         for (repo, worktree_path) in repos_with_changes {
@@ -386,7 +464,7 @@ impl LocalContainerService {
                 &worktree_path
             );
 
-            match self.git().commit(&worktree_path, message) {
+            match self.git().commit(&worktree_path, &message_for(&repo)) {
                 Ok(true) => {
                     any_committed = true;
                     tracing::info!("Committed changes in repo '{}'", repo.name);
@@ -403,6 +481,114 @@ impl LocalContainerService {
         any_committed
     }
 
+    /// Number of `ctx`'s coding agent turn among the turns the user asked for
+    /// in its session. Commit message, conflict resolution and fallback runs
+    /// don't count.
+    async fn turn_number(&self, ctx: &ExecutionContext) -> i64 {
+        CodingAgentTurn::count_user_turns(
+            &self.db.pool,
+            ctx.session.id,
+            ctx.execution_process.created_at,
+        )
+        .await
+        .map(|count| count.max(1))
+        .unwrap_or(1)
+    }
+
+    /// The commit message run started as `execution_process_id`, if any
+    async fn find_commit_message_run(
+        &self,
+        execution_process_id: Uuid,
+    ) -> Option<CommitMessageRun> {
+        CommitMessageRun::find_by_execution_process_id(&self.db.pool, execution_process_id)
+            .await
+            .inspect_err(|e| {
+                tracing::warn!(
+                    "Failed to load commit message run of execution {}: {}",
+                    execution_process_id,
+                    e
+                )
+            })
+            .ok()
+            .flatten()
+    }
+
+    /// Co-authors configured on the profile that ran `ctx`'s turn
+    fn co_authors(&self, ctx: &ExecutionContext) -> Vec<String> {
+        ctx.execution_process
+            .executor_action()
+            .ok()
+            .and_then(|action| action.executor_profile_id())
+            .and_then(|profile_id| ExecutorConfigs::get_cached().get_coding_agent(profile_id))
+            .and_then(|agent| agent.cmd_overrides().and_then(|cmd| cmd.co_authors.clone()))
+            .unwrap_or_default()
+    }
+
+    /// When a coding agent turn changed repos that want agent-written commit
+    /// messages, ask the agent to describe its changes before committing them.
+    /// Returns true if such a run was started; the turn's next action then
+    /// follows that run instead.
+    async fn start_commit_message_run_if_needed(&self, ctx: &ExecutionContext) -> bool {
+        if !matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::CodingAgent
+        ) || ctx.execution_process.status != ExecutionProcessStatus::Completed
+            // Never ask a commit message run for a commit message
+            || self
+                .find_commit_message_run(ctx.execution_process.id)
+                .await
+                .is_some()
+        {
+            return false;
+        }
+        let Some(container_ref) = ctx.workspace.container_ref.as_ref() else {
+            return false;
+        };
+        let agent_repos: Vec<Repo> = ctx
+            .repos
+            .iter()
+            .filter(|repo| repo.agent_commit_messages)
+            .cloned()
+            .collect();
+        if agent_repos.is_empty() {
+            return false;
+        }
+        let changed_repos: Vec<Repo> =
+            match self.check_repos_for_changes(Path::new(container_ref), &agent_repos) {
+                Ok(repos) => repos.into_iter().map(|(repo, _)| repo).collect(),
+                Err(e) => {
+                    tracing::warn!("Failed to check repos for a commit message run: {}", e);
+                    return false;
+                }
+            };
+        if changed_repos.is_empty() {
+            return false;
+        }
+
+        let turn = self.turn_number(ctx).await;
+        match self
+            .start_commit_message_run(ctx, &changed_repos, turn)
+            .await
+        {
+            Ok(process) => {
+                tracing::info!(
+                    "Asked the agent for a commit message for process {} in run {}",
+                    ctx.execution_process.id,
+                    process.id
+                );
+                true
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to start commit message run for process {}: {}",
+                    ctx.execution_process.id,
+                    e
+                );
+                false
+            }
+        }
+    }
+
     /// Spawn a background task that polls the child process for completion and
     /// cleans up the execution entry when it exits.
     pub fn spawn_exit_monitor(
@@ -473,6 +659,12 @@ impl LocalContainerService {
             {
                 tracing::error!("Failed to update execution process completion: {}", e);
             }
+            container.record_resource_usage(exec_id).await;
+            container.port_detectors.write().await.remove(&exec_id);
+            container.steer_senders.write().await.remove(&exec_id);
+            container.oci_executions.write().await.remove(&exec_id);
+            // A coding agent slot may have freed up
+            container.start_queued_executions().await;
 
             let mut should_delay_for_session = false;
 
@@ -493,10 +685,35 @@ impl LocalContainerService {
                     tracing::warn!("Failed to update executor session summary: {}", e);
                 }
 
-                let success = matches!(
+                let run_failed = !matches!(
                     ctx.execution_process.status,
                     ExecutionProcessStatus::Completed
-                ) && exit_code == Some(0);
+                ) || exit_code != Some(0);
+
+                // A run that failed after hitting a rate limit or failing to
+                // authenticate is continued by its fallback profile instead of
+                // being wrapped up. Runs that recovered and completed are not.
+                let fallback_started = match container
+                    .get_msg_store_by_id(&exec_id)
+                    .await
+                    .and_then(|msg_store| FallbackReason::from_history(&msg_store.get_history()))
+                {
+                    Some(reason)
+                        if run_failed
+                            && ctx.execution_process.status != ExecutionProcessStatus::Killed =>
+                    {
+                        container
+                            .try_start_fallback(&ctx, reason)
+                            .await
+                            .unwrap_or_else(|e| {
+                                tracing::error!("Failed to start fallback profile: {}", e);
+                                false
+                            })
+                    }
+                    _ => false,
+                };
+
+                let success = !fallback_started && !run_failed;
 
                 let cleanup_done = matches!(
                     ctx.execution_process.run_reason,
@@ -506,7 +723,21 @@ impl LocalContainerService {
                     ExecutionProcessStatus::Running
                 );
 
-                if success || cleanup_done {
+                // Continue a rebase/merge whose conflicts the agent was asked to resolve
+                let resolving_conflicts = container.continue_conflict_resolution(&ctx).await;
+                // Let the agent describe its changes before they are committed
+                let writing_commit_message = !resolving_conflicts
+                    && !fallback_started
+                    && container.start_commit_message_run_if_needed(&ctx).await;
+
+                if !success && container.find_commit_message_run(exec_id).await.is_some() {
+                    // Still commit the described turn's changes, with its own summary
+                    if let Err(e) = container.try_commit_changes(&ctx).await {
+                        tracing::error!("Failed to commit changes after execution: {}", e);
+                    }
+                }
+
+                if (success || cleanup_done) && !resolving_conflicts {
                     // Commit changes (if any) and get feedback about whether changes were made
                     let changes_committed = match container.try_commit_changes(&ctx).await {
                         Ok(committed) => committed,
@@ -517,7 +748,10 @@ impl LocalContainerService {
                         }
                     };
 
-                    let should_start_next = if matches!(
+                    let should_start_next = if writing_commit_message {
+                        // Started once the commit message run has finished
+                        false
+                    } else if matches!(
                         ctx.execution_process.run_reason,
                         ExecutionProcessRunReason::CodingAgent
                     ) {
@@ -536,7 +770,7 @@ impl LocalContainerService {
                         if let Err(e) = container.try_start_next_action(&ctx).await {
                             tracing::error!("Failed to start next action after completion: {}", e);
                         }
-                    } else {
+                    } else if !writing_commit_message {
                         tracing::info!(
                             "Skipping cleanup script for workspace {} - no changes made by coding agent",
                             ctx.workspace.id
@@ -547,7 +781,34 @@ impl LocalContainerService {
                     }
                 }
 
-                if container.should_finalize(&ctx) {
+                if let Err(e) = CommitMessageRun::delete(&db.pool, exec_id).await {
+                    tracing::warn!("Failed to remove commit message run {}: {}", exec_id, e);
+                }
+
+                if container.should_finalize(&ctx)
+                    && !resolving_conflicts
+                    && !writing_commit_message
+                    && !fallback_started
+                {
+                    // Bring workspaces stacked on this one up to its new commits
+                    for repo in &ctx.repos {
+                        if let Err(e) = StackedBranches::restack_children(
+                            &db.pool,
+                            container.git(),
+                            container.scheduler(),
+                            &ctx.workspace,
+                            repo,
+                        )
+                        .await
+                        {
+                            tracing::error!(
+                                "Failed to rebase workspaces stacked on {}: {}",
+                                ctx.workspace.id,
+                                e
+                            );
+                        }
+                    }
+
                     // Only execute queued messages if the execution succeeded
                     // If it failed or was killed, just clear the queue and finalize
                     let should_execute_queued = !matches!(
@@ -975,6 +1236,7 @@ impl LocalContainerService {
                 prompt: queued_data.message.clone(),
                 executor_profile_id: executor_profile_id.clone(),
                 working_dir,
+                fallback_profiles: None,
             })
         };
 
@@ -1021,6 +1283,10 @@ impl ContainerService for LocalContainerService {
         &self.notification_service
     }
 
+    fn scheduler(&self) -> &ExecutionScheduler {
+        &self.scheduler
+    }
+
     async fn store_db_stream_handle(&self, id: Uuid, handle: JoinHandle<()>) {
         self.add_db_stream_handle(id, handle).await;
     }
@@ -1033,6 +1299,10 @@ impl ContainerService for LocalContainerService {
         self.config.read().await.git_branch_prefix.clone()
     }
 
+    async fn concurrency_config(&self) -> ConcurrencyConfig {
+        self.config.read().await.concurrency.clone()
+    }
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
         PathBuf::from(workspace.container_ref.clone().unwrap_or_default())
     }
@@ -1100,7 +1370,8 @@ impl ContainerService for LocalContainerService {
 
     async fn delete(&self, workspace: &Workspace) -> Result<(), ContainerError> {
         self.try_stop(workspace, true).await;
-        Self::cleanup_workspace(&self.db, workspace).await;
+        let remove_container = self.config.read().await.workspace_container.enabled;
+        Self::cleanup_workspace(&self.db, workspace, remove_container).await;
         Ok(())
     }
 
@@ -1196,7 +1467,10 @@ impl ContainerService for LocalContainerService {
                     | BaseCodingAgent::ClaudeCode
                     | BaseCodingAgent::Gemini
                     | BaseCodingAgent::QwenCode
-                    | BaseCodingAgent::Opencode,
+                    | BaseCodingAgent::Opencode
+                    | BaseCodingAgent::CursorAgent
+                    | BaseCodingAgent::Droid
+                    | BaseCodingAgent::Amp,
                 ) => ExecutorApprovalBridge::new(
                     self.approvals.clone(),
                     self.db.clone(),
@@ -1216,6 +1490,8 @@ impl ContainerService for LocalContainerService {
             .commit_reminder_prompt
             .clone()
             .unwrap_or_else(|| DEFAULT_COMMIT_REMINDER_PROMPT.to_string());
+        let workspace_container = config.workspace_container.clone();
+        let port_allocation = config.port_allocation.clone();
         drop(config);
         let mut env = ExecutionEnv::new(
             repo_context,
@@ -1223,6 +1499,18 @@ impl ContainerService for LocalContainerService {
             commit_reminder_prompt,
         );
 
+        // Started before the spawn timeout below, pulling an image can take a while
+        if workspace_container.enabled {
+            env.oci = Some(
+                oci::ensure_workspace_container(
+                    &workspace_container,
+                    &workspace.id,
+                    &env.repo_context,
+                )
+                .await?,
+            );
+        }
+
         // Load task and project context for environment variables
         let task = workspace
             .parent_task(&self.db.pool)
@@ -1241,6 +1529,38 @@ impl ContainerService for LocalContainerService {
         env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
         env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
         env.insert("VK_SESSION_ID", execution_process.session_id.to_string());
+        if env.oci.is_some() {
+            env.insert(oci::OCI_EXECUTION_ID, execution_process.id.to_string());
+        }
+
+        if port_allocation.enabled {
+            match workspace_ports::ensure_workspace_ports(
+                &self.db.pool,
+                workspace.id,
+                &port_allocation,
+            )
+            .await
+            {
+                Ok(ports) => {
+                    if let Some(port) = ports.first() {
+                        env.insert("PORT", port.to_string());
+                    }
+                    for (slot, port) in ports.iter().enumerate() {
+                        env.insert(format!("VK_PORT_{slot}"), port.to_string());
+                    }
+                }
+                Err(e) => tracing::warn!(
+                    "Failed to reserve ports for workspace {}: {}",
+                    workspace.id,
+                    e
+                ),
+            }
+        }
+
+        env.mcp_servers =
+            McpServers::resolve_for_workspace(&self.db.pool, project.id, workspace.id).await?;
+        env.agent_instructions =
+            AgentInstructions::resolve(&self.db.pool, project.id, task.id).await?;
 
         // Create the child and stream, add to execution tracker with timeout
         let mut spawned = tokio::time::timeout(
@@ -1257,6 +1577,33 @@ impl ContainerService for LocalContainerService {
         self.track_child_msgs_in_store(execution_process.id, &mut spawned.child)
             .await;
 
+        if let Some(oci) = &env.oci {
+            self.oci_executions
+                .write()
+                .await
+                .insert(execution_process.id, oci.clone());
+        }
+
+        // Processes inside a workspace container are not visible to host-side sampling
+        let host_pid = spawned.child.id().filter(|_| env.oci.is_none());
+        if let Some(pid) = host_pid {
+            self.add_resource_monitor(execution_process.id, ResourceMonitor::spawn(pid))
+                .await;
+        }
+        if execution_process.run_reason == ExecutionProcessRunReason::DevServer
+            && let Some(msg_store) = self.get_msg_store_by_id(&execution_process.id).await
+        {
+            self.port_detectors.write().await.insert(
+                execution_process.id,
+                PortDetector::spawn(
+                    self.db.pool.clone(),
+                    execution_process.id,
+                    msg_store,
+                    host_pid,
+                ),
+            );
+        }
+
         self.add_child_to_store(execution_process.id, spawned.child)
             .await;
 
@@ -1266,6 +1613,13 @@ impl ContainerService for LocalContainerService {
                 .await;
         }
 
+        if let Some(steer) = spawned.steer {
+            self.steer_senders
+                .write()
+                .await
+                .insert(execution_process.id, steer);
+        }
+
         // Spawn unified exit monitor: watches OS exit and optional executor signal
         let hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
         self.add_exit_monitor_handle(execution_process.id, hn).await;
@@ -1278,12 +1632,20 @@ impl ContainerService for LocalContainerService {
         execution_process: &ExecutionProcess,
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError> {
-        let child = self
-            .get_child_from_store(&execution_process.id)
-            .await
-            .ok_or_else(|| {
-                ContainerError::Other(anyhow!("Child process not found for execution"))
-            })?;
+        let Some(child) = self.get_child_from_store(&execution_process.id).await else {
+            // Still waiting for a slot: there is nothing to kill
+            if ExecutionProcess::cancel_queued(&self.db.pool, execution_process.id, status).await? {
+                if let Ok(ctx) =
+                    ExecutionProcess::load_context(&self.db.pool, execution_process.id).await
+                {
+                    Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await?;
+                }
+                return Ok(());
+            }
+            return Err(ContainerError::Other(anyhow!(
+                "Child process not found for execution"
+            )));
+        };
         let exit_code = if status == ExecutionProcessStatus::Completed {
             Some(0)
         } else {
@@ -1293,6 +1655,11 @@ impl ContainerService for LocalContainerService {
         ExecutionProcess::update_completion(&self.db.pool, execution_process.id, status, exit_code)
             .await?;
 
+        self.steer_senders
+            .write()
+            .await
+            .remove(&execution_process.id);
+
         // Try graceful cancellation first, then force kill
         if let Some(cancel) = self.take_cancellation_token(&execution_process.id).await {
             cancel.cancel();
@@ -1314,6 +1681,21 @@ impl ContainerService for LocalContainerService {
             }
         }
 
+        // Killing the host-side `exec` client leaves the processes in the container running
+        if let Some(oci) = self
+            .oci_executions
+            .write()
+            .await
+            .remove(&execution_process.id)
+            && let Err(e) = oci.kill_execution(&execution_process.id).await
+        {
+            tracing::error!(
+                "Failed to stop execution process {} in its container: {}",
+                execution_process.id,
+                e
+            );
+        }
+
         {
             let mut child_guard = child.write().await;
             if let Err(e) = command::kill_process_group(&mut child_guard).await {
@@ -1326,6 +1708,12 @@ impl ContainerService for LocalContainerService {
             }
         }
         self.remove_child_from_store(&execution_process.id).await;
+        self.record_resource_usage(execution_process.id).await;
+        self.port_detectors
+            .write()
+            .await
+            .remove(&execution_process.id);
+        self.start_queued_executions().await;
 
         // Mark the process finished in the MsgStore and wait for DB persistence
         let db_stream_handle = self.take_db_stream_handle(&execution_process.id).await;
@@ -1434,6 +1822,20 @@ impl ContainerService for LocalContainerService {
         Ok(Box::pin(futures::stream::select_all(streams)))
     }
 
+    async fn steer_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+        request: SteerRequest,
+    ) -> Result<(), ContainerError> {
+        let senders = self.steer_senders.read().await;
+        let steer = senders
+            .get(&execution_process.id)
+            .ok_or(ContainerError::SteeringUnavailable)?;
+        steer
+            .send(request)
+            .map_err(|_| ContainerError::SteeringUnavailable)
+    }
+
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
         if !matches!(
             ctx.execution_process.run_reason,
@@ -1442,8 +1844,6 @@ impl ContainerService for LocalContainerService {
             return Ok(false);
         }
 
-        let message = self.get_commit_message(ctx).await;
-
         let container_ref = ctx
             .workspace
             .container_ref
@@ -1451,13 +1851,101 @@ impl ContainerService for LocalContainerService {
             .ok_or_else(|| ContainerError::Other(anyhow!("Container reference not found")))?;
         let workspace_root = PathBuf::from(container_ref);
 
-        let repos_with_changes = self.check_repos_for_changes(&workspace_root, &ctx.repos)?;
+        let mut repos_with_changes = self.check_repos_for_changes(&workspace_root, &ctx.repos)?;
+        // These are committed once the agent has written their message
+        if CommitMessageRun::is_waiting_for_turn(&self.db.pool, ctx.execution_process.id)
+            .await
+            .unwrap_or(false)
+        {
+            repos_with_changes.retain(|(repo, _)| !repo.agent_commit_messages);
+        }
         if repos_with_changes.is_empty() {
             tracing::debug!("No changes to commit in any repository");
             return Ok(false);
         }
 
-        Ok(self.commit_repos(repos_with_changes, &message))
+        if matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::CleanupScript
+        ) {
+            let message = self.get_commit_message(ctx, ctx.execution_process.id).await;
+            return Ok(self.commit_repos(repos_with_changes, |_| message.clone()));
+        }
+
+        // A commit message run commits the turn it describes; if it failed or
+        // was stopped, that turn's own summary is used instead
+        let commit_message_run = self.find_commit_message_run(ctx.execution_process.id).await;
+        let (summary_process_id, turn) = match &commit_message_run {
+            Some(pending) if ctx.execution_process.status == ExecutionProcessStatus::Completed => {
+                (ctx.execution_process.id, pending.turn)
+            }
+            Some(pending) => (pending.turn_process_id, pending.turn),
+            None => (ctx.execution_process.id, self.turn_number(ctx).await),
+        };
+        let summary = self.get_commit_message(ctx, summary_process_id).await;
+        let executor = ctx
+            .execution_process
+            .executor_action()
+            .ok()
+            .and_then(|action| action.executor_profile_id())
+            .map(|profile_id| profile_id.to_string())
+            .unwrap_or_default();
+        let vars = CommitMessageVars {
+            summary: &summary,
+            task_id: ctx.task.id,
+            task_title: &ctx.task.title,
+            workspace_id: ctx.workspace.id,
+            branch: &ctx.workspace.branch,
+            executor: &executor,
+            turn,
+        };
+        let co_authors = self.co_authors(ctx);
+
+        let committed = self.commit_repos(repos_with_changes, |repo| {
+            format_commit_message(repo, &vars, &co_authors)
+        });
+
+        // Attribute the commits to the turn that made the changes, so that
+        // reverting that turn undoes them
+        if let Some(pending) = commit_message_run {
+            for repo in &ctx.repos {
+                let Ok(head) = self.git().get_head_info(&workspace_root.join(&repo.name)) else {
+                    continue;
+                };
+                if let Err(e) = ExecutionProcessRepoState::update_after_head_commit(
+                    &self.db.pool,
+                    pending.turn_process_id,
+                    repo.id,
+                    &head.oid,
+                )
+                .await
+                {
+                    tracing::warn!(
+                        "Failed to update after_head_commit for repo {} on process {}: {}",
+                        repo.id,
+                        pending.turn_process_id,
+                        e
+                    );
+                }
+                if let Err(e) = ExecutionProcessRepoState::update_before_head_commit(
+                    &self.db.pool,
+                    ctx.execution_process.id,
+                    repo.id,
+                    &head.oid,
+                )
+                .await
+                {
+                    tracing::warn!(
+                        "Failed to update before_head_commit for repo {} on process {}: {}",
+                        repo.id,
+                        ctx.execution_process.id,
+                        e
+                    );
+                }
+            }
+        }
+
+        Ok(committed)
     }
 
     /// Copy files from the original project directory to the worktree.
@@ -1485,6 +1973,8 @@ impl ContainerService for LocalContainerService {
 
     async fn kill_all_running_processes(&self) -> Result<(), ContainerError> {
         tracing::info!("Killing all running processes");
+        // Leave queued executions in the database for the next start
+        self.scheduler.pause();
         let running_processes = ExecutionProcess::find_running(&self.db.pool).await?;
 
         tracing::info!(
@@ -10,6 +10,7 @@ use services::services::{
     analytics::{AnalyticsConfig, AnalyticsContext, AnalyticsService, generate_user_id},
     approvals::Approvals,
     auth::AuthContext,
+    auto_rebase::AutoRebaseService,
     config::{Config, load_config_from_file, save_config_to_file},
     container::ContainerService,
     events::EventService,
@@ -35,7 +36,9 @@ use crate::{container::LocalContainerService, pty::PtyService};
 mod command;
 pub mod container;
 mod copy;
+mod port_detection;
 pub mod pty;
+mod resource_usage;
 
 #[derive(Clone)]
 pub struct LocalDeployment {
@@ -199,6 +202,7 @@ impl Deployment for LocalDeployment {
             let rc = remote_client.clone().ok();
             PrMonitorService::spawn(db, analytics, container, rc).await;
         }
+        AutoRebaseService::spawn(db.clone(), config.clone(), container.clone()).await;
 
         let deployment = Self {
             config,
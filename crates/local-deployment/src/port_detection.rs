@@ -0,0 +1,425 @@
+//! Detection of the port a dev server listens on.
+//!
+//! The server's output is scanned for the URLs and `host:port`s it mentions,
+//! and the listening TCP sockets of its process group are probed once per
+//! interval. A mention only counts once its port is actually listening, so a
+//! database the server connects to is not taken for the server itself. An
+//! http(s) URL from the output wins over a bare `host:port` or a probed port,
+//! as it also carries the scheme and path the server expects.
+
+use std::{
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
+
+use db::models::execution_process::ExecutionProcess;
+use futures::StreamExt;
+use regex::Regex;
+use sqlx::SqlitePool;
+use tokio::task::JoinHandle;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+const PROBE_INTERVAL: Duration = Duration::from_secs(1);
+/// Mentions waiting for their port to listen
+const MAX_CANDIDATES: usize = 32;
+
+static URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)(https?)://(\[[0-9a-f:]+\]|localhost|127\.0\.0\.1|0\.0\.0\.0|\d{1,3}(?:\.\d{1,3}){3}):(\d{2,5})(/\S*)?",
+    )
+    .unwrap()
+});
+static HOST_PORT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:localhost|127\.0\.0\.1|0\.0\.0\.0|\[[0-9a-f:]+\]|(?:\d{1,3}\.){3}\d{1,3}):(\d{2,5})\b").unwrap()
+});
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedUrl {
+    pub port: u16,
+    pub url: String,
+}
+
+impl DetectedUrl {
+    fn from_port(port: u16) -> Self {
+        Self {
+            port,
+            url: format!("http://localhost:{port}"),
+        }
+    }
+}
+
+/// A place the dev server's output mentions
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Mention {
+    /// An http(s) URL
+    Url(DetectedUrl),
+    /// A bare `host:port`
+    HostPort(DetectedUrl),
+}
+
+impl Mention {
+    fn detected(&self) -> &DetectedUrl {
+        match self {
+            Self::Url(detected) | Self::HostPort(detected) => detected,
+        }
+    }
+}
+
+/// Background detector for one dev server
+pub struct PortDetector {
+    handle: JoinHandle<()>,
+}
+
+impl PortDetector {
+    /// Watch the output in `msg_store` and, when `pgid` is given, the sockets
+    /// of that process group. Detected URLs are stored on the execution process.
+    pub fn spawn(
+        pool: SqlitePool,
+        execution_process_id: Uuid,
+        msg_store: Arc<MsgStore>,
+        pgid: Option<u32>,
+    ) -> Self {
+        let handle = tokio::spawn(async move {
+            let mut output = msg_store.history_plus_stream();
+            let mut interval = tokio::time::interval(PROBE_INTERVAL);
+            let mut pending = String::new();
+            let mut candidates: Vec<Mention> = Vec::new();
+            let mut stored: Option<DetectedUrl> = None;
+
+            loop {
+                tokio::select! {
+                    msg = output.next() => match msg {
+                        Some(Ok(LogMsg::Stdout(chunk) | LogMsg::Stderr(chunk))) => {
+                            pending.push_str(&chunk);
+                            let Some(end) = pending.rfind('\n') else {
+                                continue;
+                            };
+                            let lines: String = pending.drain(..=end).collect();
+                            let before = candidates.len();
+                            for mention in lines.lines().flat_map(parse_mentions) {
+                                if !candidates.contains(&mention) {
+                                    candidates.push(mention);
+                                }
+                            }
+                            let excess = candidates.len().saturating_sub(MAX_CANDIDATES);
+                            candidates.drain(..excess);
+                            if candidates.len() == before && excess == 0 {
+                                continue;
+                            }
+                        }
+                        Some(Ok(LogMsg::Finished)) | None => break,
+                        Some(_) => continue,
+                    },
+                    _ = interval.tick() => {
+                        if candidates.is_empty() && stored.is_some() {
+                            continue;
+                        }
+                    }
+                }
+
+                let listening = match pgid {
+                    Some(pgid) => listening_ports(pgid).await,
+                    None => None,
+                };
+                let detected = match confirm(&mut candidates, listening.as_deref()) {
+                    Some(Mention::Url(detected)) => {
+                        if stored.as_ref() != Some(&detected) {
+                            store(&pool, execution_process_id, &detected).await;
+                        }
+                        break;
+                    }
+                    Some(Mention::HostPort(detected)) => detected,
+                    // Until the output names it, go by the port the server listens on
+                    None if stored.is_none() => {
+                        match listening.and_then(|ports| ports.into_iter().min()) {
+                            Some(port) => DetectedUrl::from_port(port),
+                            None => continue,
+                        }
+                    }
+                    None => continue,
+                };
+                // Keep reading the output, which may still announce a URL
+                if stored.as_ref() != Some(&detected) {
+                    store(&pool, execution_process_id, &detected).await;
+                    stored = Some(detected);
+                }
+            }
+        });
+        Self { handle }
+    }
+}
+
+impl Drop for PortDetector {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn store(pool: &SqlitePool, execution_process_id: Uuid, detected: &DetectedUrl) {
+    if let Err(e) = ExecutionProcess::update_detected_url(
+        pool,
+        execution_process_id,
+        i64::from(detected.port),
+        &detected.url,
+    )
+    .await
+    {
+        tracing::warn!(
+            "Failed to store detected URL for {}: {}",
+            execution_process_id,
+            e
+        );
+    }
+}
+
+/// Take the first of `candidates` whose port is `listening`, preferring
+/// http(s) URLs. When the listening ports are unknown every mention counts.
+/// Once a bare `host:port` is confirmed only URLs are kept waiting.
+fn confirm(candidates: &mut Vec<Mention>, listening: Option<&[u16]>) -> Option<Mention> {
+    let is_listening =
+        |mention: &Mention| listening.is_none_or(|ports| ports.contains(&mention.detected().port));
+    let index = candidates
+        .iter()
+        .position(|mention| matches!(mention, Mention::Url(_)) && is_listening(mention))
+        .or_else(|| candidates.iter().position(is_listening))?;
+    let mention = candidates.remove(index);
+    if matches!(mention, Mention::HostPort(_)) {
+        candidates.retain(|mention| matches!(mention, Mention::Url(_)));
+    }
+    Some(mention)
+}
+
+/// The local http(s) URLs and bare `host:port`s a line of dev server output
+/// mentions. Addresses with another scheme (e.g. `ws://` or `postgres://`) are
+/// ignored, and wildcard addresses are replaced with `localhost`.
+fn parse_mentions(line: &str) -> Vec<Mention> {
+    let line = strip_ansi_escapes::strip_str(line);
+    let mut mentions = Vec::new();
+
+    for captures in URL_RE.captures_iter(&line) {
+        let Some(port) = captures[3].parse().ok().filter(|port| *port > 0) else {
+            continue;
+        };
+        let host = match &captures[2] {
+            "0.0.0.0" | "[::]" => "localhost",
+            host => host,
+        };
+        let path = captures.get(4).map_or("", |path| path.as_str());
+        mentions.push(Mention::Url(DetectedUrl {
+            port,
+            url: format!("{}://{host}:{port}{path}", captures[1].to_ascii_lowercase()),
+        }));
+    }
+
+    let scheme = if line.to_ascii_lowercase().contains("https") {
+        "https"
+    } else {
+        "http"
+    };
+    for captures in HOST_PORT_RE.captures_iter(&line) {
+        let Some(whole) = captures.get(0) else {
+            continue;
+        };
+        // Part of a URL: an http(s) one was taken above, others are not ours
+        if line[..whole.start()].ends_with("://") {
+            continue;
+        }
+        let Some(port) = captures[1].parse().ok().filter(|port| *port > 0) else {
+            continue;
+        };
+        mentions.push(Mention::HostPort(DetectedUrl {
+            port,
+            url: format!("{scheme}://localhost:{port}"),
+        }));
+    }
+    mentions
+}
+
+/// The TCP ports the processes of the group listen on
+#[cfg(target_os = "linux")]
+async fn listening_ports(pgid: u32) -> Option<Vec<u16>> {
+    use std::collections::HashSet;
+
+    tokio::task::spawn_blocking(move || {
+        let mut inodes = HashSet::new();
+        for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+            let name = entry.file_name();
+            let Some(pid) = name
+                .to_str()
+                .filter(|n| n.bytes().all(|b| b.is_ascii_digit()))
+            else {
+                continue;
+            };
+            let Ok(stat) = std::fs::read_to_string(format!("/proc/{pid}/stat")) else {
+                continue;
+            };
+            if crate::resource_usage::parse_proc_stat(&stat).map(|(group, _)| group) != Some(pgid) {
+                continue;
+            }
+            let Ok(fds) = std::fs::read_dir(format!("/proc/{pid}/fd")) else {
+                continue;
+            };
+            for fd in fds.flatten() {
+                if let Ok(target) = std::fs::read_link(fd.path())
+                    && let Some(inode) = target
+                        .to_str()
+                        .and_then(|t| t.strip_prefix("socket:["))
+                        .and_then(|t| t.strip_suffix(']'))
+                        .and_then(|t| t.parse::<u64>().ok())
+                {
+                    inodes.insert(inode);
+                }
+            }
+        }
+        if inodes.is_empty() {
+            return Some(Vec::new());
+        }
+
+        Some(
+            ["/proc/net/tcp", "/proc/net/tcp6"]
+                .iter()
+                .filter_map(|path| std::fs::read_to_string(path).ok())
+                .flat_map(|table| parse_proc_net_tcp_listeners(&table))
+                .filter(|(_, inode)| inodes.contains(inode))
+                .map(|(port, _)| port)
+                .collect(),
+        )
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Local port and socket inode of every listening socket in `/proc/net/tcp`
+/// or `/proc/net/tcp6`
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_net_tcp_listeners(table: &str) -> Vec<(u16, u64)> {
+    const TCP_LISTEN: &str = "0A";
+
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.get(3) != Some(&TCP_LISTEN) {
+                return None;
+            }
+            let (_, port) = fields.get(1)?.rsplit_once(':')?;
+            let port = u16::from_str_radix(port, 16).ok()?;
+            let inode = fields.get(9)?.parse().ok()?;
+            Some((port, inode))
+        })
+        .collect()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+async fn listening_ports(pgid: u32) -> Option<Vec<u16>> {
+    let output = tokio::process::Command::new("lsof")
+        .args(["-nP", "-a", "-iTCP", "-sTCP:LISTEN", "-Fn", "-g"])
+        .arg(pgid.to_string())
+        .output()
+        .await
+        .ok()?;
+    Some(parse_lsof_ports(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Ports of the `n<address>:<port>` records of `lsof -Fn`
+#[cfg(any(all(unix, not(target_os = "linux")), test))]
+fn parse_lsof_ports(output: &str) -> Vec<u16> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix('n')?.rsplit_once(':')?.1.parse().ok())
+        .collect()
+}
+
+#[cfg(windows)]
+async fn listening_ports(_pgid: u32) -> Option<Vec<u16>> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_urls_from_dev_server_output() {
+        assert_eq!(
+            parse_mentions(
+                "  \x1b[32m➜\x1b[39m  Local:   \x1b[36mhttp://localhost:\x1b[1m5173\x1b[22m/\x1b[39m"
+            ),
+            vec![Mention::Url(DetectedUrl {
+                port: 5173,
+                url: "http://localhost:5173/".to_string()
+            })]
+        );
+        assert_eq!(
+            parse_mentions("Server listening on https://0.0.0.0:8443/app"),
+            vec![Mention::Url(DetectedUrl {
+                port: 8443,
+                url: "https://localhost:8443/app".to_string()
+            })]
+        );
+        assert_eq!(
+            parse_mentions("Listening on 127.0.0.1:3000"),
+            vec![Mention::HostPort(DetectedUrl::from_port(3000))]
+        );
+        assert_eq!(parse_mentions("Compiled in 2.1s"), vec![]);
+    }
+
+    #[test]
+    fn ignores_addresses_with_other_schemes() {
+        assert_eq!(
+            parse_mentions("Debugger listening on ws://127.0.0.1:9229/0f2c"),
+            vec![]
+        );
+        assert_eq!(
+            parse_mentions("Using postgres://localhost:5432/app"),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn only_listening_mentions_are_confirmed_and_urls_win() {
+        let database = Mention::HostPort(DetectedUrl::from_port(5432));
+        let server = Mention::Url(DetectedUrl {
+            port: 3000,
+            url: "http://localhost:3000/".to_string(),
+        });
+        let mut candidates = vec![database.clone()];
+        assert_eq!(confirm(&mut candidates, Some(&[3000])), None);
+        assert_eq!(candidates, vec![database.clone()]);
+
+        let mut candidates = vec![database.clone(), server.clone()];
+        assert_eq!(
+            confirm(&mut candidates, Some(&[3000, 5432])),
+            Some(server.clone())
+        );
+
+        // Without socket information every mention counts
+        let mut candidates = vec![database.clone(), server.clone()];
+        assert_eq!(confirm(&mut candidates, None), Some(server));
+        let mut candidates = vec![database.clone()];
+        assert_eq!(confirm(&mut candidates, None), Some(database));
+    }
+
+    #[test]
+    fn parses_listening_sockets_from_proc_net_tcp() {
+        let table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 41234 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:1F90 0100007F:C350 01 00000000:00000000 00:00000000 00000000  1000        0 41240 1 0000000000000000 20 4 30 10 -1
+   2: 00000000000000000000000000000000:1435 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 51234 1 0000000000000000 100 0 0 10 0";
+        assert_eq!(
+            parse_proc_net_tcp_listeners(table),
+            vec![(8080, 41234), (5173, 51234)]
+        );
+    }
+
+    #[test]
+    fn parses_lsof_ports() {
+        assert_eq!(
+            parse_lsof_ports("p4242\nf23\nn*:3000\nf24\nn127.0.0.1:9229\n"),
+            vec![3000, 9229]
+        );
+    }
+}
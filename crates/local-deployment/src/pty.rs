@@ -1,17 +1,24 @@
 use std::{
-    collections::HashMap,
-    io::{Read, Write},
-    path::PathBuf,
+    collections::{HashMap, VecDeque},
+    fs::File,
+    io::{self, BufWriter, Read, Write},
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
     thread,
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
 
-use portable_pty::{CommandBuilder, NativePtySystem, PtySize, PtySystem};
+use portable_pty::{ChildKiller, CommandBuilder, NativePtySystem, PtySize, PtySystem};
 use thiserror::Error;
-use tokio::sync::mpsc;
+use tokio::sync::broadcast;
 use utils::shell::get_interactive_shell;
 use uuid::Uuid;
 
+/// Output kept per session and replayed to clients that (re)attach
+const SCROLLBACK_LIMIT: usize = 1024 * 1024;
+/// Output chunks buffered for attached clients that fall behind
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
 #[derive(Debug, Error)]
 pub enum PtyError {
     #[error("Failed to create PTY: {0}")]
@@ -26,13 +33,69 @@ pub enum PtyError {
     SessionClosed,
 }
 
+#[derive(Debug, Clone)]
+pub enum PtyEvent {
+    Output(Vec<u8>),
+    /// The shell exited; the session is gone
+    Exited,
+}
+
+/// A session's output as seen by a newly attached client: everything still in
+/// the scrollback, followed by live events
+pub struct PtyAttachment {
+    pub scrollback: Vec<u8>,
+    pub events: broadcast::Receiver<PtyEvent>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PtySessionInfo {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub working_dir: PathBuf,
+    pub created_at: SystemTime,
+    /// Asciicast file the session is recorded to, if any
+    pub recording: Option<PathBuf>,
+}
+
+/// State shared between a session and the thread reading its output
+struct PtyOutput {
+    scrollback: VecDeque<u8>,
+    events: broadcast::Sender<PtyEvent>,
+    recorder: Option<AsciicastRecorder>,
+    /// Set by the output thread, under the sessions lock, once the shell exited
+    exited: bool,
+}
+
+impl PtyOutput {
+    fn push(&mut self, data: &[u8]) {
+        self.scrollback.extend(data);
+        let excess = self.scrollback.len().saturating_sub(SCROLLBACK_LIMIT);
+        self.scrollback.drain(..excess);
+        if let Some(recorder) = self.recorder.as_mut()
+            && let Err(e) = recorder.output(data)
+        {
+            tracing::warn!(
+                "Failed to record terminal output, stopping recording: {}",
+                e
+            );
+            self.recorder = None;
+        }
+        // No receivers just means nobody is attached right now
+        let _ = self.events.send(PtyEvent::Output(data.to_vec()));
+    }
+}
+
 struct PtySession {
+    info: PtySessionInfo,
     writer: Box<dyn Write + Send>,
     master: Box<dyn portable_pty::MasterPty + Send>,
+    killer: Box<dyn ChildKiller + Send + Sync>,
+    output: Arc<Mutex<PtyOutput>>,
     _output_handle: thread::JoinHandle<()>,
-    closed: bool,
 }
 
+/// Shell sessions keyed by ID. A session lives until its shell exits or it is
+/// killed; websocket clients attach to and detach from it freely.
 #[derive(Clone)]
 pub struct PtyService {
     sessions: Arc<Mutex<HashMap<Uuid, PtySession>>>,
@@ -45,15 +108,22 @@ impl PtyService {
         }
     }
 
+    /// Start a shell in `working_dir`. When `recordings_dir` is set, the
+    /// session is also written there as `<session id>.cast` (asciicast v2).
     pub async fn create_session(
         &self,
+        workspace_id: Uuid,
         working_dir: PathBuf,
         cols: u16,
         rows: u16,
-    ) -> Result<(Uuid, mpsc::UnboundedReceiver<Vec<u8>>), PtyError> {
+        recordings_dir: Option<PathBuf>,
+    ) -> Result<Uuid, PtyError> {
         let session_id = Uuid::new_v4();
-        let (output_tx, output_rx) = mpsc::unbounded_channel();
+        let recording = recordings_dir.map(|dir| dir.join(format!("{session_id}.cast")));
         let shell = get_interactive_shell().await;
+        let sessions = self.sessions.clone();
+        let cwd = working_dir.clone();
+        let recording_path = recording.clone();
 
         let result = tokio::task::spawn_blocking(move || {
             let pty_system = NativePtySystem::default();
@@ -68,7 +138,7 @@ impl PtyService {
                 .map_err(|e| PtyError::CreateFailed(e.to_string()))?;
 
             let mut cmd = CommandBuilder::new(&shell);
-            cmd.cwd(&working_dir);
+            cmd.cwd(&cwd);
 
             // Configure shell-specific options
             let shell_name = shell.file_name().and_then(|n| n.to_str()).unwrap_or("");
@@ -94,10 +164,11 @@ impl PtyService {
             cmd.env("TERM", "xterm-256color");
             cmd.env("COLORTERM", "truecolor");
 
-            let child = pty_pair
+            let mut child = pty_pair
                 .slave
                 .spawn_command(cmd)
                 .map_err(|e| PtyError::CreateFailed(e.to_string()))?;
+            let killer = child.clone_killer();
 
             let mut writer = pty_pair
                 .master
@@ -116,42 +187,116 @@ impl PtyService {
                 .try_clone_reader()
                 .map_err(|e| PtyError::CreateFailed(e.to_string()))?;
 
+            // A recording that cannot be started should not cost the user their shell
+            let recorder = recording_path.as_deref().and_then(|path| {
+                AsciicastRecorder::create(path, cols, rows, &shell.to_string_lossy())
+                    .inspect_err(|e| {
+                        tracing::warn!("Failed to start terminal recording {:?}: {}", path, e)
+                    })
+                    .ok()
+            });
+            let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+            let output = Arc::new(Mutex::new(PtyOutput {
+                scrollback: VecDeque::new(),
+                events,
+                recorder,
+                exited: false,
+            }));
+
+            let thread_output = output.clone();
             let output_handle = thread::spawn(move || {
                 let mut buf = [0u8; 4096];
                 loop {
                     match reader.read(&mut buf) {
                         Ok(0) => break,
                         Ok(n) => {
-                            if output_tx.send(buf[..n].to_vec()).is_err() {
-                                break;
+                            if let Ok(mut output) = thread_output.lock() {
+                                output.push(&buf[..n]);
                             }
                         }
                         Err(_) => break,
                     }
                 }
-                drop(child);
+                let _ = child.wait();
+
+                // Under the sessions lock, so create_session either sees the
+                // exit or has already registered the session removed here
+                if let Ok(mut sessions) = sessions.lock() {
+                    if let Ok(mut output) = thread_output.lock() {
+                        output.exited = true;
+                    }
+                    sessions.remove(&session_id);
+                }
+                if let Ok(mut output) = thread_output.lock() {
+                    if let Some(recorder) = output.recorder.as_mut() {
+                        let _ = recorder.flush();
+                    }
+                    let _ = output.events.send(PtyEvent::Exited);
+                }
             });
 
-            Ok::<_, PtyError>((pty_pair.master, writer, output_handle))
+            Ok::<_, PtyError>((pty_pair.master, writer, killer, output, output_handle))
         })
         .await
         .map_err(|e| PtyError::CreateFailed(e.to_string()))??;
 
-        let (master, writer, output_handle) = result;
+        let (master, writer, killer, output, output_handle) = result;
 
         let session = PtySession {
+            info: PtySessionInfo {
+                id: session_id,
+                workspace_id,
+                working_dir,
+                created_at: SystemTime::now(),
+                recording,
+            },
             writer,
             master,
+            killer,
+            output,
             _output_handle: output_handle,
-            closed: false,
         };
 
-        self.sessions
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|e| PtyError::CreateFailed(e.to_string()))?;
+        // A shell that exited already has nobody left to remove its session
+        let exited = session
+            .output
             .lock()
-            .map_err(|e| PtyError::CreateFailed(e.to_string()))?
-            .insert(session_id, session);
+            .map(|output| output.exited)
+            .unwrap_or(true);
+        if exited {
+            return Err(PtyError::SessionClosed);
+        }
+        sessions.insert(session_id, session);
 
-        Ok((session_id, output_rx))
+        Ok(session_id)
+    }
+
+    /// Attach a client to a running session
+    pub fn attach(&self, session_id: Uuid) -> Result<PtyAttachment, PtyError> {
+        let sessions = self.sessions.lock().map_err(|_| PtyError::SessionClosed)?;
+        let session = sessions
+            .get(&session_id)
+            .ok_or(PtyError::SessionNotFound(session_id))?;
+        // Snapshot and subscribe under one lock so no output is lost or repeated
+        let output = session.output.lock().map_err(|_| PtyError::SessionClosed)?;
+        Ok(PtyAttachment {
+            scrollback: output.scrollback.iter().copied().collect(),
+            events: output.events.subscribe(),
+        })
+    }
+
+    /// The recent output of a session without attaching to it
+    pub fn scrollback(&self, session_id: Uuid) -> Result<Vec<u8>, PtyError> {
+        let sessions = self.sessions.lock().map_err(|_| PtyError::SessionClosed)?;
+        let session = sessions
+            .get(&session_id)
+            .ok_or(PtyError::SessionNotFound(session_id))?;
+        let output = session.output.lock().map_err(|_| PtyError::SessionClosed)?;
+        Ok(output.scrollback.iter().copied().collect())
     }
 
     pub async fn write(&self, session_id: Uuid, data: &[u8]) -> Result<(), PtyError> {
@@ -163,10 +308,6 @@ impl PtyService {
             .get_mut(&session_id)
             .ok_or(PtyError::SessionNotFound(session_id))?;
 
-        if session.closed {
-            return Err(PtyError::SessionClosed);
-        }
-
         session
             .writer
             .write_all(data)
@@ -189,10 +330,6 @@ impl PtyService {
             .get(&session_id)
             .ok_or(PtyError::SessionNotFound(session_id))?;
 
-        if session.closed {
-            return Err(PtyError::SessionClosed);
-        }
-
         session
             .master
             .resize(PtySize {
@@ -203,26 +340,70 @@ impl PtyService {
             })
             .map_err(|e| PtyError::ResizeFailed(e.to_string()))?;
 
+        if let Ok(mut output) = session.output.lock()
+            && let Some(recorder) = output.recorder.as_mut()
+        {
+            let _ = recorder.resize(cols, rows);
+        }
+
         Ok(())
     }
 
-    pub async fn close_session(&self, session_id: Uuid) -> Result<(), PtyError> {
-        if let Some(mut session) = self
+    /// Kill the session's shell and everything running in it
+    pub async fn kill_session(&self, session_id: Uuid) -> Result<(), PtyError> {
+        let session = self
             .sessions
             .lock()
             .map_err(|_| PtyError::SessionClosed)?
             .remove(&session_id)
-        {
-            session.closed = true;
-        }
+            .ok_or(PtyError::SessionNotFound(session_id))?;
+        Self::kill(session);
         Ok(())
     }
 
-    pub fn session_exists(&self, session_id: &Uuid) -> bool {
+    /// Kill every session of a workspace, e.g. before it is deleted
+    pub fn kill_workspace_sessions(&self, workspace_id: Uuid) {
+        let Ok(mut sessions) = self.sessions.lock() else {
+            return;
+        };
+        let ids: Vec<Uuid> = sessions
+            .values()
+            .filter(|session| session.info.workspace_id == workspace_id)
+            .map(|session| session.info.id)
+            .collect();
+        for id in ids {
+            if let Some(session) = sessions.remove(&id) {
+                Self::kill(session);
+            }
+        }
+    }
+
+    fn kill(mut session: PtySession) {
+        if let Err(e) = session.killer.kill() {
+            tracing::warn!("Failed to kill terminal session {}: {}", session.info.id, e);
+        }
+    }
+
+    pub fn get_session(&self, session_id: &Uuid) -> Option<PtySessionInfo> {
         self.sessions
             .lock()
-            .map(|s| s.contains_key(session_id))
-            .unwrap_or(false)
+            .ok()?
+            .get(session_id)
+            .map(|session| session.info.clone())
+    }
+
+    /// Running sessions of a workspace, oldest first
+    pub fn list_sessions(&self, workspace_id: Uuid) -> Vec<PtySessionInfo> {
+        let Ok(sessions) = self.sessions.lock() else {
+            return Vec::new();
+        };
+        let mut infos: Vec<PtySessionInfo> = sessions
+            .values()
+            .filter(|session| session.info.workspace_id == workspace_id)
+            .map(|session| session.info.clone())
+            .collect();
+        infos.sort_by_key(|info| info.created_at);
+        infos
     }
 }
 
@@ -231,3 +412,121 @@ impl Default for PtyService {
         Self::new()
     }
 }
+
+/// Writes a session to disk in the asciicast v2 format
+/// (<https://docs.asciinema.org/manual/asciicast/v2/>)
+struct AsciicastRecorder {
+    file: BufWriter<File>,
+    started: Instant,
+    /// Trailing bytes of an incomplete UTF-8 sequence, completed by the next chunk
+    partial: Vec<u8>,
+}
+
+impl AsciicastRecorder {
+    fn create(path: &Path, cols: u16, rows: u16, shell: &str) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = BufWriter::new(File::create(path)?);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let header = serde_json::json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": timestamp,
+            "env": { "SHELL": shell, "TERM": "xterm-256color" },
+        });
+        writeln!(file, "{header}")?;
+        file.flush()?;
+        Ok(Self {
+            file,
+            started: Instant::now(),
+            partial: Vec::new(),
+        })
+    }
+
+    fn event(&mut self, code: &str, data: &str) -> io::Result<()> {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        writeln!(self.file, "{}", serde_json::json!([elapsed, code, data]))?;
+        self.file.flush()
+    }
+
+    fn output(&mut self, data: &[u8]) -> io::Result<()> {
+        self.partial.extend_from_slice(data);
+        let complete = match std::str::from_utf8(&self.partial) {
+            Ok(text) => text.len(),
+            // Keep an incomplete sequence at the end for the next chunk
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => self.partial.len(),
+        };
+        let bytes: Vec<u8> = self.partial.drain(..complete).collect();
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        self.event("o", &String::from_utf8_lossy(&bytes))
+    }
+
+    fn resize(&mut self, cols: u16, rows: u16) -> io::Result<()> {
+        self.event("r", &format!("{cols}x{rows}"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrollback_keeps_the_latest_output() {
+        let (events, _) = broadcast::channel(4);
+        let mut output = PtyOutput {
+            scrollback: VecDeque::new(),
+            events,
+            recorder: None,
+        };
+        output.push(&vec![b'a'; SCROLLBACK_LIMIT]);
+        output.push(b"tail");
+
+        assert_eq!(output.scrollback.len(), SCROLLBACK_LIMIT);
+        let end: Vec<u8> = output
+            .scrollback
+            .iter()
+            .rev()
+            .take(4)
+            .rev()
+            .copied()
+            .collect();
+        assert_eq!(end, b"tail");
+    }
+
+    #[test]
+    fn recording_is_valid_asciicast_across_split_utf8() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("session.cast");
+        let mut recorder = AsciicastRecorder::create(&path, 80, 24, "/bin/bash").unwrap();
+        let snowman = "☃".as_bytes();
+        recorder.output(&[b'$', b' ', snowman[0]]).unwrap();
+        recorder.output(&snowman[1..]).unwrap();
+        recorder.resize(100, 30).unwrap();
+        recorder.flush().unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 80);
+        assert_eq!(lines[1][1], "o");
+        assert_eq!(lines[1][2], "$ ");
+        assert_eq!(lines[2][2], "☃");
+        assert_eq!(lines[3][1], "r");
+        assert_eq!(lines[3][2], "100x30");
+    }
+}
@@ -0,0 +1,204 @@
+//! Peak memory and CPU time accounting for execution processes.
+//!
+//! Every executor process is spawned as the leader of its own process group,
+//! so the group is sampled periodically and the highest values seen are kept.
+//! Processes that live shorter than one sample interval may not be counted.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::task::JoinHandle;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    pub peak_memory_bytes: u64,
+    pub cpu_time_ms: u64,
+}
+
+impl ResourceUsage {
+    fn max(self, other: ResourceUsage) -> ResourceUsage {
+        ResourceUsage {
+            peak_memory_bytes: self.peak_memory_bytes.max(other.peak_memory_bytes),
+            cpu_time_ms: self.cpu_time_ms.max(other.cpu_time_ms),
+        }
+    }
+}
+
+/// Background sampler for one process group
+pub struct ResourceMonitor {
+    usage: Arc<Mutex<Option<ResourceUsage>>>,
+    handle: JoinHandle<()>,
+}
+
+impl ResourceMonitor {
+    pub fn spawn(pgid: u32) -> Self {
+        let usage: Arc<Mutex<Option<ResourceUsage>>> = Arc::new(Mutex::new(None));
+        let handle = {
+            let usage = usage.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    let Some(sample) = sample_process_group(pgid).await else {
+                        continue;
+                    };
+                    let mut usage = usage.lock().unwrap();
+                    *usage = Some(usage.map_or(sample, |prev| prev.max(sample)));
+                }
+            })
+        };
+        Self { usage, handle }
+    }
+
+    /// Stop sampling and return the highest usage observed, if any.
+    pub fn finish(self) -> Option<ResourceUsage> {
+        *self.usage.lock().unwrap()
+    }
+}
+
+impl Drop for ResourceMonitor {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+#[cfg(target_os = "linux")]
+async fn sample_process_group(pgid: u32) -> Option<ResourceUsage> {
+    tokio::task::spawn_blocking(move || {
+        let mut total = ResourceUsage::default();
+        let mut found = false;
+        for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+            let name = entry.file_name();
+            let Some(pid) = name
+                .to_str()
+                .filter(|n| n.bytes().all(|b| b.is_ascii_digit()))
+            else {
+                continue;
+            };
+            let Ok(stat) = std::fs::read_to_string(format!("/proc/{pid}/stat")) else {
+                continue;
+            };
+            let Some((group, cpu_ticks)) = parse_proc_stat(&stat) else {
+                continue;
+            };
+            if group != pgid {
+                continue;
+            }
+            found = true;
+            // USER_HZ is 100 on every Linux architecture we ship for
+            total.cpu_time_ms += cpu_ticks * 10;
+            if let Ok(status) = std::fs::read_to_string(format!("/proc/{pid}/status")) {
+                total.peak_memory_bytes += parse_vm_rss_kb(&status).unwrap_or(0) * 1024;
+            }
+        }
+        found.then_some(total)
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Process group and utime + stime + cutime + cstime from `/proc/<pid>/stat`.
+///
+/// Children's times move into `cutime`/`cstime` when they are reaped, so this
+/// keeps counting CPU spent by short-lived subprocesses.
+#[cfg(any(target_os = "linux", test))]
+pub(crate) fn parse_proc_stat(stat: &str) -> Option<(u32, u64)> {
+    // The command name may contain spaces and parentheses; fields resume after the last ')'
+    let fields: Vec<&str> = stat
+        .get(stat.rfind(')')? + 1..)?
+        .split_whitespace()
+        .collect();
+    let pgrp = fields.get(2)?.parse().ok()?;
+    let ticks = fields
+        .get(11..15)?
+        .iter()
+        .map(|field| field.parse::<i64>().unwrap_or(0).max(0) as u64)
+        .sum();
+    Some((pgrp, ticks))
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn parse_vm_rss_kb(status: &str) -> Option<u64> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+async fn sample_process_group(pgid: u32) -> Option<ResourceUsage> {
+    let output = tokio::process::Command::new("ps")
+        .args(["-A", "-o", "pgid=,rss=,time="])
+        .output()
+        .await
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let mut total = ResourceUsage::default();
+    let mut found = false;
+    for line in stdout.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(group), Some(rss_kb), Some(time)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        if group.parse::<u32>().ok() != Some(pgid) {
+            continue;
+        }
+        found = true;
+        total.peak_memory_bytes += rss_kb.parse::<u64>().unwrap_or(0) * 1024;
+        total.cpu_time_ms += parse_ps_time_ms(time).unwrap_or(0);
+    }
+    found.then_some(total)
+}
+
+/// Parse `ps` cumulative time: `[[dd-]hh:]mm:ss[.cc]`.
+#[cfg(any(all(unix, not(target_os = "linux")), test))]
+fn parse_ps_time_ms(time: &str) -> Option<u64> {
+    let (days, clock) = match time.split_once('-') {
+        Some((days, clock)) => (days.parse::<u64>().ok()?, clock),
+        None => (0, time),
+    };
+    let mut seconds = 0.0;
+    for part in clock.split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(((days * 86_400) as f64 * 1000.0 + seconds * 1000.0).round() as u64)
+}
+
+#[cfg(windows)]
+async fn sample_process_group(_pgid: u32) -> Option<ResourceUsage> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_proc_stat_with_spaces_in_command() {
+        let stat = "4242 (node (dev server)) S 1 4240 4240 0 -1 4194560 100 0 0 0 250 50 7 3 20 0 11 0 123 456 789";
+        assert_eq!(parse_proc_stat(stat), Some((4240, 310)));
+    }
+
+    #[test]
+    fn parses_vm_rss() {
+        let status = "Name:\tnode\nVmPeak:\t 900 kB\nVmRSS:\t  123456 kB\n";
+        assert_eq!(parse_vm_rss_kb(status), Some(123456));
+    }
+
+    #[test]
+    fn parses_ps_time() {
+        assert_eq!(parse_ps_time_ms("0:01.50"), Some(1500));
+        assert_eq!(parse_ps_time_ms("01:02:03"), Some(3_723_000));
+        assert_eq!(parse_ps_time_ms("1-00:00:01"), Some(86_401_000));
+    }
+}
@@ -11,6 +11,9 @@ pub enum ReviewError {
     #[error("Invalid GitHub PR URL format. Expected: https://github.com/owner/repo/pull/123")]
     InvalidPrUrl,
 
+    #[error("Invalid review target: {0}")]
+    InvalidReviewTarget(String),
+
     #[error("Failed to get PR information: {0}")]
     PrInfoFailed(String),
 
@@ -20,6 +23,12 @@ pub enum ReviewError {
     #[error("Failed to checkout PR: {0}")]
     CheckoutFailed(String),
 
+    #[error("Git command failed: {0}")]
+    GitFailed(String),
+
+    #[error("Failed to look up vibe-kanban workspace: {0}")]
+    WorkspaceLookupFailed(String),
+
     #[error("Failed to create archive: {0}")]
     ArchiveFailed(String),
 
@@ -0,0 +1,234 @@
+use std::{path::Path, process::Command};
+
+use tracing::debug;
+
+use crate::{
+    error::ReviewError,
+    target::{CommitRange, MergeRequest, MergeRequestHost, PrInfo},
+};
+
+/// Run a git command and return its trimmed stdout
+fn run_git(args: &[&str], dir: Option<&Path>) -> Result<String, ReviewError> {
+    let mut cmd = Command::new("git");
+    cmd.args(args);
+    if let Some(dir) = dir {
+        cmd.current_dir(dir);
+    }
+
+    debug!("git {}", args.join(" "));
+
+    let output = cmd
+        .output()
+        .map_err(|e| ReviewError::GitFailed(e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(ReviewError::GitFailed(format!(
+            "git {}: {}",
+            args.join(" "),
+            stderr.trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn rev_parse(rev: &str, dir: &Path) -> Result<String, ReviewError> {
+    run_git(
+        &["rev-parse", "--verify", &format!("{rev}^{{commit}}")],
+        Some(dir),
+    )
+}
+
+/// Detect the default branch of a local repository (`origin/HEAD`, then `main`/`master`)
+fn default_branch(repo_path: &Path) -> Result<String, ReviewError> {
+    if let Ok(origin_head) = run_git(
+        &["symbolic-ref", "--short", "refs/remotes/origin/HEAD"],
+        Some(repo_path),
+    ) {
+        return Ok(origin_head);
+    }
+
+    ["main", "master"]
+        .into_iter()
+        .find(|branch| rev_parse(branch, repo_path).is_ok())
+        .map(str::to_string)
+        .ok_or_else(|| {
+            ReviewError::InvalidReviewTarget(
+                "Could not detect the default branch, pass --range base..head".to_string(),
+            )
+        })
+}
+
+/// Use the head commit subject as title and the commit list as description
+fn describe_commits(base: &str, head: &str, dir: &Path) -> Result<(String, String), ReviewError> {
+    let title = run_git(&["log", "-1", "--format=%s", head], Some(dir))?;
+    let description = run_git(
+        &[
+            "log",
+            "--reverse",
+            "--format=- %s",
+            &format!("{base}..{head}"),
+        ],
+        Some(dir),
+    )?;
+    Ok((title, description))
+}
+
+/// Clone a local repository and check out the head of `range`
+///
+/// The user's repository is never modified; everything happens in `target_dir`.
+pub fn checkout_local_range(
+    repo_path: &Path,
+    range: Option<&CommitRange>,
+    title: Option<String>,
+    target_dir: &Path,
+) -> Result<PrInfo, ReviewError> {
+    let repo_path = repo_path
+        .canonicalize()
+        .map_err(|e| ReviewError::InvalidReviewTarget(e.to_string()))?;
+    let repo_root = run_git(&["rev-parse", "--show-toplevel"], Some(&repo_path)).map_err(|_| {
+        ReviewError::InvalidReviewTarget(format!("{} is not a git repository", repo_path.display()))
+    })?;
+    let repo_root = Path::new(&repo_root);
+
+    let range = match range {
+        Some(range) => range.clone(),
+        None => CommitRange {
+            base: default_branch(repo_root)?,
+            head: "HEAD".to_string(),
+            symmetric: true,
+        },
+    };
+
+    let head_commit = rev_parse(&range.head, repo_root)?;
+    let base_commit = if range.symmetric {
+        run_git(&["merge-base", &range.base, &head_commit], Some(repo_root))?
+    } else {
+        rev_parse(&range.base, repo_root)?
+    };
+
+    if base_commit == head_commit {
+        return Err(ReviewError::InvalidReviewTarget(format!(
+            "Range {range} contains no changes"
+        )));
+    }
+
+    let head_ref_name = run_git(&["rev-parse", "--abbrev-ref", &range.head], Some(repo_root))
+        .ok()
+        .filter(|name| !name.is_empty() && name != "HEAD")
+        .unwrap_or_else(|| head_commit.clone());
+
+    let (head_title, description) = describe_commits(&base_commit, &head_commit, repo_root)?;
+
+    debug!(
+        "Cloning {} ({base_commit}..{head_commit}) to {}",
+        repo_root.display(),
+        target_dir.display()
+    );
+
+    let target = target_dir
+        .to_str()
+        .ok_or_else(|| ReviewError::CloneFailed("Invalid target path".to_string()))?;
+    let source = repo_root
+        .to_str()
+        .ok_or_else(|| ReviewError::CloneFailed("Invalid repository path".to_string()))?;
+
+    run_git(&["clone", "--quiet", "--no-checkout", source, target], None)
+        .map_err(|e| ReviewError::CloneFailed(e.to_string()))?;
+    // The clone only has branches and tags; remote-tracking refs and
+    // unreferenced commits have to be fetched by hash
+    run_git(
+        &["fetch", "--quiet", "origin", &head_commit, &base_commit],
+        Some(target_dir),
+    )
+    .map_err(|e| ReviewError::CloneFailed(e.to_string()))?;
+    run_git(&["checkout", "--quiet", &head_commit], Some(target_dir))
+        .map_err(|e| ReviewError::CheckoutFailed(e.to_string()))?;
+
+    let repo = repo_root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "repo".to_string());
+
+    Ok(PrInfo {
+        owner: "local".to_string(),
+        repo,
+        title: title.unwrap_or(head_title),
+        description,
+        base_commit,
+        head_commit,
+        head_ref_name,
+    })
+}
+
+/// Target branch tip of a GitLab merge request
+///
+/// `--base` wins; otherwise the first parent of the merge ref GitLab publishes
+/// for mergeable requests, falling back to the default branch.
+fn gitlab_target(mr: &MergeRequest, dir: &Path) -> Result<String, ReviewError> {
+    if let Some(branch) = &mr.target_branch {
+        run_git(&["fetch", "--quiet", "origin", branch], Some(dir)).map_err(|e| {
+            ReviewError::PrInfoFailed(format!("Failed to fetch base branch {branch}: {e}"))
+        })?;
+        return rev_parse("FETCH_HEAD", dir);
+    }
+
+    let merge_ref = format!("refs/merge-requests/{}/merge", mr.number);
+    if run_git(&["fetch", "--quiet", "origin", &merge_ref], Some(dir)).is_ok() {
+        return rev_parse("FETCH_HEAD^1", dir);
+    }
+
+    debug!("{merge_ref} is not published, diffing against the default branch");
+    default_branch(dir)
+}
+
+/// Clone a GitLab / Azure DevOps repository and check out a merge request
+///
+/// Uses the refs the host publishes for merge requests, so no host-specific
+/// CLI is needed; credentials come from the user's git configuration.
+pub fn checkout_merge_request(mr: &MergeRequest, target_dir: &Path) -> Result<PrInfo, ReviewError> {
+    let target = target_dir
+        .to_str()
+        .ok_or_else(|| ReviewError::CloneFailed("Invalid target path".to_string()))?;
+
+    debug!("Cloning {} to {}", mr.clone_url, target_dir.display());
+    run_git(&["clone", "--quiet", &mr.clone_url, target], None)
+        .map_err(|e| ReviewError::CloneFailed(e.to_string()))?;
+
+    let fetch_ref = mr.fetch_ref();
+    run_git(
+        &["fetch", "--quiet", "origin", &fetch_ref],
+        Some(target_dir),
+    )
+    .map_err(|e| ReviewError::PrInfoFailed(format!("Failed to fetch {fetch_ref}: {e}")))?;
+
+    let fetched = rev_parse("FETCH_HEAD", target_dir)?;
+    let (base_commit, head_commit) = match mr.host {
+        MergeRequestHost::GitLab => {
+            let target = gitlab_target(mr, target_dir)?;
+            let base = run_git(&["merge-base", &target, &fetched], Some(target_dir))?;
+            (base, fetched)
+        }
+        // The merge preview commit's parents are (target tip, source tip)
+        MergeRequestHost::AzureDevOps => (
+            rev_parse(&format!("{fetched}^1"), target_dir)?,
+            rev_parse(&format!("{fetched}^2"), target_dir)?,
+        ),
+    };
+
+    run_git(&["checkout", "--quiet", &head_commit], Some(target_dir))
+        .map_err(|e| ReviewError::CheckoutFailed(e.to_string()))?;
+
+    let (title, description) = describe_commits(&base_commit, &head_commit, target_dir)?;
+
+    Ok(PrInfo {
+        owner: mr.owner.clone(),
+        repo: mr.repo.clone(),
+        title,
+        description,
+        base_commit,
+        head_ref_name: head_commit.clone(),
+        head_commit,
+    })
+}
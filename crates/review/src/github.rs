@@ -3,19 +3,7 @@ use std::{path::Path, process::Command};
 use serde::Deserialize;
 use tracing::debug;
 
-use crate::error::ReviewError;
-
-/// Information about a pull request
-#[derive(Debug)]
-pub struct PrInfo {
-    pub owner: String,
-    pub repo: String,
-    pub title: String,
-    pub description: String,
-    pub base_commit: String,
-    pub head_commit: String,
-    pub head_ref_name: String,
-}
+use crate::{error::ReviewError, target::PrInfo};
 
 /// Response from `gh pr view --json`
 #[derive(Debug, Deserialize)]
@@ -3,8 +3,11 @@ mod archive;
 mod claude_session;
 mod config;
 mod error;
+mod git;
 mod github;
 mod session_selector;
+mod target;
+mod workspace;
 
 use std::time::Duration;
 
@@ -12,8 +15,9 @@ use anyhow::Result;
 use api::{ReviewApiClient, ReviewStatus, StartRequest};
 use clap::Parser;
 use error::ReviewError;
-use github::{checkout_commit, clone_repo, get_pr_info, parse_pr_url};
+use github::{checkout_commit, clone_repo, get_pr_info};
 use indicatif::{ProgressBar, ProgressStyle};
+use target::{PrInfo, ReviewTarget, TargetOptions};
 use tempfile::TempDir;
 use tracing::debug;
 use tracing_subscriber::EnvFilter;
@@ -35,12 +39,31 @@ const BANNER: &str = r#"
 #[derive(Parser, Debug)]
 #[command(name = "review")]
 #[command(
-    about = "Vibe-Kanban Review helps you review GitHub pull requests by turning them into a clear, story-driven summary instead of a wall of diffs. You provide a pull request URL, optionally link a Claude Code project for additional context, and it builds a narrative that highlights key events and important decisions, helping you prioritise what actually needs attention. It's particularly useful when reviewing large amounts of AI-generated code. Note that code is uploaded to and processed on Vibe-Kanban servers using AI."
+    about = "Vibe-Kanban Review helps you review pull requests and local branches by turning them into a clear, story-driven summary instead of a wall of diffs. You provide a pull request URL, a local repository and commit range, or a vibe-kanban workspace, optionally link a Claude Code project for additional context, and it builds a narrative that highlights key events and important decisions, helping you prioritise what actually needs attention. It's particularly useful when reviewing large amounts of AI-generated code. Note that code is uploaded to and processed on Vibe-Kanban servers using AI."
 )]
 #[command(version)]
 struct Args {
-    /// GitHub PR URL (e.g., https://github.com/owner/repo/pull/123)
-    pr_url: String,
+    /// What to review: a GitHub PR URL (https://github.com/owner/repo/pull/123), a GitLab or
+    /// Azure DevOps merge request URL, a local repository path, or a vibe-kanban workspace ID
+    target: String,
+
+    /// Commit range to review in a local repository (e.g., main..feature or origin/main...HEAD).
+    /// Defaults to the changes since the merge base with the default branch
+    #[arg(long)]
+    range: Option<String>,
+
+    /// Target branch of a GitLab merge request. Defaults to the branch GitLab's merge ref
+    /// was built on, or the default branch when the merge request is not mergeable
+    #[arg(long)]
+    base: Option<String>,
+
+    /// Repository to review when a vibe-kanban workspace contains several
+    #[arg(long)]
+    repo: Option<String>,
+
+    /// vibe-kanban server URL used to resolve workspace IDs (defaults to the running instance)
+    #[arg(long, env = "VIBE_KANBAN_URL")]
+    vibe_kanban_url: Option<String>,
 
     /// Enable verbose output
     #[arg(short, long, default_value_t = false)]
@@ -135,15 +158,22 @@ async fn run(args: Args) -> Result<(), ReviewError> {
     let mut config = config::Config::load();
     let email = prompt_email(&mut config);
 
-    // 2. Parse PR URL
-    let spinner = create_spinner("Parsing PR URL...");
-    let (owner, repo, pr_number) = parse_pr_url(&args.pr_url)?;
-    spinner.finish_with_message(format!("PR: {owner}/{repo}#{pr_number}"));
-
-    // 3. Get PR info
-    let spinner = create_spinner("Fetching PR information...");
-    let pr_info = get_pr_info(&owner, &repo, pr_number)?;
-    spinner.finish_with_message(format!("PR: {}", pr_info.title));
+    // 2. Parse review target
+    let spinner = create_spinner("Parsing review target...");
+    let target = ReviewTarget::parse(
+        &args.target,
+        TargetOptions {
+            range: args.range.as_deref(),
+            base: args.base.as_deref(),
+            repo: args.repo.as_deref(),
+        },
+    )?;
+    spinner.finish_with_message(format!("Target: {target}"));
+
+    // 3. Fetch the changes into a temp directory and collect their metadata
+    let temp_dir = TempDir::new().map_err(|e| ReviewError::CloneFailed(e.to_string()))?;
+    let repo_dir = temp_dir.path().join("repo");
+    let pr_info = checkout_target(&target, &args, &repo_dir).await?;
 
     // 4. Select Claude Code session (optional)
     let session_files = match session_selector::select_session(&pr_info.head_ref_name) {
@@ -162,20 +192,7 @@ async fn run(args: Args) -> Result<(), ReviewError> {
         }
     };
 
-    // 5. Clone repository to temp directory
-    let temp_dir = TempDir::new().map_err(|e| ReviewError::CloneFailed(e.to_string()))?;
-    let repo_dir = temp_dir.path().join(&repo);
-
-    let spinner = create_spinner("Cloning repository...");
-    clone_repo(&owner, &repo, &repo_dir)?;
-    spinner.finish_with_message("Repository cloned");
-
-    // 6. Checkout PR head commit
-    let spinner = create_spinner("Checking out PR...");
-    checkout_commit(&pr_info.head_commit, &repo_dir)?;
-    spinner.finish_with_message("PR checked out");
-
-    // 7. Create tarball (with optional session data)
+    // 5. Create tarball (with optional session data)
     let spinner = create_spinner("Creating archive...");
 
     // If sessions were selected, write .agent-messages.json to repo root
@@ -190,18 +207,20 @@ async fn run(args: Args) -> Result<(), ReviewError> {
     let size_mb = payload.len() as f64 / 1_048_576.0;
     spinner.finish_with_message(format!("Archive created ({size_mb:.2} MB)"));
 
-    // 8. Initialize review
+    // 6. Initialize review
     let client = ReviewApiClient::new(args.api_url.clone());
     let spinner = create_spinner("Initializing review...");
-    let init_response = client.init(&args.pr_url, &email, &pr_info.title).await?;
+    let init_response = client
+        .init(&review_source_url(&target, &args), &email, &pr_info.title)
+        .await?;
     spinner.finish_with_message(format!("Review ID: {}", init_response.review_id));
 
-    // 9. Upload archive
+    // 7. Upload archive
     let spinner = create_spinner("Uploading archive...");
     client.upload(&init_response.upload_url, payload).await?;
     spinner.finish_with_message("Upload complete");
 
-    // 10. Start review
+    // 8. Start review
     let spinner = create_spinner("Starting review...");
     let codebase_url = format!("r2://{}", init_response.object_key);
     client
@@ -217,7 +236,7 @@ async fn run(args: Args) -> Result<(), ReviewError> {
         .await?;
     spinner.finish_with_message(format!("Review started, we'll send you an email at {} when the review is ready. This can take a few minutes, you may now close the terminal", email));
 
-    // 11. Poll for completion
+    // 9. Poll for completion
     let spinner = create_spinner("Review in progress...");
     let start_time = std::time::Instant::now();
 
@@ -251,10 +270,87 @@ async fn run(args: Args) -> Result<(), ReviewError> {
         }
     }
 
-    // 12. Print result URL
+    // 10. Print result URL
     let review_url = client.review_url(&init_response.review_id.to_string());
     println!("\nReview available at:");
     println!("  {review_url}");
 
     Ok(())
 }
+
+/// Clone/checkout the target into `repo_dir` and return the review metadata
+async fn checkout_target(
+    target: &ReviewTarget,
+    args: &Args,
+    repo_dir: &std::path::Path,
+) -> Result<PrInfo, ReviewError> {
+    match target {
+        ReviewTarget::GitHubPr {
+            owner,
+            repo,
+            number,
+        } => {
+            let spinner = create_spinner("Fetching PR information...");
+            let pr_info = get_pr_info(owner, repo, *number)?;
+            spinner.finish_with_message(format!("PR: {}", pr_info.title));
+
+            let spinner = create_spinner("Cloning repository...");
+            clone_repo(owner, repo, repo_dir)?;
+            spinner.finish_with_message("Repository cloned");
+
+            let spinner = create_spinner("Checking out PR...");
+            checkout_commit(&pr_info.head_commit, repo_dir)?;
+            spinner.finish_with_message("PR checked out");
+
+            Ok(pr_info)
+        }
+        ReviewTarget::MergeRequest(mr) => {
+            let spinner = create_spinner("Fetching merge request...");
+            let pr_info = git::checkout_merge_request(mr, repo_dir)?;
+            spinner.finish_with_message(format!("MR: {}", pr_info.title));
+            Ok(pr_info)
+        }
+        ReviewTarget::Local { repo_path, range } => {
+            let spinner = create_spinner("Checking out commit range...");
+            let pr_info = git::checkout_local_range(repo_path, range.as_ref(), None, repo_dir)?;
+            spinner.finish_with_message(format!("Changes: {}", pr_info.title));
+            Ok(pr_info)
+        }
+        ReviewTarget::Workspace(workspace_id) => {
+            let spinner = create_spinner("Looking up workspace...");
+            let vibe_kanban_url = args
+                .vibe_kanban_url
+                .clone()
+                .or_else(workspace::default_vibe_kanban_url)
+                .ok_or_else(|| {
+                    ReviewError::WorkspaceLookupFailed(
+                        "vibe-kanban does not appear to be running, pass --vibe-kanban-url"
+                            .to_string(),
+                    )
+                })?;
+            let resolved =
+                workspace::resolve_workspace(&vibe_kanban_url, *workspace_id, args.repo.as_deref())
+                    .await?;
+            spinner.finish_with_message(format!("Workspace branch: {}", resolved.range.head));
+
+            let spinner = create_spinner("Checking out workspace branch...");
+            let pr_info = git::checkout_local_range(
+                &resolved.repo_path,
+                Some(&resolved.range),
+                resolved.title,
+                repo_dir,
+            )?;
+            spinner.finish_with_message(format!("Changes: {}", pr_info.title));
+            Ok(pr_info)
+        }
+    }
+}
+
+/// Identifier sent to the review service for the reviewed changes
+fn review_source_url(target: &ReviewTarget, args: &Args) -> String {
+    match target {
+        ReviewTarget::GitHubPr { .. } => args.target.clone(),
+        ReviewTarget::MergeRequest(mr) => mr.url.clone(),
+        ReviewTarget::Local { .. } | ReviewTarget::Workspace(_) => format!("local:{target}"),
+    }
+}
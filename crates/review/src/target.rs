@@ -0,0 +1,400 @@
+use std::{fmt, path::PathBuf};
+
+use uuid::Uuid;
+
+use crate::error::ReviewError;
+
+/// Information about the changes under review
+///
+/// Produced for every kind of [`ReviewTarget`], whether it is backed by a
+/// hosted pull request or a plain local commit range.
+#[derive(Debug)]
+pub struct PrInfo {
+    pub owner: String,
+    pub repo: String,
+    pub title: String,
+    pub description: String,
+    pub base_commit: String,
+    pub head_commit: String,
+    pub head_ref_name: String,
+}
+
+/// Hosting provider of a merge request URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeRequestHost {
+    GitLab,
+    AzureDevOps,
+}
+
+/// A merge request on a non-GitHub host, fetched through plain git refs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeRequest {
+    pub host: MergeRequestHost,
+    pub url: String,
+    pub clone_url: String,
+    pub owner: String,
+    pub repo: String,
+    pub number: i64,
+    /// Branch the merge request targets, when given with `--base`
+    pub target_branch: Option<String>,
+}
+
+impl MergeRequest {
+    /// The ref the host publishes for this merge request
+    ///
+    /// GitLab exposes the source branch head, Azure DevOps only exposes the
+    /// preview merge commit (whose first parent is the target branch tip).
+    pub fn fetch_ref(&self) -> String {
+        match self.host {
+            MergeRequestHost::GitLab => format!("refs/merge-requests/{}/head", self.number),
+            MergeRequestHost::AzureDevOps => format!("refs/pull/{}/merge", self.number),
+        }
+    }
+}
+
+/// A `base..head` or `base...head` commit range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitRange {
+    pub base: String,
+    pub head: String,
+    /// `base...head`: review against the merge base instead of `base` itself
+    pub symmetric: bool,
+}
+
+impl CommitRange {
+    /// Parse a `base..head` / `base...head` range. An omitted head means `HEAD`.
+    pub fn parse(range: &str) -> Result<Self, ReviewError> {
+        let range = range.trim();
+        let (base, head, symmetric) = if let Some((base, head)) = range.split_once("...") {
+            (base, head, true)
+        } else if let Some((base, head)) = range.split_once("..") {
+            (base, head, false)
+        } else {
+            return Err(ReviewError::InvalidReviewTarget(format!(
+                "Invalid commit range '{range}'. Expected: base..head"
+            )));
+        };
+
+        if base.is_empty() {
+            return Err(ReviewError::InvalidReviewTarget(format!(
+                "Commit range '{range}' is missing a base"
+            )));
+        }
+
+        Ok(Self {
+            base: base.to_string(),
+            head: if head.is_empty() { "HEAD" } else { head }.to_string(),
+            symmetric,
+        })
+    }
+}
+
+impl fmt::Display for CommitRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sep = if self.symmetric { "..." } else { ".." };
+        write!(f, "{}{sep}{}", self.base, self.head)
+    }
+}
+
+/// Command-line options that refine a [`ReviewTarget`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TargetOptions<'a> {
+    /// `--range`, only valid for a local repository
+    pub range: Option<&'a str>,
+    /// `--base`, only valid for a GitLab merge request
+    pub base: Option<&'a str>,
+    /// `--repo`, only valid for a workspace
+    pub repo: Option<&'a str>,
+}
+
+/// What the user asked us to review
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewTarget {
+    /// https://github.com/owner/repo/pull/123
+    GitHubPr {
+        owner: String,
+        repo: String,
+        number: i64,
+    },
+    /// GitLab or Azure DevOps merge request URL
+    MergeRequest(MergeRequest),
+    /// A local repository and a commit range (defaults to the repo's default branch)
+    Local {
+        repo_path: PathBuf,
+        range: Option<CommitRange>,
+    },
+    /// A vibe-kanban workspace, resolved through the local vibe-kanban server
+    Workspace(Uuid),
+}
+
+impl ReviewTarget {
+    /// Parse the positional `review` argument
+    ///
+    /// Accepts a GitHub PR URL, a GitLab/Azure DevOps merge request URL, a
+    /// vibe-kanban workspace ID, or a path to a local git repository. Options
+    /// that do not apply to the kind of target are rejected.
+    pub fn parse(input: &str, options: TargetOptions) -> Result<Self, ReviewError> {
+        let input = input.trim();
+
+        let target = if input.starts_with("http://") || input.starts_with("https://") {
+            if input.contains("/pull/") && !input.contains("/_git/") {
+                let (owner, repo, number) = crate::github::parse_pr_url(input)?;
+                ReviewTarget::GitHubPr {
+                    owner,
+                    repo,
+                    number,
+                }
+            } else {
+                ReviewTarget::MergeRequest(MergeRequest {
+                    target_branch: options.base.map(str::to_string),
+                    ..parse_merge_request_url(input)?
+                })
+            }
+        } else if let Ok(id) = Uuid::parse_str(input) {
+            ReviewTarget::Workspace(id)
+        } else {
+            let repo_path = PathBuf::from(input);
+            if !repo_path.is_dir() {
+                return Err(ReviewError::InvalidReviewTarget(format!(
+                    "'{input}' is not a PR/MR URL, a workspace ID or a local directory"
+                )));
+            }
+            ReviewTarget::Local {
+                repo_path,
+                range: options.range.map(CommitRange::parse).transpose()?,
+            }
+        };
+
+        if options.range.is_some() && !matches!(target, ReviewTarget::Local { .. }) {
+            return Err(ReviewError::InvalidReviewTarget(
+                "--range can only be used with a local repository path".to_string(),
+            ));
+        }
+        let gitlab = matches!(
+            target,
+            ReviewTarget::MergeRequest(MergeRequest {
+                host: MergeRequestHost::GitLab,
+                ..
+            })
+        );
+        if options.base.is_some() && !gitlab {
+            return Err(ReviewError::InvalidReviewTarget(
+                "--base can only be used with a GitLab merge request URL".to_string(),
+            ));
+        }
+        if options.repo.is_some() && !matches!(target, ReviewTarget::Workspace(_)) {
+            return Err(ReviewError::InvalidReviewTarget(
+                "--repo can only be used with a workspace ID".to_string(),
+            ));
+        }
+
+        Ok(target)
+    }
+}
+
+impl fmt::Display for ReviewTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReviewTarget::GitHubPr {
+                owner,
+                repo,
+                number,
+            } => write!(f, "{owner}/{repo}#{number}"),
+            ReviewTarget::MergeRequest(mr) => write!(f, "{}/{}!{}", mr.owner, mr.repo, mr.number),
+            ReviewTarget::Local {
+                repo_path,
+                range: Some(range),
+            } => write!(f, "{} ({range})", repo_path.display()),
+            ReviewTarget::Local {
+                repo_path,
+                range: None,
+            } => write!(f, "{}", repo_path.display()),
+            ReviewTarget::Workspace(id) => write!(f, "workspace {id}"),
+        }
+    }
+}
+
+/// Parse a GitLab or Azure DevOps merge request URL
+///
+/// Expected formats:
+/// - https://gitlab.com/group/subgroup/project/-/merge_requests/42
+/// - https://dev.azure.com/org/project/_git/repo/pullrequest/42
+/// - https://org.visualstudio.com/project/_git/repo/pullrequest/42
+pub fn parse_merge_request_url(url: &str) -> Result<MergeRequest, ReviewError> {
+    let url = url.trim().trim_end_matches('/');
+    let invalid = || ReviewError::InvalidReviewTarget(format!("Unsupported PR/MR URL: {url}"));
+
+    let (scheme, rest) = url.split_once("://").ok_or_else(invalid)?;
+    let parts: Vec<&str> = rest.split('/').collect();
+    let host = parts[0];
+
+    if let Some(mr_idx) = parts.iter().position(|&p| p == "merge_requests") {
+        // GitLab: host / namespace... / project / - / merge_requests / number
+        if mr_idx < 4 || parts[mr_idx - 1] != "-" || parts.len() <= mr_idx + 1 {
+            return Err(invalid());
+        }
+        let number: i64 = parts[mr_idx + 1].parse().map_err(|_| invalid())?;
+        let project_parts = &parts[1..mr_idx - 1];
+        let (repo, namespace) = project_parts.split_last().ok_or_else(invalid)?;
+        if namespace.is_empty() || number <= 0 {
+            return Err(invalid());
+        }
+
+        return Ok(MergeRequest {
+            host: MergeRequestHost::GitLab,
+            url: url.to_string(),
+            clone_url: format!("{scheme}://{host}/{}.git", project_parts.join("/")),
+            owner: namespace.join("/"),
+            repo: repo.to_string(),
+            number,
+            target_branch: None,
+        });
+    }
+
+    if let Some(git_idx) = parts.iter().position(|&p| p == "_git") {
+        // Azure DevOps: host / [org /] project / _git / repo / pullrequest / number
+        if parts.len() < git_idx + 4 || parts[git_idx + 2] != "pullrequest" {
+            return Err(invalid());
+        }
+        let number: i64 = parts[git_idx + 3].parse().map_err(|_| invalid())?;
+        let repo = parts[git_idx + 1];
+        if git_idx < 2 || repo.is_empty() || number <= 0 {
+            return Err(invalid());
+        }
+
+        return Ok(MergeRequest {
+            host: MergeRequestHost::AzureDevOps,
+            url: url.to_string(),
+            clone_url: format!("{scheme}://{}", parts[..git_idx + 2].join("/")),
+            owner: parts[1..git_idx].join("/"),
+            repo: repo.to_string(),
+            number,
+            target_branch: None,
+        });
+    }
+
+    Err(invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gitlab_mr_url() {
+        let mr = parse_merge_request_url(
+            "https://gitlab.com/group/subgroup/project/-/merge_requests/42/",
+        )
+        .expect("Should parse GitLab URL");
+        assert_eq!(mr.host, MergeRequestHost::GitLab);
+        assert_eq!(mr.owner, "group/subgroup");
+        assert_eq!(mr.repo, "project");
+        assert_eq!(mr.number, 42);
+        assert_eq!(
+            mr.clone_url,
+            "https://gitlab.com/group/subgroup/project.git"
+        );
+        assert_eq!(mr.fetch_ref(), "refs/merge-requests/42/head");
+    }
+
+    #[test]
+    fn test_parse_azure_pr_url() {
+        let mr =
+            parse_merge_request_url("https://dev.azure.com/org/project/_git/repo/pullrequest/7")
+                .expect("Should parse Azure URL");
+        assert_eq!(mr.host, MergeRequestHost::AzureDevOps);
+        assert_eq!(mr.owner, "org/project");
+        assert_eq!(mr.repo, "repo");
+        assert_eq!(mr.number, 7);
+        assert_eq!(mr.clone_url, "https://dev.azure.com/org/project/_git/repo");
+        assert_eq!(mr.fetch_ref(), "refs/pull/7/merge");
+
+        let legacy =
+            parse_merge_request_url("https://org.visualstudio.com/project/_git/repo/pullrequest/9")
+                .expect("Should parse legacy Azure URL");
+        assert_eq!(legacy.owner, "project");
+        assert_eq!(
+            legacy.clone_url,
+            "https://org.visualstudio.com/project/_git/repo"
+        );
+    }
+
+    #[test]
+    fn test_parse_merge_request_url_invalid() {
+        assert!(parse_merge_request_url("https://gitlab.com/group/project/-/issues/1").is_err());
+        assert!(parse_merge_request_url("https://gitlab.com/-/merge_requests/1").is_err());
+        assert!(parse_merge_request_url("https://dev.azure.com/org/project/_git/repo").is_err());
+        assert!(parse_merge_request_url("https://example.com/foo").is_err());
+    }
+
+    #[test]
+    fn test_parse_commit_range() {
+        let range = CommitRange::parse("main..feature").expect("Should parse range");
+        assert_eq!(range.base, "main");
+        assert_eq!(range.head, "feature");
+        assert!(!range.symmetric);
+
+        let range = CommitRange::parse("origin/main...").expect("Should parse range");
+        assert_eq!(range.base, "origin/main");
+        assert_eq!(range.head, "HEAD");
+        assert!(range.symmetric);
+
+        assert!(CommitRange::parse("main").is_err());
+        assert!(CommitRange::parse("..feature").is_err());
+    }
+
+    #[test]
+    fn test_parse_target_kinds() {
+        let none = TargetOptions::default();
+        assert!(matches!(
+            ReviewTarget::parse("https://github.com/owner/repo/pull/1", none),
+            Ok(ReviewTarget::GitHubPr { number: 1, .. })
+        ));
+        assert!(matches!(
+            ReviewTarget::parse("https://gitlab.com/owner/repo/-/merge_requests/3", none),
+            Ok(ReviewTarget::MergeRequest(_))
+        ));
+        assert!(matches!(
+            ReviewTarget::parse("6f1d2c1e-8f9b-4a3e-9a53-0d6c3f0b8a11", none),
+            Ok(ReviewTarget::Workspace(_))
+        ));
+        let range = TargetOptions {
+            range: Some("main..HEAD"),
+            ..none
+        };
+        assert!(matches!(
+            ReviewTarget::parse(".", range),
+            Ok(ReviewTarget::Local { range: Some(_), .. })
+        ));
+        assert!(ReviewTarget::parse("https://github.com/owner/repo/pull/1", range).is_err());
+        assert!(ReviewTarget::parse("/definitely/not/a/dir", none).is_err());
+    }
+
+    #[test]
+    fn test_parse_target_rejects_options_of_other_kinds() {
+        let base = TargetOptions {
+            base: Some("release"),
+            ..Default::default()
+        };
+        assert!(matches!(
+            ReviewTarget::parse("https://gitlab.com/owner/repo/-/merge_requests/3", base),
+            Ok(ReviewTarget::MergeRequest(MergeRequest { target_branch: Some(ref b), .. })) if b == "release"
+        ));
+        assert!(ReviewTarget::parse(".", base).is_err());
+        assert!(
+            ReviewTarget::parse(
+                "https://dev.azure.com/org/project/_git/repo/pullrequest/7",
+                base
+            )
+            .is_err()
+        );
+
+        let repo = TargetOptions {
+            repo: Some("frontend"),
+            ..Default::default()
+        };
+        assert!(ReviewTarget::parse("6f1d2c1e-8f9b-4a3e-9a53-0d6c3f0b8a11", repo).is_ok());
+        assert!(ReviewTarget::parse(".", repo).is_err());
+        assert!(ReviewTarget::parse("https://github.com/owner/repo/pull/1", repo).is_err());
+    }
+}
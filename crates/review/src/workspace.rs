@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+
+use reqwest::Client;
+use serde::{Deserialize, de::DeserializeOwned};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::{error::ReviewError, target::CommitRange};
+
+/// A vibe-kanban workspace resolved to a local repository and commit range
+#[derive(Debug)]
+pub struct ResolvedWorkspace {
+    pub repo_path: PathBuf,
+    pub range: CommitRange,
+    pub title: Option<String>,
+}
+
+/// Envelope used by every vibe-kanban API response
+#[derive(Debug, Deserialize)]
+struct ApiResponse<T> {
+    success: bool,
+    data: Option<T>,
+    message: Option<String>,
+}
+
+/// Subset of `GET /api/task-attempts/{id}`
+#[derive(Debug, Deserialize)]
+struct Workspace {
+    branch: String,
+    name: Option<String>,
+}
+
+/// Subset of `GET /api/task-attempts/{id}/repos`
+#[derive(Debug, Deserialize)]
+struct WorkspaceRepo {
+    name: String,
+    path: PathBuf,
+    target_branch: String,
+}
+
+/// Locate the running vibe-kanban server from the port file it writes on startup
+pub fn default_vibe_kanban_url() -> Option<String> {
+    let path = std::env::temp_dir()
+        .join("vibe-kanban")
+        .join("vibe-kanban.port");
+    let port: u16 = std::fs::read_to_string(path).ok()?.trim().parse().ok()?;
+    Some(format!("http://127.0.0.1:{port}"))
+}
+
+async fn get<T: DeserializeOwned>(client: &Client, url: &str) -> Result<T, ReviewError> {
+    debug!("GET {url}");
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| ReviewError::WorkspaceLookupFailed(e.to_string()))?;
+
+    let status = response.status();
+    let body: ApiResponse<T> = response
+        .json()
+        .await
+        .map_err(|e| ReviewError::WorkspaceLookupFailed(format!("{status}: {e}")))?;
+
+    if !body.success {
+        return Err(ReviewError::WorkspaceLookupFailed(
+            body.message.unwrap_or_else(|| status.to_string()),
+        ));
+    }
+
+    body.data
+        .ok_or_else(|| ReviewError::WorkspaceLookupFailed("Empty response".to_string()))
+}
+
+/// Resolve a workspace to its branch range (`target_branch...branch`)
+///
+/// Multi-repo workspaces require `repo_name` to pick which repository to review.
+pub async fn resolve_workspace(
+    vibe_kanban_url: &str,
+    workspace_id: Uuid,
+    repo_name: Option<&str>,
+) -> Result<ResolvedWorkspace, ReviewError> {
+    let client = Client::new();
+    let base_url = format!(
+        "{}/api/task-attempts/{workspace_id}",
+        vibe_kanban_url.trim_end_matches('/')
+    );
+
+    let workspace: Workspace = get(&client, &base_url).await?;
+    let repos: Vec<WorkspaceRepo> = get(&client, &format!("{base_url}/repos")).await?;
+
+    let repo = match repo_name {
+        Some(name) => repos.into_iter().find(|r| r.name == name).ok_or_else(|| {
+            ReviewError::WorkspaceLookupFailed(format!(
+                "Workspace has no repository named '{name}'"
+            ))
+        })?,
+        None if repos.len() == 1 => repos.into_iter().next().expect("checked length"),
+        None => {
+            let names: Vec<String> = repos.into_iter().map(|r| r.name).collect();
+            return Err(ReviewError::WorkspaceLookupFailed(format!(
+                "Workspace has {} repositories, pick one with --repo ({})",
+                names.len(),
+                names.join(", ")
+            )));
+        }
+    };
+
+    Ok(ResolvedWorkspace {
+        repo_path: repo.path,
+        range: CommitRange {
+            base: repo.target_branch,
+            head: workspace.branch,
+            symmetric: true,
+        },
+        title: workspace.name,
+    })
+}
@@ -16,10 +16,14 @@ fn generate_types_content() -> String {
         db::models::project::UpdateProject::decl(),
         db::models::project::SearchResult::decl(),
         db::models::project::SearchMatchType::decl(),
+        db::models::repo::MergeStrategy::decl(),
         db::models::repo::Repo::decl(),
         db::models::repo::UpdateRepo::decl(),
         db::models::project_repo::ProjectRepo::decl(),
         db::models::project_repo::CreateProjectRepo::decl(),
+        db::models::mcp_servers::McpServers::decl(),
+        db::models::mcp_servers::WorkspaceMcpServers::decl(),
+        db::models::agent_instructions::AgentInstructions::decl(),
         db::models::workspace_repo::WorkspaceRepo::decl(),
         db::models::workspace_repo::CreateWorkspaceRepo::decl(),
         db::models::workspace_repo::RepoWithTargetBranch::decl(),
@@ -114,6 +118,8 @@ fn generate_types_content() -> String {
         server::routes::oauth::CurrentUserResponse::decl(),
         server::routes::sessions::CreateFollowUpAttempt::decl(),
         server::routes::sessions::ResetProcessRequest::decl(),
+        server::routes::sessions::RevertProcessRequest::decl(),
+        server::routes::sessions::HandoffSessionRequest::decl(),
         server::routes::task_attempts::ChangeTargetBranchRequest::decl(),
         server::routes::task_attempts::ChangeTargetBranchResponse::decl(),
         server::routes::task_attempts::MergeTaskAttemptRequest::decl(),
@@ -128,6 +134,8 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::pr::CreatePrApiRequest::decl(),
         server::routes::images::ImageResponse::decl(),
         server::routes::images::ImageMetadata::decl(),
+        server::routes::terminal::TerminalSession::decl(),
+        server::routes::terminal::TerminalRecording::decl(),
         server::routes::task_attempts::CreateTaskAttemptBody::decl(),
         server::routes::task_attempts::WorkspaceRepoInput::decl(),
         server::routes::task_attempts::RunAgentSetupRequest::decl(),
@@ -136,6 +144,7 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::RebaseTaskAttemptRequest::decl(),
         server::routes::task_attempts::ContinueRebaseRequest::decl(),
         server::routes::task_attempts::AbortConflictsRequest::decl(),
+        server::routes::task_attempts::ResolveConflictsRequest::decl(),
         server::routes::task_attempts::GitOperationError::decl(),
         server::routes::task_attempts::PushError::decl(),
         server::routes::task_attempts::pr::PrError::decl(),
@@ -159,6 +168,9 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::workspace_summary::WorkspaceSummary::decl(),
         server::routes::task_attempts::workspace_summary::WorkspaceSummaryResponse::decl(),
         server::routes::task_attempts::workspace_summary::DiffStats::decl(),
+        server::routes::task_attempts::output::CaptureOutputQuery::decl(),
+        server::routes::task_attempts::output::CapturedOutput::decl(),
+        server::routes::task_attempts::output::AttachOutputRequest::decl(),
         services::services::filesystem::DirectoryEntry::decl(),
         services::services::filesystem::DirectoryListResponse::decl(),
         services::services::file_search::SearchMode::decl(),
@@ -173,6 +185,10 @@ fn generate_types_content() -> String {
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
         services::services::config::SendMessageShortcut::decl(),
+        services::services::config::ConcurrencyConfig::decl(),
+        services::services::config::AutoRebaseConfig::decl(),
+        services::services::config::TerminalConfig::decl(),
+        services::services::config::PortAllocationConfig::decl(),
         git::GitBranch::decl(),
         services::services::queued_message::QueuedMessage::decl(),
         services::services::queued_message::QueueStatus::decl(),
@@ -188,10 +204,16 @@ fn generate_types_content() -> String {
         executors::executors::SlashCommandDescription::decl(),
         executors::executors::AvailabilityInfo::decl(),
         executors::command::CommandBuilder::decl(),
+        executors::sandbox::ProcessSandbox::decl(),
+        executors::sandbox::SandboxNetwork::decl(),
+        executors::resource_limits::ResourceLimits::decl(),
+        executors::oci::OciEngine::decl(),
+        executors::oci::WorkspaceContainerConfig::decl(),
         executors::profile::ExecutorProfileId::decl(),
         executors::profile::ExecutorConfig::decl(),
         executors::profile::ExecutorConfigs::decl(),
         executors::executors::BaseAgentCapability::decl(),
+        executors::steering::SteerRequest::decl(),
         executors::executors::claude::ClaudeCode::decl(),
         executors::executors::gemini::Gemini::decl(),
         executors::executors::amp::Amp::decl(),
@@ -209,6 +231,11 @@ fn generate_types_content() -> String {
         executors::executors::droid::Autonomy::decl(),
         executors::executors::droid::ReasoningEffortLevel::decl(),
         executors::executors::pi::Pi::decl(),
+        executors::executors::aider::Aider::decl(),
+        executors::executors::custom::Custom::decl(),
+        executors::executors::custom::CustomOutputFormat::decl(),
+        executors::executors::custom::CustomEntryType::decl(),
+        executors::executors::custom::JsonLineRule::decl(),
         executors::executors::AppendPrompt::decl(),
         executors::actions::coding_agent_initial::CodingAgentInitialRequest::decl(),
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
@@ -315,6 +342,14 @@ fn generate_schemas() -> Result<HashMap<&'static str, String>, serde_json::Error
             "pi",
             generate_json_schema::<executors::executors::pi::Pi>()?,
         ),
+        (
+            "aider",
+            generate_json_schema::<executors::executors::aider::Aider>()?,
+        ),
+        (
+            "custom",
+            generate_json_schema::<executors::executors::custom::Custom>()?,
+        ),
     ]);
     println!(
         "✅ JSON schemas generated. {} schemas created.",
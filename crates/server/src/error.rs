@@ -5,7 +5,7 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use db::models::{
-    execution_process::ExecutionProcessError, project::ProjectError,
+    execution_process::ExecutionProcessError, mcp_servers::McpServersError, project::ProjectError,
     project_repo::ProjectRepoError, repo::RepoError, scratch::ScratchError, session::SessionError,
     workspace::WorkspaceError,
 };
@@ -374,6 +374,9 @@ impl IntoResponse for ApiError {
             ),
 
             ApiError::Deployment(_) => ErrorInfo::internal("DeploymentError"),
+            ApiError::Container(ContainerError::SteeringUnavailable) => {
+                ErrorInfo::conflict("ContainerError", format!("{}", self))
+            }
             ApiError::Container(_) => ErrorInfo::internal("ContainerError"),
             ApiError::Executor(_) => ErrorInfo::internal("ExecutorError"),
             ApiError::CommandBuilder(_) => ErrorInfo::internal("CommandBuildError"),
@@ -494,6 +497,15 @@ impl From<RepoServiceError> for ApiError {
     }
 }
 
+impl From<McpServersError> for ApiError {
+    fn from(err: McpServersError) -> Self {
+        match err {
+            McpServersError::Database(db_err) => ApiError::Database(db_err),
+            McpServersError::InvalidServer(..) => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
 impl From<ProjectRepoError> for ApiError {
     fn from(err: ProjectRepoError) -> Self {
         match err {
@@ -27,6 +27,15 @@ pub enum VibeKanbanError {
 
 #[tokio::main]
 async fn main() -> Result<(), VibeKanbanError> {
+    // Agents run this binary as their tool-call approval hook
+    if let Some(code) = executors::approval_hook::run_hook_from_args() {
+        std::process::exit(code);
+    }
+    // Sandboxed agents with a network allowlist run under this binary's shim
+    if let Some(code) = executors::sandbox::run_forwarder_from_args() {
+        std::process::exit(code);
+    }
+
     // Install rustls crypto provider before any TLS operations
     rustls::crypto::aws_lc_rs::default_provider()
         .install_default()
@@ -67,6 +76,8 @@ async fn main() -> Result<(), VibeKanbanError> {
         .backfill_repo_names()
         .await
         .map_err(DeploymentError::from)?;
+    // Resume coding agents that were still waiting for a slot at shutdown
+    deployment.container().start_queued_executions().await;
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
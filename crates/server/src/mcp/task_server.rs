@@ -23,7 +23,10 @@ use uuid::Uuid;
 
 use crate::routes::{
     containers::ContainerQuery,
-    task_attempts::{CreateTaskAttemptBody, WorkspaceRepoInput},
+    task_attempts::{
+        CreateTaskAttemptBody, WorkspaceRepoInput,
+        output::{AttachOutputRequest, CaptureOutputQuery, CapturedOutput},
+    },
 };
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -173,6 +176,8 @@ pub struct TaskSummary {
     pub updated_at: String,
     #[schemars(description = "Whether the task has an in-progress execution attempt")]
     pub has_in_progress_attempt: Option<bool>,
+    #[schemars(description = "Whether the task's coding agent is waiting for a concurrency slot")]
+    pub is_queued: Option<bool>,
     #[schemars(description = "Whether the last execution attempt failed")]
     pub last_attempt_failed: Option<bool>,
 }
@@ -186,6 +191,7 @@ impl TaskSummary {
             created_at: task.created_at.to_rfc3339(),
             updated_at: task.updated_at.to_rfc3339(),
             has_in_progress_attempt: Some(task.has_in_progress_attempt),
+            is_queued: Some(task.is_queued),
             last_attempt_failed: Some(task.last_attempt_failed),
         }
     }
@@ -276,7 +282,7 @@ pub struct StartWorkspaceSessionRequest {
     #[schemars(description = "The ID of the task to start")]
     pub task_id: Uuid,
     #[schemars(
-        description = "The coding agent executor to run ('CLAUDE_CODE', 'AMP', 'GEMINI', 'CODEX', 'OPENCODE', 'CURSOR_AGENT', 'QWEN_CODE', 'COPILOT', 'DROID')"
+        description = "The coding agent executor to run ('CLAUDE_CODE', 'AMP', 'GEMINI', 'CODEX', 'OPENCODE', 'CURSOR_AGENT', 'QWEN_CODE', 'COPILOT', 'DROID', 'PI', 'AIDER', 'CUSTOM')"
     )]
     pub executor: String,
     #[schemars(description = "Optional executor variant, if needed")]
@@ -307,6 +313,36 @@ pub struct GetTaskResponse {
     pub task: TaskDetails,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetWorkspaceOutputRequest {
+    #[schemars(
+        description = "The workspace to read from. Defaults to the current workspace when running inside one."
+    )]
+    pub workspace_id: Option<Uuid>,
+    #[schemars(description = "A terminal session of the workspace to read")]
+    pub terminal_session_id: Option<Uuid>,
+    #[schemars(
+        description = "An execution process (script, dev server or agent run) of the workspace to read. Without this or `terminal_session_id`, the running dev server is read."
+    )]
+    pub execution_process_id: Option<Uuid>,
+    #[schemars(description = "Number of lines to return from the end (default: 200)")]
+    pub lines: Option<usize>,
+    #[schemars(
+        description = "Also append the output to the follow-up draft of the workspace's latest session, so it is sent with the next prompt"
+    )]
+    pub attach_to_follow_up: Option<bool>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetWorkspaceOutputResponse {
+    #[schemars(description = "What produced the output, e.g. 'terminal' or 'dev server'")]
+    pub source: String,
+    #[schemars(description = "The last lines of output as plain text")]
+    pub content: String,
+    #[schemars(description = "Whether the output was added to the follow-up draft")]
+    pub attached_to_follow_up: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct TaskServer {
     client: reqwest::Client,
@@ -999,12 +1035,63 @@ impl TaskServer {
 
         TaskServer::success(&response)
     }
+
+    #[tool(
+        description = "Read the latest output of a terminal, dev server or script in a workspace, e.g. to see a stack trace. Optionally attach it to the next follow-up prompt."
+    )]
+    async fn get_workspace_output(
+        &self,
+        Parameters(GetWorkspaceOutputRequest {
+            workspace_id,
+            terminal_session_id,
+            execution_process_id,
+            lines,
+            attach_to_follow_up,
+        }): Parameters<GetWorkspaceOutputRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let Some(workspace_id) = workspace_id.or(self.context.as_ref().map(|ctx| ctx.workspace_id))
+        else {
+            return Self::err(
+                "`workspace_id` is required outside of a workspace.".to_string(),
+                None::<String>,
+            );
+        };
+
+        let capture = CaptureOutputQuery {
+            terminal_session_id,
+            execution_process_id,
+            lines,
+        };
+        let attach = attach_to_follow_up.unwrap_or(false);
+        let request = if attach {
+            self.client
+                .post(self.url(&format!("/api/task-attempts/{workspace_id}/output/attach")))
+                .json(&AttachOutputRequest {
+                    session_id: None,
+                    capture,
+                })
+        } else {
+            self.client
+                .get(self.url(&format!("/api/task-attempts/{workspace_id}/output")))
+                .query(&capture)
+        };
+        let output: CapturedOutput = match self.send_json(request).await {
+            Ok(output) => output,
+            Err(e) => return Ok(e),
+        };
+
+        TaskServer::success(&GetWorkspaceOutputResponse {
+            source: output.source,
+            content: output.content,
+            attached_to_follow_up: attach,
+        })
+    }
 }
 
 #[tool_handler]
 impl ServerHandler for TaskServer {
     fn get_info(&self) -> ServerInfo {
-        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_workspace_session', 'get_task', 'update_task', 'delete_task', 'list_repos', 'get_repo', 'update_setup_script', 'update_cleanup_script', 'update_dev_server_script'. Make sure to pass `project_id`, `task_id`, or `repo_id` where required. You can use list tools to get the available ids.".to_string();
+        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_workspace_session', 'get_task', 'update_task', 'delete_task', 'list_repos', 'get_repo', 'update_setup_script', 'update_cleanup_script', 'update_dev_server_script', 'get_workspace_output'. Make sure to pass `project_id`, `task_id`, or `repo_id` where required. You can use list tools to get the available ids.".to_string();
         if self.context.is_some() {
             let context_instruction = "Use 'get_context' to fetch project/task/workspace metadata for the active Vibe Kanban workspace session when available.";
             instruction = format!("{} {}", context_instruction, instruction);
@@ -200,6 +200,14 @@ async fn handle_config_events(deployment: &DeploymentImpl, old: &Config, new: &C
             deployment_clone.trigger_auto_project_setup().await;
         });
     }
+
+    // Raised or removed limits may let queued coding agents start
+    if old.concurrency != new.concurrency {
+        let deployment_clone = deployment.clone();
+        tokio::spawn(async move {
+            deployment_clone.container().start_queued_executions().await;
+        });
+    }
 }
 
 async fn get_sound(Path(sound): Path<SoundFile>) -> Result<Response, ApiError> {
@@ -13,6 +13,8 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
+    agent_instructions::AgentInstructions,
+    mcp_servers::McpServers,
     project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
     project_repo::{CreateProjectRepo, ProjectRepo},
     repo::Repo,
@@ -433,6 +435,65 @@ pub async fn get_project_repository(
     }
 }
 
+pub async fn get_project_mcp_servers(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<McpServers>>, ApiError> {
+    let servers = McpServers::find_for_project(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(McpServers { servers })))
+}
+
+pub async fn update_project_mcp_servers(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<McpServers>,
+) -> Result<ResponseJson<ApiResponse<McpServers>>, ApiError> {
+    McpServers::validate(&payload.servers, false)?;
+    McpServers::set_for_project(&deployment.db().pool, project.id, &payload.servers).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_mcp_servers_updated",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "server_count": payload.servers.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(payload)))
+}
+
+pub async fn get_project_instructions(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<AgentInstructions>>, ApiError> {
+    let content = AgentInstructions::find_for_project(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(AgentInstructions {
+        content,
+    })))
+}
+
+pub async fn update_project_instructions(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<AgentInstructions>,
+) -> Result<ResponseJson<ApiResponse<AgentInstructions>>, ApiError> {
+    AgentInstructions::set_for_project(&deployment.db().pool, project.id, &payload.content).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_instructions_updated",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "length": payload.content.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(payload)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let project_id_router = Router::new()
         .route(
@@ -445,6 +506,14 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/repositories",
             get(get_project_repositories).post(add_project_repository),
         )
+        .route(
+            "/mcp-servers",
+            get(get_project_mcp_servers).put(update_project_mcp_servers),
+        )
+        .route(
+            "/instructions",
+            get(get_project_instructions).put(update_project_instructions),
+        )
         .layer(from_fn_with_state(
             deployment.clone(),
             load_project_middleware,
@@ -1,6 +1,8 @@
 pub mod queue;
 pub mod review;
 
+use std::path::PathBuf;
+
 use axum::{
     Extension, Json, Router,
     extract::{Query, State},
@@ -10,7 +12,8 @@ use axum::{
 };
 use db::models::{
     coding_agent_turn::CodingAgentTurn,
-    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    execution_process_repo_state::ExecutionProcessRepoState,
     scratch::{Scratch, ScratchType},
     session::{CreateSession, Session, SessionError},
     workspace::{Workspace, WorkspaceError},
@@ -22,14 +25,19 @@ use executors::{
         ExecutorAction, ExecutorActionType, coding_agent_follow_up::CodingAgentFollowUpRequest,
     },
     profile::ExecutorProfileId,
+    steering::SteerRequest,
 };
+use git::{ConflictOp, GitServiceError};
 use serde::Deserialize;
 use services::services::container::ContainerService;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware};
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::load_session_middleware,
+    routes::task_attempts::GitOperationError,
+};
 
 #[derive(Debug, Deserialize)]
 pub struct SessionQuery {
@@ -99,6 +107,20 @@ pub struct ResetProcessRequest {
     pub perform_git_reset: Option<bool>,
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct RevertProcessRequest {
+    pub process_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct HandoffSessionRequest {
+    pub executor_profile_id: ExecutorProfileId,
+    /// What the new agent should do next; it continues the task when empty
+    #[serde(default)]
+    #[ts(optional)]
+    pub prompt: Option<String>,
+}
+
 pub async fn follow_up(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
@@ -189,6 +211,7 @@ pub async fn follow_up(
                 prompt,
                 executor_profile_id: executor_profile_id.clone(),
                 working_dir,
+                fallback_profiles: None,
             },
         )
     };
@@ -240,11 +263,249 @@ pub async fn reset_process(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Undo the commits one coding agent turn made, as new revert commits on top of
+/// the workspace's history. Turns after it are kept; conflicts with them leave
+/// the revert in progress for the user or an agent to resolve. A turn that
+/// committed to several repos is reverted in all of them or, on any failure,
+/// in none.
+pub async fn revert_process(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RevertProcessRequest>,
+) -> Result<ResponseJson<ApiResponse<(), GitOperationError>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let process = ExecutionProcess::find_by_id(pool, payload.process_id)
+        .await?
+        .filter(|process| process.session_id == session.id)
+        .ok_or_else(|| ApiError::BadRequest("Process not found in this session".to_string()))?;
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+        pool,
+        session.workspace_id,
+    )
+    .await?
+    {
+        return Err(ApiError::Conflict(
+            "Cannot revert while processes are running. Stop all processes first.".to_string(),
+        ));
+    }
+
+    let workspace = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let workspace_dir = PathBuf::from(container_ref);
+
+    let repo_states =
+        ExecutionProcessRepoState::find_by_execution_process_id(pool, process.id).await?;
+    let repos =
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+
+    let mut targets = Vec::new();
+    for repo_with_branch in repos {
+        let Some((before, after)) = repo_states
+            .iter()
+            .find(|state| state.repo_id == repo_with_branch.repo.id)
+            .and_then(|state| {
+                Some((
+                    state.before_head_commit.clone()?,
+                    state.after_head_commit.clone()?,
+                ))
+            })
+            .filter(|(before, after)| before != after)
+        else {
+            continue;
+        };
+        targets.push((repo_with_branch, before, after));
+    }
+    if targets.is_empty() {
+        return Err(ApiError::BadRequest(
+            "This turn made no commits to revert".to_string(),
+        ));
+    }
+
+    // Check every repo before touching any, so a turn spanning several repos
+    // is reverted in all of them or in none
+    let git = deployment.git();
+    let mut heads = Vec::with_capacity(targets.len());
+    for (repo_with_branch, _, _) in &targets {
+        let worktree_path = workspace_dir.join(&repo_with_branch.repo.name);
+        match git.detect_conflict_op(&worktree_path)? {
+            Some(ConflictOp::Rebase) => {
+                return Ok(ResponseJson(ApiResponse::error_with_data(
+                    GitOperationError::RebaseInProgress,
+                )));
+            }
+            Some(op) => {
+                return Err(ApiError::Conflict(format!(
+                    "A {op:?} is in progress in {}; resolve or abort it before reverting",
+                    repo_with_branch.repo.name
+                )));
+            }
+            None => {}
+        }
+        if !git.is_worktree_clean(&worktree_path)? {
+            return Err(ApiError::Conflict(format!(
+                "{} has uncommitted changes; commit or discard them before reverting",
+                repo_with_branch.repo.name
+            )));
+        }
+        heads.push((worktree_path, git.get_head_info(&worktree_path)?.oid));
+    }
+
+    for (index, (repo_with_branch, before, after)) in targets.iter().enumerate() {
+        let (worktree_path, _) = &heads[index];
+        let error = match git.revert_commits(worktree_path, before, after) {
+            Ok(head) => {
+                tracing::info!(
+                    "Reverted process {} in {} ({}..{}), now at {}",
+                    process.id,
+                    repo_with_branch.repo.name,
+                    before,
+                    after,
+                    head
+                );
+                continue;
+            }
+            Err(e) => e,
+        };
+
+        // A single repo keeps its conflicted revert for the user or an agent
+        // to resolve; with several, resolving one would leave the rest undone
+        if targets.len() == 1
+            && let GitServiceError::MergeConflicts {
+                message,
+                conflicted_files,
+            } = error
+        {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                GitOperationError::MergeConflicts {
+                    message,
+                    op: ConflictOp::Revert,
+                    conflicted_files,
+                    target_branch: repo_with_branch.target_branch.clone(),
+                },
+            )));
+        }
+
+        if let Err(e) = git.abort_conflicts(worktree_path) {
+            tracing::warn!(
+                "Failed to abort revert in {}: {}",
+                repo_with_branch.repo.name,
+                e
+            );
+        }
+        for (reverted_path, head) in &heads[..index] {
+            if let Err(e) = git.reset_worktree_to_commit(reverted_path, head, false) {
+                tracing::warn!(
+                    "Failed to roll back revert in {}: {}",
+                    reverted_path.display(),
+                    e
+                );
+            }
+        }
+        return match error {
+            GitServiceError::MergeConflicts {
+                conflicted_files, ..
+            } => Err(ApiError::Conflict(format!(
+                "Reverting this turn conflicts with later changes in {} ({}); nothing was reverted",
+                repo_with_branch.repo.name,
+                conflicted_files.join(", ")
+            ))),
+            e => Err(ApiError::GitService(e)),
+        };
+    }
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Switch the session to another coding agent. Running agents are stopped and
+/// the new one starts a fresh session, prompted with the conversation so far
+/// and the workspace's changes.
+pub async fn handoff_session(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<HandoffSessionRequest>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let from = ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::BadRequest("This session has no agent turns to hand off".to_string())
+        })?;
+    if from.executor == payload.executor_profile_id.executor {
+        return Err(ApiError::BadRequest(format!(
+            "This session already uses {}; send a follow-up instead",
+            from.executor
+        )));
+    }
+
+    let workspace = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+    deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+
+    let execution_process = deployment
+        .container()
+        .start_handoff(
+            &workspace,
+            &session,
+            &from,
+            &payload.executor_profile_id,
+            payload.prompt.as_deref(),
+        )
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
+/// Send a message to the agent while its turn is running, optionally
+/// interrupting what it is doing
+pub async fn steer_session(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SteerRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if payload.prompt.trim().is_empty() {
+        return Err(ApiError::BadRequest("The message is empty".to_string()));
+    }
+
+    let execution_process = ExecutionProcess::find_latest_by_session_and_run_reason(
+        &deployment.db().pool,
+        session.id,
+        &ExecutionProcessRunReason::CodingAgent,
+    )
+    .await?
+    .filter(|process| process.status == ExecutionProcessStatus::Running)
+    .ok_or_else(|| ApiError::Conflict("No agent is running in this session".to_string()))?;
+
+    deployment
+        .container()
+        .steer_execution(&execution_process, payload)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let session_id_router = Router::new()
         .route("/", get(get_session))
         .route("/follow-up", post(follow_up))
         .route("/reset", post(reset_process))
+        .route("/revert", post(revert_process))
+        .route("/handoff", post(handoff_session))
+        .route("/steer", post(steer_session))
         .route("/review", post(review::start_review))
         .layer(from_fn_with_state(
             deployment.clone(),
@@ -2,6 +2,8 @@ pub mod codex_setup;
 pub mod cursor_setup;
 pub mod gh_cli_setup;
 pub mod images;
+pub mod mcp_servers;
+pub mod output;
 pub mod pr;
 pub mod workspace_summary;
 
@@ -26,8 +28,8 @@ use db::models::{
     coding_agent_turn::CodingAgentTurn,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
-    repo::{Repo, RepoError},
-    session::{CreateSession, Session},
+    repo::{MergeStrategy, Repo, RepoError},
+    session::{CreateSession, Session, SessionError},
     task::{Task, TaskRelationships, TaskStatus},
     workspace::{CreateWorkspace, Workspace, WorkspaceError},
     workspace_repo::{CreateWorkspaceRepo, RepoWithTargetBranch, WorkspaceRepo},
@@ -39,6 +41,7 @@ use executors::{
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
     executors::{CodingAgent, ExecutorError},
+    oci,
     profile::{ExecutorConfigs, ExecutorProfileId},
 };
 use git::{ConflictOp, GitCliError, GitService, GitServiceError};
@@ -46,7 +49,7 @@ use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService, diff_stream, remote_client::RemoteClientError, remote_sync,
-    workspace_manager::WorkspaceManager,
+    stacked_branches::StackedBranches, workspace_manager::WorkspaceManager,
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
@@ -70,6 +73,14 @@ pub struct AbortConflictsRequest {
     pub repo_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ResolveConflictsRequest {
+    pub repo_id: Uuid,
+    /// Session to run the agent in; a new session is created when omitted
+    pub session_id: Option<Uuid>,
+    pub executor_profile_id: ExecutorProfileId,
+}
+
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct ContinueRebaseRequest {
     pub repo_id: Uuid,
@@ -461,6 +472,10 @@ async fn handle_workspaces_ws(
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct MergeTaskAttemptRequest {
     pub repo_id: Uuid,
+    /// Overrides the repo's merge strategy for this merge
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub strategy: Option<MergeStrategy>,
 }
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -512,7 +527,7 @@ pub async fn merge_task_attempt(
         .ensure_container_exists(&workspace)
         .await?;
     let workspace_path = Path::new(&container_ref);
-    let worktree_path = workspace_path.join(repo.name);
+    let worktree_path = workspace_path.join(&repo.name);
 
     let task = workspace
         .parent_task(pool)
@@ -531,13 +546,29 @@ pub async fn merge_task_attempt(
         commit_message.push_str(description);
     }
 
-    let merge_commit_id = deployment.git().merge_changes(
-        &repo.path,
-        &worktree_path,
-        &workspace.branch,
-        &workspace_repo.target_branch,
-        &commit_message,
-    )?;
+    let strategy = request.strategy.unwrap_or(repo.merge_strategy);
+    let merge_commit_id = match strategy {
+        MergeStrategy::Squash => deployment.git().merge_changes(
+            &repo.path,
+            &worktree_path,
+            &workspace.branch,
+            &workspace_repo.target_branch,
+            &commit_message,
+        )?,
+        MergeStrategy::MergeCommit => deployment.git().merge_changes_with_merge_commit(
+            &repo.path,
+            &worktree_path,
+            &workspace.branch,
+            &workspace_repo.target_branch,
+            &commit_message,
+        )?,
+        MergeStrategy::Rebase => deployment.git().rebase_and_fast_forward(
+            &repo.path,
+            &worktree_path,
+            &workspace.branch,
+            &workspace_repo.target_branch,
+        )?,
+    };
 
     Merge::create_direct(
         pool,
@@ -547,6 +578,26 @@ pub async fn merge_task_attempt(
         &merge_commit_id,
     )
     .await?;
+
+    // Workspaces stacked on this one now build on the target branch instead
+    if let Err(e) = StackedBranches::retarget_children_after_merge(
+        pool,
+        deployment.git(),
+        deployment.container().scheduler(),
+        &workspace,
+        &repo,
+        &workspace_repo.target_branch,
+        true,
+    )
+    .await
+    {
+        tracing::error!(
+            "Failed to retarget workspaces stacked on {}: {}",
+            workspace.id,
+            e
+        );
+    }
+
     Task::update_status(pool, task.id, TaskStatus::Done).await?;
     if !workspace.pinned
         && let Err(e) = deployment.container().archive_workspace(workspace.id).await
@@ -560,6 +611,7 @@ pub async fn merge_task_attempt(
             serde_json::json!({
                 "task_id": task.id.to_string(),
                 "workspace_id": workspace.id.to_string(),
+                "merge_strategy": strategy,
             }),
         )
         .await;
@@ -958,6 +1010,22 @@ pub async fn change_target_branch(
         )));
     };
 
+    // Targeting a workspace stacked on this one would make the stack a cycle
+    let stacked_on_self = new_target_branch == workspace.branch
+        || StackedBranches::descendants(pool, &workspace, repo_id)
+            .await?
+            .iter()
+            .any(|child| child.branch == new_target_branch);
+    if stacked_on_self {
+        return Ok(ResponseJson(ApiResponse::error(
+            format!(
+                "Branch '{}' is stacked on this workspace and can't be its target",
+                new_target_branch
+            )
+            .as_str(),
+        )));
+    }
+
     WorkspaceRepo::update_target_branch(pool, workspace.id, repo_id, &new_target_branch).await?;
 
     let status =
@@ -1211,6 +1279,22 @@ pub async fn rebase_task_attempt(
         };
     }
 
+    if let Err(e) = StackedBranches::restack_children(
+        pool,
+        deployment.git(),
+        deployment.container().scheduler(),
+        &workspace,
+        &repo,
+    )
+    .await
+    {
+        tracing::error!(
+            "Failed to rebase workspaces stacked on {}: {}",
+            workspace.id,
+            e
+        );
+    }
+
     deployment
         .track_if_analytics_allowed(
             "task_attempt_rebased",
@@ -1248,6 +1332,94 @@ pub async fn abort_conflicts_task_attempt(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+#[axum::debug_handler]
+pub async fn resolve_conflicts_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ResolveConflictsRequest>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, payload.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let executor_profile_id = payload.executor_profile_id;
+    let session = match payload.session_id {
+        Some(session_id) => {
+            let session = Session::find_by_id(pool, session_id)
+                .await?
+                .filter(|s| s.workspace_id == workspace.id)
+                .ok_or(SessionError::NotFound)?;
+
+            // Same rule as follow-ups: a session keeps the executor it started with
+            let expected_executor: Option<String> =
+                ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
+                    .await?
+                    .map(|profile| profile.executor.to_string())
+                    .or_else(|| session.executor.clone());
+            if let Some(expected) = expected_executor {
+                let actual = executor_profile_id.executor.to_string();
+                if expected != actual {
+                    return Err(ApiError::Session(SessionError::ExecutorMismatch {
+                        expected,
+                        actual,
+                    }));
+                }
+            }
+            if session.executor.is_none() {
+                Session::update_executor(
+                    pool,
+                    session.id,
+                    &executor_profile_id.executor.to_string(),
+                )
+                .await?;
+            }
+            session
+        }
+        None => {
+            Session::create(
+                pool,
+                &CreateSession {
+                    executor: Some(executor_profile_id.executor.to_string()),
+                },
+                Uuid::new_v4(),
+                workspace.id,
+            )
+            .await?
+        }
+    };
+
+    let execution_process = deployment
+        .container()
+        .start_conflict_resolution(
+            &workspace,
+            &session,
+            &repo,
+            &workspace_repo.target_branch,
+            executor_profile_id.clone(),
+        )
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "conflict_resolution_started",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": repo.id.to_string(),
+                "executor": executor_profile_id.executor.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
 #[axum::debug_handler]
 pub async fn continue_rebase_task_attempt(
     Extension(workspace): Extension<Workspace>,
@@ -1269,6 +1441,22 @@ pub async fn continue_rebase_task_attempt(
 
     deployment.git().continue_rebase(&worktree_path)?;
 
+    if let Err(e) = StackedBranches::restack_children(
+        pool,
+        deployment.git(),
+        deployment.container().scheduler(),
+        &workspace,
+        &repo,
+    )
+    .await
+    {
+        tracing::error!(
+            "Failed to rebase workspaces stacked on {}: {}",
+            workspace.id,
+            e
+        );
+    }
+
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
@@ -1358,6 +1546,7 @@ pub async fn start_dev_server(
                 language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::DevServer,
                 working_dir: Some(repo.name.clone()),
+                resource_limits: repo.resource_limits(),
             }),
             None,
         );
@@ -1778,6 +1967,10 @@ pub async fn delete_workspace(
         }
     }
 
+    // Terminal sessions would otherwise outlive their worktree
+    deployment.pty().kill_workspace_sessions(workspace.id);
+    let _ = tokio::fs::remove_dir_all(super::terminal::recordings_dir(workspace.id)).await;
+
     // Gather data needed for background cleanup
     let workspace_dir = workspace.container_ref.clone().map(PathBuf::from);
     let repositories = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
@@ -1835,6 +2028,7 @@ pub async fn delete_workspace(
     // Spawn background cleanup task for filesystem resources
     if let Some(workspace_dir) = workspace_dir {
         let workspace_id = workspace.id;
+        let remove_container = deployment.config().read().await.workspace_container.enabled;
         let delete_branches = query.delete_branches;
         let branch_name = workspace.branch.clone();
         let repo_paths: Vec<PathBuf> = repositories.iter().map(|r| r.path.clone()).collect();
@@ -1846,6 +2040,11 @@ pub async fn delete_workspace(
                 workspace_dir.display()
             );
 
+            // The container's bind mounts point into the worktree
+            if remove_container {
+                oci::remove_workspace_container(&workspace_id).await;
+            }
+
             if let Err(e) = WorkspaceManager::cleanup_workspace(&workspace_dir, &repositories).await
             {
                 tracing::error!(
@@ -1971,6 +2170,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
                 .route("/rebase", post(rebase_task_attempt))
                 .route("/rebase/continue", post(continue_rebase_task_attempt))
                 .route("/conflicts/abort", post(abort_conflicts_task_attempt))
+                .route("/conflicts/resolve", post(resolve_conflicts_task_attempt))
                 .route("/pr", post(pr::create_pr))
                 .route("/pr/attach", post(pr::attach_existing_pr))
                 .route("/pr/comments", get(pr::get_pr_comments))
@@ -1981,6 +2181,12 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
                 .route("/rename-branch", post(rename_branch))
                 .route("/repos", get(get_task_attempt_repos))
                 .route("/first-message", get(get_first_user_message))
+                .route("/output", get(output::get_output))
+                .route("/output/attach", post(output::attach_output))
+                .route(
+                    "/mcp-servers",
+                    get(mcp_servers::get_mcp_servers).put(mcp_servers::update_mcp_servers),
+                )
                 .route("/mark-seen", put(mark_seen))
                 .route("/link", post(link_workspace))
                 .layer(from_fn_with_state(
@@ -96,6 +96,7 @@ fi"#
             language: ScriptRequestLanguage::Bash,
             context: ScriptContext::ToolInstallScript,
             working_dir: None,
+            resource_limits: None,
         };
 
         // Auth script
@@ -111,6 +112,7 @@ gh auth login --web --git-protocol https --skip-ssh-key
             language: ScriptRequestLanguage::Bash,
             context: ScriptContext::ToolInstallScript,
             working_dir: None,
+            resource_limits: None,
         };
 
         // Chain them: install → auth
@@ -0,0 +1,52 @@
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    mcp_servers::{McpServers, WorkspaceMcpServers},
+    workspace::{Workspace, WorkspaceError},
+};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+async fn load(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+) -> Result<WorkspaceMcpServers, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
+    let overrides = McpServers::find_for_workspace(pool, workspace.id).await?;
+    let effective = McpServers::merge(
+        McpServers::find_for_project(pool, task.project_id).await?,
+        overrides.clone(),
+    );
+    Ok(WorkspaceMcpServers {
+        overrides,
+        effective,
+    })
+}
+
+pub async fn get_mcp_servers(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceMcpServers>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        load(&deployment, &workspace).await?,
+    )))
+}
+
+/// Replace the workspace's overrides of the project MCP servers. They apply to
+/// coding agents started afterwards.
+pub async fn update_mcp_servers(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<McpServers>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceMcpServers>>, ApiError> {
+    McpServers::validate(&payload.servers, true)?;
+    McpServers::set_for_workspace(&deployment.db().pool, workspace.id, &payload.servers).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        load(&deployment, &workspace).await?,
+    )))
+}
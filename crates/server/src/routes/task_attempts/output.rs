@@ -0,0 +1,215 @@
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessError, ExecutionProcessRunReason},
+    scratch::{DraftFollowUpData, Scratch, ScratchPayload, ScratchType, UpdateScratch},
+    session::{Session, SessionError},
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use local_deployment::pty::PtyError;
+use serde::{Deserialize, Serialize};
+use services::services::container::ContainerService;
+use ts_rs::TS;
+use utils::{response::ApiResponse, text::tail_lines};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+const DEFAULT_LINES: usize = 200;
+const MAX_LINES: usize = 2000;
+
+/// Which output to capture. Without an ID, the workspace's running dev server
+/// is used.
+#[derive(Debug, Default, Deserialize, Serialize, TS)]
+pub struct CaptureOutputQuery {
+    #[ts(optional)]
+    pub terminal_session_id: Option<Uuid>,
+    #[ts(optional)]
+    pub execution_process_id: Option<Uuid>,
+    /// Number of lines to keep from the end (default 200, at most 2000)
+    #[ts(optional)]
+    pub lines: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct CapturedOutput {
+    /// What produced the output, e.g. "terminal" or "dev server"
+    pub source: String,
+    /// The last lines of output as plain text
+    pub content: String,
+    /// The output wrapped in a code block, as it is added to a prompt
+    pub markdown: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct AttachOutputRequest {
+    /// Coding agent session whose next follow-up gets the output. Defaults to
+    /// the workspace's latest session.
+    #[ts(optional)]
+    pub session_id: Option<Uuid>,
+    #[serde(flatten)]
+    pub capture: CaptureOutputQuery,
+}
+
+fn source_label(run_reason: &ExecutionProcessRunReason) -> &'static str {
+    match run_reason {
+        ExecutionProcessRunReason::SetupScript => "setup script",
+        ExecutionProcessRunReason::CleanupScript => "cleanup script",
+        ExecutionProcessRunReason::ArchiveScript => "archive script",
+        ExecutionProcessRunReason::CodingAgent => "coding agent",
+        ExecutionProcessRunReason::DevServer => "dev server",
+    }
+}
+
+fn to_markdown(source: &str, content: &str) -> String {
+    // A fence longer than any backtick run in the output cannot be closed early
+    let longest_run = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!(
+        "Latest output of the {source} ({} lines):\n\n{fence}\n{content}\n{fence}",
+        content.lines().count()
+    )
+}
+
+async fn capture(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    query: &CaptureOutputQuery,
+) -> Result<CapturedOutput, ApiError> {
+    let pool = &deployment.db().pool;
+    let lines = query.lines.unwrap_or(DEFAULT_LINES).clamp(1, MAX_LINES);
+
+    let (source, raw) = match (query.terminal_session_id, query.execution_process_id) {
+        (Some(_), Some(_)) => {
+            return Err(ApiError::BadRequest(
+                "Pass either terminal_session_id or execution_process_id, not both".to_string(),
+            ));
+        }
+        (Some(id), None) => {
+            deployment
+                .pty()
+                .get_session(&id)
+                .filter(|session| session.workspace_id == workspace.id)
+                .ok_or(PtyError::SessionNotFound(id))?;
+            let scrollback = deployment.pty().scrollback(id)?;
+            (
+                "terminal",
+                String::from_utf8_lossy(&scrollback).into_owned(),
+            )
+        }
+        (None, id) => {
+            let process = match id {
+                Some(id) => ExecutionProcess::find_by_id(pool, id)
+                    .await?
+                    .ok_or(ExecutionProcessError::ExecutionProcessNotFound)?,
+                None => ExecutionProcess::find_running_dev_servers_by_workspace(pool, workspace.id)
+                    .await?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| {
+                        ApiError::BadRequest(
+                            "No dev server is running in this workspace".to_string(),
+                        )
+                    })?,
+            };
+            Session::find_by_id(pool, process.session_id)
+                .await?
+                .filter(|session| session.workspace_id == workspace.id)
+                .ok_or(ExecutionProcessError::ExecutionProcessNotFound)?;
+            let raw = deployment
+                .container()
+                .raw_log_text(&process.id)
+                .await
+                .unwrap_or_default();
+            (source_label(&process.run_reason), raw)
+        }
+    };
+
+    let content = tail_lines(&raw, lines);
+    if content.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "The {source} has no output yet"
+        )));
+    }
+    Ok(CapturedOutput {
+        source: source.to_string(),
+        markdown: to_markdown(source, &content),
+        content,
+    })
+}
+
+/// The last lines of a terminal session or execution process of the workspace
+pub async fn get_output(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<CaptureOutputQuery>,
+) -> Result<ResponseJson<ApiResponse<CapturedOutput>>, ApiError> {
+    let output = capture(&deployment, &workspace, &query).await?;
+    Ok(ResponseJson(ApiResponse::success(output)))
+}
+
+/// Append the last lines of a terminal session or execution process to the
+/// follow-up draft of a session, so they are sent with the next prompt
+pub async fn attach_output(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<AttachOutputRequest>,
+) -> Result<ResponseJson<ApiResponse<CapturedOutput>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let session = match payload.session_id {
+        Some(id) => Session::find_by_id(pool, id).await?,
+        None => Session::find_latest_by_workspace_id(pool, workspace.id).await?,
+    }
+    .filter(|session| session.workspace_id == workspace.id)
+    .ok_or(SessionError::NotFound)?;
+
+    if deployment.queued_message_service().has_queued(session.id) {
+        return Err(ApiError::BadRequest(
+            "Cannot edit the follow-up while a message is queued".to_string(),
+        ));
+    }
+
+    let output = capture(&deployment, &workspace, &payload.capture).await?;
+
+    let draft = Scratch::find_by_id(pool, session.id, &ScratchType::DraftFollowUp)
+        .await?
+        .and_then(|scratch| match scratch.payload {
+            ScratchPayload::DraftFollowUp(data) => Some(data),
+            _ => None,
+        });
+    let data = match draft {
+        Some(mut data) => {
+            data.message = if data.message.trim().is_empty() {
+                output.markdown.clone()
+            } else {
+                format!("{}\n\n{}", data.message.trim_end(), output.markdown)
+            };
+            data
+        }
+        None => DraftFollowUpData {
+            message: output.markdown.clone(),
+            executor_profile_id: ExecutionProcess::latest_executor_profile_for_session(
+                pool, session.id,
+            )
+            .await?
+            .ok_or_else(|| {
+                ApiError::BadRequest("The session has not run a coding agent yet".to_string())
+            })?,
+        },
+    };
+    Scratch::update(
+        pool,
+        session.id,
+        &ScratchType::DraftFollowUp,
+        &UpdateScratch {
+            payload: ScratchPayload::DraftFollowUp(data),
+        },
+    )
+    .await?;
+
+    Ok(ResponseJson(ApiResponse::success(output)))
+}
@@ -165,6 +165,7 @@ async fn trigger_pr_description_follow_up(
             prompt,
             executor_profile_id: executor_profile_id.clone(),
             working_dir,
+            fallback_profiles: None,
         })
     };
 
@@ -229,6 +230,24 @@ pub async fn create_pr(
             Err(_) => (push_remote.clone(), target_branch.clone()),
         };
 
+    // A stacked PR targets the branch of the workspace it is stacked on, which
+    // has to be on the remote before the PR can be opened against it
+    let base_workspace = match workspace_repo.base_workspace_id {
+        Some(id) if target_branch == workspace_repo.target_branch => {
+            Workspace::find_by_id(pool, id).await?
+        }
+        _ => None,
+    };
+    if base_workspace.is_some()
+        && let Err(e) = git.push_to_remote(&worktree_path, &target_branch, false)
+    {
+        tracing::warn!(
+            "Failed to push base branch '{}' of stacked PR: {}",
+            target_branch,
+            e
+        );
+    }
+
     match git.check_remote_branch_exists(&repo_path, &target_remote.url, &base_branch) {
         Ok(false) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -285,10 +304,28 @@ pub async fn create_pr(
 
     let provider = git_host.provider_kind();
 
+    let mut body = request.body.clone();
+    if let Some(base_workspace) = &base_workspace
+        && let Some(base_pr) =
+            Merge::find_by_workspace_and_repo_id(pool, base_workspace.id, repo.id)
+                .await?
+                .into_iter()
+                .find_map(|merge| match merge {
+                    Merge::Pr(pr) if matches!(pr.pr_info.status, MergeStatus::Open) => Some(pr),
+                    _ => None,
+                })
+    {
+        let note = format!("Stacked on {}", base_pr.pr_info.url);
+        body = Some(match body {
+            Some(body) if !body.trim().is_empty() => format!("{body}\n\n{note}"),
+            _ => note,
+        });
+    }
+
     // Create the PR
     let pr_request = CreatePrRequest {
         title: request.title.clone(),
-        body: request.body.clone(),
+        body,
         head_branch: workspace.branch.clone(),
         base_branch: base_branch.clone(),
         draft: request.draft,
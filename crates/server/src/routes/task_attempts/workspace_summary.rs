@@ -42,6 +42,8 @@ pub struct WorkspaceSummary {
     pub latest_process_status: Option<ExecutionProcessStatus>,
     /// Is a dev server currently running?
     pub has_running_dev_server: bool,
+    /// Port detected for the running dev server, if known
+    pub dev_server_port: Option<i64>,
     /// Does this workspace have unseen coding agent turns?
     pub has_unseen_turns: bool,
     /// PR status for this workspace (if any PR exists)
@@ -149,7 +151,8 @@ pub async fn get_workspace_summaries(
                 lines_removed: stats.map(|s| s.lines_removed),
                 latest_process_completed_at: latest.and_then(|p| p.completed_at),
                 latest_process_status: latest.map(|p| p.status.clone()),
-                has_running_dev_server: dev_server_workspaces.contains(&id),
+                has_running_dev_server: dev_server_workspaces.contains_key(&id),
+                dev_server_port: dev_server_workspaces.get(&id).copied().flatten(),
                 has_unseen_turns: unseen_workspaces.contains(&id),
                 pr_status: pr_statuses.get(&id).cloned(),
             }
@@ -13,6 +13,8 @@ use axum::{
     routing::{delete, get, post, put},
 };
 use db::models::{
+    agent_instructions::AgentInstructions,
+    execution_process::ExecutionProcessStatus,
     image::TaskImage,
     repo::{Repo, RepoError},
     task::{CreateTask, Task, TaskWithAttemptStatus, UpdateTask},
@@ -220,12 +222,16 @@ pub async fn create_task_and_start(
         .collect();
     WorkspaceRepo::create_many(&deployment.db().pool, workspace.id, &workspace_repos).await?;
 
-    let is_attempt_running = deployment
+    let started = deployment
         .container()
         .start_workspace(&workspace, payload.executor_profile_id.clone())
         .await
         .inspect_err(|err| tracing::error!("Failed to start task attempt: {}", err))
-        .is_ok();
+        .ok();
+    let is_queued = started
+        .as_ref()
+        .is_some_and(|process| process.status == ExecutionProcessStatus::Queued);
+    let is_attempt_running = started.is_some() && !is_queued;
     deployment
         .track_if_analytics_allowed(
             "task_attempt_started",
@@ -246,6 +252,7 @@ pub async fn create_task_and_start(
     Ok(ResponseJson(ApiResponse::success(TaskWithAttemptStatus {
         task,
         has_in_progress_attempt: is_attempt_running,
+        is_queued,
         last_attempt_failed: false,
         executor: payload.executor_profile_id.executor.to_string(),
     })))
@@ -395,6 +402,25 @@ pub async fn delete_task(
     Ok((StatusCode::ACCEPTED, ResponseJson(ApiResponse::success(()))))
 }
 
+pub async fn get_task_instructions(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<AgentInstructions>>, ApiError> {
+    let content = AgentInstructions::find_for_task(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(AgentInstructions {
+        content,
+    })))
+}
+
+pub async fn update_task_instructions(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<AgentInstructions>,
+) -> Result<ResponseJson<ApiResponse<AgentInstructions>>, ApiError> {
+    AgentInstructions::set_for_task(&deployment.db().pool, task.id, &payload.content).await?;
+    Ok(ResponseJson(ApiResponse::success(payload)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_actions_router = Router::new()
         .route("/", put(update_task))
@@ -402,6 +428,10 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let task_id_router = Router::new()
         .route("/", get(get_task))
+        .route(
+            "/instructions",
+            get(get_task_instructions).put(update_task_instructions),
+        )
         .merge(task_actions_router)
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
@@ -2,18 +2,26 @@ use std::path::PathBuf;
 
 use axum::{
     Router,
+    body::Body,
     extract::{
-        Query, State,
+        Path, Query, State,
         ws::{Message, WebSocket, WebSocketUpgrade},
     },
-    response::IntoResponse,
-    routing::get,
+    http::{StatusCode, header},
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::{delete, get},
 };
 use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use chrono::{DateTime, Utc};
 use db::models::{workspace::Workspace, workspace_repo::WorkspaceRepo};
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt};
+use local_deployment::pty::{PtyError, PtyEvent, PtySessionInfo};
 use serde::{Deserialize, Serialize};
+use tokio::{fs::File, sync::broadcast::error::RecvError};
+use tokio_util::io::ReaderStream;
+use ts_rs::TS;
+use utils::{assets::asset_dir, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError};
@@ -21,12 +29,56 @@ use crate::{DeploymentImpl, error::ApiError};
 #[derive(Debug, Deserialize)]
 pub struct TerminalQuery {
     pub workspace_id: Uuid,
+    /// Reattach to this session instead of starting a new shell
+    pub session_id: Option<Uuid>,
     #[serde(default = "default_cols")]
     pub cols: u16,
     #[serde(default = "default_rows")]
     pub rows: u16,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct WorkspaceQuery {
+    pub workspace_id: Uuid,
+}
+
+/// A running terminal session of a workspace
+#[derive(Debug, Serialize, TS)]
+pub struct TerminalSession {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub cwd: String,
+    pub created_at: DateTime<Utc>,
+    pub recorded: bool,
+}
+
+impl From<PtySessionInfo> for TerminalSession {
+    fn from(info: PtySessionInfo) -> Self {
+        Self {
+            id: info.id,
+            workspace_id: info.workspace_id,
+            cwd: info.working_dir.to_string_lossy().to_string(),
+            created_at: info.created_at.into(),
+            recorded: info.recording.is_some(),
+        }
+    }
+}
+
+/// A recorded terminal session, kept after the session has ended
+#[derive(Debug, Serialize, TS)]
+pub struct TerminalRecording {
+    pub session_id: Uuid,
+    pub size_bytes: u64,
+    pub modified_at: DateTime<Utc>,
+}
+
+/// Where the recorded terminal sessions of a workspace are kept
+pub fn recordings_dir(workspace_id: Uuid) -> PathBuf {
+    asset_dir()
+        .join("terminal-recordings")
+        .join(workspace_id.to_string())
+}
+
 fn default_cols() -> u16 {
     80
 }
@@ -45,8 +97,17 @@ enum TerminalCommand {
 #[derive(Debug, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 enum TerminalMessage {
-    Output { data: String },
-    Error { message: String },
+    /// Sent first; the client reconnects with this ID to get the same shell back
+    Attached {
+        session_id: Uuid,
+    },
+    Output {
+        data: String,
+    },
+    Exit,
+    Error {
+        message: String,
+    },
 }
 
 pub async fn terminal_ws(
@@ -87,50 +148,124 @@ pub async fn terminal_ws(
         }
     }
 
+    // Reattach only to a session of this workspace; a session that has since
+    // exited is replaced by a new shell
+    let existing_session = query.session_id.filter(|session_id| {
+        deployment
+            .pty()
+            .get_session(session_id)
+            .is_some_and(|info| info.workspace_id == query.workspace_id)
+    });
+
     Ok(ws.on_upgrade(move |socket| {
-        handle_terminal_ws(socket, deployment, working_dir, query.cols, query.rows)
+        handle_terminal_ws(
+            socket,
+            deployment,
+            query.workspace_id,
+            existing_session,
+            working_dir,
+            query.cols,
+            query.rows,
+        )
     }))
 }
 
 async fn handle_terminal_ws(
     socket: WebSocket,
     deployment: DeploymentImpl,
+    workspace_id: Uuid,
+    existing_session: Option<Uuid>,
     working_dir: PathBuf,
     cols: u16,
     rows: u16,
 ) {
-    let (session_id, mut output_rx) = match deployment
-        .pty()
-        .create_session(working_dir, cols, rows)
-        .await
-    {
-        Ok(result) => result,
+    let session_id = match existing_session {
+        Some(session_id) => session_id,
+        None => {
+            let recordings_dir = deployment
+                .config()
+                .read()
+                .await
+                .terminal
+                .record_sessions
+                .then(|| recordings_dir(workspace_id));
+            match deployment
+                .pty()
+                .create_session(workspace_id, working_dir, cols, rows, recordings_dir)
+                .await
+            {
+                Ok(session_id) => session_id,
+                Err(e) => {
+                    tracing::error!("Failed to create PTY session: {}", e);
+                    let _ = send_error(socket, &e.to_string()).await;
+                    return;
+                }
+            }
+        }
+    };
+    let attachment = match deployment.pty().attach(session_id) {
+        Ok(attachment) => attachment,
         Err(e) => {
-            tracing::error!("Failed to create PTY session: {}", e);
             let _ = send_error(socket, &e.to_string()).await;
             return;
         }
     };
+    if existing_session.is_some() {
+        // The client may have a different size than whoever attached last
+        let _ = deployment.pty().resize(session_id, cols, rows).await;
+    }
 
     let (mut ws_sender, mut ws_receiver) = socket.split();
 
     let pty_service = deployment.pty().clone();
     let session_id_for_input = session_id;
 
+    let output_pty = pty_service.clone();
     let output_task = tokio::spawn(async move {
-        while let Some(data) = output_rx.recv().await {
-            let msg = TerminalMessage::Output {
-                data: BASE64.encode(&data),
-            };
-            let json = match serde_json::to_string(&msg) {
-                Ok(j) => j,
-                Err(_) => continue,
-            };
-            if ws_sender.send(Message::Text(json.into())).await.is_err() {
-                break;
+        let mut events = attachment.events;
+        let mut pending = attached_messages(session_id, attachment.scrollback);
+        loop {
+            for msg in pending.drain(..) {
+                let json = match serde_json::to_string(&msg) {
+                    Ok(j) => j,
+                    Err(_) => continue,
+                };
+                if ws_sender.send(Message::Text(json.into())).await.is_err() {
+                    return ws_sender;
+                }
+            }
+            match events.recv().await {
+                Ok(PtyEvent::Output(data)) => pending.push(TerminalMessage::Output {
+                    data: BASE64.encode(&data),
+                }),
+                Ok(PtyEvent::Exited) | Err(RecvError::Closed) => {
+                    let json = serde_json::to_string(&TerminalMessage::Exit).unwrap_or_default();
+                    let _ = ws_sender.send(Message::Text(json.into())).await;
+                    return ws_sender;
+                }
+                // Output was dropped while this client was behind; attach again
+                // so the client redraws from the scrollback
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(
+                        "Terminal client of session {} fell {} chunks behind, resyncing",
+                        session_id,
+                        skipped
+                    );
+                    match output_pty.attach(session_id) {
+                        Ok(attachment) => {
+                            events = attachment.events;
+                            pending = attached_messages(session_id, attachment.scrollback);
+                        }
+                        Err(_) => {
+                            let json =
+                                serde_json::to_string(&TerminalMessage::Exit).unwrap_or_default();
+                            let _ = ws_sender.send(Message::Text(json.into())).await;
+                            return ws_sender;
+                        }
+                    }
+                }
             }
         }
-        ws_sender
     });
 
     while let Some(Ok(msg)) = ws_receiver.next().await {
@@ -154,10 +289,100 @@ async fn handle_terminal_ws(
         }
     }
 
-    let _ = deployment.pty().close_session(session_id).await;
+    // Detach only: the shell keeps running until it exits or is killed
     output_task.abort();
 }
 
+/// List the running terminal sessions of a workspace
+pub async fn list_sessions(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<WorkspaceQuery>,
+) -> ResponseJson<ApiResponse<Vec<TerminalSession>>> {
+    let sessions = deployment
+        .pty()
+        .list_sessions(query.workspace_id)
+        .into_iter()
+        .map(TerminalSession::from)
+        .collect();
+    ResponseJson(ApiResponse::success(sessions))
+}
+
+/// Kill a terminal session's shell and everything running in it
+pub async fn kill_session(
+    Path(session_id): Path<Uuid>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    deployment.pty().kill_session(session_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// List the recorded terminal sessions of a workspace, newest first
+pub async fn list_recordings(
+    Query(query): Query<WorkspaceQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TerminalRecording>>>, ApiError> {
+    let mut recordings = Vec::new();
+    let mut entries = match tokio::fs::read_dir(recordings_dir(query.workspace_id)).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(ResponseJson(ApiResponse::success(recordings)));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let Some(session_id) = path
+            .file_stem()
+            .filter(|_| path.extension().is_some_and(|ext| ext == "cast"))
+            .and_then(|stem| Uuid::parse_str(&stem.to_string_lossy()).ok())
+        else {
+            continue;
+        };
+        let metadata = entry.metadata().await?;
+        recordings.push(TerminalRecording {
+            session_id,
+            size_bytes: metadata.len(),
+            modified_at: metadata.modified()?.into(),
+        });
+    }
+    recordings.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
+    Ok(ResponseJson(ApiResponse::success(recordings)))
+}
+
+/// Download a session recording as an asciicast v2 file
+pub async fn download_recording(
+    Path(session_id): Path<Uuid>,
+    Query(query): Query<WorkspaceQuery>,
+) -> Result<Response, ApiError> {
+    let path = recordings_dir(query.workspace_id).join(format!("{session_id}.cast"));
+    let file = File::open(&path)
+        .await
+        .map_err(|_| ApiError::Pty(PtyError::SessionNotFound(session_id)))?;
+    let metadata = file.metadata().await?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/x-asciicast")
+        .header(header::CONTENT_LENGTH, metadata.len())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{session_id}.cast\""),
+        )
+        .body(Body::from_stream(ReaderStream::new(file)))
+        .map_err(|e| ApiError::BadRequest(e.to_string()))
+}
+
+/// Messages that (re)start a client's view of a session: `Attached`, which
+/// clears the screen, then the scrollback
+fn attached_messages(session_id: Uuid, scrollback: Vec<u8>) -> Vec<TerminalMessage> {
+    let mut messages = vec![TerminalMessage::Attached { session_id }];
+    if !scrollback.is_empty() {
+        messages.push(TerminalMessage::Output {
+            data: BASE64.encode(&scrollback),
+        });
+    }
+    messages
+}
+
 async fn send_error(mut socket: WebSocket, message: &str) -> Result<(), axum::Error> {
     let msg = TerminalMessage::Error {
         message: message.to_string(),
@@ -169,5 +394,10 @@ async fn send_error(mut socket: WebSocket, message: &str) -> Result<(), axum::Er
 }
 
 pub fn router() -> Router<DeploymentImpl> {
-    Router::new().route("/terminal/ws", get(terminal_ws))
+    Router::new()
+        .route("/terminal/ws", get(terminal_ws))
+        .route("/terminal/sessions", get(list_sessions))
+        .route("/terminal/sessions/{session_id}", delete(kill_session))
+        .route("/terminal/recordings", get(list_recordings))
+        .route("/terminal/recordings/{session_id}", get(download_recording))
 }
@@ -21,7 +21,7 @@ use executors::{
 use futures::future::{BoxFuture, FutureExt, Shared};
 use sqlx::{Error as SqlxError, SqlitePool};
 use thiserror::Error;
-use tokio::sync::{RwLock, oneshot};
+use tokio::sync::{RwLock, broadcast::error::RecvError, oneshot};
 use utils::{
     approvals::{ApprovalRequest, ApprovalResponse, ApprovalStatus},
     log_msg::LogMsg,
@@ -29,6 +29,9 @@ use utils::{
 };
 use uuid::Uuid;
 
+/// How long an approval request waits for its tool call to be logged
+const TOOL_USE_ENTRY_WAIT: StdDuration = StdDuration::from_secs(2);
+
 #[derive(Debug)]
 struct PendingApproval {
     entry_index: usize,
@@ -91,7 +94,8 @@ impl Approvals {
 
         if let Some(store) = self.msg_store_by_id(&request.execution_process_id).await {
             // Find the matching tool use entry by name and input
-            let matching_tool = find_matching_tool_use(store.clone(), &request.tool_call_id);
+            let matching_tool =
+                wait_for_matching_tool_use(store.clone(), &request.tool_call_id).await;
 
             if let Some((idx, matching_tool)) = matching_tool {
                 let approval_entry = matching_tool
@@ -320,6 +324,37 @@ pub(crate) async fn ensure_task_in_review(pool: &SqlitePool, execution_process_i
     }
 }
 
+/// Like [`find_matching_tool_use`], but waits briefly for an entry that is not
+/// there yet. Approval hooks log the tool call just before asking, so the
+/// normalizer may not have added its entry by the time the request arrives.
+async fn wait_for_matching_tool_use(
+    store: Arc<MsgStore>,
+    tool_call_id: &str,
+) -> Option<(usize, NormalizedEntry)> {
+    // Subscribe before checking the history so an entry added in between is seen
+    let mut rx = store.get_receiver();
+    if let Some(found) = find_matching_tool_use(store.clone(), tool_call_id) {
+        return Some(found);
+    }
+
+    tokio::time::timeout(TOOL_USE_ENTRY_WAIT, async {
+        loop {
+            match rx.recv().await {
+                Ok(LogMsg::JsonPatch(_)) | Err(RecvError::Lagged(_)) => {
+                    if let Some(found) = find_matching_tool_use(store.clone(), tool_call_id) {
+                        return Some(found);
+                    }
+                }
+                Ok(_) => {}
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
 /// Find a matching tool use entry that hasn't been assigned to an approval yet
 /// Matches by tool call id from tool metadata
 fn find_matching_tool_use(
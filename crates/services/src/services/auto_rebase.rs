@@ -0,0 +1,279 @@
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
+
+use db::{
+    DBService,
+    models::{
+        execution_process::ExecutionProcess,
+        repo::Repo,
+        session::{CreateSession, Session, SessionError},
+        workspace::{Workspace, WorkspaceError},
+        workspace_repo::WorkspaceRepo,
+    },
+};
+use git::GitServiceError;
+use sqlx::error::Error as SqlxError;
+use thiserror::Error;
+use tokio::{sync::RwLock, time::interval};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::services::{
+    config::{AutoRebaseConfig, Config},
+    container::{ContainerError, ContainerService},
+    stacked_branches::StackedBranches,
+};
+
+#[derive(Debug, Error)]
+enum AutoRebaseError {
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    Sqlx(#[from] SqlxError),
+}
+
+/// Service that rebases idle workspaces onto their target branch when the
+/// target gets new commits. Only runs when enabled in the config.
+pub struct AutoRebaseService<C: ContainerService> {
+    db: DBService,
+    config: Arc<RwLock<Config>>,
+    poll_interval: Duration,
+    container: C,
+    /// Target commit a rebase last failed against (for reasons other than
+    /// conflicts), keyed by workspace and repo. Retried once the target moves.
+    failed: HashMap<(Uuid, Uuid), String>,
+}
+
+impl<C: ContainerService + Send + Sync + 'static> AutoRebaseService<C> {
+    pub async fn spawn(
+        db: DBService,
+        config: Arc<RwLock<Config>>,
+        container: C,
+    ) -> tokio::task::JoinHandle<()> {
+        let mut service = Self {
+            db,
+            config,
+            poll_interval: Duration::from_secs(60), // Check every minute
+            container,
+            failed: HashMap::new(),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&mut self) {
+        info!(
+            "Starting auto-rebase service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            let settings = self.config.read().await.auto_rebase.clone();
+            if !settings.enabled {
+                continue;
+            }
+            if let Err(e) = self.check_all_workspaces(&settings).await {
+                error!("Error checking workspaces for auto-rebase: {}", e);
+            }
+        }
+    }
+
+    async fn check_all_workspaces(
+        &mut self,
+        settings: &AutoRebaseConfig,
+    ) -> Result<(), AutoRebaseError> {
+        let workspaces = Workspace::fetch_all(&self.db.pool, None).await?;
+
+        for workspace in workspaces
+            .into_iter()
+            .filter(|workspace| !workspace.archived && workspace.container_ref.is_some())
+        {
+            if let Err(e) = self.check_workspace(&workspace, settings).await {
+                error!("Error auto-rebasing workspace {}: {}", workspace.id, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Rebase every repo of an idle workspace whose target branch is ahead
+    async fn check_workspace(
+        &mut self,
+        workspace: &Workspace,
+        settings: &AutoRebaseConfig,
+    ) -> Result<(), AutoRebaseError> {
+        let pool = &self.db.pool;
+        let Some(container_ref) = workspace.container_ref.as_ref() else {
+            return Ok(());
+        };
+        if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+            .await?
+        {
+            return Ok(());
+        }
+
+        let repos =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+        // Remote targets only move once fetched
+        for repo_with_branch in &repos {
+            if let Err(e) = self.container.git().fetch_if_remote_branch(
+                &repo_with_branch.repo.path,
+                &repo_with_branch.target_branch,
+            ) {
+                warn!(
+                    "Failed to fetch {} for auto-rebase of workspace {}: {}",
+                    repo_with_branch.target_branch, workspace.id, e
+                );
+            }
+        }
+
+        // Starting an execution takes the same lock, so once the workspace is
+        // seen idle under it no agent can start until the rebase is done
+        let workspace_guard = self
+            .container
+            .scheduler()
+            .lock_workspace(workspace.id)
+            .await;
+        if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+            .await?
+        {
+            return Ok(());
+        }
+
+        for repo_with_branch in repos {
+            let repo = &repo_with_branch.repo;
+            let target_branch = &repo_with_branch.target_branch;
+            let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+            if !worktree_path.exists() {
+                continue;
+            }
+
+            let git = self.container.git();
+            // A stopped rebase or merge is waiting on the user or an agent
+            if git.detect_conflict_op(&worktree_path)?.is_some() {
+                continue;
+            }
+            let key = (workspace.id, repo.id);
+            let target_oid = git.get_branch_oid(&repo.path, target_branch)?;
+            if self.failed.get(&key) == Some(&target_oid) {
+                continue;
+            }
+            let (_, behind) =
+                git.get_branch_status(&repo.path, &workspace.branch, target_branch)?;
+            if behind == 0 {
+                continue;
+            }
+            if !git.is_worktree_clean(&worktree_path)? {
+                debug!(
+                    "Skipping auto-rebase of workspace {} ({}): uncommitted changes",
+                    workspace.id, repo.name
+                );
+                continue;
+            }
+
+            match git.rebase_branch(
+                &repo.path,
+                &worktree_path,
+                target_branch,
+                target_branch,
+                &workspace.branch,
+            ) {
+                Ok(head) => {
+                    info!(
+                        "Rebased workspace {} ({}) onto {} ({} new commits), now at {}",
+                        workspace.id, repo.name, target_branch, behind, head
+                    );
+                    self.failed.remove(&key);
+                    StackedBranches::restack_children(
+                        pool,
+                        git,
+                        self.container.scheduler(),
+                        workspace,
+                        repo,
+                    )
+                    .await?;
+                }
+                Err(GitServiceError::MergeConflicts {
+                    conflicted_files, ..
+                }) => {
+                    info!(
+                        "Auto-rebase of workspace {} ({}) onto {} stopped with conflicts in {:?}",
+                        workspace.id, repo.name, target_branch, conflicted_files
+                    );
+                    // Without an agent the rebase stays stopped, which the
+                    // workspace shows as conflicts to resolve or abort
+                    if settings.resolve_conflicts_with_agent {
+                        // Starting the agent takes the workspace lock itself
+                        drop(workspace_guard);
+                        self.start_agent(workspace, repo, target_branch).await?;
+                        // The agent now owns the workspace; the remaining
+                        // repos are picked up once it is idle again
+                        return Ok(());
+                    }
+                }
+                Err(e) => {
+                    warn!(
+                        "Auto-rebase of workspace {} ({}) onto {} failed: {}",
+                        workspace.id, repo.name, target_branch, e
+                    );
+                    self.failed.insert(key, target_oid);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Hand the conflicts to the agent of the most recent session, or to the
+    /// default agent in a new session when none has run yet
+    async fn start_agent(
+        &self,
+        workspace: &Workspace,
+        repo: &Repo,
+        target_branch: &str,
+    ) -> Result<(), AutoRebaseError> {
+        let pool = &self.db.pool;
+        let latest = match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+            Some(session) => {
+                ExecutionProcess::latest_executor_profile_for_session(pool, session.id)
+                    .await?
+                    .map(|profile| (session, profile))
+            }
+            None => None,
+        };
+        let (session, executor_profile_id) = match latest {
+            Some(latest) => latest,
+            None => {
+                let executor_profile_id = self.config.read().await.executor_profile.clone();
+                let session = Session::create(
+                    pool,
+                    &CreateSession {
+                        executor: Some(executor_profile_id.executor.to_string()),
+                    },
+                    Uuid::new_v4(),
+                    workspace.id,
+                )
+                .await?;
+                (session, executor_profile_id)
+            }
+        };
+
+        self.container
+            .start_conflict_resolution(
+                workspace,
+                &session,
+                repo,
+                target_branch,
+                executor_profile_id,
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+use db::models::repo::Repo;
+use uuid::Uuid;
+
+/// Used when a repo has no template of its own
+pub const DEFAULT_TEMPLATE: &str = "{summary}";
+
+const CONVENTIONAL_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// Values for the placeholders of a commit message template
+#[derive(Debug, Clone)]
+pub struct CommitMessageVars<'a> {
+    pub summary: &'a str,
+    pub task_id: Uuid,
+    pub task_title: &'a str,
+    pub workspace_id: Uuid,
+    pub branch: &'a str,
+    pub executor: &'a str,
+    pub turn: i64,
+}
+
+/// Render `template`, replacing `{summary}`, `{task_id}`, `{task_title}`,
+/// `{workspace_id}`, `{branch}`, `{executor}` and `{turn}`. Unknown
+/// placeholders are left as they are.
+pub fn render_template(template: &str, vars: &CommitMessageVars) -> String {
+    let rendered = template
+        .replace("{task_id}", &vars.task_id.to_string())
+        .replace("{task_title}", vars.task_title)
+        .replace("{workspace_id}", &vars.workspace_id.to_string())
+        .replace("{branch}", vars.branch)
+        .replace("{executor}", vars.executor)
+        .replace("{turn}", &vars.turn.to_string())
+        // Last, so placeholders inside the agent's summary stay untouched
+        .replace("{summary}", vars.summary.trim());
+    let rendered = rendered.trim();
+    if rendered.is_empty() {
+        vars.summary.trim().to_string()
+    } else {
+        rendered.to_string()
+    }
+}
+
+fn is_conventional(subject: &str) -> bool {
+    let Some((prefix, description)) = subject.split_once(": ") else {
+        return false;
+    };
+    let prefix = prefix.strip_suffix('!').unwrap_or(prefix);
+    let typ = match prefix.split_once('(') {
+        Some((typ, scope)) if scope.ends_with(')') && scope.len() > 1 => typ,
+        Some(_) => return false,
+        None => prefix,
+    };
+    !description.trim().is_empty() && !typ.is_empty() && typ.chars().all(|c| c.is_ascii_lowercase())
+}
+
+fn infer_type(subject: &str) -> &'static str {
+    let first_word = subject
+        .split(|c: char| !c.is_alphanumeric())
+        .find(|word| !word.is_empty())
+        .unwrap_or_default()
+        .to_lowercase();
+    match first_word.as_str() {
+        "fix" | "fixes" | "fixed" | "resolve" | "resolves" | "resolved" => "fix",
+        "add" | "adds" | "added" | "implement" | "implements" | "implemented" | "support"
+        | "introduce" | "introduces" | "introduced" | "create" | "creates" | "created" => "feat",
+        "refactor" | "refactors" | "refactored" | "restructure" | "simplify" => "refactor",
+        "document" | "documents" | "documented" | "docs" => "docs",
+        "test" | "tests" | "tested" => "test",
+        "revert" | "reverts" | "reverted" => "revert",
+        "optimize" | "optimise" | "speed" => "perf",
+        _ => "chore",
+    }
+}
+
+/// Rewrite the subject line of `message` as a Conventional Commits subject
+/// (`type: description`). Subjects that already follow the format are kept;
+/// otherwise the type is inferred from the first word.
+pub fn to_conventional(message: &str) -> String {
+    let message = message.trim();
+    let (subject, rest) = match message.split_once('\n') {
+        Some((subject, rest)) => (subject.trim(), Some(rest)),
+        None => (message, None),
+    };
+    if subject.is_empty() || is_conventional(subject) {
+        return message.to_string();
+    }
+    if let Some(typ) = CONVENTIONAL_TYPES
+        .iter()
+        .find(|typ| subject.to_lowercase().starts_with(&format!("{typ}: ")))
+    {
+        // Right type, wrong case
+        let description = &subject[typ.len() + 2..];
+        return join_subject(&format!("{typ}: {description}"), rest);
+    }
+
+    let description = subject.trim_end_matches('.');
+    let mut chars = description.chars();
+    let description = match chars.next() {
+        // Keep acronyms such as "API" as they are
+        Some(first) if !chars.next().is_some_and(|c| c.is_uppercase()) => first
+            .to_lowercase()
+            .chain(description.chars().skip(1))
+            .collect(),
+        _ => description.to_string(),
+    };
+    join_subject(&format!("{}: {}", infer_type(subject), description), rest)
+}
+
+fn join_subject(subject: &str, rest: Option<&str>) -> String {
+    match rest {
+        Some(rest) => format!("{subject}\n{rest}"),
+        None => subject.to_string(),
+    }
+}
+
+/// Append a `Co-authored-by` trailer for each of `co_authors` that the
+/// message does not already credit
+pub fn append_co_authors(message: &str, co_authors: &[String]) -> String {
+    let mut message = message.trim_end().to_string();
+    let existing = message.to_lowercase();
+    let trailers: Vec<String> = co_authors
+        .iter()
+        .map(|co_author| co_author.trim())
+        .filter(|co_author| !co_author.is_empty())
+        .map(|co_author| format!("Co-authored-by: {co_author}"))
+        .filter(|trailer| !existing.contains(&trailer.to_lowercase()))
+        .collect();
+    if trailers.is_empty() {
+        return message;
+    }
+
+    // Join an existing trailer block instead of starting a second one
+    let last_paragraph = message.rsplit("\n\n").next().unwrap_or_default();
+    let ends_in_trailers = message.contains("\n\n")
+        && last_paragraph.lines().all(|line| {
+            line.split_once(": ")
+                .is_some_and(|(key, _)| !key.contains(' '))
+        });
+    message.push_str(if ends_in_trailers { "\n" } else { "\n\n" });
+    message.push_str(&trailers.join("\n"));
+    message
+}
+
+/// Word the commit of an agent turn in `repo` following the repo's template
+/// and Conventional Commits setting, crediting `co_authors`
+pub fn format_commit_message(
+    repo: &Repo,
+    vars: &CommitMessageVars,
+    co_authors: &[String],
+) -> String {
+    let template = repo
+        .commit_message_template
+        .as_deref()
+        .filter(|template| !template.trim().is_empty())
+        .unwrap_or(DEFAULT_TEMPLATE);
+    let mut message = render_template(template, vars);
+    if repo.conventional_commits {
+        message = to_conventional(&message);
+    }
+    append_co_authors(&message, co_authors)
+}
+
+/// Prompt asking the agent of a finished turn to describe its own changes as a
+/// commit message. Its reply becomes the turn's summary, which is then
+/// committed like any other.
+pub fn build_commit_message_prompt(repo_names: &[String], conventional: bool) -> String {
+    let mut prompt = format!(
+        "Write a git commit message for the uncommitted changes in {}. Inspect them with `git status` and `git diff HEAD`.",
+        repo_names
+            .iter()
+            .map(|name| format!("'{name}'"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    prompt.push_str(
+        "\n\nUse an imperative subject line of at most 72 characters, then a blank line and a short body explaining what changed and why if the subject alone is not enough.",
+    );
+    if conventional {
+        prompt.push_str(
+            " Format the subject as a Conventional Commit, e.g. `feat(api): add pagination`.",
+        );
+    }
+    prompt.push_str(
+        "\n\nDo not edit, stage or commit any files. Reply with the commit message only, without code fences or commentary.",
+    );
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(summary: &str) -> CommitMessageVars<'_> {
+        CommitMessageVars {
+            summary,
+            task_id: Uuid::nil(),
+            task_title: "Add login page",
+            workspace_id: Uuid::nil(),
+            branch: "vk/1234-add-login",
+            executor: "CLAUDE_CODE",
+            turn: 3,
+        }
+    }
+
+    #[test]
+    fn template_placeholders_are_replaced() {
+        let message = render_template(
+            "{summary}\n\nTask: {task_title} ({branch}), turn {turn} by {executor}",
+            &vars("Add the login form"),
+        );
+        assert_eq!(
+            message,
+            "Add the login form\n\nTask: Add login page (vk/1234-add-login), turn 3 by CLAUDE_CODE"
+        );
+        assert_eq!(
+            render_template("{summary}", &vars("Mention {turn} literally")),
+            "Mention {turn} literally"
+        );
+    }
+
+    #[test]
+    fn conventional_subjects_are_inferred_and_kept() {
+        assert_eq!(
+            to_conventional("Fixed the crash on empty input.\n\nDetails"),
+            "fix: fixed the crash on empty input\n\nDetails"
+        );
+        assert_eq!(to_conventional("Add login form"), "feat: add login form");
+        assert_eq!(to_conventional("Update README"), "chore: update README");
+        assert_eq!(to_conventional("API cleanup"), "chore: API cleanup");
+        assert_eq!(
+            to_conventional("feat(auth)!: drop sessions"),
+            "feat(auth)!: drop sessions"
+        );
+        assert_eq!(to_conventional("Docs: typo"), "docs: typo");
+    }
+
+    #[test]
+    fn co_authors_join_existing_trailers_once() {
+        let co_authors = vec!["Bot <bot@example.com>".to_string()];
+        assert_eq!(
+            append_co_authors("feat: x", &co_authors),
+            "feat: x\n\nCo-authored-by: Bot <bot@example.com>"
+        );
+        assert_eq!(
+            append_co_authors("feat: x\n\nSigned-off-by: A <a@example.com>", &co_authors),
+            "feat: x\n\nSigned-off-by: A <a@example.com>\nCo-authored-by: Bot <bot@example.com>"
+        );
+        let credited = "feat: x\n\nCo-authored-by: Bot <bot@example.com>";
+        assert_eq!(append_co_authors(credited, &co_authors), credited);
+    }
+}
@@ -42,6 +42,10 @@ pub type GitHubConfig = versions::v8::GitHubConfig;
 pub type UiLanguage = versions::v8::UiLanguage;
 pub type ShowcaseState = versions::v8::ShowcaseState;
 pub type SendMessageShortcut = versions::v8::SendMessageShortcut;
+pub type ConcurrencyConfig = versions::v8::ConcurrencyConfig;
+pub type AutoRebaseConfig = versions::v8::AutoRebaseConfig;
+pub type TerminalConfig = versions::v8::TerminalConfig;
+pub type PortAllocationConfig = versions::v8::PortAllocationConfig;
 
 /// Will always return config, trying old schemas or eventually returning default
 pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
@@ -1,5 +1,9 @@
+use std::collections::HashMap;
+
 use anyhow::Error;
-use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use executors::{
+    executors::BaseCodingAgent, oci::WorkspaceContainerConfig, profile::ExecutorProfileId,
+};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 pub use v7::{
@@ -21,6 +25,18 @@ fn default_commit_reminder_enabled() -> bool {
     true
 }
 
+fn default_port_range_start() -> u16 {
+    4100
+}
+
+fn default_port_range_end() -> u16 {
+    4999
+}
+
+fn default_ports_per_workspace() -> u16 {
+    1
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
 pub enum SendMessageShortcut {
     #[default]
@@ -28,6 +44,73 @@ pub enum SendMessageShortcut {
     Enter,
 }
 
+/// Caps on how many coding agents run at once. Starts beyond a cap wait in a
+/// FIFO queue until a running agent finishes.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct ConcurrencyConfig {
+    /// Maximum coding agents running across all workspaces; unlimited when unset
+    #[serde(default)]
+    pub max_running_agents: Option<u32>,
+    /// Maximum running agents per executor, e.g. `{ "CLAUDE_CODE": 2 }`
+    #[serde(default)]
+    pub per_executor: HashMap<BaseCodingAgent, u32>,
+}
+
+impl ConcurrencyConfig {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_running_agents.is_none() && self.per_executor.is_empty()
+    }
+}
+
+/// Background rebasing of idle workspaces onto their target branch
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct AutoRebaseConfig {
+    /// Rebase idle, clean workspaces when their target branch gets new commits
+    #[serde(default)]
+    pub enabled: bool,
+    /// On conflicts, start a coding agent to resolve them instead of leaving
+    /// the rebase stopped for the user
+    #[serde(default)]
+    pub resolve_conflicts_with_agent: bool,
+}
+
+/// Workspace terminal sessions
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct TerminalConfig {
+    /// Record each terminal session as an asciicast file for later review
+    #[serde(default)]
+    pub record_sessions: bool,
+}
+
+/// Ports reserved for each workspace so parallel dev servers do not collide
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct PortAllocationConfig {
+    /// Reserve ports for each workspace and pass them to its processes as
+    /// `PORT` and `VK_PORT_0`, `VK_PORT_1`, ...
+    #[serde(default)]
+    pub enabled: bool,
+    /// First port of the range ports are reserved from
+    #[serde(default = "default_port_range_start")]
+    pub range_start: u16,
+    /// Last port of the range ports are reserved from
+    #[serde(default = "default_port_range_end")]
+    pub range_end: u16,
+    /// Number of ports reserved for each workspace
+    #[serde(default = "default_ports_per_workspace")]
+    pub ports_per_workspace: u16,
+}
+
+impl Default for PortAllocationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            range_start: default_port_range_start(),
+            range_end: default_port_range_end(),
+            ports_per_workspace: default_ports_per_workspace(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -62,6 +145,16 @@ pub struct Config {
     pub commit_reminder_prompt: Option<String>,
     #[serde(default)]
     pub send_message_shortcut: SendMessageShortcut,
+    #[serde(default)]
+    pub workspace_container: WorkspaceContainerConfig,
+    #[serde(default)]
+    pub concurrency: ConcurrencyConfig,
+    #[serde(default)]
+    pub auto_rebase: AutoRebaseConfig,
+    #[serde(default)]
+    pub terminal: TerminalConfig,
+    #[serde(default)]
+    pub port_allocation: PortAllocationConfig,
 }
 
 impl Config {
@@ -92,6 +185,11 @@ impl Config {
             commit_reminder_enabled: true,
             commit_reminder_prompt: None,
             send_message_shortcut: SendMessageShortcut::default(),
+            workspace_container: WorkspaceContainerConfig::default(),
+            concurrency: ConcurrencyConfig::default(),
+            auto_rebase: AutoRebaseConfig::default(),
+            terminal: TerminalConfig::default(),
+            port_allocation: PortAllocationConfig::default(),
         }
     }
 
@@ -147,6 +245,11 @@ impl Default for Config {
             commit_reminder_enabled: true,
             commit_reminder_prompt: None,
             send_message_shortcut: SendMessageShortcut::default(),
+            workspace_container: WorkspaceContainerConfig::default(),
+            concurrency: ConcurrencyConfig::default(),
+            auto_rebase: AutoRebaseConfig::default(),
+            terminal: TerminalConfig::default(),
+            port_allocation: PortAllocationConfig::default(),
         }
     }
 }
@@ -0,0 +1,120 @@
+use git::{ConflictOp, ConflictedFile};
+use utils::text::truncate_to_char_boundary;
+
+/// Files listed in full in the prompt; the rest are only counted
+const MAX_PROMPT_FILES: usize = 20;
+/// Per-file budget for conflict hunks in the prompt
+const MAX_FILE_HUNK_BYTES: usize = 8 * 1024;
+
+fn op_label(op: &ConflictOp) -> &'static str {
+    match op {
+        ConflictOp::Rebase => "rebase",
+        ConflictOp::Merge => "merge",
+        ConflictOp::CherryPick => "cherry-pick",
+        ConflictOp::Revert => "revert",
+    }
+}
+
+/// Prompt asking an agent to resolve the conflicts of a stopped operation.
+/// `sides` are the HEAD and incoming commits as returned by
+/// `GitService::conflict_sides`.
+pub fn build_conflict_resolution_prompt(
+    op: &ConflictOp,
+    repo_name: &str,
+    task_branch: &str,
+    target_branch: &str,
+    sides: Option<&(String, String)>,
+    files: &[ConflictedFile],
+) -> String {
+    let label = op_label(op);
+    let mut prompt = match op {
+        ConflictOp::Rebase => format!(
+            "Rebasing '{task_branch}' onto '{target_branch}' in repository '{repo_name}' stopped with conflicts."
+        ),
+        _ => format!(
+            "A {label} on '{task_branch}' in repository '{repo_name}' stopped with conflicts."
+        ),
+    };
+
+    if let Some((ours, theirs)) = sides {
+        prompt.push_str("\n\n");
+        match op {
+            ConflictOp::Rebase => prompt.push_str(&format!(
+                "In the conflict blocks, the upper side (HEAD, \"ours\") is '{target_branch}' plus the task commits replayed so far, currently at {ours}. The lower side (\"theirs\") is the task commit being replayed: {theirs}."
+            )),
+            _ => prompt.push_str(&format!(
+                "In the conflict blocks, the upper side (HEAD, \"ours\") is {ours}. The lower side (\"theirs\") is the commit being applied: {theirs}."
+            )),
+        }
+    }
+
+    prompt.push_str("\n\nConflicts:\n");
+    for file in files.iter().take(MAX_PROMPT_FILES) {
+        prompt.push_str(&format!("\n### {}\n", file.path));
+        if file.hunks.is_empty() {
+            prompt.push_str("(no conflict markers; binary file or a delete/modify conflict)\n");
+            continue;
+        }
+        let mut hunks = String::new();
+        for hunk in &file.hunks {
+            hunks.push_str(&format!(
+                "Line {}:\n```\n{}\n```\n",
+                hunk.start_line, hunk.text
+            ));
+        }
+        if hunks.len() > MAX_FILE_HUNK_BYTES {
+            prompt.push_str(truncate_to_char_boundary(&hunks, MAX_FILE_HUNK_BYTES));
+            prompt.push_str("\n(truncated; open the file for the remaining conflicts)\n");
+        } else {
+            prompt.push_str(&hunks);
+        }
+    }
+    if files.len() > MAX_PROMPT_FILES {
+        prompt.push_str(&format!(
+            "\n...and {} more conflicted files (see `git diff --name-only --diff-filter=U`).\n",
+            files.len() - MAX_PROMPT_FILES
+        ));
+    }
+
+    prompt.push_str(&format!(
+        "\nEdit each file so it keeps the intent of both sides and remove every conflict marker. Do not run `git {label} --continue`, `--abort` or `git commit`; the {label} is continued automatically once no conflict markers remain."
+    ));
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use git::ConflictHunk;
+
+    use super::*;
+
+    #[test]
+    fn rebase_prompt_includes_sides_and_hunks() {
+        let files = vec![ConflictedFile {
+            path: "src/lib.rs".to_string(),
+            hunks: vec![ConflictHunk {
+                start_line: 3,
+                text: "<<<<<<< HEAD\na\n=======\nb\n>>>>>>> abc1234 (Change)".to_string(),
+            }],
+        }];
+        let sides = (
+            "def5678 Update main".to_string(),
+            "abc1234 Change".to_string(),
+        );
+
+        let prompt = build_conflict_resolution_prompt(
+            &ConflictOp::Rebase,
+            "app",
+            "vk/1234-task",
+            "main",
+            Some(&sides),
+            &files,
+        );
+
+        assert!(prompt.starts_with("Rebasing 'vk/1234-task' onto 'main' in repository 'app'"));
+        assert!(prompt.contains("currently at def5678 Update main"));
+        assert!(prompt.contains("being replayed: abc1234 Change"));
+        assert!(prompt.contains("### src/lib.rs\nLine 3:\n```\n<<<<<<< HEAD"));
+        assert!(prompt.contains("Do not run `git rebase --continue`"));
+    }
+}
@@ -10,6 +10,8 @@ use db::{
     DBService,
     models::{
         coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
+        commit_message_run::CommitMessageRun,
+        conflict_resolution_run::ConflictResolutionRun,
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessError,
             ExecutionProcessRunReason, ExecutionProcessStatus,
@@ -27,20 +29,24 @@ use db::{
 };
 #[cfg(feature = "qa-mode")]
 use executors::executors::qa_mock::QaMockExecutor;
-#[cfg(not(feature = "qa-mode"))]
-use executors::profile::ExecutorConfigs;
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
+        coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
     executors::{ExecutorError, StandardCodingAgentExecutor},
-    logs::{NormalizedEntry, NormalizedEntryError, NormalizedEntryType, utils::ConversationPatch},
-    profile::ExecutorProfileId,
+    logs::{
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
+        token_usage::{estimated_usage_entry, latest_usage},
+        utils::{ConversationPatch, EntryIndexProvider, patch::add_normalized_entry},
+    },
+    profile::{ExecutorConfigs, ExecutorProfileId},
+    steering::SteerRequest,
 };
 use futures::{StreamExt, future, stream::BoxStream};
-use git::{GitService, GitServiceError};
+use git::{DiffTarget, GitService, GitServiceError};
 use json_patch::Patch;
 use sqlx::Error as SqlxError;
 use thiserror::Error;
@@ -53,7 +59,16 @@ use utils::{
 use uuid::Uuid;
 
 use crate::services::{
-    notification::NotificationService, workspace_manager::WorkspaceError as WorkspaceManagerError,
+    commit_message::build_commit_message_prompt,
+    config::ConcurrencyConfig,
+    conflict_resolution::build_conflict_resolution_prompt,
+    fallback::{
+        FallbackReason, build_fallback_prompt, follow_up_fallback_instructions, progress_summary,
+    },
+    handoff::{HandoffTurn, build_handoff_prompt, render_diff},
+    notification::NotificationService,
+    scheduler::ExecutionScheduler,
+    workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
 };
 pub type ContainerRef = String;
@@ -80,6 +95,8 @@ pub enum ContainerError {
     Io(#[from] std::io::Error),
     #[error("Failed to kill process: {0}")]
     KillFailed(std::io::Error),
+    #[error("The agent is not running or does not accept messages while running")]
+    SteeringUnavailable,
     #[error(transparent)]
     Other(#[from] AnyhowError), // Catches any unclassified errors
 }
@@ -94,6 +111,8 @@ pub trait ContainerService {
 
     fn notification_service(&self) -> &NotificationService;
 
+    fn scheduler(&self) -> &ExecutionScheduler;
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf;
 
     async fn available_agent_slash_commands(
@@ -170,7 +189,10 @@ pub trait ContainerService {
                     ExecutionProcess::find_by_session_id(&self.db().pool, session.id, false).await
                 {
                     for process in processes {
-                        if process.status == ExecutionProcessStatus::Running {
+                        if matches!(
+                            process.status,
+                            ExecutionProcessStatus::Running | ExecutionProcessStatus::Queued
+                        ) {
                             return Ok(true);
                         }
                     }
@@ -278,6 +300,10 @@ pub trait ContainerService {
                 );
                 continue;
             }
+            // An interrupted conflict resolution or commit message run is not
+            // continued; the turn's changes are committed with the next one
+            let _ = ConflictResolutionRun::take(&self.db().pool, process.id).await;
+            let _ = CommitMessageRun::delete(&self.db().pool, process.id).await;
             // Capture after-head commit OID per repository
             if let Ok(ctx) = ExecutionProcess::load_context(&self.db().pool, process.id).await
                 && let Some(ref container_ref) = ctx.workspace.container_ref
@@ -424,6 +450,7 @@ pub trait ContainerService {
                 language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::CleanupScript,
                 working_dir: Some(first.name.clone()),
+                resource_limits: first.resource_limits(),
             }),
             None,
         );
@@ -435,6 +462,7 @@ pub trait ContainerService {
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::CleanupScript,
                     working_dir: Some(repo.name.clone()),
+                    resource_limits: repo.resource_limits(),
                 }),
                 None,
             ));
@@ -461,6 +489,7 @@ pub trait ContainerService {
                 language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::ArchiveScript,
                 working_dir: Some(first.name.clone()),
+                resource_limits: first.resource_limits(),
             }),
             None,
         );
@@ -472,6 +501,7 @@ pub trait ContainerService {
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::ArchiveScript,
                     working_dir: Some(repo.name.clone()),
+                    resource_limits: repo.resource_limits(),
                 }),
                 None,
             ));
@@ -575,6 +605,7 @@ pub trait ContainerService {
                 language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::SetupScript,
                 working_dir: Some(first.name.clone()),
+                resource_limits: first.resource_limits(),
             }),
             None,
         );
@@ -586,6 +617,7 @@ pub trait ContainerService {
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::SetupScript,
                     working_dir: Some(repo.name.clone()),
+                    resource_limits: repo.resource_limits(),
                 }),
                 None,
             ));
@@ -602,6 +634,7 @@ pub trait ContainerService {
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::SetupScript,
                     working_dir: Some(repo.name.clone()),
+                    resource_limits: repo.resource_limits(),
                 }),
                 None,
             )
@@ -621,6 +654,7 @@ pub trait ContainerService {
                         language: ScriptRequestLanguage::Bash,
                         context: ScriptContext::SetupScript,
                         working_dir: Some(repo.name.clone()),
+                        resource_limits: repo.resource_limits(),
                     }),
                     Some(Box::new(chained)),
                 );
@@ -722,7 +756,10 @@ pub trait ContainerService {
                     {
                         continue;
                     }
-                    if process.status == ExecutionProcessStatus::Running {
+                    if matches!(
+                        process.status,
+                        ExecutionProcessStatus::Running | ExecutionProcessStatus::Queued
+                    ) {
                         self.stop_execution(&process, ExecutionProcessStatus::Killed)
                             .await
                             .unwrap_or_else(|e| {
@@ -759,6 +796,13 @@ pub trait ContainerService {
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError>;
 
+    /// Send a message into the running turn of a coding agent execution
+    async fn steer_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+        request: SteerRequest,
+    ) -> Result<(), ContainerError>;
+
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError>;
 
     async fn copy_project_files(
@@ -783,6 +827,8 @@ pub trait ContainerService {
 
     async fn git_branch_prefix(&self) -> String;
 
+    async fn concurrency_config(&self) -> ConcurrencyConfig;
+
     async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String {
         let task_title_id = git_branch_id(task_title);
         let prefix = self.git_branch_prefix().await;
@@ -794,6 +840,29 @@ pub trait ContainerService {
         }
     }
 
+    /// Stdout and stderr of an execution process as they were printed, from
+    /// memory while it runs and from the database once it has finished
+    async fn raw_log_text(&self, id: &Uuid) -> Option<String> {
+        let history = match self.get_msg_store_by_id(id).await {
+            Some(store) => store.get_history(),
+            None => {
+                let records = ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id)
+                    .await
+                    .ok()?;
+                ExecutionProcessLogs::parse_logs(&records).ok()?
+            }
+        };
+        Some(
+            history
+                .into_iter()
+                .filter_map(|msg| match msg {
+                    LogMsg::Stdout(content) | LogMsg::Stderr(content) => Some(content),
+                    _ => None,
+                })
+                .collect(),
+        )
+    }
+
     async fn stream_raw_logs(
         &self,
         id: &Uuid,
@@ -1203,6 +1272,7 @@ pub trait ContainerService {
                 prompt,
                 executor_profile_id: executor_profile_id.clone(),
                 working_dir,
+                fallback_profiles: None,
             }),
             cleanup_action.map(Box::new),
         );
@@ -1251,6 +1321,30 @@ pub trait ContainerService {
         session: &Session,
         executor_action: &ExecutorAction,
         run_reason: &ExecutionProcessRunReason,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        self.start_execution_with_id(
+            workspace,
+            session,
+            executor_action,
+            run_reason,
+            Uuid::new_v4(),
+            false,
+        )
+        .await
+    }
+
+    /// [`Self::start_execution`] with the id of the execution process chosen by
+    /// the caller, so what the run is for can be recorded before it can finish.
+    /// `automated` marks coding agent turns vibe-kanban starts on its own, which
+    /// don't count as turns of the user.
+    async fn start_execution_with_id(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        executor_action: &ExecutorAction,
+        run_reason: &ExecutionProcessRunReason,
+        execution_process_id: Uuid,
+        automated: bool,
     ) -> Result<ExecutionProcess, ContainerError> {
         // Update task status to InProgress when starting an execution
         let task = workspace
@@ -1278,6 +1372,9 @@ pub trait ContainerService {
             .map(std::path::PathBuf::from)
             .ok_or_else(|| ContainerError::Other(anyhow!("Container ref not found")))?;
 
+        // Held until the process is recorded, so nothing that checks the
+        // workspace is idle under this lock (e.g. auto-rebase) can miss it
+        let workspace_guard = self.scheduler().lock_workspace(workspace.id).await;
         let mut repo_states = Vec::with_capacity(repositories.len());
         for repo in &repositories {
             let repo_path = workspace_root.join(&repo.name);
@@ -1295,13 +1392,22 @@ pub trait ContainerService {
             run_reason: run_reason.clone(),
         };
 
-        let execution_process = ExecutionProcess::create(
+        // Coding agents go through the queue whenever a concurrency limit is set
+        let queue = *run_reason == ExecutionProcessRunReason::CodingAgent
+            && !self.concurrency_config().await.is_unlimited();
+        let mut execution_process = ExecutionProcess::create(
             &self.db().pool,
             &create_execution_process,
-            Uuid::new_v4(),
+            execution_process_id,
+            if queue {
+                ExecutionProcessStatus::Queued
+            } else {
+                ExecutionProcessStatus::Running
+            },
             &repo_states,
         )
         .await?;
+        drop(workspace_guard);
         if *run_reason != ExecutionProcessRunReason::ArchiveScript {
             Workspace::set_archived(&self.db().pool, workspace.id, false).await?;
         }
@@ -1321,6 +1427,7 @@ pub trait ContainerService {
             let create_coding_agent_turn = CreateCodingAgentTurn {
                 execution_process_id: execution_process.id,
                 prompt: Some(prompt),
+                automated,
             };
 
             let coding_agent_turn_id = Uuid::new_v4();
@@ -1333,8 +1440,137 @@ pub trait ContainerService {
             .await?;
         }
 
+        if queue {
+            if !self.try_admit(&execution_process).await? {
+                tracing::info!(
+                    "Execution {} queued until a coding agent slot is free",
+                    execution_process.id
+                );
+                return Ok(execution_process);
+            }
+            execution_process.status = ExecutionProcessStatus::Running;
+        }
+
+        self.launch_execution(workspace, &execution_process, executor_action, task.id)
+            .await?;
+        Ok(execution_process)
+    }
+
+    /// Move a queued process to running if a slot is free and no older
+    /// process for the same executor is still waiting, so a newcomer never
+    /// overtakes the queue.
+    async fn try_admit(
+        &self,
+        execution_process: &ExecutionProcess,
+    ) -> Result<bool, ContainerError> {
+        let Some(executor) = execution_process.executor_action()?.base_executor() else {
+            return Ok(
+                ExecutionProcess::mark_started(&self.db().pool, execution_process.id).await?,
+            );
+        };
+        let limits = self.concurrency_config().await;
+
+        let _guard = self.scheduler().lock().await;
+        let queued = ExecutionScheduler::queued_agents(&self.db().pool).await?;
+        if !ExecutionScheduler::is_next_in_line(&queued, execution_process.id, executor) {
+            return Ok(false);
+        }
+        let running = ExecutionScheduler::running_agents(&self.db().pool).await?;
+        if !ExecutionScheduler::has_slot(&limits, &running, executor) {
+            return Ok(false);
+        }
+        Ok(ExecutionProcess::mark_started(&self.db().pool, execution_process.id).await?)
+    }
+
+    /// Start queued coding agents, oldest first, while slots are free
+    async fn start_queued_executions(&self) {
+        if self.scheduler().is_paused() {
+            return;
+        }
+        let pool = &self.db().pool;
+        let limits = self.concurrency_config().await;
+
+        let admitted = {
+            let _guard = self.scheduler().lock().await;
+            let queued = match ExecutionProcess::find_queued(pool).await {
+                Ok(queued) if !queued.is_empty() => queued,
+                Ok(_) => return,
+                Err(e) => {
+                    tracing::error!("Failed to load queued executions: {}", e);
+                    return;
+                }
+            };
+            let mut running = match ExecutionScheduler::running_agents(pool).await {
+                Ok(running) => running,
+                Err(e) => {
+                    tracing::error!("Failed to count running coding agents: {}", e);
+                    return;
+                }
+            };
+
+            let mut admitted = Vec::new();
+            for process in queued {
+                let Some(executor) = process
+                    .executor_action()
+                    .ok()
+                    .and_then(|action| action.base_executor())
+                else {
+                    continue;
+                };
+                if !ExecutionScheduler::has_slot(&limits, &running, executor) {
+                    continue;
+                }
+                match ExecutionProcess::mark_started(pool, process.id).await {
+                    Ok(true) => {
+                        running.push(executor);
+                        admitted.push(process.id);
+                    }
+                    Ok(false) => {}
+                    Err(e) => {
+                        tracing::error!("Failed to start queued execution {}: {}", process.id, e)
+                    }
+                }
+            }
+            admitted
+        };
+
+        for exec_id in admitted {
+            let ctx = match ExecutionProcess::load_context(pool, exec_id).await {
+                Ok(ctx) => ctx,
+                Err(e) => {
+                    tracing::error!("Failed to load queued execution {}: {}", exec_id, e);
+                    continue;
+                }
+            };
+            let Ok(executor_action) = ctx.execution_process.executor_action().cloned() else {
+                continue;
+            };
+            tracing::info!("Starting queued execution {}", exec_id);
+            if let Err(e) = self
+                .launch_execution(
+                    &ctx.workspace,
+                    &ctx.execution_process,
+                    &executor_action,
+                    ctx.task.id,
+                )
+                .await
+            {
+                tracing::error!("Failed to start queued execution {}: {}", exec_id, e);
+            }
+        }
+    }
+
+    /// Spawn an execution process whose record already exists and start
+    /// streaming its logs. On failure the process is marked failed.
+    async fn launch_execution(
+        &self,
+        workspace: &Workspace,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+        task_id: Uuid,
+    ) -> Result<(), ContainerError> {
         if let Err(start_error) = self
-            .start_execution_inner(workspace, &execution_process, executor_action)
+            .start_execution_inner(workspace, execution_process, executor_action)
             .await
         {
             // Mark process as failed
@@ -1352,7 +1588,7 @@ pub trait ContainerService {
                     update_error
                 );
             }
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await?;
+            Task::update_status(&self.db().pool, task_id, TaskStatus::InReview).await?;
 
             // Emit stderr error message
             let log_message = LogMsg::Stderr(format!("Failed to start execution: {start_error}"));
@@ -1388,6 +1624,27 @@ pub trait ContainerService {
                     .await;
                 }
             };
+
+            // Hand runs whose agent could not authenticate to their fallback profile
+            if let ContainerError::ExecutorError(ExecutorError::AuthRequired(message)) =
+                &start_error
+            {
+                match ExecutionProcess::load_context(&self.db().pool, execution_process.id).await {
+                    Ok(ctx) => match self
+                        .try_start_fallback(&ctx, FallbackReason::AuthFailed(message.clone()))
+                        .await
+                    {
+                        Ok(true) => return Ok(()),
+                        Ok(false) => {}
+                        Err(e) => tracing::error!("Failed to start fallback profile: {}", e),
+                    },
+                    Err(e) => tracing::error!(
+                        "Failed to load context of execution process {}: {}",
+                        execution_process.id,
+                        e
+                    ),
+                }
+            }
             return Err(start_error);
         }
 
@@ -1411,6 +1668,10 @@ pub trait ContainerService {
                 _ => None,
             }
         {
+            if let ExecutorActionType::CodingAgentFollowUpRequest(_) = executor_action.typ() {
+                self.carry_over_context_usage(execution_process, &msg_store)
+                    .await;
+            }
             #[cfg(feature = "qa-mode")]
             {
                 let executor = QaMockExecutor;
@@ -1421,7 +1682,13 @@ pub trait ContainerService {
                 if let Some(executor) =
                     ExecutorConfigs::get_cached().get_coding_agent(executor_profile_id)
                 {
-                    executor.normalize_logs(msg_store, &working_dir);
+                    executor.normalize_logs(msg_store.clone(), &working_dir);
+                    executors::replay::record_when_finished(
+                        msg_store,
+                        executor,
+                        working_dir,
+                        execution_process.id.to_string(),
+                    );
                 } else {
                     tracing::error!(
                         "Failed to resolve profile '{:?}' for normalization",
@@ -1435,7 +1702,7 @@ pub trait ContainerService {
         let db_stream_handle = self.spawn_stream_raw_logs_to_db(&execution_process.id);
         self.store_db_stream_handle(execution_process.id, db_stream_handle)
             .await;
-        Ok(execution_process)
+        Ok(())
     }
 
     async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
@@ -1472,4 +1739,580 @@ pub trait ContainerService {
         tracing::debug!("Started next action: {:?}", next_action);
         Ok(())
     }
+
+    /// Continue the coding agent run of `ctx`, which stopped for `reason`, in a
+    /// new session with the next profile of its fallback chain. An initial
+    /// run's prompt carries over along with a summary of its progress; a
+    /// follow-up hands over the whole session, as a handoff does. Returns false
+    /// when there is no profile left to fall back to.
+    async fn try_start_fallback(
+        &self,
+        ctx: &ExecutionContext,
+        reason: FallbackReason,
+    ) -> Result<bool, ContainerError> {
+        if ctx.execution_process.run_reason != ExecutionProcessRunReason::CodingAgent {
+            return Ok(false);
+        }
+        let action = ctx.execution_process.executor_action()?;
+        let (prompt, executor_profile_id, working_dir, chain) = match action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => (
+                &request.prompt,
+                &request.executor_profile_id,
+                &request.working_dir,
+                request.fallback_profiles.clone(),
+            ),
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => (
+                &request.prompt,
+                &request.executor_profile_id,
+                &request.working_dir,
+                None,
+            ),
+            _ => return Ok(false),
+        };
+        // Runs started as a fallback carry the rest of the chain they came from
+        let chain = chain
+            .unwrap_or_else(|| ExecutorConfigs::get_cached().fallback_chain(executor_profile_id));
+        let Some((next, rest)) = chain.split_first() else {
+            return Ok(false);
+        };
+
+        let msg_store = self.get_msg_store_by_id(&ctx.execution_process.id).await;
+        let prompt = match action.typ() {
+            ExecutorActionType::CodingAgentFollowUpRequest(_) => {
+                let (turns, diff) = self.handoff_context(&ctx.workspace, ctx.session.id).await?;
+                build_handoff_prompt(
+                    executor_profile_id,
+                    &turns,
+                    diff.as_deref(),
+                    Some(&follow_up_fallback_instructions(
+                        executor_profile_id,
+                        &reason,
+                    )),
+                )
+            }
+            _ => {
+                let progress = msg_store
+                    .as_ref()
+                    .and_then(|msg_store| progress_summary(&msg_store.get_history()));
+                build_fallback_prompt(prompt, executor_profile_id, &reason, progress.as_deref())
+            }
+        };
+
+        let session = Session::create(
+            &self.db().pool,
+            &CreateSession {
+                executor: Some(next.executor.to_string()),
+            },
+            Uuid::new_v4(),
+            ctx.workspace.id,
+        )
+        .await?;
+        let fallback_action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt,
+                executor_profile_id: next.clone(),
+                working_dir: working_dir.clone(),
+                fallback_profiles: Some(rest.to_vec()),
+            }),
+            action.next_action().cloned().map(Box::new),
+        );
+
+        tracing::info!(
+            "{} stopped in workspace {} ({:?}), continuing with {}",
+            executor_profile_id,
+            ctx.workspace.id,
+            reason,
+            next
+        );
+        if let Some(msg_store) = msg_store {
+            add_normalized_entry(
+                &msg_store,
+                &EntryIndexProvider::start_from(&msg_store),
+                NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::SystemMessage,
+                    content: format!("Continuing with {next} in a new session"),
+                    metadata: None,
+                },
+            );
+        }
+
+        self.start_execution_with_id(
+            &ctx.workspace,
+            &session,
+            &fallback_action,
+            &ExecutionProcessRunReason::CodingAgent,
+            Uuid::new_v4(),
+            true,
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// The normalized conversation patches of a process: from its message store
+    /// while it is in memory, re-normalized from the stored logs otherwise
+    async fn normalized_history(&self, id: &Uuid) -> Vec<LogMsg> {
+        if let Some(store) = self.get_msg_store_by_id(id).await {
+            return store.get_history();
+        }
+        match self.stream_normalized_logs(id).await {
+            Some(stream) => {
+                stream
+                    .take_while(|msg| future::ready(!matches!(msg, Ok(LogMsg::Finished))))
+                    .filter_map(|msg| future::ready(msg.ok()))
+                    .collect()
+                    .await
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Start a follow-up's context usage where the session's previous turn left
+    /// it, so estimates count the earlier turns. The entry is stored with the
+    /// process's logs, so it is there again when they are normalized later.
+    async fn carry_over_context_usage(
+        &self,
+        execution_process: &ExecutionProcess,
+        msg_store: &MsgStore,
+    ) {
+        let pool = &self.db().pool;
+        let processes =
+            match ExecutionProcess::find_by_session_id(pool, execution_process.session_id, false)
+                .await
+            {
+                Ok(processes) => processes,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to load the turns of session {}: {}",
+                        execution_process.session_id,
+                        e
+                    );
+                    return;
+                }
+            };
+        let Some(previous) = processes.iter().rev().find(|process| {
+            process.run_reason == ExecutionProcessRunReason::CodingAgent
+                && process.created_at < execution_process.created_at
+        }) else {
+            return;
+        };
+        let Some(usage) = latest_usage(&self.normalized_history(&previous.id).await) else {
+            return;
+        };
+
+        let patch = ConversationPatch::add_normalized_entry(
+            EntryIndexProvider::start_from(msg_store).next(),
+            estimated_usage_entry(usage.total_tokens, usage.model_context_window),
+        );
+        msg_store.push_patch(patch.clone());
+        match serde_json::to_string(&LogMsg::JsonPatch(patch)) {
+            Ok(json_line) => {
+                if let Err(e) = ExecutionProcessLogs::append_log_line(
+                    pool,
+                    execution_process.id,
+                    &format!("{json_line}\n"),
+                )
+                .await
+                {
+                    tracing::warn!(
+                        "Failed to store the context usage carried over to {}: {}",
+                        execution_process.id,
+                        e
+                    );
+                }
+            }
+            Err(e) => tracing::warn!("Failed to serialize carried over context usage: {}", e),
+        }
+    }
+
+    /// The coding agent turns of a session and the workspace's changes, which
+    /// prompts continuing the session with another agent recount
+    async fn handoff_context(
+        &self,
+        workspace: &Workspace,
+        session_id: Uuid,
+    ) -> Result<(Vec<HandoffTurn>, Option<String>), ContainerError> {
+        let pool = &self.db().pool;
+        let mut turns = Vec::new();
+        for process in ExecutionProcess::find_by_session_id(pool, session_id, false).await? {
+            if process.run_reason != ExecutionProcessRunReason::CodingAgent {
+                continue;
+            }
+            let prompt = match process.executor_action()?.typ() {
+                ExecutorActionType::CodingAgentInitialRequest(request) => request.prompt.clone(),
+                ExecutorActionType::CodingAgentFollowUpRequest(request) => request.prompt.clone(),
+                _ => continue,
+            };
+            turns.push(HandoffTurn {
+                prompt,
+                history: self.normalized_history(&process.id).await,
+            });
+        }
+
+        let workspace_dir = self.workspace_to_current_dir(workspace);
+        let mut diffs = Vec::new();
+        for repo_with_branch in
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?
+        {
+            let repo = &repo_with_branch.repo;
+            let repo_diffs = self
+                .git()
+                .get_base_commit(
+                    &repo.path,
+                    &workspace.branch,
+                    &repo_with_branch.target_branch,
+                )
+                .and_then(|base_commit| {
+                    self.git().get_diffs(
+                        DiffTarget::Worktree {
+                            worktree_path: &workspace_dir.join(&repo.name),
+                            base_commit: &base_commit,
+                        },
+                        None,
+                    )
+                });
+            match repo_diffs {
+                Ok(repo_diffs) => diffs.extend(repo_diffs.into_iter().map(|mut diff| {
+                    diff.old_path = diff.old_path.map(|path| format!("{}/{path}", repo.name));
+                    diff.new_path = diff.new_path.map(|path| format!("{}/{path}", repo.name));
+                    diff
+                })),
+                Err(e) => tracing::warn!(
+                    "Failed to diff {} for handoff of session {}: {}",
+                    repo.name,
+                    session_id,
+                    e
+                ),
+            }
+        }
+
+        Ok((turns, render_diff(&diffs)))
+    }
+
+    /// Hand `session`, so far run with `from`, over to `to`. The session's
+    /// running agents are stopped and `to` starts a fresh session of its own
+    /// in the same session row, prompted with the conversation so far and the
+    /// workspace's changes.
+    async fn start_handoff(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        from: &ExecutorProfileId,
+        to: &ExecutorProfileId,
+        instructions: Option<&str>,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+        let processes = ExecutionProcess::find_by_session_id(pool, session.id, false).await?;
+        for process in &processes {
+            if process.run_reason != ExecutionProcessRunReason::DevServer
+                && matches!(
+                    process.status,
+                    ExecutionProcessStatus::Running | ExecutionProcessStatus::Queued
+                )
+            {
+                self.stop_execution(process, ExecutionProcessStatus::Killed)
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::debug!(
+                            "Failed to stop execution process {} for handoff of session {}: {}",
+                            process.id,
+                            session.id,
+                            e
+                        );
+                    });
+            }
+        }
+
+        let (turns, diff) = self.handoff_context(workspace, session.id).await?;
+        let prompt = build_handoff_prompt(from, &turns, diff.as_deref(), instructions);
+
+        tracing::info!(
+            "Handing session {} over from {} to {} ({} turns)",
+            session.id,
+            from,
+            to,
+            turns.len()
+        );
+        Session::update_executor(pool, session.id, &to.executor.to_string()).await?;
+
+        let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+        let working_dir = workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+        let action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt,
+                executor_profile_id: to.clone(),
+                working_dir,
+                fallback_profiles: None,
+            }),
+            self.cleanup_actions_for_repos(&repos).map(Box::new),
+        );
+
+        self.start_execution(
+            workspace,
+            session,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await
+    }
+
+    /// Start a coding agent turn in `session` that resolves the conflicts of the
+    /// rebase or merge stopped in `repo`'s worktree. The operation is continued
+    /// once the turn finishes without leaving conflict markers behind.
+    async fn start_conflict_resolution(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        repo: &Repo,
+        target_branch: &str,
+        executor_profile_id: ExecutorProfileId,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let container_ref = self.ensure_container_exists(workspace).await?;
+        let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+
+        let op = self
+            .git()
+            .detect_conflict_op(&worktree_path)?
+            .ok_or_else(|| anyhow!("No rebase or merge in progress in '{}'", repo.name))?;
+        let files = self.git().get_conflict_hunks(&worktree_path)?;
+        if files.is_empty() {
+            return Err(anyhow!("No conflicted files in '{}'", repo.name).into());
+        }
+        let sides = self.git().conflict_sides(&worktree_path, &op).ok();
+        let prompt = build_conflict_resolution_prompt(
+            &op,
+            &repo.display_name,
+            &workspace.branch,
+            target_branch,
+            sides.as_ref(),
+            &files,
+        );
+
+        let working_dir = workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+        let action_type =
+            match CodingAgentTurn::find_latest_session_info(&self.db().pool, session.id).await? {
+                Some(info) => {
+                    ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                        prompt,
+                        session_id: info.session_id,
+                        reset_to_message_id: None,
+                        executor_profile_id,
+                        working_dir,
+                    })
+                }
+                None => ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                    prompt,
+                    executor_profile_id,
+                    working_dir,
+                    fallback_profiles: None,
+                }),
+            };
+        // No cleanup script: it would run against the half-finished operation
+        let action = ExecutorAction::new(action_type, None);
+
+        // Recorded first, so even a run that finishes right away is continued
+        let execution_process_id = Uuid::new_v4();
+        let files: Vec<String> = files.into_iter().map(|file| file.path).collect();
+        ConflictResolutionRun::create(&self.db().pool, execution_process_id, repo.id, &files)
+            .await?;
+        let started = self
+            .start_execution_with_id(
+                workspace,
+                session,
+                &action,
+                &ExecutionProcessRunReason::CodingAgent,
+                execution_process_id,
+                true,
+            )
+            .await;
+        if started.is_err() {
+            let _ = ConflictResolutionRun::take(&self.db().pool, execution_process_id).await;
+        }
+        started
+    }
+
+    /// Called when a coding agent turn finishes. If it was a conflict resolution
+    /// turn that left no conflict markers, stage the worktree and continue the
+    /// stopped operation. When that stops on the next conflict (e.g. a later
+    /// commit of a rebase), hand it to the agent again. Returns true if another
+    /// resolution turn was started.
+    async fn continue_conflict_resolution(&self, ctx: &ExecutionContext) -> bool {
+        let pending =
+            match ConflictResolutionRun::take(&self.db().pool, ctx.execution_process.id).await {
+                Ok(Some(pending)) => pending,
+                Ok(None) => return false,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to load conflict resolution of execution {}: {}",
+                        ctx.execution_process.id,
+                        e
+                    );
+                    return false;
+                }
+            };
+        if ctx.execution_process.status != ExecutionProcessStatus::Completed {
+            return false;
+        }
+        let (Some(repo), Some(container_ref)) = (
+            ctx.repos.iter().find(|repo| repo.id == pending.repo_id),
+            ctx.workspace.container_ref.as_ref(),
+        ) else {
+            return false;
+        };
+        let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+
+        let Ok(Some(op)) = self.git().detect_conflict_op(&worktree_path) else {
+            // Resolved and continued by hand
+            return false;
+        };
+        let remaining = self
+            .git()
+            .files_with_conflict_markers(&worktree_path, &pending.files.0);
+        if !remaining.is_empty() {
+            tracing::info!(
+                "Conflict markers remain in {:?} of workspace {}; leaving the {:?} in progress",
+                remaining,
+                ctx.workspace.id,
+                op
+            );
+            return false;
+        }
+
+        let Err(e) = self.git().continue_conflict_op(&worktree_path, &op) else {
+            tracing::info!(
+                "Continued {:?} in workspace {} after conflict resolution",
+                op,
+                ctx.workspace.id
+            );
+            return false;
+        };
+        if !self
+            .git()
+            .get_conflicted_files(&worktree_path)
+            .is_ok_and(|files| !files.is_empty())
+        {
+            tracing::warn!(
+                "Failed to continue {:?} in workspace {}: {}",
+                op,
+                ctx.workspace.id,
+                e
+            );
+            return false;
+        }
+
+        let pool = &self.db().pool;
+        let next = async {
+            let target_branch =
+                WorkspaceRepo::find_by_workspace_and_repo_id(pool, ctx.workspace.id, repo.id)
+                    .await?
+                    .ok_or(SqlxError::RowNotFound)?
+                    .target_branch;
+            let executor_profile_id =
+                ExecutionProcess::latest_executor_profile_for_session(pool, ctx.session.id)
+                    .await?
+                    .ok_or(SqlxError::RowNotFound)?;
+            self.start_conflict_resolution(
+                &ctx.workspace,
+                &ctx.session,
+                repo,
+                &target_branch,
+                executor_profile_id,
+            )
+            .await
+        };
+        match next.await {
+            Ok(_) => true,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to start the next conflict resolution for workspace {}: {}",
+                    ctx.workspace.id,
+                    e
+                );
+                false
+            }
+        }
+    }
+
+    /// Follow the finished coding agent turn in `ctx` with one that asks the
+    /// same agent for a commit message describing its uncommitted changes in
+    /// `repos`. Those changes are committed once that run finishes, after which
+    /// the turn's next action (e.g. the cleanup script) runs.
+    async fn start_commit_message_run(
+        &self,
+        ctx: &ExecutionContext,
+        repos: &[Repo],
+        turn: i64,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let action = ctx.execution_process.executor_action()?;
+        let executor_profile_id = action
+            .executor_profile_id()
+            .cloned()
+            .ok_or_else(|| anyhow!("Execution process is not a coding agent turn"))?;
+        let prompt = build_commit_message_prompt(
+            &repos
+                .iter()
+                .map(|repo| repo.display_name.clone())
+                .collect::<Vec<_>>(),
+            repos.iter().any(|repo| repo.conventional_commits),
+        );
+
+        let working_dir = ctx
+            .workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+        let action_type =
+            match CodingAgentTurn::find_latest_session_info(&self.db().pool, ctx.session.id).await?
+            {
+                Some(info) => {
+                    ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                        prompt,
+                        session_id: info.session_id,
+                        reset_to_message_id: None,
+                        executor_profile_id,
+                        working_dir,
+                    })
+                }
+                None => ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                    prompt,
+                    executor_profile_id,
+                    working_dir,
+                    fallback_profiles: None,
+                }),
+            };
+        let action = ExecutorAction::new(action_type, action.next_action().cloned().map(Box::new));
+
+        // Recorded first, so even a run that finishes right away commits the turn
+        let execution_process_id = Uuid::new_v4();
+        CommitMessageRun::create(
+            &self.db().pool,
+            execution_process_id,
+            ctx.execution_process.id,
+            turn,
+        )
+        .await?;
+        let started = self
+            .start_execution_with_id(
+                &ctx.workspace,
+                &ctx.session,
+                &action,
+                &ExecutionProcessRunReason::CodingAgent,
+                execution_process_id,
+                true,
+            )
+            .await;
+        if started.is_err() {
+            let _ = CommitMessageRun::delete(&self.db().pool, execution_process_id).await;
+        }
+        started
+    }
 }
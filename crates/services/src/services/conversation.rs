@@ -0,0 +1,53 @@
+//! Reading the normalized conversation of a coding agent run back from its
+//! log messages.
+
+use std::collections::BTreeMap;
+
+use executors::logs::{NormalizedEntry, utils::patch::extract_normalized_entry_from_patch};
+use utils::log_msg::LogMsg;
+
+/// The latest version of each conversation entry in `history`, by index
+pub fn latest_entries(history: &[LogMsg]) -> BTreeMap<usize, NormalizedEntry> {
+    history
+        .iter()
+        .filter_map(|msg| match msg {
+            LogMsg::JsonPatch(patch) => extract_normalized_entry_from_patch(patch),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Builders for the conversations the fallback and handoff tests run on
+#[cfg(test)]
+pub(crate) mod test_builders {
+    use executors::logs::{
+        ActionType, NormalizedEntry, NormalizedEntryType, ToolStatus,
+        utils::patch::ConversationPatch,
+    };
+    use utils::log_msg::LogMsg;
+
+    pub(crate) fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    pub(crate) fn tool(action_type: ActionType) -> NormalizedEntryType {
+        NormalizedEntryType::ToolUse {
+            tool_name: "tool".to_string(),
+            action_type,
+            status: ToolStatus::Success,
+        }
+    }
+
+    pub(crate) fn history(entries: Vec<NormalizedEntry>) -> Vec<LogMsg> {
+        entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(i, entry)))
+            .collect()
+    }
+}
@@ -0,0 +1,268 @@
+//! Handing a coding agent run that hit a rate limit or failed to authenticate
+//! to the next profile of its fallback chain.
+
+use executors::{
+    logs::{ActionType, NormalizedEntryError, NormalizedEntryType},
+    profile::ExecutorProfileId,
+};
+use utils::{log_msg::LogMsg, text::truncate_to_char_boundary};
+
+use crate::services::conversation::latest_entries;
+
+/// Files and commands listed in the progress summary; the rest are only counted
+const MAX_SUMMARY_ITEMS: usize = 20;
+const MAX_LAST_MESSAGE_BYTES: usize = 4096;
+
+/// Why a run is continued with a fallback profile
+#[derive(Debug, Clone, PartialEq)]
+pub enum FallbackReason {
+    RateLimited(String),
+    AuthFailed(String),
+}
+
+impl FallbackReason {
+    /// The first rate limit or authentication error the run's logs report
+    pub fn from_history(history: &[LogMsg]) -> Option<Self> {
+        latest_entries(history)
+            .into_values()
+            .find_map(|entry| match entry.entry_type {
+                NormalizedEntryType::ErrorMessage {
+                    error_type: NormalizedEntryError::RateLimited,
+                } => Some(Self::RateLimited(entry.content)),
+                NormalizedEntryType::ErrorMessage {
+                    error_type: NormalizedEntryError::SetupRequired,
+                } => Some(Self::AuthFailed(entry.content)),
+                _ => None,
+            })
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::RateLimited(message) => format!("hit a rate limit ({})", message.trim()),
+            Self::AuthFailed(message) => format!("failed to authenticate ({})", message.trim()),
+        }
+    }
+}
+
+fn push_list(summary: &mut String, title: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    summary.push_str(&format!("{title}:\n"));
+    for item in items.iter().take(MAX_SUMMARY_ITEMS) {
+        summary.push_str(&format!("- {item}\n"));
+    }
+    if items.len() > MAX_SUMMARY_ITEMS {
+        summary.push_str(&format!(
+            "- ... and {} more\n",
+            items.len() - MAX_SUMMARY_ITEMS
+        ));
+    }
+}
+
+/// What a run got done before it stopped: the files it changed, the commands
+/// it ran and its last message
+pub fn progress_summary(history: &[LogMsg]) -> Option<String> {
+    let mut files: Vec<String> = Vec::new();
+    let mut commands = Vec::new();
+    let mut last_message = None;
+
+    for entry in latest_entries(history).into_values() {
+        match entry.entry_type {
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::FileEdit { path, .. },
+                ..
+            } if !files.contains(&path) => files.push(path),
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::CommandRun { command, .. },
+                ..
+            } => commands.push(command),
+            NormalizedEntryType::AssistantMessage if !entry.content.trim().is_empty() => {
+                last_message = Some(entry.content)
+            }
+            _ => {}
+        }
+    }
+
+    let mut summary = String::new();
+    push_list(&mut summary, "Files changed", &files);
+    push_list(&mut summary, "Commands run", &commands);
+    if let Some(message) = last_message {
+        let message = message.trim();
+        let truncated = truncate_to_char_boundary(message, MAX_LAST_MESSAGE_BYTES);
+        summary.push_str("Last message:\n");
+        summary.push_str(truncated);
+        if truncated.len() < message.len() {
+            summary.push_str("...");
+        }
+        summary.push('\n');
+    }
+
+    let summary = summary.trim_end();
+    (!summary.is_empty()).then(|| summary.to_string())
+}
+
+/// Prompt continuing `prompt` with a fallback profile after the run of `from`
+/// stopped for `reason`
+pub fn build_fallback_prompt(
+    prompt: &str,
+    from: &ExecutorProfileId,
+    reason: &FallbackReason,
+    progress: Option<&str>,
+) -> String {
+    let mut fallback_prompt = format!(
+        "{}\n\n---\nThis task was started with {from}, which {}. Continue it from where it left off; its changes are already in the working tree.",
+        prompt.trim(),
+        reason.describe()
+    );
+    match progress {
+        Some(progress) => {
+            fallback_prompt.push_str("\n\nProgress so far:\n");
+            fallback_prompt.push_str(progress);
+        }
+        None => fallback_prompt.push_str("\n\nIt made no progress before stopping."),
+    }
+    fallback_prompt
+}
+
+/// What the agent continuing a follow-up turn of `from` with a fallback
+/// profile is asked to do. A follow-up's prompt means little on its own, so
+/// that agent is handed the whole session, as [`build_handoff_prompt`] lays
+/// it out, ending with these instructions.
+///
+/// [`build_handoff_prompt`]: crate::services::handoff::build_handoff_prompt
+pub fn follow_up_fallback_instructions(
+    from: &ExecutorProfileId,
+    reason: &FallbackReason,
+) -> String {
+    format!(
+        "{from} {} during the last turn. Continue the task from where it left off.",
+        reason.describe()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::executors::BaseCodingAgent;
+
+    use super::*;
+    use crate::services::{
+        conversation::test_builders::{entry, history, tool},
+        handoff::{HandoffTurn, build_handoff_prompt},
+    };
+
+    #[test]
+    fn rate_limit_is_found_and_progress_summarized() {
+        let history = history(vec![
+            entry(NormalizedEntryType::AssistantMessage, "Looking into it."),
+            entry(
+                tool(ActionType::FileEdit {
+                    path: "src/lib.rs".to_string(),
+                    changes: vec![],
+                }),
+                "src/lib.rs",
+            ),
+            entry(
+                tool(ActionType::CommandRun {
+                    command: "cargo test".to_string(),
+                    result: None,
+                }),
+                "cargo test",
+            ),
+            entry(
+                NormalizedEntryType::AssistantMessage,
+                "Fixed the parser, tests remain.",
+            ),
+            entry(
+                NormalizedEntryType::ErrorMessage {
+                    error_type: NormalizedEntryError::RateLimited,
+                },
+                "Claude AI usage limit reached",
+            ),
+        ]);
+
+        let reason = FallbackReason::from_history(&history).unwrap();
+        assert_eq!(
+            reason,
+            FallbackReason::RateLimited("Claude AI usage limit reached".to_string())
+        );
+        let progress = progress_summary(&history).unwrap();
+        assert_eq!(
+            progress,
+            "Files changed:\n- src/lib.rs\nCommands run:\n- cargo test\nLast message:\nFixed the parser, tests remain."
+        );
+
+        let prompt = build_fallback_prompt(
+            "Fix the parser",
+            &ExecutorProfileId::with_variant(BaseCodingAgent::ClaudeCode, "OPUS".to_string()),
+            &reason,
+            Some(&progress),
+        );
+        assert!(prompt.starts_with(
+            "Fix the parser\n\n---\nThis task was started with CLAUDE_CODE:OPUS, which hit a rate limit (Claude AI usage limit reached)."
+        ));
+        assert!(prompt.ends_with(&progress));
+    }
+
+    #[test]
+    fn other_errors_are_no_reason_to_fall_back() {
+        let history = history(vec![entry(
+            NormalizedEntryType::ErrorMessage {
+                error_type: NormalizedEntryError::Other,
+            },
+            "error: could not compile",
+        )]);
+
+        assert_eq!(FallbackReason::from_history(&history), None);
+        assert_eq!(progress_summary(&history), None);
+    }
+
+    #[test]
+    fn follow_up_fallback_recounts_the_session() {
+        let from = ExecutorProfileId::new(BaseCodingAgent::ClaudeCode);
+        let reason = FallbackReason::RateLimited("usage limit reached".to_string());
+        let turns = [
+            HandoffTurn {
+                prompt: "Fix the parser".to_string(),
+                history: history(vec![entry(
+                    NormalizedEntryType::AssistantMessage,
+                    "Fixed the parser.",
+                )]),
+            },
+            HandoffTurn {
+                prompt: "Now add tests".to_string(),
+                history: history(vec![entry(
+                    NormalizedEntryType::ErrorMessage {
+                        error_type: NormalizedEntryError::RateLimited,
+                    },
+                    "usage limit reached",
+                )]),
+            },
+        ];
+
+        let prompt = build_handoff_prompt(
+            &from,
+            &turns,
+            None,
+            Some(&follow_up_fallback_instructions(&from, &reason)),
+        );
+        assert!(prompt.contains("\nUser:\nFix the parser\n\nAgent:\nFixed the parser.\n"));
+        assert!(prompt.contains("\nUser:\nNow add tests\n"));
+        assert!(prompt.ends_with(
+            "CLAUDE_CODE hit a rate limit (usage limit reached) during the last turn. Continue the task from where it left off."
+        ));
+    }
+
+    #[test]
+    fn fallback_profiles_parse() {
+        assert_eq!(
+            "claude-code:sonnet".parse::<ExecutorProfileId>().unwrap(),
+            ExecutorProfileId::with_variant(BaseCodingAgent::ClaudeCode, "SONNET".to_string())
+        );
+        assert_eq!(
+            "CODEX".parse::<ExecutorProfileId>().unwrap(),
+            ExecutorProfileId::new(BaseCodingAgent::Codex)
+        );
+        assert!("NOT_AN_AGENT".parse::<ExecutorProfileId>().is_err());
+    }
+}
@@ -0,0 +1,316 @@
+//! Continuing a session with a different coding agent. Agents can only resume
+//! their own sessions, so the new agent starts fresh with a prompt recounting
+//! the conversation so far and the changes already made.
+
+use executors::{
+    logs::{ActionType, NormalizedEntryType, TodoItem},
+    profile::ExecutorProfileId,
+};
+use git::GitService;
+use utils::{
+    diff::{Diff, create_unified_diff},
+    log_msg::LogMsg,
+    text::truncate_to_char_boundary,
+};
+
+use crate::services::conversation::latest_entries;
+
+/// Turns recounted in full; earlier ones are only counted
+const MAX_TURNS: usize = 10;
+const MAX_MESSAGE_BYTES: usize = 2048;
+const MAX_FILES: usize = 50;
+const MAX_DIFF_BYTES: usize = 32 * 1024;
+
+/// One coding agent turn of the session being handed off
+#[derive(Debug, Clone)]
+pub struct HandoffTurn {
+    /// The prompt the turn was started with
+    pub prompt: String,
+    /// The turn's normalized conversation patches
+    pub history: Vec<LogMsg>,
+}
+
+fn truncated(text: &str, max_len: usize) -> String {
+    let text = text.trim();
+    let cut = truncate_to_char_boundary(text, max_len);
+    if cut.len() < text.len() {
+        format!("{cut}...")
+    } else {
+        cut.to_string()
+    }
+}
+
+/// Render the workspace's changes as unified diffs, listing only the paths of
+/// files once the size budget is used up
+pub fn render_diff(diffs: &[Diff]) -> Option<String> {
+    let mut rendered = String::new();
+    let mut skipped = Vec::new();
+
+    for diff in diffs {
+        let path = GitService::diff_path(diff);
+        if diff.content_omitted {
+            skipped.push(path);
+            continue;
+        }
+        let file_diff = create_unified_diff(
+            &path,
+            diff.old_content.as_deref().unwrap_or_default(),
+            diff.new_content.as_deref().unwrap_or_default(),
+        );
+        if rendered.len() + file_diff.len() > MAX_DIFF_BYTES {
+            skipped.push(path);
+            continue;
+        }
+        rendered.push_str(&file_diff);
+    }
+
+    if !skipped.is_empty() {
+        rendered.push_str("Also changed (diff omitted):\n");
+        for path in skipped {
+            rendered.push_str(&format!("- {path}\n"));
+        }
+    }
+
+    let rendered = rendered.trim_end();
+    (!rendered.is_empty()).then(|| rendered.to_string())
+}
+
+/// Prompt handing the session's `turns`, run with `from`, to another agent.
+/// `diff` is the workspace's changes against its target branches as rendered
+/// by [`render_diff`] and `instructions` what the user wants done next, if
+/// anything.
+pub fn build_handoff_prompt(
+    from: &ExecutorProfileId,
+    turns: &[HandoffTurn],
+    diff: Option<&str>,
+    instructions: Option<&str>,
+) -> String {
+    let mut files: Vec<String> = Vec::new();
+    let mut todos: Option<Vec<TodoItem>> = None;
+    let mut replies = Vec::with_capacity(turns.len());
+
+    for turn in turns {
+        let mut reply = None;
+        for entry in latest_entries(&turn.history).into_values() {
+            match entry.entry_type {
+                NormalizedEntryType::ToolUse {
+                    action_type: ActionType::FileEdit { path, .. },
+                    ..
+                } if !files.contains(&path) => files.push(path),
+                NormalizedEntryType::ToolUse {
+                    action_type: ActionType::TodoManagement { todos: latest, .. },
+                    ..
+                } if !latest.is_empty() => todos = Some(latest),
+                NormalizedEntryType::AssistantMessage if !entry.content.trim().is_empty() => {
+                    reply = Some(entry.content)
+                }
+                _ => {}
+            }
+        }
+        replies.push(reply);
+    }
+
+    let mut prompt = format!(
+        "You are taking over a task from {from}, which worked on it in another session. Its changes are already in the workspace."
+    );
+
+    let skipped = turns.len().saturating_sub(MAX_TURNS);
+    if !turns.is_empty() {
+        prompt.push_str("\n\nConversation so far:\n");
+        if skipped > 0 {
+            prompt.push_str(&format!("({skipped} earlier turns omitted)\n"));
+        }
+    }
+    for (turn, reply) in turns.iter().zip(&replies).skip(skipped) {
+        prompt.push_str(&format!(
+            "\nUser:\n{}\n",
+            truncated(&turn.prompt, MAX_MESSAGE_BYTES)
+        ));
+        if let Some(reply) = reply {
+            prompt.push_str(&format!(
+                "\nAgent:\n{}\n",
+                truncated(reply, MAX_MESSAGE_BYTES)
+            ));
+        }
+    }
+
+    if !files.is_empty() {
+        prompt.push_str("\nFiles edited:\n");
+        for path in files.iter().take(MAX_FILES) {
+            prompt.push_str(&format!("- {path}\n"));
+        }
+        if files.len() > MAX_FILES {
+            prompt.push_str(&format!("- ... and {} more\n", files.len() - MAX_FILES));
+        }
+    }
+
+    if let Some(todos) = todos {
+        prompt.push_str("\nTodo list:\n");
+        for todo in todos {
+            prompt.push_str(&format!("- [{}] {}\n", todo.status, todo.content));
+        }
+    }
+
+    match diff {
+        Some(diff) => {
+            prompt.push_str("\nCurrent changes:\n```diff\n");
+            prompt.push_str(diff);
+            prompt.push_str("\n```\n");
+        }
+        None => prompt.push_str("\nThe workspace has no changes yet.\n"),
+    }
+
+    prompt.push_str("\n---\n");
+    match instructions.map(str::trim).filter(|text| !text.is_empty()) {
+        Some(instructions) => prompt.push_str(instructions),
+        None => prompt.push_str("Continue the task from where it left off."),
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::{executors::BaseCodingAgent, logs::NormalizedEntry};
+    use utils::diff::DiffChangeKind;
+
+    use super::*;
+    use crate::services::conversation::test_builders::{entry, history, tool};
+
+    fn turn(prompt: &str, entries: Vec<NormalizedEntry>) -> HandoffTurn {
+        HandoffTurn {
+            prompt: prompt.to_string(),
+            history: history(entries),
+        }
+    }
+
+    fn todo(content: &str, status: &str) -> TodoItem {
+        TodoItem {
+            content: content.to_string(),
+            status: status.to_string(),
+            priority: None,
+        }
+    }
+
+    fn modified(path: &str, old: &str, new: &str) -> Diff {
+        Diff {
+            change: DiffChangeKind::Modified,
+            old_path: Some(path.to_string()),
+            new_path: Some(path.to_string()),
+            old_content: Some(old.to_string()),
+            new_content: Some(new.to_string()),
+            content_omitted: false,
+            additions: None,
+            deletions: None,
+            repo_id: None,
+        }
+    }
+
+    #[test]
+    fn handoff_prompt_recounts_turns_files_and_todos() {
+        let turns = vec![
+            turn(
+                "Add pagination to the API",
+                vec![
+                    entry(NormalizedEntryType::AssistantMessage, "Let me look."),
+                    entry(
+                        tool(ActionType::TodoManagement {
+                            todos: vec![todo("Add cursor type", "pending")],
+                            operation: "write".to_string(),
+                        }),
+                        "",
+                    ),
+                    entry(
+                        tool(ActionType::FileEdit {
+                            path: "api/list.rs".to_string(),
+                            changes: vec![],
+                        }),
+                        "api/list.rs",
+                    ),
+                    entry(
+                        NormalizedEntryType::AssistantMessage,
+                        "Added a cursor to the list endpoint.",
+                    ),
+                ],
+            ),
+            turn(
+                "Now update the client",
+                vec![entry(
+                    tool(ActionType::TodoManagement {
+                        todos: vec![
+                            todo("Add cursor type", "completed"),
+                            todo("Update client", "in_progress"),
+                        ],
+                        operation: "write".to_string(),
+                    }),
+                    "",
+                )],
+            ),
+        ];
+
+        let prompt = build_handoff_prompt(
+            &ExecutorProfileId::new(BaseCodingAgent::Codex),
+            &turns,
+            Some("--- a/api/list.rs\n+++ b/api/list.rs"),
+            None,
+        );
+
+        assert_eq!(
+            prompt,
+            "You are taking over a task from CODEX, which worked on it in another session. Its changes are already in the workspace.\n\
+             \n\
+             Conversation so far:\n\
+             \n\
+             User:\n\
+             Add pagination to the API\n\
+             \n\
+             Agent:\n\
+             Added a cursor to the list endpoint.\n\
+             \n\
+             User:\n\
+             Now update the client\n\
+             \n\
+             Files edited:\n\
+             - api/list.rs\n\
+             \n\
+             Todo list:\n\
+             - [completed] Add cursor type\n\
+             - [in_progress] Update client\n\
+             \n\
+             Current changes:\n\
+             ```diff\n\
+             --- a/api/list.rs\n\
+             +++ b/api/list.rs\n\
+             ```\n\
+             \n\
+             ---\n\
+             Continue the task from where it left off."
+        );
+    }
+
+    #[test]
+    fn instructions_replace_the_default_ending() {
+        let prompt = build_handoff_prompt(
+            &ExecutorProfileId::with_variant(BaseCodingAgent::ClaudeCode, "PLAN".to_string()),
+            &[],
+            None,
+            Some("  Review the changes first. "),
+        );
+
+        assert!(prompt.contains("The workspace has no changes yet."));
+        assert!(prompt.ends_with("---\nReview the changes first."));
+        assert!(!prompt.contains("Conversation so far"));
+    }
+
+    #[test]
+    fn diff_lists_omitted_files() {
+        let mut large = modified("assets/big.json", "", "");
+        large.content_omitted = true;
+
+        let diff = render_diff(&[modified("src/lib.rs", "a\n", "b\n"), large]).unwrap();
+
+        assert!(diff.starts_with("--- a/src/lib.rs\n+++ b/src/lib.rs\n@@"));
+        assert!(diff.contains("-a\n+b\n"));
+        assert!(diff.ends_with("Also changed (diff omitted):\n- assets/big.json"));
+        assert_eq!(render_diff(&[]), None);
+    }
+}
@@ -1,15 +1,21 @@
 pub mod analytics;
 pub mod approvals;
 pub mod auth;
+pub mod auto_rebase;
+pub mod commit_message;
 pub mod config;
+pub mod conflict_resolution;
 pub mod container;
+pub mod conversation;
 pub mod diff_stream;
 pub mod events;
+pub mod fallback;
 pub mod file_ranker;
 pub mod file_search;
 pub mod filesystem;
 pub mod filesystem_watcher;
 pub mod git_host;
+pub mod handoff;
 pub mod image;
 pub mod migration;
 pub mod notification;
@@ -22,5 +28,8 @@ pub mod queued_message;
 pub mod remote_client;
 pub mod remote_sync;
 pub mod repo;
+pub mod scheduler;
+pub mod stacked_branches;
 pub mod workspace_manager;
+pub mod workspace_ports;
 pub mod worktree_manager;
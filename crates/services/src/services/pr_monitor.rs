@@ -6,10 +6,13 @@ use db::{
     DBService,
     models::{
         merge::{Merge, MergeStatus, PrMerge},
+        repo::Repo,
         task::{Task, TaskStatus},
         workspace::{Workspace, WorkspaceError},
+        workspace_repo::WorkspaceRepo,
     },
 };
+use git2::BranchType;
 use serde_json::json;
 use sqlx::error::Error as SqlxError;
 use thiserror::Error;
@@ -22,6 +25,7 @@ use crate::services::{
     git_host::{self, GitHostError, GitHostProvider},
     remote_client::RemoteClient,
     remote_sync,
+    stacked_branches::StackedBranches,
 };
 
 #[derive(Debug, Error)]
@@ -100,6 +104,40 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
         Ok(())
     }
 
+    /// Point workspaces stacked on a merged PR's branch at the PR's target.
+    /// They are only rebased when the target is a remote branch, which is
+    /// fetched first; a local target doesn't contain the merge yet.
+    async fn retarget_stacked_children(&self, workspace: &Workspace, pr_merge: &PrMerge) {
+        let pool = &self.db.pool;
+        let (Ok(Some(repo)), Ok(Some(workspace_repo))) = (
+            Repo::find_by_id(pool, pr_merge.repo_id).await,
+            WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, pr_merge.repo_id)
+                .await,
+        ) else {
+            return;
+        };
+        let git = self.container.git();
+        let target_is_remote = git
+            .find_branch_type(&repo.path, &workspace_repo.target_branch)
+            .is_ok_and(|branch_type| branch_type == BranchType::Remote);
+        if let Err(e) = StackedBranches::retarget_children_after_merge(
+            pool,
+            git,
+            self.container.scheduler(),
+            workspace,
+            &repo,
+            &workspace_repo.target_branch,
+            target_is_remote,
+        )
+        .await
+        {
+            error!(
+                "Failed to retarget workspaces stacked on {}: {}",
+                workspace.id, e
+            );
+        }
+    }
+
     /// Check the status of a specific PR
     async fn check_pr_status(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
         let git_host = git_host::GitHostService::from_url(&pr_merge.pr_info.url)?;
@@ -134,6 +172,7 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
                     pr_merge.pr_info.number, workspace.task_id
                 );
                 Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
+                self.retarget_stacked_children(&workspace, pr_merge).await;
                 if !workspace.pinned
                     && let Err(e) = self.container.archive_workspace(workspace.id).await
                 {
@@ -0,0 +1,252 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use db::models::execution_process::{ExecutionProcess, ExecutionProcessRunReason};
+use executors::executors::BaseCodingAgent;
+use sqlx::SqlitePool;
+use tokio::sync::{Mutex, MutexGuard, OwnedMutexGuard};
+use uuid::Uuid;
+
+use crate::services::config::ConcurrencyConfig;
+
+/// Admission control for coding agent executions.
+///
+/// Coding agents that would exceed the configured limits are stored with the
+/// `queued` status and started oldest-first as running agents finish. The
+/// admission lock serialises the "count running, then mark started" step so
+/// two starts can't both take the last slot.
+///
+/// Each workspace also has a lock that is held while a new execution is
+/// recorded, so work that must not overlap one (e.g. an automatic rebase) can
+/// check the workspace is idle and finish before anything else starts there.
+#[derive(Clone, Default)]
+pub struct ExecutionScheduler {
+    admission: Arc<Mutex<()>>,
+    workspaces: WorkspaceLocks,
+    paused: Arc<AtomicBool>,
+}
+
+type WorkspaceLocks = Arc<std::sync::Mutex<HashMap<Uuid, Arc<Mutex<()>>>>>;
+
+/// Holds a workspace's lock. The workspace's entry is dropped from the
+/// scheduler with the last guard, so idle workspaces don't keep a mutex around.
+pub struct WorkspaceGuard {
+    guard: Option<OwnedMutexGuard<()>>,
+    lock: Arc<Mutex<()>>,
+    workspaces: WorkspaceLocks,
+    workspace_id: Uuid,
+}
+
+impl Drop for WorkspaceGuard {
+    fn drop(&mut self) {
+        // Waiters clone the lock under the map lock, so nobody else holds it
+        // when only the map and this guard do
+        let mut workspaces = self.workspaces.lock().unwrap();
+        self.guard.take();
+        if Arc::strong_count(&self.lock) == 2 {
+            workspaces.remove(&self.workspace_id);
+        }
+    }
+}
+
+impl ExecutionScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn lock(&self) -> MutexGuard<'_, ()> {
+        self.admission.lock().await
+    }
+
+    pub async fn lock_workspace(&self, workspace_id: Uuid) -> WorkspaceGuard {
+        let lock = self
+            .workspaces
+            .lock()
+            .unwrap()
+            .entry(workspace_id)
+            .or_default()
+            .clone();
+        let guard = lock.clone().lock_owned().await;
+        WorkspaceGuard {
+            guard: Some(guard),
+            lock,
+            workspaces: self.workspaces.clone(),
+            workspace_id,
+        }
+    }
+
+    /// Stop starting queued executions, e.g. while shutting down. Queued
+    /// processes stay in the database and are picked up on the next start.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Executors of the coding agents currently running
+    pub async fn running_agents(pool: &SqlitePool) -> Result<Vec<BaseCodingAgent>, sqlx::Error> {
+        Ok(ExecutionProcess::find_running(pool)
+            .await?
+            .into_iter()
+            .filter(|process| process.run_reason == ExecutionProcessRunReason::CodingAgent)
+            .filter_map(|process| process.executor_action().ok()?.base_executor())
+            .collect())
+    }
+
+    /// Ids and executors of the queued coding agents, oldest first
+    pub async fn queued_agents(
+        pool: &SqlitePool,
+    ) -> Result<Vec<(Uuid, BaseCodingAgent)>, sqlx::Error> {
+        Ok(ExecutionProcess::find_queued(pool)
+            .await?
+            .into_iter()
+            .filter_map(|process| {
+                Some((process.id, process.executor_action().ok()?.base_executor()?))
+            })
+            .collect())
+    }
+
+    /// Whether `id` is the oldest queued `executor` agent, so starting it does
+    /// not overtake an earlier request for the same executor
+    pub fn is_next_in_line(
+        queued: &[(Uuid, BaseCodingAgent)],
+        id: Uuid,
+        executor: BaseCodingAgent,
+    ) -> bool {
+        queued
+            .iter()
+            .find(|(_, queued_executor)| *queued_executor == executor)
+            .is_none_or(|(queued_id, _)| *queued_id == id)
+    }
+
+    /// Whether another `executor` agent fits next to the `running` ones
+    pub fn has_slot(
+        limits: &ConcurrencyConfig,
+        running: &[BaseCodingAgent],
+        executor: BaseCodingAgent,
+    ) -> bool {
+        if limits
+            .max_running_agents
+            .is_some_and(|max| running.len() >= max as usize)
+        {
+            return false;
+        }
+        match limits.per_executor.get(&executor) {
+            Some(max) => running.iter().filter(|e| **e == executor).count() < *max as usize,
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt;
+
+    use super::*;
+
+    #[test]
+    fn global_and_per_executor_limits() {
+        let limits = ConcurrencyConfig {
+            max_running_agents: Some(3),
+            per_executor: HashMap::from([(BaseCodingAgent::ClaudeCode, 1)]),
+        };
+        let running = [BaseCodingAgent::ClaudeCode, BaseCodingAgent::Codex];
+
+        assert!(!ExecutionScheduler::has_slot(
+            &limits,
+            &running,
+            BaseCodingAgent::ClaudeCode
+        ));
+        assert!(ExecutionScheduler::has_slot(
+            &limits,
+            &running,
+            BaseCodingAgent::Codex
+        ));
+        assert!(!ExecutionScheduler::has_slot(
+            &limits,
+            &[running.as_slice(), &[BaseCodingAgent::Gemini]].concat(),
+            BaseCodingAgent::Codex
+        ));
+        assert!(ExecutionScheduler::has_slot(
+            &ConcurrencyConfig::default(),
+            &running,
+            BaseCodingAgent::ClaudeCode
+        ));
+    }
+
+    #[tokio::test]
+    async fn busy_workspace_lock_only_blocks_that_workspace() {
+        let scheduler = ExecutionScheduler::new();
+        let (busy, other) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let guard = scheduler.lock_workspace(busy).await;
+        assert!(scheduler.lock_workspace(busy).now_or_never().is_none());
+        assert!(scheduler.lock_workspace(other).now_or_never().is_some());
+
+        drop(guard);
+        assert!(scheduler.lock_workspace(busy).now_or_never().is_some());
+    }
+
+    #[tokio::test]
+    async fn workspace_lock_is_forgotten_after_the_last_guard() {
+        let scheduler = ExecutionScheduler::new();
+        let workspace = Uuid::new_v4();
+
+        let first = scheduler.lock_workspace(workspace).await;
+        let waiter = {
+            let scheduler = scheduler.clone();
+            tokio::spawn(async move { scheduler.lock_workspace(workspace).await })
+        };
+        tokio::task::yield_now().await;
+        drop(first);
+        let second = waiter.await.unwrap();
+        assert!(
+            scheduler
+                .workspaces
+                .lock()
+                .unwrap()
+                .contains_key(&workspace)
+        );
+
+        drop(second);
+        assert!(scheduler.workspaces.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn only_the_oldest_queued_agent_of_an_executor_is_next() {
+        let (first, second, other) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let queued = [
+            (first, BaseCodingAgent::ClaudeCode),
+            (other, BaseCodingAgent::Codex),
+            (second, BaseCodingAgent::ClaudeCode),
+        ];
+
+        assert!(ExecutionScheduler::is_next_in_line(
+            &queued,
+            first,
+            BaseCodingAgent::ClaudeCode
+        ));
+        assert!(!ExecutionScheduler::is_next_in_line(
+            &queued,
+            second,
+            BaseCodingAgent::ClaudeCode
+        ));
+        assert!(ExecutionScheduler::is_next_in_line(
+            &queued,
+            other,
+            BaseCodingAgent::Codex
+        ));
+        assert!(ExecutionScheduler::is_next_in_line(
+            &[],
+            second,
+            BaseCodingAgent::Gemini
+        ));
+    }
+}
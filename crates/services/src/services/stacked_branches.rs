@@ -0,0 +1,198 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use db::models::{
+    execution_process::ExecutionProcess, repo::Repo, workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use git::GitService;
+use sqlx::SqlitePool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::services::scheduler::ExecutionScheduler;
+
+/// Keeps workspaces stacked on another workspace's branch in step with it.
+///
+/// A workspace repo is stacked when its target branch is the branch of another
+/// active workspace (`WorkspaceRepo::base_workspace_id`). Children that are
+/// running, dirty or stopped mid-rebase are skipped and show as behind their
+/// target instead. A child is rebased under its workspace lock, so no agent or
+/// automatic rebase can start there until it is done.
+pub struct StackedBranches;
+
+impl StackedBranches {
+    /// Rebase the workspaces stacked on `parent` in `repo` onto the parent's
+    /// branch tip, then their own children in turn
+    pub async fn restack_children(
+        pool: &SqlitePool,
+        git: &GitService,
+        scheduler: &ExecutionScheduler,
+        parent: &Workspace,
+        repo: &Repo,
+    ) -> Result<(), sqlx::Error> {
+        let mut visited = HashSet::from([parent.id]);
+        let mut bases = vec![parent.clone()];
+        while let Some(base) = bases.pop() {
+            for child_repo in WorkspaceRepo::find_stacked_children(pool, base.id, repo.id).await? {
+                if !visited.insert(child_repo.workspace_id) {
+                    continue;
+                }
+                let Some(child) = Workspace::find_by_id(pool, child_repo.workspace_id).await?
+                else {
+                    continue;
+                };
+                // The child's own commits start at the base's tip from before
+                // it was rebased, which the base branch's reflog still has
+                let fork_point = match git.get_fork_point(&repo.path, &base.branch, &child.branch) {
+                    Ok(fork_point) => fork_point,
+                    Err(e) => {
+                        warn!(
+                            "Failed to find where stacked workspace {} forked from {}: {}",
+                            child.id, base.branch, e
+                        );
+                        continue;
+                    }
+                };
+                if Self::rebase_child(
+                    pool,
+                    git,
+                    scheduler,
+                    &child,
+                    repo,
+                    &base.branch,
+                    &fork_point,
+                )
+                .await?
+                {
+                    bases.push(child);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Every active workspace stacked on `workspace` in `repo_id`, directly or
+    /// through another stacked workspace
+    pub async fn descendants(
+        pool: &SqlitePool,
+        workspace: &Workspace,
+        repo_id: Uuid,
+    ) -> Result<Vec<Workspace>, sqlx::Error> {
+        let mut visited = HashSet::from([workspace.id]);
+        let mut pending = vec![workspace.id];
+        let mut found = Vec::new();
+        while let Some(id) = pending.pop() {
+            for child_repo in WorkspaceRepo::find_stacked_children(pool, id, repo_id).await? {
+                if visited.insert(child_repo.workspace_id)
+                    && let Some(child) =
+                        Workspace::find_by_id(pool, child_repo.workspace_id).await?
+                {
+                    pending.push(child.id);
+                    found.push(child);
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// After `parent` landed on `target_branch`, point the workspaces stacked
+    /// on it at `target_branch`. With `rebase`, also replay only their own
+    /// commits onto it; pass false when the local `target_branch` doesn't
+    /// contain the merge yet.
+    pub async fn retarget_children_after_merge(
+        pool: &SqlitePool,
+        git: &GitService,
+        scheduler: &ExecutionScheduler,
+        parent: &Workspace,
+        repo: &Repo,
+        target_branch: &str,
+        rebase: bool,
+    ) -> Result<(), sqlx::Error> {
+        for child_repo in WorkspaceRepo::find_stacked_children(pool, parent.id, repo.id).await? {
+            let Some(child) = Workspace::find_by_id(pool, child_repo.workspace_id).await? else {
+                continue;
+            };
+            WorkspaceRepo::update_target_branch(pool, child.id, repo.id, target_branch).await?;
+            info!(
+                "Retargeted stacked workspace {} ({}) from {} to {}",
+                child.id, repo.name, parent.branch, target_branch
+            );
+
+            // The parent's branch still marks where the child's own commits start
+            if rebase
+                && Self::rebase_child(
+                    pool,
+                    git,
+                    scheduler,
+                    &child,
+                    repo,
+                    target_branch,
+                    &parent.branch,
+                )
+                .await?
+            {
+                Self::restack_children(pool, git, scheduler, &child, repo).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Rebase `child` onto `new_base` if it is idle and behind. Returns true if
+    /// the child's branch moved.
+    async fn rebase_child(
+        pool: &SqlitePool,
+        git: &GitService,
+        scheduler: &ExecutionScheduler,
+        child: &Workspace,
+        repo: &Repo,
+        new_base: &str,
+        old_base: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let Some(container_ref) = child.container_ref.as_ref() else {
+            return Ok(false);
+        };
+        let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+        let _workspace_guard = scheduler.lock_workspace(child.id).await;
+        if !worktree_path.exists()
+            || ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, child.id)
+                .await?
+        {
+            return Ok(false);
+        }
+
+        match git.get_branch_status(&repo.path, &child.branch, new_base) {
+            Ok((_, 0)) => return Ok(false),
+            Ok(_) => {}
+            Err(e) => {
+                warn!(
+                    "Failed to compare stacked workspace {} with {}: {}",
+                    child.id, new_base, e
+                );
+                return Ok(false);
+            }
+        }
+
+        match git.rebase_branch(
+            &repo.path,
+            &worktree_path,
+            new_base,
+            old_base,
+            &child.branch,
+        ) {
+            Ok(head) => {
+                info!(
+                    "Rebased stacked workspace {} ({}) onto {}, now at {}",
+                    child.id, repo.name, new_base, head
+                );
+                Ok(true)
+            }
+            Err(e) => {
+                warn!(
+                    "Could not rebase stacked workspace {} ({}) onto {}: {}",
+                    child.id, repo.name, new_base, e
+                );
+                Ok(false)
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::{collections::HashSet, net::TcpListener};
+
+use db::models::workspace_port::WorkspacePort;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::services::config::PortAllocationConfig;
+
+/// The ports reserved for a workspace, in slot order, reserving missing ones
+/// from the configured range. A port is only reserved if no other workspace
+/// holds it and nothing on this machine listens on it. Reserved ports are
+/// kept until the workspace is deleted, even while something else uses them.
+///
+/// Returns fewer ports than configured when the range runs out.
+pub async fn ensure_workspace_ports(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+    config: &PortAllocationConfig,
+) -> Result<Vec<u16>, sqlx::Error> {
+    let existing = WorkspacePort::find_by_workspace_id(pool, workspace_id).await?;
+    let mut taken: HashSet<u16> = WorkspacePort::find_all_ports(pool)
+        .await?
+        .into_iter()
+        .filter_map(|port| u16::try_from(port).ok())
+        .collect();
+    let mut ports = Vec::with_capacity(config.ports_per_workspace as usize);
+
+    for slot in 0..i64::from(config.ports_per_workspace) {
+        if let Some(port) = existing
+            .iter()
+            .find(|reserved| reserved.slot == slot)
+            .and_then(|reserved| u16::try_from(reserved.port).ok())
+        {
+            ports.push(port);
+            continue;
+        }
+
+        loop {
+            let Some(port) = next_free_port(config, &taken, is_port_free) else {
+                tracing::warn!(
+                    "No free port left in {}-{} for workspace {}",
+                    config.range_start,
+                    config.range_end,
+                    workspace_id
+                );
+                return Ok(ports);
+            };
+            taken.insert(port);
+            match WorkspacePort::reserve(pool, workspace_id, slot, i64::from(port)).await {
+                // A concurrent start may have reserved the slot with another port
+                Ok(reserved) => {
+                    ports.push(u16::try_from(reserved.port).unwrap_or(port));
+                    break;
+                }
+                // Reserved by another workspace in the meantime
+                Err(e)
+                    if e.as_database_error()
+                        .is_some_and(|db_err| db_err.is_unique_violation()) => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    Ok(ports)
+}
+
+fn next_free_port(
+    config: &PortAllocationConfig,
+    taken: &HashSet<u16>,
+    is_free: impl Fn(u16) -> bool,
+) -> Option<u16> {
+    (config.range_start..=config.range_end).find(|port| !taken.contains(port) && is_free(*port))
+}
+
+fn is_port_free(port: u16) -> bool {
+    TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+
+    use super::*;
+
+    /// An in-memory database without foreign keys, so ports can be reserved
+    /// for workspaces that don't exist
+    async fn test_pool() -> SqlitePool {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .unwrap();
+        sqlx::migrate!("../db/migrations").run(&pool).await.unwrap();
+        pool
+    }
+
+    fn test_config() -> PortAllocationConfig {
+        PortAllocationConfig {
+            enabled: true,
+            range_start: 47100,
+            range_end: 47199,
+            ports_per_workspace: 2,
+        }
+    }
+
+    #[tokio::test]
+    async fn reserved_ports_are_kept_and_not_shared() {
+        let pool = test_pool().await;
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let first_ports = ensure_workspace_ports(&pool, first, &test_config())
+            .await
+            .unwrap();
+        assert_eq!(first_ports.len(), 2);
+        assert_eq!(
+            ensure_workspace_ports(&pool, first, &test_config())
+                .await
+                .unwrap(),
+            first_ports
+        );
+
+        let second_ports = ensure_workspace_ports(&pool, second, &test_config())
+            .await
+            .unwrap();
+        assert_eq!(second_ports.len(), 2);
+        assert!(second_ports.iter().all(|port| !first_ports.contains(port)));
+    }
+
+    #[tokio::test]
+    async fn reserving_a_taken_slot_returns_the_existing_port() {
+        let pool = test_pool().await;
+        let workspace = Uuid::new_v4();
+
+        let reserved = WorkspacePort::reserve(&pool, workspace, 0, 47100)
+            .await
+            .unwrap();
+        assert_eq!(reserved.port, 47100);
+        // A concurrent start that picked another port for the same slot
+        let raced = WorkspacePort::reserve(&pool, workspace, 0, 47101)
+            .await
+            .unwrap();
+        assert_eq!(raced.port, 47100);
+        assert_eq!(
+            WorkspacePort::find_all_ports(&pool).await.unwrap(),
+            vec![47100]
+        );
+
+        // Another workspace can't take the port
+        let err = WorkspacePort::reserve(&pool, Uuid::new_v4(), 0, 47100)
+            .await
+            .unwrap_err();
+        assert!(
+            err.as_database_error()
+                .is_some_and(|db_err| db_err.is_unique_violation())
+        );
+    }
+
+    #[test]
+    fn next_free_port_skips_reserved_and_busy_ports() {
+        let config = PortAllocationConfig {
+            enabled: true,
+            range_start: 4100,
+            range_end: 4103,
+            ports_per_workspace: 1,
+        };
+        let taken = HashSet::from([4100]);
+        assert_eq!(
+            next_free_port(&config, &taken, |port| port != 4101),
+            Some(4102)
+        );
+        assert_eq!(next_free_port(&config, &taken, |_| false), None);
+    }
+}
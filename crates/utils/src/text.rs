@@ -40,6 +40,25 @@ pub fn truncate_to_char_boundary(content: &str, max_len: usize) -> &str {
     &content[..cutoff]
 }
 
+/// The last `max_lines` lines of terminal or process output as plain text.
+/// ANSI escape sequences are removed, lines redrawn with a carriage return
+/// (progress bars, spinners) keep only their final state and trailing blank
+/// lines are dropped.
+pub fn tail_lines(output: &str, max_lines: usize) -> String {
+    let mut lines: Vec<String> = output
+        .rsplit('\n')
+        .map(|line| {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let line = line.rsplit('\r').next().unwrap_or(line);
+            strip_ansi_escapes::strip_str(line).trim_end().to_string()
+        })
+        .skip_while(|line| line.is_empty())
+        .take(max_lines)
+        .collect();
+    lines.reverse();
+    lines.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -57,4 +76,14 @@ mod tests {
         assert_eq!(truncate_to_char_boundary(input, 5), "🔥");
         assert_eq!(truncate_to_char_boundary(input, 3), "");
     }
+
+    #[test]
+    fn test_tail_lines() {
+        use super::tail_lines;
+
+        let output = "one\r\ntwo\n\x1b[31mthree\x1b[0m\n 10%\r 50%\r100%\n\n\n";
+        assert_eq!(tail_lines(output, 3), "two\nthree\n100%");
+        assert_eq!(tail_lines(output, 10), "one\ntwo\nthree\n100%");
+        assert_eq!(tail_lines("\n\n", 5), "");
+    }
 }
@@ -40,6 +40,10 @@ export function getAgentName(
       return 'Droid';
     case BaseCodingAgent.PI:
       return 'Pi';
+    case BaseCodingAgent.AIDER:
+      return 'Aider';
+    case BaseCodingAgent.CUSTOM:
+      return 'Custom';
   }
 }
 
@@ -87,6 +91,9 @@ export function AgentIcon({ agent, className = 'h-4 w-4' }: AgentIconProps) {
     case BaseCodingAgent.PI:
       iconPath = `/agents/pi${suffix}.svg`;
       break;
+    case BaseCodingAgent.AIDER:
+      iconPath = `/agents/aider${suffix}.svg`;
+      break;
     default:
       return null;
   }
@@ -1,4 +1,4 @@
-import { useState, useEffect, useRef } from 'react';
+import { useState, useEffect, useRef, useMemo } from 'react';
 import { useParams } from 'react-router-dom';
 import { useTranslation } from 'react-i18next';
 import { Loader2, X, Wrench } from 'lucide-react';
@@ -7,7 +7,10 @@ import { useDevserverPreview } from '@/hooks/useDevserverPreview';
 import { useDevServer } from '@/hooks/useDevServer';
 import { useHasDevServerScript } from '@/hooks/useHasDevServerScript';
 import { useLogStream } from '@/hooks/useLogStream';
-import { useDevserverUrlFromLogs } from '@/hooks/useDevserverUrl';
+import {
+  detectedDevserverUrl,
+  useDevserverUrlFromLogs,
+} from '@/hooks/useDevserverUrl';
 import { ClickToComponentListener } from '@/utils/previewBridge';
 import { useClickedElements } from '@/contexts/ClickedElementsProvider';
 import { Alert } from '@/components/ui/alert';
@@ -50,7 +53,12 @@ export function PreviewPanel() {
 
   const primaryDevServer = runningDevServers[0];
   const logStream = useLogStream(primaryDevServer?.id ?? '');
-  const lastKnownUrl = useDevserverUrlFromLogs(logStream.logs);
+  const logUrl = useDevserverUrlFromLogs(logStream.logs);
+  const detectedUrl = useMemo(
+    () => detectedDevserverUrl(primaryDevServer),
+    [primaryDevServer]
+  );
+  const lastKnownUrl = detectedUrl ?? logUrl;
 
   const previewState = useDevserverPreview(attemptId, {
     projectHasDevScript,
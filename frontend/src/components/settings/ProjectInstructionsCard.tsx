@@ -0,0 +1,136 @@
+import { useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Loader2 } from 'lucide-react';
+import {
+  Card,
+  CardContent,
+  CardDescription,
+  CardHeader,
+  CardTitle,
+} from '@/components/ui/card';
+import { Button } from '@/components/ui/button';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { Textarea } from '@/components/ui/textarea';
+import { projectsApi } from '@/lib/api';
+
+interface ProjectInstructionsCardProps {
+  projectId: string;
+}
+
+export default function ProjectInstructionsCard({
+  projectId,
+}: ProjectInstructionsCardProps) {
+  const { t } = useTranslation('settings');
+  const [saved, setSaved] = useState('');
+  const [value, setValue] = useState('');
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [success, setSuccess] = useState(false);
+
+  useEffect(() => {
+    let cancelled = false;
+    setLoading(true);
+    setError(null);
+    setSuccess(false);
+    projectsApi
+      .getInstructions(projectId)
+      .then(({ content }) => {
+        if (cancelled) return;
+        setSaved(content);
+        setValue(content);
+      })
+      .catch((err) => {
+        if (!cancelled) {
+          setError(
+            err instanceof Error
+              ? err.message
+              : t('settings.projects.instructions.loadError')
+          );
+        }
+      })
+      .finally(() => {
+        if (!cancelled) setLoading(false);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [projectId, t]);
+
+  const handleSave = async () => {
+    setError(null);
+    setSuccess(false);
+    setSaving(true);
+    try {
+      const result = await projectsApi.updateInstructions(projectId, {
+        content: value,
+      });
+      setSaved(result.content);
+      setValue(result.content);
+      setSuccess(true);
+      setTimeout(() => setSuccess(false), 3000);
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : t('settings.projects.instructions.saveError')
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const hasChanges = value !== saved;
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>{t('settings.projects.instructions.title')}</CardTitle>
+        <CardDescription>
+          {t('settings.projects.instructions.description')}
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+        {success && (
+          <Alert variant="success">
+            <AlertDescription className="font-medium">
+              {t('settings.projects.instructions.saved')}
+            </AlertDescription>
+          </Alert>
+        )}
+
+        <Textarea
+          id="project-instructions"
+          value={loading ? t('settings.projects.instructions.loading') : value}
+          onChange={(e) => setValue(e.target.value)}
+          placeholder={t('settings.projects.instructions.placeholder')}
+          disabled={loading}
+          rows={10}
+          className="font-mono text-sm"
+        />
+        <p className="text-sm text-muted-foreground">
+          {t('settings.projects.instructions.helper')}
+        </p>
+
+        <div className="flex justify-end gap-2">
+          <Button
+            variant="outline"
+            onClick={() => setValue(saved)}
+            disabled={saving || !hasChanges}
+          >
+            {t('settings.projects.save.discard')}
+          </Button>
+          <Button onClick={handleSave} disabled={saving || !hasChanges}>
+            {saving && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+            {t('settings.projects.instructions.save')}
+          </Button>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,158 @@
+import { useEffect, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { Loader2 } from 'lucide-react';
+import {
+  Card,
+  CardContent,
+  CardDescription,
+  CardHeader,
+  CardTitle,
+} from '@/components/ui/card';
+import { Button } from '@/components/ui/button';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { JSONEditor } from '@/components/ui/json-editor';
+import { projectsApi } from '@/lib/api';
+import type { McpServers } from 'shared/types';
+
+const PLACEHOLDER =
+  '{\n  "database": {\n    "command": "npx",\n    "args": ["-y", "@example/db-mcp"],\n    "env": { "DATABASE_URL": "postgres://localhost/app" }\n  }\n}';
+
+interface ProjectMcpServersCardProps {
+  projectId: string;
+}
+
+function format(servers: unknown): string {
+  return JSON.stringify(servers, null, 2);
+}
+
+export default function ProjectMcpServersCard({
+  projectId,
+}: ProjectMcpServersCardProps) {
+  const { t } = useTranslation('settings');
+  const [saved, setSaved] = useState('{}');
+  const [value, setValue] = useState('{}');
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [success, setSuccess] = useState(false);
+
+  useEffect(() => {
+    let cancelled = false;
+    setLoading(true);
+    setError(null);
+    setSuccess(false);
+    projectsApi
+      .getMcpServers(projectId)
+      .then(({ servers }) => {
+        if (cancelled) return;
+        const text = format(servers);
+        setSaved(text);
+        setValue(text);
+      })
+      .catch((err) => {
+        if (!cancelled) {
+          setError(
+            err instanceof Error
+              ? err.message
+              : t('settings.projects.mcpServers.loadError')
+          );
+        }
+      })
+      .finally(() => {
+        if (!cancelled) setLoading(false);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [projectId, t]);
+
+  const handleSave = async () => {
+    setError(null);
+    setSuccess(false);
+
+    let servers: unknown;
+    try {
+      servers = value.trim() ? JSON.parse(value) : {};
+    } catch {
+      setError(t('settings.projects.mcpServers.invalidJson'));
+      return;
+    }
+    if (!servers || typeof servers !== 'object' || Array.isArray(servers)) {
+      setError(t('settings.projects.mcpServers.invalidJson'));
+      return;
+    }
+
+    setSaving(true);
+    try {
+      const result = await projectsApi.updateMcpServers(projectId, {
+        servers: servers as McpServers['servers'],
+      });
+      const text = format(result.servers);
+      setSaved(text);
+      setValue(text);
+      setSuccess(true);
+      setTimeout(() => setSuccess(false), 3000);
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : t('settings.projects.mcpServers.saveError')
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const hasChanges = value !== saved;
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>{t('settings.projects.mcpServers.title')}</CardTitle>
+        <CardDescription>
+          {t('settings.projects.mcpServers.description')}
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+        {success && (
+          <Alert variant="success">
+            <AlertDescription className="font-medium">
+              {t('settings.projects.mcpServers.saved')}
+            </AlertDescription>
+          </Alert>
+        )}
+
+        <JSONEditor
+          id="project-mcp-servers"
+          placeholder={PLACEHOLDER}
+          value={loading ? t('settings.projects.mcpServers.loading') : value}
+          onChange={setValue}
+          disabled={loading}
+          minHeight={200}
+        />
+        <p className="text-sm text-muted-foreground">
+          {t('settings.projects.mcpServers.helper')}
+        </p>
+
+        <div className="flex justify-end gap-2">
+          <Button
+            variant="outline"
+            onClick={() => setValue(saved)}
+            disabled={saving || !hasChanges}
+          >
+            {t('settings.projects.save.discard')}
+          </Button>
+          <Button onClick={handleSave} disabled={saving || !hasChanges}>
+            {saving && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+            {t('settings.projects.mcpServers.save')}
+          </Button>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
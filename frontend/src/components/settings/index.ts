@@ -1 +1,3 @@
 export { default as ExecutorProfileSelector } from './ExecutorProfileSelector';
+export { default as ProjectMcpServersCard } from './ProjectMcpServersCard';
+export { default as ProjectInstructionsCard } from './ProjectInstructionsCard';
@@ -961,6 +961,7 @@ export const Actions = {
         task: {
           ...task,
           has_in_progress_attempt: false,
+          is_queued: false,
           last_attempt_failed: false,
           executor: '',
         },
@@ -1007,14 +1008,10 @@ export const Actions = {
 
       if (hasConflicts && repoStatus) {
         // Show resolve conflicts dialog
-        const workspace = await getWorkspace(ctx.queryClient, workspaceId);
         const result = await ResolveConflictsDialog.show({
           workspaceId,
-          conflictOp: repoStatus.conflict_op ?? 'merge',
-          sourceBranch: workspace.branch,
-          targetBranch: repoStatus.target_branch_name,
+          repoId,
           conflictedFiles: repoStatus.conflicted_files ?? [],
-          repoName: repoStatus.repo_name,
         });
 
         if (result.action === 'resolved') {
@@ -7,8 +7,11 @@ import {
 } from './VirtualizedProcessLogs';
 import { useLogStream } from '@/hooks/useLogStream';
 import { useLogsPanel } from '@/contexts/LogsPanelContext';
+import { useWorkspaceContext } from '@/contexts/WorkspaceContext';
+import { useTerminal } from '@/contexts/TerminalContext';
+import { useAttachOutput } from '@/hooks/useAttachOutput';
 import { TerminalPanelContainer } from './TerminalPanelContainer';
-import { ArrowsInSimpleIcon } from '@phosphor-icons/react';
+import { ArrowsInSimpleIcon, PaperPlaneTiltIcon } from '@phosphor-icons/react';
 
 export type LogsPanelContent =
   | { type: 'process'; processId: string }
@@ -33,6 +36,15 @@ export function LogsContentContainer({ className }: LogsContentContainerProps) {
     collapseTerminal,
   } = useLogsPanel();
   const { t } = useTranslation('common');
+  const { workspaceId, selectedSessionId } = useWorkspaceContext();
+  const { getActiveTab } = useTerminal();
+  const { mutate: attachOutput } = useAttachOutput(
+    workspaceId,
+    selectedSessionId
+  );
+  const terminalSessionId = workspaceId
+    ? getActiveTab(workspaceId)?.sessionId
+    : undefined;
   // Get logs for process content (only when type is 'process')
   const processId = content?.type === 'process' ? content.processId : '';
   const { logs, error } = useLogStream(processId);
@@ -109,14 +121,28 @@ export function LogsContentContainer({ className }: LogsContentContainerProps) {
           <span className="text-sm font-medium text-normal">
             {t('processes.terminal')}
           </span>
-          <button
-            type="button"
-            onClick={collapseTerminal}
-            className="text-low hover:text-normal transition-colors"
-            title={t('actions.collapse')}
-          >
-            <ArrowsInSimpleIcon className="size-icon-sm" weight="bold" />
-          </button>
+          <div className="flex items-center gap-base">
+            {terminalSessionId && (
+              <button
+                type="button"
+                onClick={() =>
+                  attachOutput({ terminal_session_id: terminalSessionId })
+                }
+                className="text-low hover:text-normal transition-colors"
+                title={t('actions.sendTerminalOutput')}
+              >
+                <PaperPlaneTiltIcon className="size-icon-sm" weight="bold" />
+              </button>
+            )}
+            <button
+              type="button"
+              onClick={collapseTerminal}
+              className="text-low hover:text-normal transition-colors"
+              title={t('actions.collapse')}
+            >
+              <ArrowsInSimpleIcon className="size-icon-sm" weight="bold" />
+            </button>
+          </div>
         </div>
         <div className="flex-1 flex min-h-0 border-t border-border">
           <div className="flex-1 min-h-0 w-full">
@@ -548,7 +548,13 @@ function UserMessageEntry({
 }) {
   const [expanded, toggle] = usePersistedExpanded(`user:${expansionKey}`, true);
   const { startEdit, isEntryGreyed, isInEditMode } = useMessageEditContext();
-  const { resetProcess, canResetProcess, isResetPending } = resetAction;
+  const {
+    resetProcess,
+    canResetProcess,
+    isResetPending,
+    revertProcess,
+    canRevertProcess,
+  } = resetAction;
 
   const isGreyed = isEntryGreyed(expansionKey);
 
@@ -564,10 +570,17 @@ function UserMessageEntry({
     }
   };
 
+  const handleRevert = () => {
+    if (executionProcessId) {
+      revertProcess(executionProcessId);
+    }
+  };
+
   // Only show edit button if we have a process ID and not already in edit mode
   const canEdit = !!executionProcessId && !isInEditMode && !isResetPending;
   // Only show reset if we have a process ID, not in edit mode, not pending, and not first process
   const canReset = canEdit && canResetProcess(executionProcessId);
+  const canRevert = canEdit && canRevertProcess(executionProcessId);
 
   return (
     <ChatUserMessage
@@ -577,6 +590,7 @@ function UserMessageEntry({
       workspaceId={workspaceId}
       onEdit={canEdit ? handleEdit : undefined}
       onReset={canReset ? handleReset : undefined}
+      onRevert={canRevert ? handleRevert : undefined}
       isGreyed={isGreyed}
     />
   );
@@ -12,7 +12,7 @@ import {
   PHONE_FRAME_PADDING,
 } from '../views/PreviewBrowser';
 import { usePreviewDevServer } from '../hooks/usePreviewDevServer';
-import { usePreviewUrl } from '../hooks/usePreviewUrl';
+import { detectedPreviewUrl, usePreviewUrl } from '../hooks/usePreviewUrl';
 import {
   usePreviewSettings,
   type ScreenSize,
@@ -51,7 +51,10 @@ export function PreviewBrowserContainer({
 
   const primaryDevServer = runningDevServers[0];
   const { logs } = useLogStream(primaryDevServer?.id ?? '');
-  const urlInfo = usePreviewUrl(logs);
+  const logUrlInfo = usePreviewUrl(logs);
+  // Prefer the URL the server detected, which also covers servers that never
+  // print one
+  const urlInfo = detectedPreviewUrl(primaryDevServer) ?? logUrlInfo;
 
   // Detect failed dev server process (failed status or completed with non-zero exit code)
   const failedDevServerProcess = devServerProcesses.find(
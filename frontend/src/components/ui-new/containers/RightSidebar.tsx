@@ -7,8 +7,10 @@ import { TerminalPanelContainer } from '@/components/ui-new/containers/TerminalP
 import { WorkspaceNotesContainer } from '@/components/ui-new/containers/WorkspaceNotesContainer';
 import { useChangesView } from '@/contexts/ChangesViewContext';
 import { useWorkspaceContext } from '@/contexts/WorkspaceContext';
-import { ArrowsOutSimpleIcon } from '@phosphor-icons/react';
+import { ArrowsOutSimpleIcon, PaperPlaneTiltIcon } from '@phosphor-icons/react';
 import { useLogsPanel } from '@/contexts/LogsPanelContext';
+import { useTerminal } from '@/contexts/TerminalContext';
+import { useAttachOutput } from '@/hooks/useAttachOutput';
 import type { RepoWithTargetBranch, Workspace } from 'shared/types';
 import {
   PERSIST_KEYS,
@@ -46,7 +48,15 @@ export function RightSidebar({
 }: RightSidebarProps) {
   const { t } = useTranslation(['tasks', 'common']);
   const { selectFile } = useChangesView();
-  const { diffs } = useWorkspaceContext();
+  const { diffs, selectedSessionId } = useWorkspaceContext();
+  const { getActiveTab } = useTerminal();
+  const { mutate: attachOutput } = useAttachOutput(
+    selectedWorkspace?.id,
+    selectedSessionId
+  );
+  const activeTerminalSessionId = selectedWorkspace
+    ? getActiveTab(selectedWorkspace.id)?.sessionId
+    : undefined;
   const { setExpanded } = useExpandedAll();
   const isTerminalVisible = useUiPreferencesStore((s) => s.isTerminalVisible);
   const { expandTerminal, isTerminalExpanded } = useLogsPanel();
@@ -116,7 +126,21 @@ export function RightSidebar({
         visible: isTerminalVisible && !isTerminalExpanded,
         expanded: terminalExpanded,
         content: <TerminalPanelContainer />,
-        actions: [{ icon: ArrowsOutSimpleIcon, onClick: expandTerminal }],
+        actions: [
+          ...(activeTerminalSessionId
+            ? [
+                {
+                  icon: PaperPlaneTiltIcon,
+                  onClick: () =>
+                    attachOutput({
+                      terminal_session_id: activeTerminalSessionId,
+                    }),
+                  title: t('common:actions.sendTerminalOutput'),
+                },
+              ]
+            : []),
+          { icon: ArrowsOutSimpleIcon, onClick: expandTerminal },
+        ],
       },
       {
         title: t('common:sections.notes'),
@@ -175,7 +199,13 @@ export function RightSidebar({
                 className=""
               />
             ),
-            actions: [],
+            actions: [
+              {
+                icon: PaperPlaneTiltIcon,
+                onClick: () => attachOutput({}),
+                title: t('common:actions.sendDevServerOutput'),
+              },
+            ],
           });
         }
         break;
@@ -1,6 +1,7 @@
-import { useEffect, useRef } from 'react';
+import { useEffect, useRef, useState } from 'react';
 import { useWorkspaceContext } from '@/contexts/WorkspaceContext';
 import { useTerminal } from '@/contexts/TerminalContext';
+import { terminalApi } from '@/lib/api';
 import { TerminalPanel } from '../views/TerminalPanel';
 
 export function TerminalPanelContainer() {
@@ -9,6 +10,7 @@ export function TerminalPanelContainer() {
     getTabsForWorkspace,
     getActiveTab,
     createTab,
+    restoreTabs,
     closeTab,
     setActiveTab,
     clearWorkspaceTabs,
@@ -21,6 +23,9 @@ export function TerminalPanelContainer() {
 
   const creatingRef = useRef(false);
   const prevWorkspaceIdRef = useRef<string | null>(null);
+  const [restoredWorkspaceId, setRestoredWorkspaceId] = useState<
+    string | null
+  >(null);
 
   // Clean up terminals when workspace changes
   useEffect(() => {
@@ -33,11 +38,36 @@ export function TerminalPanelContainer() {
     prevWorkspaceIdRef.current = workspaceId ?? null;
   }, [workspaceId, clearWorkspaceTabs]);
 
+  // Reattach to shells still running on the server for this workspace
+  useEffect(() => {
+    if (!workspaceId) return;
+    let cancelled = false;
+    terminalApi
+      .listSessions(workspaceId)
+      .then((sessions) => {
+        if (cancelled) return;
+        restoreTabs(
+          workspaceId,
+          sessions.map((s) => ({ sessionId: s.id, cwd: s.cwd }))
+        );
+      })
+      .catch(() => {
+        // Fall back to a fresh terminal
+      })
+      .finally(() => {
+        if (!cancelled) setRestoredWorkspaceId(workspaceId);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [workspaceId, restoreTabs]);
+
   // Auto-create first tab when workspace is selected and terminal mode is active
   useEffect(() => {
     if (
       workspaceId &&
       containerRef &&
+      restoredWorkspaceId === workspaceId &&
       tabs.length === 0 &&
       !creatingRef.current
     ) {
@@ -47,7 +77,7 @@ export function TerminalPanelContainer() {
     if (tabs.length > 0) {
       creatingRef.current = false;
     }
-  }, [workspaceId, containerRef, tabs.length, createTab]);
+  }, [workspaceId, containerRef, restoredWorkspaceId, tabs.length, createTab]);
 
   return (
     <TerminalPanel
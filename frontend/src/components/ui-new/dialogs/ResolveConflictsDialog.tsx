@@ -15,24 +15,16 @@ import { AgentSelector } from '@/components/tasks/AgentSelector';
 import { ConfigSelector } from '@/components/tasks/ConfigSelector';
 import { useUserSystem } from '@/components/ConfigProvider';
 import { useWorkspaceContext } from '@/contexts/WorkspaceContext';
-import { sessionsApi } from '@/lib/api';
+import { attemptsApi } from '@/lib/api';
 import { useQueryClient } from '@tanstack/react-query';
 import NiceModal, { useModal } from '@ebay/nice-modal-react';
 import { defineModal } from '@/lib/modals';
-import { buildResolveConflictsInstructions } from '@/lib/conflicts';
-import type {
-  BaseCodingAgent,
-  ExecutorProfileId,
-  ConflictOp,
-} from 'shared/types';
+import type { BaseCodingAgent, ExecutorProfileId } from 'shared/types';
 
 export interface ResolveConflictsDialogProps {
   workspaceId: string;
-  conflictOp: ConflictOp;
-  sourceBranch: string | null;
-  targetBranch: string;
+  repoId: string;
   conflictedFiles: string[];
-  repoName?: string;
 }
 
 export type ResolveConflictsDialogResult =
@@ -41,14 +33,7 @@ export type ResolveConflictsDialogResult =
 
 const ResolveConflictsDialogImpl =
   NiceModal.create<ResolveConflictsDialogProps>(
-    ({
-      workspaceId,
-      conflictOp,
-      sourceBranch,
-      targetBranch,
-      conflictedFiles,
-      repoName,
-    }) => {
+    ({ workspaceId, repoId, conflictedFiles }) => {
       const modal = useModal();
       const queryClient = useQueryClient();
       const { profiles, config } = useUserSystem();
@@ -89,19 +74,6 @@ const ResolveConflictsDialogImpl =
       const effectiveProfile = userSelectedProfile ?? resolvedDefaultProfile;
       const canSubmit = Boolean(effectiveProfile && !isSubmitting);
 
-      // Build the conflict resolution instructions
-      const conflictInstructions = useMemo(
-        () =>
-          buildResolveConflictsInstructions(
-            sourceBranch,
-            targetBranch,
-            conflictedFiles,
-            conflictOp,
-            repoName
-          ),
-        [sourceBranch, targetBranch, conflictedFiles, conflictOp, repoName]
-      );
-
       const handleSubmit = useCallback(async () => {
         if (!effectiveProfile) return;
 
@@ -109,32 +81,19 @@ const ResolveConflictsDialogImpl =
         setError(null);
 
         try {
-          let targetSessionId = selectedSessionId;
-          const creatingNewSession = createNewSession || !selectedSessionId;
-
-          // Create new session if user selected that option or no existing session
-          if (creatingNewSession) {
-            const session = await sessionsApi.create({
-              workspace_id: workspaceId,
-              executor: effectiveProfile.executor,
-            });
-            targetSessionId = session.id;
-          }
-
-          if (!targetSessionId) {
-            setError('Failed to create session');
-            setIsSubmitting(false);
-            return;
-          }
+          const existingSessionId = createNewSession
+            ? undefined
+            : selectedSessionId;
+          const creatingNewSession = !existingSessionId;
 
-          // Send follow-up with conflict resolution instructions
-          await sessionsApi.followUp(targetSessionId, {
-            prompt: conflictInstructions,
+          // The server builds the prompt from the conflict hunks and continues
+          // the operation once the agent has removed every conflict marker
+          const process = await attemptsApi.resolveConflicts(workspaceId, {
+            repo_id: repoId,
+            session_id: existingSessionId ?? null,
             executor_profile_id: effectiveProfile,
-            retry_process_id: null,
-            force_when_dirty: null,
-            perform_git_reset: null,
           });
+          const targetSessionId = process.session_id;
 
           // Invalidate queries and wait for them to complete
           await Promise.all([
@@ -151,7 +110,7 @@ const ResolveConflictsDialogImpl =
 
           // Navigate to the new session if one was created
           // Do this after queries are refreshed so the session exists in the list
-          if (creatingNewSession && targetSessionId) {
+          if (creatingNewSession) {
             selectSession(targetSessionId);
           }
 
@@ -171,7 +130,7 @@ const ResolveConflictsDialogImpl =
         selectedSessionId,
         createNewSession,
         workspaceId,
-        conflictInstructions,
+        repoId,
         queryClient,
         selectSession,
         modal,
@@ -6,7 +6,7 @@ import { GitBranchIcon, SpinnerIcon } from '@phosphor-icons/react';
 import { useRepoBranches } from '@/hooks/useRepoBranches';
 import { useScriptPlaceholders } from '@/hooks/useScriptPlaceholders';
 import { repoApi } from '@/lib/api';
-import type { Repo, UpdateRepo } from 'shared/types';
+import type { MergeStrategy, Repo, UpdateRepo } from 'shared/types';
 import { SearchableDropdownContainer } from '../../containers/SearchableDropdownContainer';
 import {
   DropdownMenu,
@@ -24,10 +24,20 @@ import {
   SettingsSaveBar,
 } from './SettingsComponents';
 
+const MERGE_STRATEGY_LABEL_KEYS: Record<MergeStrategy, string> = {
+  squash: 'settings.repos.general.mergeStrategy.squash',
+  merge_commit: 'settings.repos.general.mergeStrategy.mergeCommit',
+  rebase: 'settings.repos.general.mergeStrategy.rebase',
+};
+
 interface RepoScriptsFormState {
   display_name: string;
   default_working_dir: string;
   default_target_branch: string;
+  merge_strategy: MergeStrategy;
+  commit_message_template: string;
+  conventional_commits: boolean;
+  agent_commit_messages: boolean;
   setup_script: string;
   parallel_setup_script: boolean;
   cleanup_script: string;
@@ -41,6 +51,10 @@ function repoToFormState(repo: Repo): RepoScriptsFormState {
     display_name: repo.display_name,
     default_working_dir: repo.default_working_dir ?? '',
     default_target_branch: repo.default_target_branch ?? '',
+    merge_strategy: repo.merge_strategy,
+    commit_message_template: repo.commit_message_template ?? '',
+    conventional_commits: repo.conventional_commits,
+    agent_commit_messages: repo.agent_commit_messages,
     setup_script: repo.setup_script ?? '',
     parallel_setup_script: repo.parallel_setup_script,
     cleanup_script: repo.cleanup_script ?? '',
@@ -164,6 +178,10 @@ export function ReposSettingsSection({
         display_name: draft.display_name.trim() || null,
         default_working_dir: draft.default_working_dir.trim() || null,
         default_target_branch: draft.default_target_branch.trim() || null,
+        merge_strategy: draft.merge_strategy,
+        commit_message_template: draft.commit_message_template.trim() || null,
+        conventional_commits: draft.conventional_commits,
+        agent_commit_messages: draft.agent_commit_messages,
         setup_script: draft.setup_script.trim() || null,
         cleanup_script: draft.cleanup_script.trim() || null,
         archive_script: draft.archive_script.trim() || null,
@@ -373,6 +391,72 @@ export function ReposSettingsSection({
                 }
               />
             </SettingsField>
+
+            <SettingsField
+              label={t('settings.repos.general.mergeStrategy.label')}
+              description={t('settings.repos.general.mergeStrategy.helper')}
+            >
+              <DropdownMenu>
+                <DropdownMenuTrigger asChild>
+                  <DropdownMenuTriggerButton
+                    label={t(MERGE_STRATEGY_LABEL_KEYS[draft.merge_strategy])}
+                    className="w-full justify-between"
+                  />
+                </DropdownMenuTrigger>
+                <DropdownMenuContent className="w-[var(--radix-dropdown-menu-trigger-width)]">
+                  {(
+                    Object.keys(MERGE_STRATEGY_LABEL_KEYS) as MergeStrategy[]
+                  ).map((strategy) => (
+                    <DropdownMenuItem
+                      key={strategy}
+                      onClick={() => updateDraft({ merge_strategy: strategy })}
+                    >
+                      {t(MERGE_STRATEGY_LABEL_KEYS[strategy])}
+                    </DropdownMenuItem>
+                  ))}
+                </DropdownMenuContent>
+              </DropdownMenu>
+            </SettingsField>
+          </SettingsCard>
+
+          {/* Commit message settings */}
+          <SettingsCard
+            title={t('settings.repos.commits.title')}
+            description={t('settings.repos.commits.description')}
+          >
+            <SettingsField
+              label={t('settings.repos.commits.template.label')}
+              description={t('settings.repos.commits.template.helper')}
+            >
+              <SettingsTextarea
+                value={draft.commit_message_template}
+                onChange={(value) =>
+                  updateDraft({ commit_message_template: value })
+                }
+                placeholder="{summary}"
+                monospace
+              />
+            </SettingsField>
+
+            <SettingsCheckbox
+              id="conventional-commits"
+              label={t('settings.repos.commits.conventional.label')}
+              description={t('settings.repos.commits.conventional.helper')}
+              checked={draft.conventional_commits}
+              onChange={(checked) =>
+                updateDraft({ conventional_commits: checked })
+              }
+            />
+
+            <SettingsCheckbox
+              id="agent-commit-messages"
+              label={t('settings.repos.commits.agentWritten.label')}
+              description={t('settings.repos.commits.agentWritten.helper')}
+              checked={draft.agent_commit_messages}
+              onChange={(checked) =>
+                updateDraft({ agent_commit_messages: checked })
+              }
+            />
           </SettingsCard>
 
           {/* Scripts settings */}
@@ -1,5 +1,6 @@
 import { useEffect, useRef, useState } from 'react';
 import { stripAnsi } from 'fancy-ansi';
+import type { ExecutionProcess } from 'shared/types';
 
 export interface PreviewUrlInfo {
   url: string;
@@ -78,6 +79,27 @@ export const detectPreviewUrl = (line: string): PreviewUrlInfo | null => {
   return null;
 };
 
+// URL the server detected for a dev server from its output or its sockets
+export const detectedPreviewUrl = (
+  process: ExecutionProcess | undefined
+): PreviewUrlInfo | undefined => {
+  if (!process?.detected_url) return undefined;
+  try {
+    const parsed = new URL(process.detected_url);
+    // The server reports loopback addresses; reach them the way the app is
+    if (['localhost', '127.0.0.1', '[::1]'].includes(parsed.hostname)) {
+      parsed.hostname = getBrowserHostname();
+    }
+    return {
+      url: parsed.toString(),
+      port: parsed.port ? Number(parsed.port) : undefined,
+      scheme: parsed.protocol === 'https:' ? 'https' : 'http',
+    };
+  } catch {
+    return undefined;
+  }
+};
+
 export function usePreviewUrl(
   logs: Array<{ content: string }> | undefined
 ): PreviewUrlInfo | undefined {
@@ -3,12 +3,16 @@ import { useExecutionProcessesContext } from '@/contexts/ExecutionProcessesConte
 import { useWorkspaceContext } from '@/contexts/WorkspaceContext';
 import { useBranchStatus } from '@/hooks/useBranchStatus';
 import { isCodingAgent } from '@/constants/processes';
+import { ExecutionProcessStatus } from 'shared/types';
 import { useResetProcessMutation } from './useResetProcessMutation';
+import { useRevertProcessMutation } from './useRevertProcessMutation';
 
 export interface UseResetProcessResult {
   resetProcess: (executionProcessId: string) => void;
   canResetProcess: (executionProcessId: string) => boolean;
   isResetPending: boolean;
+  revertProcess: (executionProcessId: string) => void;
+  canRevertProcess: (executionProcessId: string) => boolean;
 }
 
 export function useResetProcess(): UseResetProcessResult {
@@ -17,7 +21,11 @@ export function useResetProcess(): UseResetProcessResult {
   const { executionProcessesAll: processes } = useExecutionProcessesContext();
 
   const resetMutation = useResetProcessMutation(selectedSessionId ?? '');
-  const isResetPending = resetMutation.isPending;
+  const revertMutation = useRevertProcessMutation(
+    selectedSessionId ?? '',
+    workspaceId
+  );
+  const isResetPending = resetMutation.isPending || revertMutation.isPending;
 
   const firstCodingProcessId = useMemo(
     () =>
@@ -33,6 +41,20 @@ export function useResetProcess(): UseResetProcessResult {
     [firstCodingProcessId]
   );
 
+  // Only finished turns have a commit range to revert
+  const canRevertProcess = useCallback(
+    (executionProcessId: string) =>
+      processes.some(
+        (process) =>
+          process.id === executionProcessId &&
+          !process.dropped &&
+          isCodingAgent(process.run_reason) &&
+          process.status !== ExecutionProcessStatus.running &&
+          process.status !== ExecutionProcessStatus.queued
+      ),
+    [processes]
+  );
+
   const resetProcess = useCallback(
     (executionProcessId: string) => {
       if (!selectedSessionId) return;
@@ -45,12 +67,28 @@ export function useResetProcess(): UseResetProcessResult {
     [branchStatus, processes, resetMutation, selectedSessionId]
   );
 
+  const revertProcess = useCallback(
+    (executionProcessId: string) => {
+      if (!selectedSessionId) return;
+      revertMutation.mutate(executionProcessId);
+    },
+    [revertMutation, selectedSessionId]
+  );
+
   return useMemo(
     () => ({
       resetProcess,
       canResetProcess,
       isResetPending,
+      revertProcess,
+      canRevertProcess,
     }),
-    [resetProcess, canResetProcess, isResetPending]
+    [
+      resetProcess,
+      canResetProcess,
+      isResetPending,
+      revertProcess,
+      canRevertProcess,
+    ]
   );
 }
@@ -0,0 +1,59 @@
+import { useMutation, useQueryClient } from '@tanstack/react-query';
+import { useTranslation } from 'react-i18next';
+import { sessionsApi } from '@/lib/api';
+import { ConfirmDialog } from '@/components/ui-new/dialogs/ConfirmDialog';
+
+class RevertDialogCancelledError extends Error {
+  constructor() {
+    super('Revert dialog was cancelled');
+    this.name = 'RevertDialogCancelledError';
+  }
+}
+
+export function useRevertProcessMutation(
+  sessionId: string,
+  workspaceId: string | undefined
+) {
+  const { t } = useTranslation('tasks');
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationKey: ['revert-process', sessionId],
+    mutationFn: async (executionProcessId: string) => {
+      const confirmed = await ConfirmDialog.show({
+        title: t('conversation.revertDialog.title'),
+        message: t('conversation.revertDialog.message'),
+        confirmText: t('conversation.revertDialog.confirm'),
+      });
+      if (confirmed !== 'confirmed') {
+        throw new RevertDialogCancelledError();
+      }
+
+      const res = await sessionsApi.revert(sessionId, {
+        process_id: executionProcessId,
+      });
+      // Conflicts leave the revert in progress and show up in the branch
+      // status like a stopped rebase; anything else is reported here
+      if (!res.success && res.error?.type !== 'merge_conflicts') {
+        await ConfirmDialog.show({
+          title: t('conversation.revertDialog.failed'),
+          message: res.message ?? t('conversation.revertDialog.failed'),
+          confirmText: t('common:ok'),
+          showCancelButton: false,
+          variant: 'destructive',
+        });
+      }
+    },
+    onSettled: () => {
+      queryClient.invalidateQueries({
+        queryKey: ['branchStatus', workspaceId],
+      });
+    },
+    onError: (err) => {
+      if (err instanceof RevertDialogCancelledError) {
+        return;
+      }
+      console.error('Failed to revert process:', err);
+    },
+  });
+}
@@ -23,6 +23,7 @@ export interface SidebarWorkspace {
   isArchived?: boolean;
   hasPendingApproval?: boolean;
   hasRunningDevServer?: boolean;
+  devServerPort?: number;
   hasUnseenActivity?: boolean;
   latestProcessCompletedAt?: string;
   latestProcessStatus?: 'running' | 'completed' | 'failed' | 'killed';
@@ -67,6 +68,10 @@ function toSidebarWorkspace(
     // Additional data from summary
     hasPendingApproval: summary?.has_pending_approval,
     hasRunningDevServer: summary?.has_running_dev_server,
+    devServerPort:
+      summary?.dev_server_port != null
+        ? Number(summary.dev_server_port)
+        : undefined,
     hasUnseenActivity: summary?.has_unseen_turns,
     latestProcessCompletedAt: summary?.latest_process_completed_at ?? undefined,
     latestProcessStatus: summary?.latest_process_status ?? undefined,
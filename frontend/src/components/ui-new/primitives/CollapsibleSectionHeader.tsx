@@ -10,6 +10,7 @@ export type SectionAction = {
   icon: Icon;
   onClick: () => void;
   isActive?: boolean;
+  title?: string;
 };
 
 interface CollapsibleSectionHeaderProps {
@@ -57,6 +58,8 @@ export function CollapsibleSectionHeader({
               key={index}
               role="button"
               tabIndex={0}
+              title={action.title}
+              aria-label={action.title}
               onClick={(e) => handleActionClick(e, action.onClick)}
               onKeyDown={(e) => {
                 if (e.key === 'Enter' || e.key === ' ') {
@@ -39,6 +39,7 @@ const RUN_REASON_ICONS: Record<ExecutionProcessRunReason, typeof TerminalIcon> =
   };
 
 const STATUS_COLORS: Record<ExecutionProcessStatus, string> = {
+  queued: 'bg-low',
   running: 'bg-info',
   completed: 'bg-success',
   failed: 'bg-destructive',
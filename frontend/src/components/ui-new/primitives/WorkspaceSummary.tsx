@@ -25,6 +25,7 @@ interface WorkspaceSummaryProps {
   isPinned?: boolean;
   hasPendingApproval?: boolean;
   hasRunningDevServer?: boolean;
+  devServerPort?: number;
   hasUnseenActivity?: boolean;
   latestProcessCompletedAt?: string;
   latestProcessStatus?: 'running' | 'completed' | 'failed' | 'killed';
@@ -47,6 +48,7 @@ export function WorkspaceSummary({
   isPinned = false,
   hasPendingApproval = false,
   hasRunningDevServer = false,
+  devServerPort,
   hasUnseenActivity = false,
   latestProcessCompletedAt,
   latestProcessStatus,
@@ -111,10 +113,17 @@ export function WorkspaceSummary({
           <div className="flex w-full items-center gap-base text-sm h-5">
             {/* Dev server running - leftmost */}
             {hasRunningDevServer && (
-              <PlayIcon
-                className="size-icon-xs text-brand shrink-0"
-                weight="fill"
-              />
+              <span className="flex items-center gap-half shrink-0">
+                <PlayIcon
+                  className="size-icon-xs text-brand shrink-0"
+                  weight="fill"
+                />
+                {devServerPort !== undefined && (
+                  <span className="text-xs text-brand font-ibm-plex-mono">
+                    :{devServerPort}
+                  </span>
+                )}
+              </span>
             )}
 
             {/* Failed/killed status (only when not running) */}
@@ -1,5 +1,9 @@
 import { useTranslation } from 'react-i18next';
-import { PencilSimpleIcon, ArrowUUpLeftIcon } from '@phosphor-icons/react';
+import {
+  PencilSimpleIcon,
+  ArrowUUpLeftIcon,
+  ArrowCounterClockwiseIcon,
+} from '@phosphor-icons/react';
 import { ChatEntryContainer } from './ChatEntryContainer';
 import { ChatMarkdown } from './ChatMarkdown';
 import { Tooltip } from '../Tooltip';
@@ -12,6 +16,7 @@ interface ChatUserMessageProps {
   workspaceId?: string;
   onEdit?: () => void;
   onReset?: () => void;
+  onRevert?: () => void;
   isGreyed?: boolean;
 }
 
@@ -23,13 +28,29 @@ export function ChatUserMessage({
   workspaceId,
   onEdit,
   onReset,
+  onRevert,
   isGreyed,
 }: ChatUserMessageProps) {
   const { t } = useTranslation('tasks');
 
   const headerActions =
-    !isGreyed && (onEdit || onReset) ? (
+    !isGreyed && (onEdit || onReset || onRevert) ? (
       <div className="flex items-center gap-1">
+        {onRevert && (
+          <Tooltip content={t('conversation.actions.revertTooltip')}>
+            <button
+              type="button"
+              onClick={(e) => {
+                e.stopPropagation();
+                onRevert();
+              }}
+              className="p-1 rounded hover:bg-muted text-low hover:text-normal transition-colors"
+              aria-label={t('conversation.actions.revert')}
+            >
+              <ArrowCounterClockwiseIcon className="size-icon-xs" />
+            </button>
+          </Tooltip>
+        )}
         {onReset && (
           <Tooltip content={t('conversation.actions.resetTooltip')}>
             <button
@@ -11,6 +11,7 @@ import { useTerminal } from '@/contexts/TerminalContext';
 interface XTermInstanceProps {
   tabId: string;
   workspaceId: string;
+  sessionId?: string;
   isActive: boolean;
   onClose?: () => void;
 }
@@ -18,6 +19,7 @@ interface XTermInstanceProps {
 export function XTermInstance({
   tabId,
   workspaceId,
+  sessionId,
   isActive,
   onClose,
 }: XTermInstanceProps) {
@@ -26,6 +28,9 @@ export function XTermInstance({
   const terminalRef = useRef<Terminal | null>(null);
   const fitAddonRef = useRef<FitAddon | null>(null);
   const initialSizeRef = useRef({ cols: 80, rows: 24 });
+  // Only the session the tab was opened with; later ones are tracked by the
+  // connection itself
+  const initialSessionIdRef = useRef(sessionId);
   const { theme } = useTheme();
   const {
     registerTerminalInstance,
@@ -37,7 +42,10 @@ export function XTermInstance({
   const endpoint = useMemo(() => {
     const protocol = window.location.protocol === 'https:' ? 'https:' : 'http:';
     const host = window.location.host;
-    return `${protocol}//${host}/api/terminal/ws?workspace_id=${workspaceId}&cols=${initialSizeRef.current.cols}&rows=${initialSizeRef.current.rows}`;
+    const session = initialSessionIdRef.current
+      ? `&session_id=${initialSessionIdRef.current}`
+      : '';
+    return `${protocol}//${host}/api/terminal/ws?workspace_id=${workspaceId}&cols=${initialSizeRef.current.cols}&rows=${initialSizeRef.current.rows}${session}`;
   }, [workspaceId]);
 
   const fitTerminal = useCallback(() => {
@@ -90,7 +98,8 @@ export function XTermInstance({
         tabId,
         endpoint,
         (data) => terminal?.write(data),
-        onClose
+        onClose,
+        () => terminal?.reset()
       );
     }
 
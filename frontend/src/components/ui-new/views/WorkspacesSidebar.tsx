@@ -73,6 +73,7 @@ function WorkspaceList({
           isPinned={workspace.isPinned}
           hasPendingApproval={workspace.hasPendingApproval}
           hasRunningDevServer={workspace.hasRunningDevServer}
+          devServerPort={workspace.devServerPort}
           hasUnseenActivity={workspace.hasUnseenActivity}
           latestProcessCompletedAt={workspace.latestProcessCompletedAt}
           latestProcessStatus={workspace.latestProcessStatus}
@@ -219,6 +220,7 @@ export function WorkspacesSidebar({
                   isPinned={workspace.isPinned}
                   hasPendingApproval={workspace.hasPendingApproval}
                   hasRunningDevServer={workspace.hasRunningDevServer}
+                  devServerPort={workspace.devServerPort}
                   hasUnseenActivity={workspace.hasUnseenActivity}
                   latestProcessCompletedAt={workspace.latestProcessCompletedAt}
                   latestProcessStatus={workspace.latestProcessStatus}
@@ -332,6 +334,7 @@ export function WorkspacesSidebar({
                 isPinned={workspace.isPinned}
                 hasPendingApproval={workspace.hasPendingApproval}
                 hasRunningDevServer={workspace.hasRunningDevServer}
+                devServerPort={workspace.devServerPort}
                 hasUnseenActivity={workspace.hasUnseenActivity}
                 latestProcessCompletedAt={workspace.latestProcessCompletedAt}
                 latestProcessStatus={workspace.latestProcessStatus}
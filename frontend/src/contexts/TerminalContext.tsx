@@ -9,6 +9,7 @@ import {
 } from 'react';
 import type { Terminal } from '@xterm/xterm';
 import type { FitAddon } from '@xterm/addon-fit';
+import { terminalApi } from '@/lib/api';
 
 export interface TerminalInstance {
   terminal: Terminal;
@@ -20,6 +21,8 @@ export interface TerminalTab {
   title: string;
   workspaceId: string;
   cwd: string;
+  // Server-side shell session, kept alive across reconnects
+  sessionId?: string;
 }
 
 interface TerminalConnection {
@@ -33,8 +36,21 @@ interface TerminalState {
   activeTabByWorkspace: Record<string, string | null>;
 }
 
+interface ConnectionCallbacks {
+  onData: (data: string) => void;
+  onExit?: () => void;
+  // Called before the scrollback of a (re)attached session is replayed
+  onReset?: () => void;
+}
+
 type TerminalAction =
   | { type: 'CREATE_TAB'; workspaceId: string; cwd: string }
+  | {
+      type: 'RESTORE_TABS';
+      workspaceId: string;
+      sessions: { sessionId: string; cwd: string }[];
+    }
+  | { type: 'SET_TAB_SESSION'; tabId: string; sessionId: string }
   | { type: 'CLOSE_TAB'; workspaceId: string; tabId: string }
   | { type: 'SET_ACTIVE_TAB'; workspaceId: string; tabId: string }
   | {
@@ -88,6 +104,49 @@ function terminalReducer(
       };
     }
 
+    case 'RESTORE_TABS': {
+      const { workspaceId, sessions } = action;
+      const existingTabs = state.tabsByWorkspace[workspaceId] || [];
+      const known = new Set(existingTabs.map((t) => t.sessionId));
+      const restored = sessions
+        .filter(({ sessionId }) => !known.has(sessionId))
+        .map(
+          ({ sessionId, cwd }, i): TerminalTab => ({
+            id: generateTabId(),
+            title: `Terminal ${existingTabs.length + i + 1}`,
+            workspaceId,
+            cwd,
+            sessionId,
+          })
+        );
+      if (restored.length === 0) return state;
+      return {
+        ...state,
+        tabsByWorkspace: {
+          ...state.tabsByWorkspace,
+          [workspaceId]: [...existingTabs, ...restored],
+        },
+        activeTabByWorkspace: {
+          ...state.activeTabByWorkspace,
+          [workspaceId]:
+            state.activeTabByWorkspace[workspaceId] ?? restored[0].id,
+        },
+      };
+    }
+
+    case 'SET_TAB_SESSION': {
+      const { tabId, sessionId } = action;
+      return {
+        ...state,
+        tabsByWorkspace: Object.fromEntries(
+          Object.entries(state.tabsByWorkspace).map(([workspaceId, tabs]) => [
+            workspaceId,
+            tabs.map((t) => (t.id === tabId ? { ...t, sessionId } : t)),
+          ])
+        ),
+      };
+    }
+
     case 'CLOSE_TAB': {
       const { workspaceId, tabId } = action;
       const tabs = state.tabsByWorkspace[workspaceId] || [];
@@ -168,6 +227,10 @@ interface TerminalContextType {
   getTabsForWorkspace: (workspaceId: string) => TerminalTab[];
   getActiveTab: (workspaceId: string) => TerminalTab | null;
   createTab: (workspaceId: string, cwd: string) => void;
+  restoreTabs: (
+    workspaceId: string,
+    sessions: { sessionId: string; cwd: string }[]
+  ) => void;
   closeTab: (workspaceId: string, tabId: string) => void;
   setActiveTab: (workspaceId: string, tabId: string) => void;
   updateTabTitle: (workspaceId: string, tabId: string, title: string) => void;
@@ -185,7 +248,8 @@ interface TerminalContextType {
     tabId: string,
     endpoint: string,
     onData: (data: string) => void,
-    onExit?: () => void
+    onExit?: () => void,
+    onReset?: () => void
   ) => {
     send: (data: string) => void;
     resize: (cols: number, rows: number) => void;
@@ -214,9 +278,9 @@ export function TerminalProvider({ children }: TerminalProviderProps) {
   );
 
   // Store callback refs for each connection to prevent stale closures
-  const connectionCallbacksRef = useRef<
-    Map<string, { onData: (data: string) => void; onExit?: () => void }>
-  >(new Map());
+  const connectionCallbacksRef = useRef<Map<string, ConnectionCallbacks>>(
+    new Map()
+  );
 
   // Store reconnection state for each connection
   const reconnectStateRef = useRef<
@@ -224,6 +288,7 @@ export function TerminalProvider({ children }: TerminalProviderProps) {
       string,
       {
         endpoint: string;
+        sessionId: string | null;
         retryCount: number;
         retryTimer: ReturnType<typeof setTimeout> | null;
         intentionallyClosed: boolean;
@@ -252,6 +317,13 @@ export function TerminalProvider({ children }: TerminalProviderProps) {
     dispatch({ type: 'CREATE_TAB', workspaceId, cwd });
   }, []);
 
+  const restoreTabs = useCallback(
+    (workspaceId: string, sessions: { sessionId: string; cwd: string }[]) => {
+      dispatch({ type: 'RESTORE_TABS', workspaceId, sessions });
+    },
+    []
+  );
+
   const closeTerminalConnection = useCallback((tabId: string) => {
     // Mark as intentionally closed to prevent reconnection
     const reconnectState = reconnectStateRef.current.get(tabId);
@@ -279,11 +351,21 @@ export function TerminalProvider({ children }: TerminalProviderProps) {
         instance.terminal.dispose();
         terminalInstancesRef.current.delete(tabId);
       }
+      // Closing a tab ends its shell; losing the connection only detaches
+      const sessionId =
+        reconnectStateRef.current.get(tabId)?.sessionId ??
+        state.tabsByWorkspace[workspaceId]?.find((t) => t.id === tabId)
+          ?.sessionId;
+      if (sessionId) {
+        terminalApi.killSession(sessionId).catch(() => {
+          // The shell may already have exited
+        });
+      }
       // Close the WebSocket connection
       closeTerminalConnection(tabId);
       dispatch({ type: 'CLOSE_TAB', workspaceId, tabId });
     },
-    [closeTerminalConnection]
+    [state.tabsByWorkspace, closeTerminalConnection]
   );
 
   const setActiveTab = useCallback((workspaceId: string, tabId: string) => {
@@ -299,7 +381,8 @@ export function TerminalProvider({ children }: TerminalProviderProps) {
 
   const clearWorkspaceTabs = useCallback(
     (workspaceId: string) => {
-      // Dispose all terminal instances for this workspace
+      // Dispose all terminal instances for this workspace. Their shells keep
+      // running on the server and are restored when the workspace is reopened
       const tabs = state.tabsByWorkspace[workspaceId] || [];
       tabs.forEach((tab) => {
         const instance = terminalInstancesRef.current.get(tab.id);
@@ -338,7 +421,8 @@ export function TerminalProvider({ children }: TerminalProviderProps) {
       tabId: string,
       endpoint: string,
       onData: (data: string) => void,
-      onExit?: () => void
+      onExit?: () => void,
+      onReset?: () => void
     ) => {
       // Close existing connection if any
       const existing = terminalConnectionsRef.current.get(tabId);
@@ -347,7 +431,7 @@ export function TerminalProvider({ children }: TerminalProviderProps) {
       }
 
       // Store callbacks in ref so they can be updated without recreating connection
-      connectionCallbacksRef.current.set(tabId, { onData, onExit });
+      connectionCallbacksRef.current.set(tabId, { onData, onExit, onReset });
 
       // Initialize or reset reconnection state
       const existingReconnectState = reconnectStateRef.current.get(tabId);
@@ -356,6 +440,7 @@ export function TerminalProvider({ children }: TerminalProviderProps) {
       }
       reconnectStateRef.current.set(tabId, {
         endpoint,
+        sessionId: new URL(endpoint).searchParams.get('session_id'),
         retryCount: 0,
         retryTimer: null,
         intentionallyClosed: false,
@@ -367,9 +452,12 @@ export function TerminalProvider({ children }: TerminalProviderProps) {
           return;
         }
 
-        // Create new WebSocket
-        const wsEndpoint = endpoint.replace(/^http/, 'ws');
-        const ws = new WebSocket(wsEndpoint);
+        // Reattach to the same shell once the server has told us its session
+        const url = new URL(endpoint.replace(/^http/, 'ws'));
+        if (reconnectState.sessionId) {
+          url.searchParams.set('session_id', reconnectState.sessionId);
+        }
+        const ws = new WebSocket(url.toString());
 
         ws.onopen = () => {
           // Reset retry count on successful connection
@@ -383,7 +471,17 @@ export function TerminalProvider({ children }: TerminalProviderProps) {
           try {
             const msg = JSON.parse(event.data);
             const callbacks = connectionCallbacksRef.current.get(tabId);
-            if (msg.type === 'output' && msg.data && callbacks) {
+            if (msg.type === 'attached' && msg.session_id) {
+              const state = reconnectStateRef.current.get(tabId);
+              if (state) state.sessionId = msg.session_id;
+              dispatch({
+                type: 'SET_TAB_SESSION',
+                tabId,
+                sessionId: msg.session_id,
+              });
+              // The scrollback that follows replaces whatever is on screen
+              callbacks?.onReset?.();
+            } else if (msg.type === 'output' && msg.data && callbacks) {
               callbacks.onData(decodeBase64(msg.data));
             } else if (msg.type === 'exit' && callbacks) {
               callbacks.onExit?.();
@@ -468,6 +566,7 @@ export function TerminalProvider({ children }: TerminalProviderProps) {
       getTabsForWorkspace,
       getActiveTab,
       createTab,
+      restoreTabs,
       closeTab,
       setActiveTab,
       updateTabTitle,
@@ -482,6 +581,7 @@ export function TerminalProvider({ children }: TerminalProviderProps) {
       getTabsForWorkspace,
       getActiveTab,
       createTab,
+      restoreTabs,
       closeTab,
       setActiveTab,
       updateTabTitle,
@@ -0,0 +1,27 @@
+import { useMutation } from '@tanstack/react-query';
+import { attemptsApi } from '@/lib/api';
+import type { CaptureOutputQuery, CapturedOutput } from 'shared/types';
+
+/**
+ * Append the latest output of a terminal or process to the follow-up draft
+ * of a session, so it is sent with the next prompt.
+ */
+export function useAttachOutput(
+  attemptId?: string,
+  sessionId?: string,
+  onError?: (err: unknown) => void
+) {
+  return useMutation<CapturedOutput, unknown, CaptureOutputQuery>({
+    mutationFn: async (capture) => {
+      if (!attemptId) throw new Error('Attempt id is not set');
+      return attemptsApi.attachOutput(attemptId, {
+        ...capture,
+        session_id: sessionId,
+      });
+    },
+    onError: (err) => {
+      console.error('Failed to attach output:', err);
+      onError?.(err);
+    },
+  });
+}
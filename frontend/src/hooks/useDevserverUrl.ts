@@ -1,5 +1,6 @@
 import { useEffect, useRef, useState } from 'react';
 import { stripAnsi } from 'fancy-ansi';
+import type { ExecutionProcess } from 'shared/types';
 
 const urlPatterns = [
   /(https?:\/\/(?:\[[0-9a-f:]+\]|localhost|127\.0\.0\.1|0\.0\.0\.0|\d{1,3}(?:\.\d{1,3}){3})(?::\d{2,5})?(?:\/\S*)?)/i,
@@ -59,6 +60,27 @@ export const detectDevserverUrl = (line: string): DevserverUrlInfo | null => {
   return null;
 };
 
+// URL the server detected for a dev server from its output or its sockets
+export const detectedDevserverUrl = (
+  process: ExecutionProcess | undefined
+): DevserverUrlInfo | undefined => {
+  if (!process?.detected_url) return undefined;
+  try {
+    const parsed = new URL(process.detected_url);
+    // The server reports loopback addresses; reach them the way the app is
+    if (['localhost', '127.0.0.1', '[::1]'].includes(parsed.hostname)) {
+      parsed.hostname = getBrowserHostname();
+    }
+    return {
+      url: parsed.toString(),
+      port: parsed.port ? Number(parsed.port) : undefined,
+      scheme: parsed.protocol === 'https:' ? 'https' : 'http',
+    };
+  } catch {
+    return undefined;
+  }
+};
+
 export const useDevserverUrlFromLogs = (
   logs: Array<{ content: string }> | undefined
 ): DevserverUrlInfo | undefined => {
@@ -61,9 +61,16 @@ export function useSessionMessageEditor({
   const [localMessage, setLocalMessage] = useState('');
   const [hasInitialValue, setHasInitialValue] = useState(false);
 
+  // Drafts we saved ourselves, to tell their echoes apart from changes made
+  // elsewhere (e.g. terminal output attached to the draft)
+  const savedMessagesRef = useRef<string[]>([]);
+
   const saveToScratch = useCallback(
     async (message: string, executorProfileId: ExecutorProfileId) => {
       if (!scratchId) return;
+      savedMessagesRef.current = [...savedMessagesRef.current, message].slice(
+        -20
+      );
       try {
         await updateScratch({
           payload: {
@@ -87,6 +94,7 @@ export function useSessionMessageEditor({
   // Reset load state and clear message when scratchId changes (e.g., switching to approval mode)
   useEffect(() => {
     hasLoadedRef.current = false;
+    savedMessagesRef.current = [];
     setHasInitialValue(false);
     setLocalMessage('');
   }, [scratchId]);
@@ -100,6 +108,17 @@ export function useSessionMessageEditor({
     setHasInitialValue(true);
   }, [isScratchLoading, scratchData?.message]);
 
+  // Adopt drafts changed outside this editor
+  useEffect(() => {
+    const message = scratchData?.message;
+    if (!hasLoadedRef.current || message === undefined) return;
+    if (savedMessagesRef.current.includes(message)) return;
+    savedMessagesRef.current = [...savedMessagesRef.current, message].slice(
+      -20
+    );
+    setLocalMessage(message);
+  }, [scratchData?.message]);
+
   // Handle message change with debounced save
   // Pass executor profile at call-time to avoid stale closure
   const handleMessageChange = useCallback(
@@ -6,6 +6,9 @@ import {
   Config,
   CreateFollowUpAttempt,
   ResetProcessRequest,
+  RevertProcessRequest,
+  HandoffSessionRequest,
+  SteerRequest,
   EditorType,
   CreatePrApiRequest,
   CreateTask,
@@ -82,6 +85,7 @@ import {
   PushTaskAttemptRequest,
   RepoBranchStatus,
   AbortConflictsRequest,
+  ResolveConflictsRequest,
   ContinueRebaseRequest,
   Session,
   Workspace,
@@ -95,6 +99,13 @@ import {
   CreateFromPrError,
   MigrationRequest,
   MigrationResponse,
+  TerminalRecording,
+  TerminalSession,
+  AttachOutputRequest,
+  CapturedOutput,
+  McpServers,
+  WorkspaceMcpServers,
+  AgentInstructions,
 } from 'shared/types';
 import type { WorkspaceWithSession } from '@/types/attempt';
 import { createWorkspaceWithSession } from '@/types/attempt';
@@ -328,6 +339,48 @@ export const projectsApi = {
     );
     return handleApiResponse<void>(response);
   },
+
+  getMcpServers: async (projectId: string): Promise<McpServers> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/mcp-servers`
+    );
+    return handleApiResponse<McpServers>(response);
+  },
+
+  updateMcpServers: async (
+    projectId: string,
+    data: McpServers
+  ): Promise<McpServers> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/mcp-servers`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<McpServers>(response);
+  },
+
+  getInstructions: async (projectId: string): Promise<AgentInstructions> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/instructions`
+    );
+    return handleApiResponse<AgentInstructions>(response);
+  },
+
+  updateInstructions: async (
+    projectId: string,
+    data: AgentInstructions
+  ): Promise<AgentInstructions> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/instructions`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<AgentInstructions>(response);
+  },
 };
 
 // Task Management APIs
@@ -369,6 +422,22 @@ export const tasksApi = {
     });
     return handleApiResponse<void>(response);
   },
+
+  getInstructions: async (taskId: string): Promise<AgentInstructions> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/instructions`);
+    return handleApiResponse<AgentInstructions>(response);
+  },
+
+  updateInstructions: async (
+    taskId: string,
+    data: AgentInstructions
+  ): Promise<AgentInstructions> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/instructions`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<AgentInstructions>(response);
+  },
 };
 
 // Sessions API
@@ -428,6 +497,36 @@ export const sessionsApi = {
     });
     return handleApiResponse<void>(response);
   },
+
+  revert: async (
+    sessionId: string,
+    data: RevertProcessRequest
+  ): Promise<Result<void, GitOperationError>> => {
+    const response = await makeRequest(`/api/sessions/${sessionId}/revert`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponseAsResult<void, GitOperationError>(response);
+  },
+
+  handoff: async (
+    sessionId: string,
+    data: HandoffSessionRequest
+  ): Promise<ExecutionProcess> => {
+    const response = await makeRequest(`/api/sessions/${sessionId}/handoff`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ExecutionProcess>(response);
+  },
+
+  steer: async (sessionId: string, data: SteerRequest): Promise<void> => {
+    const response = await makeRequest(`/api/sessions/${sessionId}/steer`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<void>(response);
+  },
 };
 
 // Task Attempts APIs
@@ -582,6 +681,47 @@ export const attemptsApi = {
     return handleApiResponse<string | null>(response);
   },
 
+  /**
+   * Append the latest output of a terminal or process to the follow-up draft
+   */
+  attachOutput: async (
+    attemptId: string,
+    data: AttachOutputRequest
+  ): Promise<CapturedOutput> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/output/attach`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<CapturedOutput>(response);
+  },
+
+  getMcpServers: async (attemptId: string): Promise<WorkspaceMcpServers> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/mcp-servers`
+    );
+    return handleApiResponse<WorkspaceMcpServers>(response);
+  },
+
+  /**
+   * Replace the workspace's overrides of the project MCP servers
+   */
+  updateMcpServers: async (
+    attemptId: string,
+    data: McpServers
+  ): Promise<WorkspaceMcpServers> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/mcp-servers`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<WorkspaceMcpServers>(response);
+  },
+
   merge: async (
     attemptId: string,
     data: MergeTaskAttemptRequest
@@ -680,6 +820,20 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  resolveConflicts: async (
+    attemptId: string,
+    data: ResolveConflictsRequest
+  ): Promise<ExecutionProcess> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/conflicts/resolve`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ExecutionProcess>(response);
+  },
+
   continueRebase: async (
     attemptId: string,
     data: ContinueRebaseRequest
@@ -1441,6 +1595,33 @@ export const migrationApi = {
   },
 };
 
+// Terminal API (sessions outlive their websocket)
+export const terminalApi = {
+  listSessions: async (workspaceId: string): Promise<TerminalSession[]> => {
+    const response = await makeRequest(
+      `/api/terminal/sessions?workspace_id=${encodeURIComponent(workspaceId)}`
+    );
+    return handleApiResponse<TerminalSession[]>(response);
+  },
+
+  killSession: async (sessionId: string): Promise<void> => {
+    const response = await makeRequest(`/api/terminal/sessions/${sessionId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  listRecordings: async (workspaceId: string): Promise<TerminalRecording[]> => {
+    const response = await makeRequest(
+      `/api/terminal/recordings?workspace_id=${encodeURIComponent(workspaceId)}`
+    );
+    return handleApiResponse<TerminalRecording[]>(response);
+  },
+
+  getRecordingUrl: (workspaceId: string, sessionId: string): string =>
+    `/api/terminal/recordings/${sessionId}?workspace_id=${encodeURIComponent(workspaceId)}`,
+};
+
 // Search API (multi-repo file search)
 export const searchApi = {
   searchFiles: async (
@@ -25,6 +25,10 @@ import { Loader2, Plus, Trash2 } from 'lucide-react';
 import { useProjects } from '@/hooks/useProjects';
 import { useProjectMutations } from '@/hooks/useProjectMutations';
 import { RepoPickerDialog } from '@/components/dialogs/shared/RepoPickerDialog';
+import {
+  ProjectInstructionsCard,
+  ProjectMcpServersCard,
+} from '@/components/settings';
 import { projectsApi } from '@/lib/api';
 import { repoBranchKeys } from '@/hooks/useRepoBranches';
 import type { Project, Repo, UpdateProject } from 'shared/types';
@@ -542,6 +546,10 @@ export function ProjectSettings() {
             </CardContent>
           </Card>
 
+          <ProjectInstructionsCard projectId={selectedProject.id} />
+
+          <ProjectMcpServersCard projectId={selectedProject.id} />
+
           {/* Sticky Save Button for Project Name */}
           {hasUnsavedChanges && (
             <div className="sticky bottom-0 z-10 bg-background/80 backdrop-blur-sm border-t py-4">
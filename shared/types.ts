@@ -18,19 +18,95 @@ score: bigint, };
 
 export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
-export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, created_at: Date, updated_at: Date, };
+export type MergeStrategy = "squash" | "merge_commit" | "rebase";
 
-export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, archive_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, default_target_branch?: string | null, default_working_dir?: string | null, };
+export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, 
+/**
+ * Memory limit for this repo's scripts and dev servers, in megabytes
+ */
+memory_limit_mb: bigint | null, 
+/**
+ * CPU quota for this repo's scripts and dev servers, in percent of one core
+ */
+cpu_limit_percent: bigint | null, 
+/**
+ * Default strategy for direct merges into the target branch
+ */
+merge_strategy: MergeStrategy, 
+/**
+ * Template for the commit made after each agent turn, with placeholders
+ * such as `{summary}`, `{task_id}`, `{executor}` and `{turn}`
+ */
+commit_message_template: string | null, 
+/**
+ * Format commit subjects as Conventional Commits (`type: description`)
+ */
+conventional_commits: boolean, 
+/**
+ * Ask the agent to summarize its own changes as the commit message
+ */
+agent_commit_messages: boolean, created_at: Date, updated_at: Date, };
+
+export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, archive_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, default_target_branch?: string | null, default_working_dir?: string | null, memory_limit_mb?: number | null, cpu_limit_percent?: number | null, merge_strategy?: MergeStrategy, commit_message_template?: string | null, conventional_commits?: boolean, agent_commit_messages?: boolean, };
 
 export type ProjectRepo = { id: string, project_id: string, repo_id: string, };
 
 export type CreateProjectRepo = { display_name: string, git_repo_path: string, };
 
-export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string, target_branch: string, created_at: Date, updated_at: Date, };
+export type McpServers = { servers: { [key in string]?: JsonValue }, };
+
+export type WorkspaceMcpServers = { 
+/**
+ * The workspace's changes to the project servers. `null` removes the
+ * project server of that name.
+ */
+overrides: { [key in string]?: JsonValue }, 
+/**
+ * The servers the workspace's coding agents get
+ */
+effective: { [key in string]?: JsonValue }, };
+
+export type AgentInstructions = { 
+/**
+ * Markdown instructions for coding agents
+ */
+content: string, };
+
+export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string, target_branch: string, 
+/**
+ * Workspace whose branch `target_branch` is, when this repo is stacked
+ * on another in-progress workspace
+ */
+base_workspace_id: string | null, created_at: Date, updated_at: Date, };
 
 export type CreateWorkspaceRepo = { repo_id: string, target_branch: string, };
 
-export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, created_at: Date, updated_at: Date, };
+export type RepoWithTargetBranch = { target_branch: string, base_workspace_id: string | null, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, 
+/**
+ * Memory limit for this repo's scripts and dev servers, in megabytes
+ */
+memory_limit_mb: bigint | null, 
+/**
+ * CPU quota for this repo's scripts and dev servers, in percent of one core
+ */
+cpu_limit_percent: bigint | null, 
+/**
+ * Default strategy for direct merges into the target branch
+ */
+merge_strategy: MergeStrategy, 
+/**
+ * Template for the commit made after each agent turn, with placeholders
+ * such as `{summary}`, `{task_id}`, `{executor}` and `{turn}`
+ */
+commit_message_template: string | null, 
+/**
+ * Format commit subjects as Conventional Commits (`type: description`)
+ */
+conventional_commits: boolean, 
+/**
+ * Ask the agent to summarize its own changes as the commit message
+ */
+agent_commit_messages: boolean, created_at: Date, updated_at: Date, };
 
 export type Tag = { id: string, tag_name: string, content: string, created_at: string, updated_at: string, };
 
@@ -42,7 +118,11 @@ export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelle
 
 export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, };
+export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, 
+/**
+ * A coding agent for this task is waiting for a concurrency slot
+ */
+is_queued: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, };
 
 export type TaskRelationships = { parent_task: Task | null, current_workspace: Workspace, children: Array<Task>, };
 
@@ -151,6 +231,22 @@ export type WorkspaceWithStatus = { is_running: boolean, is_errored: boolean, id
 export type Session = { id: string, workspace_id: string, executor: string | null, created_at: string, updated_at: string, };
 
 export type ExecutionProcess = { id: string, session_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, 
+/**
+ * Peak resident memory of the process group, sampled while it ran
+ */
+peak_memory_bytes: bigint | null, 
+/**
+ * User + system CPU time of the process group, in milliseconds
+ */
+cpu_time_ms: bigint | null, 
+/**
+ * Port a dev server listens on, detected from its output or its sockets
+ */
+detected_port: bigint | null, 
+/**
+ * URL a dev server can be reached at, once its port is known
+ */
+detected_url: string | null, 
 /**
  * dropped: true if this process is excluded from the current
  * history view (due to restore/trimming). Hidden from logs/timeline;
@@ -158,7 +254,7 @@ export type ExecutionProcess = { id: string, session_id: string, run_reason: Exe
  */
 dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
 
-export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
+export enum ExecutionProcessStatus { queued = "queued", running = "running", completed = "completed", failed = "failed", killed = "killed" }
 
 export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "archivescript" | "codingagent" | "devserver";
 
@@ -292,11 +388,23 @@ export type CreateFollowUpAttempt = { prompt: string, executor_profile_id: Execu
 
 export type ResetProcessRequest = { process_id: string, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
 
+export type RevertProcessRequest = { process_id: string, };
+
+export type HandoffSessionRequest = { executor_profile_id: ExecutorProfileId, 
+/**
+ * What the new agent should do next; it continues the task when empty
+ */
+prompt?: string, };
+
 export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: string, };
 
 export type ChangeTargetBranchResponse = { repo_id: string, new_target_branch: string, status: [number, number], };
 
-export type MergeTaskAttemptRequest = { repo_id: string, };
+export type MergeTaskAttemptRequest = { repo_id: string, 
+/**
+ * Overrides the repo's merge strategy for this merge
+ */
+strategy?: MergeStrategy, };
 
 export type PushTaskAttemptRequest = { repo_id: string, };
 
@@ -320,6 +428,10 @@ export type ImageResponse = { id: string, file_path: string, original_name: stri
 
 export type ImageMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
 
+export type TerminalSession = { id: string, workspace_id: string, cwd: string, created_at: string, recorded: boolean, };
+
+export type TerminalRecording = { session_id: string, size_bytes: bigint, modified_at: string, };
+
 export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
 
 export type WorkspaceRepoInput = { repo_id: string, target_branch: string, };
@@ -336,6 +448,12 @@ export type ContinueRebaseRequest = { repo_id: string, };
 
 export type AbortConflictsRequest = { repo_id: string, };
 
+export type ResolveConflictsRequest = { repo_id: string, 
+/**
+ * Session to run the agent in; a new session is created when omitted
+ */
+session_id: string | null, executor_profile_id: ExecutorProfileId, };
+
 export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, conflicted_files: Array<string>, target_branch: string, } | { "type": "rebase_in_progress" };
 
 export type PushError = { "type": "force_push_required" };
@@ -425,6 +543,10 @@ latest_process_status: ExecutionProcessStatus | null,
  * Is a dev server currently running?
  */
 has_running_dev_server: boolean, 
+/**
+ * Port detected for the running dev server, if known
+ */
+dev_server_port: bigint | null, 
 /**
  * Does this workspace have unseen coding agent turns?
  */
@@ -438,13 +560,44 @@ export type WorkspaceSummaryResponse = { summaries: Array<WorkspaceSummary>, };
 
 export type DiffStats = { files_changed: number, lines_added: number, lines_removed: number, };
 
+export type CaptureOutputQuery = { terminal_session_id?: string, execution_process_id?: string, 
+/**
+ * Number of lines to keep from the end (default 200, at most 2000)
+ */
+lines?: number, };
+
+export type CapturedOutput = { 
+/**
+ * What produced the output, e.g. "terminal" or "dev server"
+ */
+source: string, 
+/**
+ * The last lines of output as plain text
+ */
+content: string, 
+/**
+ * The output wrapped in a code block, as it is added to a prompt
+ */
+markdown: string, };
+
+export type AttachOutputRequest = { 
+/**
+ * Coding agent session whose next follow-up gets the output. Defaults to
+ * the workspace's latest session.
+ */
+session_id?: string, terminal_session_id?: string, execution_process_id?: string, 
+/**
+ * Number of lines to keep from the end (default 200, at most 2000)
+ */
+lines?: number, };
+
 export type DirectoryEntry = { name: string, path: string, is_directory: boolean, is_git_repo: boolean, last_modified: bigint | null, };
 
 export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
 
 export type SearchMode = "taskform" | "settings";
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder_enabled: boolean, commit_reminder_prompt: string | null, send_message_shortcut: SendMessageShortcut, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder_enabled: boolean, commit_reminder_prompt: string | null, send_message_shortcut: SendMessageShortcut, workspace_container: WorkspaceContainerConfig, concurrency: ConcurrencyConfig, auto_rebase: AutoRebaseConfig, terminal: TerminalConfig, port_allocation: PortAllocationConfig, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
@@ -466,6 +619,52 @@ export type ShowcaseState = { seen_features: Array<string>, };
 
 export type SendMessageShortcut = "ModifierEnter" | "Enter";
 
+export type ConcurrencyConfig = { 
+/**
+ * Maximum coding agents running across all workspaces; unlimited when unset
+ */
+max_running_agents: number | null, 
+/**
+ * Maximum running agents per executor, e.g. `{ "CLAUDE_CODE": 2 }`
+ */
+per_executor: { [key in BaseCodingAgent]?: number }, };
+
+export type AutoRebaseConfig = { 
+/**
+ * Rebase idle, clean workspaces when their target branch gets new commits
+ */
+enabled: boolean, 
+/**
+ * On conflicts, start a coding agent to resolve them instead of leaving
+ * the rebase stopped for the user
+ */
+resolve_conflicts_with_agent: boolean, };
+
+export type TerminalConfig = { 
+/**
+ * Record each terminal session as an asciicast file for later review
+ */
+record_sessions: boolean, };
+
+export type PortAllocationConfig = { 
+/**
+ * Reserve ports for each workspace and pass them to its processes as
+ * `PORT` and `VK_PORT_0`, `VK_PORT_1`, ...
+ */
+enabled: boolean, 
+/**
+ * First port of the range ports are reserved from
+ */
+range_start: number, 
+/**
+ * Last port of the range ports are reserved from
+ */
+range_end: number, 
+/**
+ * Number of ports reserved for each workspace
+ */
+ports_per_workspace: number, };
+
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
 export type QueuedMessage = { 
@@ -499,13 +698,17 @@ export type ScriptRequest = { script: string, language: ScriptRequestLanguage, c
  * Optional relative path to execute the script in (relative to container_ref).
  * If None, uses the container_ref directory directly.
  */
-working_dir: string | null, };
+working_dir: string | null, 
+/**
+ * Memory/CPU limits from the repository settings
+ */
+resource_limits: ResourceLimits | null, };
 
 export type ScriptRequestLanguage = "Bash";
 
-export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", PI = "PI" }
+export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", PI = "PI", AIDER = "AIDER", CUSTOM = "CUSTOM" }
 
-export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "PI": Pi };
+export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "PI": Pi } | { "AIDER": Aider } | { "CUSTOM": Custom };
 
 export type SlashCommandDescription = { 
 /**
@@ -525,6 +728,28 @@ base: string,
  */
 params: Array<string> | null, };
 
+export type ProcessSandbox = { enabled: boolean, writable_paths?: Array<string>, network: SandboxNetwork, allowed_hosts?: Array<string>, };
+
+export type SandboxNetwork = "full" | "none" | "allowlist";
+
+export type ResourceLimits = { memory_mb?: number | null, cpu_percent?: number | null, };
+
+export type OciEngine = "docker" | "podman";
+
+export type WorkspaceContainerConfig = { 
+/**
+ * Run setup scripts, dev servers and agents inside a per-workspace container
+ */
+enabled: boolean, engine: OciEngine, 
+/**
+ * Fallback image when no repo declares one in `.devcontainer/devcontainer.json`
+ */
+image: string | null, 
+/**
+ * Extra arguments for `<engine> run`, e.g. `--network=host` or credential mounts
+ */
+run_args: Array<string>, };
+
 export type ExecutorProfileId = { 
 /**
  * The executor type (e.g., "CLAUDE_CODE", "AMP")
@@ -535,19 +760,26 @@ executor: BaseCodingAgent,
  */
 variant: string | null, };
 
-export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "PI": Pi } };
+export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "PI": Pi } | { "AIDER": Aider } | { "CUSTOM": Custom } };
 
 export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
 
-export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER", CONTEXT_USAGE = "CONTEXT_USAGE" }
+export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER", CONTEXT_USAGE = "CONTEXT_USAGE", STEERING = "STEERING" }
 
-export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type SteerRequest = { prompt: string, 
+/**
+ * Stop the agent's current step, e.g. a running tool call, and continue
+ * with `prompt` instead of letting the step finish first
+ */
+interrupt: boolean, };
 
-export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: ProcessSandbox | null, resource_limits?: ResourceLimits | null, co_authors?: Array<string> | null, fallback_profiles?: Array<string> | null, };
 
-export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: ProcessSandbox | null, resource_limits?: ResourceLimits | null, co_authors?: Array<string> | null, fallback_profiles?: Array<string> | null, };
 
-export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, approvals?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: ProcessSandbox | null, resource_limits?: ResourceLimits | null, co_authors?: Array<string> | null, fallback_profiles?: Array<string> | null, };
+
+export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: ProcessSandbox | null, resource_limits?: ResourceLimits | null, co_authors?: Array<string> | null, fallback_profiles?: Array<string> | null, };
 
 export type SandboxMode = "auto" | "read-only" | "workspace-write" | "danger-full-access";
 
@@ -559,9 +791,9 @@ export type ReasoningSummary = "auto" | "concise" | "detailed" | "none";
 
 export type ReasoningSummaryFormat = "none" | "experimental";
 
-export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, approvals?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: ProcessSandbox | null, resource_limits?: ResourceLimits | null, co_authors?: Array<string> | null, fallback_profiles?: Array<string> | null, };
 
-export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: ProcessSandbox | null, resource_limits?: ResourceLimits | null, co_authors?: Array<string> | null, fallback_profiles?: Array<string> | null, };
 
 export type Opencode = { append_prompt: AppendPrompt, model?: string | null, variant?: string | null, agent?: string | null, 
 /**
@@ -571,17 +803,27 @@ auto_approve: boolean,
 /**
  * Enable auto-compaction when the context length approaches the model's context window limit
  */
-auto_compact: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+auto_compact: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: ProcessSandbox | null, resource_limits?: ResourceLimits | null, co_authors?: Array<string> | null, fallback_profiles?: Array<string> | null, };
 
-export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: ProcessSandbox | null, resource_limits?: ResourceLimits | null, co_authors?: Array<string> | null, fallback_profiles?: Array<string> | null, };
 
-export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, approvals?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: ProcessSandbox | null, resource_limits?: ResourceLimits | null, co_authors?: Array<string> | null, fallback_profiles?: Array<string> | null, };
 
 export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-unsafe";
 
 export type DroidReasoningEffort = "none" | "dynamic" | "off" | "low" | "medium" | "high";
 
-export type Pi = { append_prompt: AppendPrompt, model?: string | null, provider?: string | null, auto_compaction?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Pi = { append_prompt: AppendPrompt, model?: string | null, provider?: string | null, auto_compaction?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: ProcessSandbox | null, resource_limits?: ResourceLimits | null, co_authors?: Array<string> | null, fallback_profiles?: Array<string> | null, };
+
+export type Aider = { append_prompt: AppendPrompt, model?: string | null, edit_format?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: ProcessSandbox | null, resource_limits?: ResourceLimits | null, co_authors?: Array<string> | null, fallback_profiles?: Array<string> | null, };
+
+export type Custom = { append_prompt: AppendPrompt, command: string, follow_up_command?: string | null, output_format: CustomOutputFormat, json_rules?: Array<JsonLineRule>, session_id_regex?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: ProcessSandbox | null, resource_limits?: ResourceLimits | null, co_authors?: Array<string> | null, fallback_profiles?: Array<string> | null, };
+
+export type CustomOutputFormat = "plain_text" | "json_lines";
+
+export type CustomEntryType = "assistant_message" | "thinking" | "system_message" | "error_message" | "tool_use";
+
+export type JsonLineRule = { path: string, equals?: string | null, entry_type: CustomEntryType, content_path: string, tool_name_path?: string | null, arguments_path?: string | null, };
 
 export type AppendPrompt = string | null;
 
@@ -594,7 +836,12 @@ executor_profile_id: ExecutorProfileId,
  * Optional relative path to execute the agent in (relative to container_ref).
  * If None, uses the container_ref directory directly.
  */
-working_dir: string | null, };
+working_dir: string | null, 
+/**
+ * Profiles left to fall back to when this run was itself started as a
+ * fallback. Otherwise the fallback profiles of its own profile apply.
+ */
+fallback_profiles?: Array<ExecutorProfileId>, };
 
 export type CodingAgentFollowUpRequest = { prompt: string, session_id: string, reset_to_message_id: string | null, 
 /**
@@ -643,7 +890,7 @@ export type ActionType = { "action": "file_read", path: string, } | { "action":
 
 export type TodoItem = { content: string, status: string, priority: string | null, };
 
-export type NormalizedEntryError = { "type": "setup_required" } | { "type": "other" };
+export type NormalizedEntryError = { "type": "setup_required" } | { "type": "rate_limited" } | { "type": "other" };
 
 export type ToolResult = { type: ToolResultValueType, 
 /**